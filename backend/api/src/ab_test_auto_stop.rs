@@ -0,0 +1,419 @@
+// api/src/ab_test_auto_stop.rs
+// Periodic evaluation of running A/B tests. Each run refreshes every running
+// test's `ab_test_results` from `calculate_statistical_significance`. For
+// tests created with `auto_stop`, once the same variant has won on consecutive
+// runs, with both variants at `min_sample_size` and the result at
+// `significance_threshold`, the test is completed and its winner recorded.
+//
+// Requiring consecutive runs keeps an early, transient crossing of the
+// threshold from ending a test. A treatment that significantly regresses any
+// of the test's guardrail metrics is never declared the winner; the guardrail
+// outcomes are stored alongside the treatment's results for every test, so
+// the verdict stays auditable. The scheduler runs the job on one replica at a
+// time, so replicas can't both count the same evaluation towards the streak.
+
+use rust_decimal::Decimal;
+use serde_json::json;
+use shared::models::{AbTest, VariantType};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::time::Duration;
+
+use crate::ab_test_handlers::{evaluate_guardrails, guardrail_violation};
+use crate::background_jobs::JobScheduler;
+
+const EVALUATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Evaluations in a row the stopping condition must hold for the same variant
+pub const REQUIRED_CONSECUTIVE_EVALUATIONS: i32 = 2;
+
+/// Recorded significance of one variant, as returned by
+/// `calculate_statistical_significance`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct VariantSignificance {
+    pub variant: VariantType,
+    pub sample_size: i32,
+    pub mean_val: Option<Decimal>,
+    pub std_dev: Option<Decimal>,
+    pub p_value: Option<Decimal>,
+    pub significance: Option<Decimal>,
+}
+
+/// Register the auto-stop evaluator with the background scheduler.
+pub fn spawn_ab_test_auto_stop_task(scheduler: &JobScheduler, pool: PgPool) {
+    let webhook_url = std::env::var("AB_TEST_AUTO_STOP_WEBHOOK_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty());
+    scheduler.spawn("ab_test_auto_stop", EVALUATION_INTERVAL, move || {
+        let pool = pool.clone();
+        let webhook_url = webhook_url.clone();
+        async move {
+            let stopped = evaluate_auto_stop_tests(&pool).await?;
+            for test in &stopped {
+                tracing::info!(
+                    test_id = %test.id,
+                    winner = ?test.winner,
+                    "ab_test_auto_stop: completed A/B test"
+                );
+                if let Some(url) = webhook_url.as_deref() {
+                    notify_stopped(url, test).await;
+                }
+            }
+            Ok(())
+        }
+    });
+}
+
+/// Refresh the results of every running test and evaluate the auto-stop ones
+/// once, returning the tests that were completed by this run.
+pub async fn evaluate_auto_stop_tests(pool: &PgPool) -> Result<Vec<AbTest>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let tests: Vec<AbTest> = sqlx::query_as("SELECT * FROM ab_tests WHERE status = 'running'")
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut stopped = Vec::new();
+    for test in tests {
+        if let Some(test) = evaluate_test(&mut tx, &test).await? {
+            stopped.push(test);
+        }
+    }
+
+    tx.commit().await?;
+    Ok(stopped)
+}
+
+async fn evaluate_test(
+    tx: &mut Transaction<'_, Postgres>,
+    test: &AbTest,
+) -> Result<Option<AbTest>, sqlx::Error> {
+    let variants: Vec<VariantSignificance> = sqlx::query_as(
+        "SELECT variant, sample_size, mean_val, std_dev, p_value, significance \
+         FROM calculate_statistical_significance($1)",
+    )
+    .bind(test.id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let guardrails = evaluate_guardrails(&mut **tx, test).await?;
+    let mut winner = stopping_winner(test, &variants);
+    if winner == Some(VariantType::Treatment) {
+        if let Some(violation) = guardrail_violation(&guardrails) {
+            tracing::info!(test_id = %test.id, %violation, "ab_test_auto_stop: guardrail veto");
+            winner = None;
+        }
+    }
+    let guardrail_outcomes = (!guardrails.is_empty()).then(|| json!(guardrails));
+
+    for variant in &variants {
+        sqlx::query(
+            r#"
+            INSERT INTO ab_test_results
+                (test_id, variant_type, sample_size, mean_value, std_deviation,
+                 p_value, statistical_significance, is_winner, guardrail_outcomes,
+                 calculated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+            ON CONFLICT (test_id, variant_type) DO UPDATE SET
+                sample_size = EXCLUDED.sample_size,
+                mean_value = EXCLUDED.mean_value,
+                std_deviation = EXCLUDED.std_deviation,
+                p_value = EXCLUDED.p_value,
+                statistical_significance = EXCLUDED.statistical_significance,
+                is_winner = EXCLUDED.is_winner,
+                guardrail_outcomes = EXCLUDED.guardrail_outcomes,
+                calculated_at = EXCLUDED.calculated_at
+            "#,
+        )
+        .bind(test.id)
+        .bind(&variant.variant)
+        .bind(variant.sample_size)
+        .bind(variant.mean_val)
+        .bind(variant.std_dev)
+        .bind(variant.p_value)
+        .bind(variant.significance)
+        .bind(winner.as_ref() == Some(&variant.variant))
+        .bind(
+            guardrail_outcomes
+                .as_ref()
+                .filter(|_| variant.variant == VariantType::Treatment),
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+    if !test.auto_stop {
+        return Ok(None);
+    }
+
+    let (candidate, streak) = next_streak(test, winner);
+    if streak >= REQUIRED_CONSECUTIVE_EVALUATIONS {
+        return sqlx::query_as(
+            r#"
+            UPDATE ab_tests
+            SET status = 'completed', ended_at = NOW(), winner = $2,
+                auto_stop_candidate = $2, auto_stop_streak = $3
+            WHERE id = $1 AND status = 'running'
+            RETURNING *
+            "#,
+        )
+        .bind(test.id)
+        .bind(&candidate)
+        .bind(streak)
+        .fetch_optional(&mut **tx)
+        .await;
+    }
+
+    sqlx::query(
+        "UPDATE ab_tests SET auto_stop_candidate = $2, auto_stop_streak = $3 \
+         WHERE id = $1 AND status = 'running'",
+    )
+    .bind(test.id)
+    .bind(&candidate)
+    .bind(streak)
+    .execute(&mut **tx)
+    .await?;
+    Ok(None)
+}
+
+/// The variant with the higher mean of the primary metric, when both variants
+/// have at least `min_sample_size` samples and the difference reaches the
+/// test's `significance_threshold`.
+pub fn stopping_winner(test: &AbTest, variants: &[VariantSignificance]) -> Option<VariantType> {
+    let find = |wanted: VariantType| variants.iter().find(|v| v.variant == wanted);
+    let (control, treatment) = (find(VariantType::Control)?, find(VariantType::Treatment)?);
+
+    let ready = [control, treatment].iter().all(|v| {
+        v.sample_size >= test.min_sample_size
+            && v.significance
+                .is_some_and(|sig| sig >= test.significance_threshold)
+    });
+    if !ready {
+        return None;
+    }
+
+    match (control.mean_val?, treatment.mean_val?) {
+        (c, t) if t > c => Some(VariantType::Treatment),
+        (c, t) if c > t => Some(VariantType::Control),
+        _ => None,
+    }
+}
+
+/// The candidate and streak to store after an evaluation that picked `winner`.
+/// The streak only grows while the same variant keeps winning.
+pub fn next_streak(test: &AbTest, winner: Option<VariantType>) -> (Option<VariantType>, i32) {
+    match winner {
+        None => (None, 0),
+        Some(winner) if test.auto_stop_candidate.as_ref() == Some(&winner) => {
+            (Some(winner), test.auto_stop_streak.saturating_add(1))
+        }
+        Some(winner) => (Some(winner), 1),
+    }
+}
+
+async fn notify_stopped(url: &str, test: &AbTest) {
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!(error = %err, "ab_test_auto_stop: could not build webhook client");
+            return;
+        }
+    };
+    let payload = json!({
+        "event": "ab_test_auto_stopped",
+        "test_id": test.id,
+        "contract_id": test.contract_id,
+        "name": test.name,
+        "winner": test.winner,
+        "ended_at": test.ended_at,
+    });
+
+    let result = client
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    if let Err(err) = result {
+        tracing::warn!(test_id = %test.id, error = %err, "ab_test_auto_stop: webhook failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use shared::models::AbTestStatus;
+    use uuid::Uuid;
+
+    fn auto_stop_test(min_sample_size: i32) -> AbTest {
+        AbTest {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            name: "checkout".to_string(),
+            description: None,
+            status: AbTestStatus::Running,
+            traffic_split: Decimal::from(50),
+            variant_a_deployment_id: Uuid::new_v4(),
+            variant_b_deployment_id: Uuid::new_v4(),
+            primary_metric: "conversion".to_string(),
+            hypothesis: None,
+            significance_threshold: Decimal::from(95),
+            min_sample_size,
+            started_at: Some(Utc::now()),
+            ended_at: None,
+            created_by: None,
+            created_at: Utc::now(),
+            auto_stop: true,
+            winner: None,
+            auto_stop_candidate: None,
+            auto_stop_streak: 0,
+            guardrail_metrics: Vec::new(),
+        }
+    }
+
+    fn variant(
+        variant: VariantType,
+        sample_size: i32,
+        mean: i64,
+        significance: i64,
+    ) -> VariantSignificance {
+        VariantSignificance {
+            variant,
+            sample_size,
+            mean_val: Some(Decimal::from(mean)),
+            std_dev: None,
+            p_value: None,
+            significance: Some(Decimal::from(significance)),
+        }
+    }
+
+    #[test]
+    fn higher_mean_wins_once_sampled_and_significant() {
+        let test = auto_stop_test(100);
+        let variants = [
+            variant(VariantType::Control, 500, 10, 99),
+            variant(VariantType::Treatment, 500, 12, 99),
+        ];
+
+        assert_eq!(
+            stopping_winner(&test, &variants),
+            Some(VariantType::Treatment)
+        );
+    }
+
+    #[test]
+    fn no_winner_while_under_sampled_or_insignificant() {
+        let test = auto_stop_test(1000);
+        let under_sampled = [
+            variant(VariantType::Control, 500, 10, 99),
+            variant(VariantType::Treatment, 5000, 12, 99),
+        ];
+        assert_eq!(stopping_winner(&test, &under_sampled), None);
+
+        let insignificant = [
+            variant(VariantType::Control, 5000, 10, 90),
+            variant(VariantType::Treatment, 5000, 12, 90),
+        ];
+        assert_eq!(stopping_winner(&test, &insignificant), None);
+    }
+
+    #[test]
+    fn a_single_crossing_does_not_stop_the_test() {
+        let mut test = auto_stop_test(100);
+
+        let (candidate, streak) = next_streak(&test, Some(VariantType::Treatment));
+        assert_eq!(streak, 1);
+        assert!(streak < REQUIRED_CONSECUTIVE_EVALUATIONS);
+
+        // The crossing held on the next run: now it stops
+        test.auto_stop_candidate = candidate;
+        test.auto_stop_streak = streak;
+        let (_, streak) = next_streak(&test, Some(VariantType::Treatment));
+        assert_eq!(streak, REQUIRED_CONSECUTIVE_EVALUATIONS);
+    }
+
+    #[test]
+    fn streak_resets_when_the_condition_lapses_or_the_winner_flips() {
+        let mut test = auto_stop_test(100);
+        test.auto_stop_candidate = Some(VariantType::Treatment);
+        test.auto_stop_streak = 1;
+
+        assert_eq!(next_streak(&test, None), (None, 0));
+        assert_eq!(
+            next_streak(&test, Some(VariantType::Control)),
+            (Some(VariantType::Control), 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn guardrail_outcomes_are_stored_for_tests_without_auto_stop() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let contract_id = crate::test_support::seed_contract(&db, "ab-guardrails").await;
+        let mut deployments = Vec::new();
+        for environment in ["blue", "green"] {
+            let id: Uuid = sqlx::query_scalar(
+                "INSERT INTO contract_deployments (contract_id, environment, wasm_hash)
+                 VALUES ($1, $2::deployment_environment, 'ab') RETURNING id",
+            )
+            .bind(contract_id)
+            .bind(environment)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+            deployments.push(id);
+        }
+        let test: AbTest = sqlx::query_as(
+            "INSERT INTO ab_tests (contract_id, name, status, variant_a_deployment_id,
+                 variant_b_deployment_id, primary_metric, min_sample_size, guardrail_metrics)
+             VALUES ($1, 'checkout', 'running', $2, $3, 'conversion', 1, ARRAY['errors'])
+             RETURNING *",
+        )
+        .bind(contract_id)
+        .bind(deployments[0])
+        .bind(deployments[1])
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        for (variant, metric, value) in [
+            ("control", "conversion", 1),
+            ("treatment", "conversion", 2),
+            ("control", "errors", 1),
+            ("treatment", "errors", 3),
+        ] {
+            sqlx::query(
+                "INSERT INTO ab_test_metrics (test_id, variant_type, metric_name, metric_value)
+                 VALUES ($1, $2::variant_type, $3, $4)",
+            )
+            .bind(test.id)
+            .bind(variant)
+            .bind(metric)
+            .bind(Decimal::from(value))
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        let mut tx = db.begin().await.unwrap();
+        assert!(evaluate_test(&mut tx, &test).await.unwrap().is_none());
+        tx.commit().await.unwrap();
+
+        let stored: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT guardrail_outcomes FROM ab_test_results
+             WHERE test_id = $1 AND variant_type = 'treatment'",
+        )
+        .bind(test.id)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(stored.unwrap()[0]["metric"], "errors");
+        let (status, streak): (AbTestStatus, i32) =
+            sqlx::query_as("SELECT status, auto_stop_streak FROM ab_tests WHERE id = $1")
+                .bind(test.id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert!(matches!(status, AbTestStatus::Running));
+        assert_eq!(streak, 0);
+    }
+}
@@ -0,0 +1,202 @@
+// api/src/ab_test_cleanup.rs
+// Periodic removal of A/B test variants, assignments and raw metrics that are
+// orphaned or belong to tests that ended longer ago than the retention window.
+// Tests themselves and their computed `ab_test_results` are kept.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::background_jobs::JobScheduler;
+
+const DEFAULT_RETENTION_DAYS: i64 = 90;
+const DEFAULT_BATCH_SIZE: i64 = 1_000;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+const CLEANUP_JITTER: Duration = Duration::from_secs(10 * 60);
+
+/// Child tables in deletion order. None reference each other, but metrics and
+/// assignments go before the variants they describe.
+const CHILD_TABLES: &[&str] = &["ab_test_metrics", "ab_test_assignments", "ab_test_variants"];
+
+#[derive(Debug, Clone)]
+pub struct CleanupConfig {
+    pub retention: chrono::Duration,
+    pub batch_size: i64,
+}
+
+impl CleanupConfig {
+    pub fn from_env() -> Self {
+        let retention_days = std::env::var("AB_TEST_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+        let batch_size = std::env::var("AB_TEST_CLEANUP_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+
+        Self {
+            retention: chrono::Duration::days(retention_days),
+            batch_size,
+        }
+    }
+}
+
+/// Register the cleanup job with the background scheduler.
+pub fn spawn_ab_test_cleanup_task(scheduler: &JobScheduler, pool: PgPool) {
+    let config = CleanupConfig::from_env();
+    scheduler.spawn_with_jitter(
+        "ab_test_cleanup",
+        CLEANUP_INTERVAL,
+        CLEANUP_JITTER,
+        move || {
+            let pool = pool.clone();
+            let config = config.clone();
+            async move {
+                let removed = run_cleanup(&pool, &config, Utc::now()).await?;
+                if removed > 0 {
+                    tracing::info!(removed, "ab_test_cleanup: removed stale A/B test rows");
+                }
+                Ok(())
+            }
+        },
+    );
+}
+
+/// Delete every eligible child row in batches of `config.batch_size`,
+/// returning how many rows were removed.
+pub async fn run_cleanup(
+    pool: &PgPool,
+    config: &CleanupConfig,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let cutoff = now - config.retention;
+    let mut removed = 0;
+
+    for table in CHILD_TABLES {
+        // No parent test, or a terminal one that ended before the cutoff
+        let query = format!(
+            r#"
+            DELETE FROM {table} WHERE id IN (
+                SELECT c.id FROM {table} c
+                LEFT JOIN ab_tests t ON t.id = c.test_id
+                WHERE t.id IS NULL
+                   OR (t.status IN ('completed', 'cancelled') AND t.ended_at < $1)
+                LIMIT $2
+            )
+            "#
+        );
+        loop {
+            let deleted = sqlx::query(&query)
+                .bind(cutoff)
+                .bind(config.batch_size)
+                .execute(pool)
+                .await?
+                .rows_affected();
+            removed += deleted;
+            if deleted < config.batch_size as u64 {
+                break;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    async fn seed_test(db: &PgPool, status: &str, ended_at: Option<DateTime<Utc>>) -> Uuid {
+        let contract_id = crate::test_support::seed_contract(db, "ab-cleanup").await;
+        let deployment_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO contract_deployments (contract_id, environment, wasm_hash)
+             VALUES ($1, 'blue', 'ab') RETURNING id",
+        )
+        .bind(contract_id)
+        .fetch_one(db)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO ab_tests (contract_id, name, status, ended_at, variant_a_deployment_id,
+                 variant_b_deployment_id, primary_metric)
+             VALUES ($1, 'cleanup', $2::ab_test_status, $3, $4, $4, 'conversion')
+             RETURNING id",
+        )
+        .bind(contract_id)
+        .bind(status)
+        .bind(ended_at)
+        .bind(deployment_id)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn seed_metrics(conn: &mut sqlx::PgConnection, test_id: Uuid) {
+        for _ in 0..3 {
+            sqlx::query(
+                "INSERT INTO ab_test_metrics (test_id, variant_type, metric_name, metric_value)
+                 VALUES ($1, 'control', 'conversion', 1)",
+            )
+            .bind(test_id)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn orphaned_and_expired_metrics_are_cleaned_while_active_data_is_kept() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let now = Utc::now();
+        let running = seed_test(&db, "running", None).await;
+        let expired = seed_test(&db, "cancelled", Some(now - chrono::Duration::days(120))).await;
+        let recent = seed_test(&db, "completed", Some(now - chrono::Duration::days(5))).await;
+        let unended = seed_test(&db, "cancelled", None).await;
+        let deleted_test = Uuid::new_v4();
+
+        let mut conn = db.acquire().await.unwrap();
+        for test_id in [running, expired, recent, unended] {
+            seed_metrics(&mut conn, test_id).await;
+        }
+        // Rows left behind by a test deleted while the foreign key was not enforced
+        sqlx::query("SET session_replication_role = replica")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        seed_metrics(&mut conn, deleted_test).await;
+        sqlx::query("SET session_replication_role = DEFAULT")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        drop(conn);
+
+        let config = CleanupConfig {
+            retention: chrono::Duration::days(DEFAULT_RETENTION_DAYS),
+            batch_size: 2,
+        };
+        let removed = run_cleanup(&db, &config, now).await.unwrap();
+        assert!(removed >= 6);
+
+        for (test_id, remaining) in [
+            (running, 3),
+            (expired, 0),
+            (recent, 3),
+            (unended, 3),
+            (deleted_test, 0),
+        ] {
+            let count: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM ab_test_metrics WHERE test_id = $1")
+                    .bind(test_id)
+                    .fetch_one(&db)
+                    .await
+                    .unwrap();
+            assert_eq!(count, remaining, "test {}", test_id);
+        }
+    }
+}
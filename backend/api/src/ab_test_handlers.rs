@@ -1,17 +1,29 @@
 use axum::{
-    extract::{Json, Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    body::Body,
+    extract::{rejection::JsonRejection, Json, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde_json::{json, Value};
 use shared::models::{
-    AbTest, AbTestMetric, AbTestResult, CreateAbTestRequest, RecordAbTestMetricRequest,
+    AbTest, AbTestAssignment, AbTestMetric, AbTestResult, CreateAbTestRequest,
+    RecordAbTestMetricRequest, VariantType,
 };
+use shared::pagination::{next_cursor, push_page, Cursor};
+use sqlx::{PgConnection, QueryBuilder};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
+    handlers::map_json_rejection,
+    idempotency::{self, Begun},
     state::AppState,
+    telemetry::db_span,
 };
 
 // ───────────────────── Query params ─────────────────────
@@ -22,6 +34,8 @@ pub struct ListAbTestsQuery {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Opaque keyset cursor from a previous page's `next_cursor`; takes precedence over `offset`
+    pub cursor: Option<String>,
     pub status: Option<String>,
 }
 
@@ -29,20 +43,149 @@ fn default_limit() -> i64 {
     20
 }
 
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct AbTestResultsQuery {
+    /// Also break the primary metric down by this segment key
+    pub segment_by: Option<SegmentBy>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct ExportAbTestMetricsQuery {
+    /// `control` or `treatment`
+    pub variant: Option<String>,
+    /// Only metrics recorded at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only metrics recorded before this time
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// How metrics are grouped into segments for `get_ab_test_results`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentBy {
+    /// The first character of `user_address` after its strkey version prefix
+    /// (the leading `G`/`C` is the same for nearly every address)
+    AddressPrefix,
+    /// The `segment` label stored in the metric's `metadata`
+    Metadata,
+}
+
+impl SegmentBy {
+    /// SQL expression for the segment key of an `ab_test_metrics` row; rows
+    /// where it is NULL are left out of the breakdown
+    fn key_sql(self) -> &'static str {
+        match self {
+            SegmentBy::AddressPrefix => "UPPER(SUBSTRING(user_address FROM 2 FOR 1))",
+            SegmentBy::Metadata => "NULLIF(metadata->>'segment', '')",
+        }
+    }
+}
+
+// ───────────────────── Responses ─────────────────────
+
+#[derive(Debug, serde::Serialize)]
+pub struct UserAssignmentResponse {
+    #[serde(flatten)]
+    pub assignment: AbTestAssignment,
+    pub metric_count: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbTestOutcome {
+    Winner,
+    /// A variant has fewer samples than the test's `min_sample_size`
+    InsufficientData,
+    /// Enough samples, but the difference isn't significant at the test's threshold
+    Inconclusive,
+    /// The treatment won on the primary metric but significantly worsened a guardrail
+    GuardrailViolation,
+}
+
+/// Treatment against control on one guardrail metric
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GuardrailOutcome {
+    pub metric: String,
+    pub control_samples: i64,
+    pub treatment_samples: i64,
+    pub control_mean: Option<f64>,
+    pub treatment_mean: Option<f64>,
+    /// Two-sided p-value of the difference, when both variants have samples
+    pub p_value: Option<f64>,
+    /// The treatment's mean is higher and the difference is significant
+    pub regressed: bool,
+}
+
+/// Treatment against control on the primary metric within one segment
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SegmentOutcome {
+    pub segment: String,
+    pub control_samples: i64,
+    pub treatment_samples: i64,
+    pub control_mean: f64,
+    pub treatment_mean: f64,
+    /// Change of the treatment's mean relative to control's, in percent;
+    /// absent when control's mean is zero
+    pub lift_percent: Option<f64>,
+    /// Two-sided p-value of the difference
+    pub p_value: f64,
+    /// The difference is significant at the test's threshold
+    pub significant: bool,
+}
+
+/// One `ab_test_metrics` row as written to the CSV export
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct MetricExportRow {
+    variant_type: String,
+    metric_name: String,
+    metric_value: Decimal,
+    user_address: Option<String>,
+    timestamp: DateTime<Utc>,
+}
+
+const METRICS_CSV_HEADER: &str = "variant_type,metric_name,metric_value,user_address,timestamp\n";
+
+/// Rows are sent to the client in chunks of roughly this many bytes
+const EXPORT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Sample count, mean and standard deviation of one metric for one variant
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetricStats {
+    pub samples: i64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
 // ───────────────────── Handlers ─────────────────────
 
 /// POST /api/contracts/:id/ab-tests — create a new A/B test
 pub async fn create_ab_test(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
-    Json(req): Json<CreateAbTestRequest>,
+    payload: Result<Json<CreateAbTestRequest>, JsonRejection>,
 ) -> ApiResult<impl IntoResponse> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let variant_a_uuid = parse_uuid(&req.variant_a_deployment_id, "variant_a_deployment")?;
     let variant_b_uuid = parse_uuid(&req.variant_b_deployment_id, "variant_b_deployment")?;
     let traffic_split = req.traffic_split.unwrap_or(50.0);
-    let significance = req.significance_threshold.unwrap_or(95.0);
+    let control_split = to_decimal(traffic_split, "traffic_split")?;
+    let treatment_split = to_decimal(100.0 - traffic_split, "traffic_split")?;
+    let significance = to_decimal(
+        req.significance_threshold.unwrap_or(95.0),
+        "significance_threshold",
+    )?;
     let min_sample = req.min_sample_size.unwrap_or(1000);
+    if req
+        .guardrail_metrics
+        .iter()
+        .any(|m| m.trim().is_empty() || *m == req.primary_metric)
+    {
+        return Err(ApiError::bad_request(
+            "InvalidGuardrailMetric",
+            "Guardrail metrics must be non-empty and differ from the primary metric",
+        ));
+    }
 
     // Ensure no running test for this contract
     let existing: Option<(Uuid,)> = sqlx::query_as(
@@ -66,22 +209,24 @@ pub async fn create_ab_test(
             (contract_id, name, description, traffic_split,
              variant_a_deployment_id, variant_b_deployment_id,
              primary_metric, hypothesis, significance_threshold,
-             min_sample_size, created_by)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             min_sample_size, created_by, auto_stop, guardrail_metrics)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
         RETURNING *
         "#,
     )
     .bind(contract_uuid)
     .bind(&req.name)
     .bind(req.description.as_deref())
-    .bind(rust_decimal::Decimal::try_from(traffic_split).unwrap_or_default())
+    .bind(control_split)
     .bind(variant_a_uuid)
     .bind(variant_b_uuid)
     .bind(&req.primary_metric)
     .bind(req.hypothesis.as_deref())
-    .bind(rust_decimal::Decimal::try_from(significance).unwrap_or_default())
+    .bind(significance)
     .bind(min_sample)
     .bind(req.created_by.as_deref())
+    .bind(req.auto_stop)
+    .bind(&req.guardrail_metrics)
     .fetch_one(&state.db)
     .await
     .map_err(|e| db_err("create ab test", e))?;
@@ -95,9 +240,9 @@ pub async fn create_ab_test(
     )
     .bind(test.id)
     .bind(variant_a_uuid)
-    .bind(rust_decimal::Decimal::try_from(traffic_split).unwrap_or_default())
+    .bind(control_split)
     .bind(variant_b_uuid)
-    .bind(rust_decimal::Decimal::try_from(100.0 - traffic_split).unwrap_or_default())
+    .bind(treatment_split)
     .execute(&state.db)
     .await;
 
@@ -112,52 +257,38 @@ pub async fn list_ab_tests(
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.max(0)
+    };
 
-    let (tests, total): (Vec<AbTest>, i64) = if let Some(ref status) = params.status {
-        let items: Vec<AbTest> = sqlx::query_as(
-            "SELECT * FROM ab_tests WHERE contract_id = $1 AND status::text = $2 ORDER BY created_at DESC LIMIT $3 OFFSET $4",
-        )
-        .bind(contract_uuid)
-        .bind(status)
-        .bind(limit)
-        .bind(offset)
+    let mut query = QueryBuilder::new("SELECT * FROM ab_tests WHERE contract_id = ");
+    query.push_bind(contract_uuid);
+    if let Some(ref status) = params.status {
+        query.push(" AND status::text = ").push_bind(status);
+    }
+    push_page(&mut query, cursor.as_ref(), "created_at", limit, offset);
+    let tests: Vec<AbTest> = query
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_err("list ab tests", e))?;
 
-        let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM ab_tests WHERE contract_id = $1 AND status::text = $2",
-        )
-        .bind(contract_uuid)
-        .bind(status)
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM ab_tests WHERE contract_id = ");
+    count.push_bind(contract_uuid);
+    if let Some(ref status) = params.status {
+        count.push(" AND status::text = ").push_bind(status);
+    }
+    let total: i64 = count
+        .build_query_scalar()
         .fetch_one(&state.db)
         .await
         .map_err(|e| db_err("count ab tests", e))?;
 
-        (items, count)
-    } else {
-        let items: Vec<AbTest> = sqlx::query_as(
-            "SELECT * FROM ab_tests WHERE contract_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
-        )
-        .bind(contract_uuid)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await
-        .map_err(|e| db_err("list ab tests", e))?;
-
-        let count: i64 =
-            sqlx::query_scalar("SELECT COUNT(*) FROM ab_tests WHERE contract_id = $1")
-                .bind(contract_uuid)
-                .fetch_one(&state.db)
-                .await
-                .map_err(|e| db_err("count ab tests", e))?;
-
-        (items, count)
-    };
-
     Ok(Json(json!({
+        "next_cursor": next_cursor(&tests, limit, |t| (t.created_at, t.id)),
         "items": tests,
         "total": total,
         "limit": limit,
@@ -277,9 +408,27 @@ pub async fn cancel_ab_test(
 pub async fn record_ab_test_metric(
     State(state): State<AppState>,
     Path(test_id): Path<String>,
-    Json(req): Json<RecordAbTestMetricRequest>,
-) -> ApiResult<impl IntoResponse> {
+    headers: HeaderMap,
+    payload: Result<Json<RecordAbTestMetricRequest>, JsonRejection>,
+) -> ApiResult<Response> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let test_uuid = parse_uuid(&test_id, "test")?;
+    let key = idempotency::key_from_headers(&headers)?;
+    let scope = format!("ab_test_metric:{}", test_uuid);
+    let mut write = match idempotency::begin(&state.db, &scope, key).await? {
+        Begun::Replay(response) => return Ok(response),
+        Begun::Write(write) => write,
+    };
+    let metric = insert_ab_test_metric(write.conn(), test_uuid, req).await?;
+    write.commit(StatusCode::CREATED, &metric).await
+}
+
+async fn insert_ab_test_metric(
+    conn: &mut PgConnection,
+    test_uuid: Uuid,
+    req: RecordAbTestMetricRequest,
+) -> ApiResult<AbTestMetric> {
+    let metric_value = to_decimal(req.metric_value, "metric_value")?;
 
     // Determine user variant assignment (uses DB function)
     let user_addr = req.user_address.as_deref().unwrap_or("anonymous");
@@ -289,7 +438,7 @@ pub async fn record_ab_test_metric(
     )
     .bind(test_uuid)
     .bind(user_addr)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *conn)
     .await
     .map_err(|e| db_err("assign variant", e))?
     .flatten();
@@ -307,26 +456,29 @@ pub async fn record_ab_test_metric(
     .bind(test_uuid)
     .bind(&variant_type)
     .bind(&req.metric_name)
-    .bind(rust_decimal::Decimal::try_from(req.metric_value).unwrap_or_default())
+    .bind(metric_value)
     .bind(req.user_address.as_deref())
     .bind(&req.metadata)
-    .fetch_one(&state.db)
+    .fetch_one(conn)
     .await
     .map_err(|e| db_err("record ab test metric", e))?;
 
-    Ok((StatusCode::CREATED, Json(metric)))
+    Ok(metric)
 }
 
 /// GET /api/ab-tests/:test_id/results — get A/B test results
+#[tracing::instrument(skip_all, fields(test_id = %test_id))]
 pub async fn get_ab_test_results(
     State(state): State<AppState>,
     Path(test_id): Path<String>,
+    Query(query): Query<AbTestResultsQuery>,
 ) -> ApiResult<Json<Value>> {
     let test_uuid = parse_uuid(&test_id, "test")?;
 
     let test: AbTest = sqlx::query_as("SELECT * FROM ab_tests WHERE id = $1")
         .bind(test_uuid)
         .fetch_one(&state.db)
+        .instrument(db_span("get ab test"))
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => {
@@ -335,11 +487,12 @@ pub async fn get_ab_test_results(
             _ => db_err("get ab test for results", e),
         })?;
 
-    let results: Vec<AbTestResult> = sqlx::query_as(
+    let mut results: Vec<AbTestResult> = sqlx::query_as(
         "SELECT * FROM ab_test_results WHERE test_id = $1 ORDER BY calculated_at DESC",
     )
     .bind(test_uuid)
     .fetch_all(&state.db)
+    .instrument(db_span("get ab test results"))
     .await
     .map_err(|e| db_err("get ab test results", e))?;
 
@@ -349,6 +502,7 @@ pub async fn get_ab_test_results(
     )
     .bind(test_uuid)
     .fetch_one(&state.db)
+    .instrument(db_span("count control metrics"))
     .await
     .unwrap_or(0);
 
@@ -357,10 +511,37 @@ pub async fn get_ab_test_results(
     )
     .bind(test_uuid)
     .fetch_one(&state.db)
+    .instrument(db_span("count treatment metrics"))
     .await
     .unwrap_or(0);
 
-    Ok(Json(json!({
+    // A winner recorded on the test by the auto-stop evaluator is final
+    let (mut outcome, mut winner) = match &test.winner {
+        Some(winner) => (AbTestOutcome::Winner, Some(winner.clone())),
+        None => decide_outcome(&test, &mut results),
+    };
+
+    let guardrails = evaluate_guardrails(&state.db, &test)
+        .instrument(db_span("evaluate ab test guardrails"))
+        .await
+        .map_err(|e| db_err("evaluate ab test guardrails", e))?;
+    let guardrail_violation = guardrail_violation(&guardrails);
+    if test.winner.is_none()
+        && winner == Some(VariantType::Treatment)
+        && guardrail_violation.is_some()
+    {
+        outcome = AbTestOutcome::GuardrailViolation;
+        winner = None;
+        for result in results.iter_mut() {
+            result.is_winner = false;
+        }
+    }
+
+    let mut response = json!({
+        "outcome": outcome,
+        "winner": winner,
+        "guardrails": guardrails,
+        "guardrail_violation": guardrail_violation,
         "test": test,
         "results": results,
         "metric_counts": {
@@ -368,11 +549,164 @@ pub async fn get_ab_test_results(
             "treatment": treatment_count,
             "total": control_count + treatment_count,
         }
-    })))
+    });
+
+    if let Some(segment_by) = query.segment_by {
+        let rows = segment_stats(&state.db, &test, segment_by)
+            .instrument(db_span("segment ab test metrics"))
+            .await
+            .map_err(|e| db_err("segment ab test metrics", e))?;
+        let significance = test.significance_threshold.to_f64().unwrap_or(95.0);
+        let (segments, omitted) =
+            segment_outcomes(&rows, i64::from(test.min_sample_size), significance);
+        response["segments"] = json!({
+            "segment_by": segment_by,
+            "metric": test.primary_metric,
+            "min_sample_size": test.min_sample_size,
+            "segments": segments,
+            "omitted_segments": omitted,
+        });
+    }
+
+    Ok(Json(response))
+}
+
+/// GET /api/ab-tests/:test_id/metrics/export — stream the raw metrics as CSV
+///
+/// Rows are read from a database cursor and written out in chunks, so large
+/// tests are never held in memory. A database error mid-stream aborts the
+/// response rather than truncating it silently.
+pub async fn export_ab_test_metrics(
+    State(state): State<AppState>,
+    Path(test_id): Path<String>,
+    Query(query): Query<ExportAbTestMetricsQuery>,
+) -> ApiResult<Response> {
+    let test_uuid = parse_uuid(&test_id, "test")?;
+    let variant = query.variant.as_deref().map(parse_variant).transpose()?;
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return Err(ApiError::bad_request(
+                "InvalidDateRange",
+                "`from` must be earlier than `to`",
+            ));
+        }
+    }
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM ab_tests WHERE id = $1)")
+        .bind(test_uuid)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_err("check ab test exists", e))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "AbTestNotFound",
+            format!("No A/B test found with ID: {}", test_id),
+        ));
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(4);
+    let db = state.db.clone();
+    tokio::spawn(async move {
+        let mut rows = sqlx::query_as::<_, MetricExportRow>(
+            r#"
+            SELECT variant_type::text AS variant_type, metric_name, metric_value,
+                   user_address, timestamp
+            FROM ab_test_metrics
+            WHERE test_id = $1
+              AND ($2::text IS NULL OR variant_type::text = $2)
+              AND ($3::timestamptz IS NULL OR timestamp >= $3)
+              AND ($4::timestamptz IS NULL OR timestamp < $4)
+            ORDER BY timestamp, id
+            "#,
+        )
+        .bind(test_uuid)
+        .bind(variant)
+        .bind(query.from)
+        .bind(query.to)
+        .fetch(&db);
+
+        let mut chunk = String::from(METRICS_CSV_HEADER);
+        loop {
+            match rows.try_next().await {
+                Ok(Some(row)) => {
+                    push_metric_csv_row(&mut chunk, &row);
+                    if chunk.len() < EXPORT_CHUNK_BYTES {
+                        continue;
+                    }
+                }
+                Ok(None) => {
+                    let _ = tx.send(Ok(chunk)).await;
+                    return;
+                }
+                Err(err) => {
+                    tracing::error!(test_id = %test_uuid, error = ?err, "ab test metrics export failed");
+                    let _ = tx.send(Err(std::io::Error::other(err))).await;
+                    return;
+                }
+            }
+            // The client went away
+            if tx.send(Ok(std::mem::take(&mut chunk))).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"ab-test-{}-metrics.csv\"", test_uuid),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response())
+}
+
+/// GET /api/ab-tests/:test_id/assignments/:user_address — get a user's variant assignment
+pub async fn get_user_assignment(
+    State(state): State<AppState>,
+    Path((test_id, user_address)): Path<(String, String)>,
+) -> ApiResult<Json<UserAssignmentResponse>> {
+    let test_uuid = parse_uuid(&test_id, "test")?;
+
+    let assignment: Option<AbTestAssignment> = sqlx::query_as(
+        "SELECT * FROM ab_test_assignments WHERE test_id = $1 AND user_address = $2",
+    )
+    .bind(test_uuid)
+    .bind(&user_address)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("get ab test assignment", e))?;
+
+    let assignment = require_assignment(assignment, &user_address)?;
+
+    let metric_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM ab_test_metrics WHERE test_id = $1 AND user_address = $2",
+    )
+    .bind(test_uuid)
+    .bind(&user_address)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("count ab test user metrics", e))?;
+
+    Ok(Json(UserAssignmentResponse {
+        assignment,
+        metric_count,
+    }))
 }
 
 // ───────────────────── Helpers ─────────────────────
 
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, ApiError> {
+    cursor
+        .map(|c| {
+            Cursor::decode(c).map_err(|e| ApiError::bad_request("InvalidCursor", e.to_string()))
+        })
+        .transpose()
+}
+
 fn parse_uuid(id: &str, label: &str) -> Result<Uuid, ApiError> {
     Uuid::parse_str(id).map_err(|_| {
         ApiError::bad_request(
@@ -382,7 +716,568 @@ fn parse_uuid(id: &str, label: &str) -> Result<Uuid, ApiError> {
     })
 }
 
+/// Only lets a winner stand when both variants' latest results reach the test's
+/// `min_sample_size` and its significance threshold; otherwise every `is_winner`
+/// flag is cleared so a stale or buggy calculation can't declare one.
+fn decide_outcome(
+    test: &AbTest,
+    results: &mut [AbTestResult],
+) -> (AbTestOutcome, Option<VariantType>) {
+    // `results` is newest first, so the first row per variant is its latest
+    let control = results
+        .iter()
+        .position(|r| matches!(r.variant_type, VariantType::Control));
+    let treatment = results
+        .iter()
+        .position(|r| matches!(r.variant_type, VariantType::Treatment));
+
+    let (outcome, winner) = match (control, treatment) {
+        (Some(c), Some(t)) => {
+            let sampled = [c, t]
+                .iter()
+                .all(|&i| results[i].sample_size >= test.min_sample_size);
+            let significant = [c, t].iter().all(|&i| {
+                results[i]
+                    .statistical_significance
+                    .is_some_and(|sig| sig >= test.significance_threshold)
+            });
+            let flagged = [c, t].into_iter().find(|&i| results[i].is_winner);
+
+            if !sampled {
+                (AbTestOutcome::InsufficientData, None)
+            } else if !significant {
+                (AbTestOutcome::Inconclusive, None)
+            } else {
+                match flagged {
+                    Some(i) => (AbTestOutcome::Winner, Some(results[i].variant_type.clone())),
+                    None => (AbTestOutcome::Inconclusive, None),
+                }
+            }
+        }
+        _ => (AbTestOutcome::InsufficientData, None),
+    };
+
+    if winner.is_none() {
+        for result in results.iter_mut() {
+            result.is_winner = false;
+        }
+    }
+    (outcome, winner)
+}
+
+/// Compare treatment against control on each of the test's guardrail metrics,
+/// at the test's significance threshold.
+pub(crate) async fn evaluate_guardrails<'e>(
+    db: impl sqlx::PgExecutor<'e>,
+    test: &AbTest,
+) -> Result<Vec<GuardrailOutcome>, sqlx::Error> {
+    if test.guardrail_metrics.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // (metric, variant, samples, mean, standard deviation)
+    type StatsRow = (String, VariantType, i64, Option<f64>, Option<f64>);
+    let rows: Vec<StatsRow> = sqlx::query_as(
+        r#"
+        SELECT metric_name, variant_type, COUNT(*),
+               AVG(metric_value)::float8, STDDEV_SAMP(metric_value)::float8
+        FROM ab_test_metrics
+        WHERE test_id = $1 AND metric_name = ANY($2)
+        GROUP BY metric_name, variant_type
+        "#,
+    )
+    .bind(test.id)
+    .bind(&test.guardrail_metrics)
+    .fetch_all(db)
+    .await?;
+
+    let stats_for = |metric: &str, variant: VariantType| {
+        rows.iter()
+            .find(|(name, v, ..)| name == metric && *v == variant)
+            .map(|&(_, _, samples, mean, std_dev)| MetricStats {
+                samples,
+                mean: mean.unwrap_or(0.0),
+                std_dev: std_dev.unwrap_or(0.0),
+            })
+            .unwrap_or_default()
+    };
+    let significance = test.significance_threshold.to_f64().unwrap_or(95.0);
+
+    Ok(test
+        .guardrail_metrics
+        .iter()
+        .map(|metric| {
+            guardrail_outcome(
+                metric,
+                stats_for(metric, VariantType::Control),
+                stats_for(metric, VariantType::Treatment),
+                significance,
+            )
+        })
+        .collect())
+}
+
+/// Per-segment stats of the test's primary metric, as (segment, variant, stats)
+async fn segment_stats<'e>(
+    db: impl sqlx::PgExecutor<'e>,
+    test: &AbTest,
+    segment_by: SegmentBy,
+) -> Result<Vec<(String, VariantType, MetricStats)>, sqlx::Error> {
+    // (segment, variant, samples, mean, standard deviation)
+    type StatsRow = (String, VariantType, i64, Option<f64>, Option<f64>);
+    let rows: Vec<StatsRow> = sqlx::query_as(&format!(
+        r#"
+        SELECT segment, variant_type, COUNT(*),
+               AVG(metric_value)::float8, STDDEV_SAMP(metric_value)::float8
+        FROM (
+            SELECT {key} AS segment, variant_type, metric_value
+            FROM ab_test_metrics
+            WHERE test_id = $1 AND metric_name = $2
+        ) m
+        WHERE segment IS NOT NULL
+        GROUP BY segment, variant_type
+        "#,
+        key = segment_by.key_sql()
+    ))
+    .bind(test.id)
+    .bind(&test.primary_metric)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(segment, variant, samples, mean, std_dev)| {
+            let stats = MetricStats {
+                samples,
+                mean: mean.unwrap_or(0.0),
+                std_dev: std_dev.unwrap_or(0.0),
+            };
+            (segment, variant, stats)
+        })
+        .collect())
+}
+
+/// Lift and significance of the treatment in each segment where both variants
+/// reach `min_sample_size`, sorted by segment, plus how many segments were
+/// left out for being too small.
+pub fn segment_outcomes(
+    rows: &[(String, VariantType, MetricStats)],
+    min_sample_size: i64,
+    significance: f64,
+) -> (Vec<SegmentOutcome>, usize) {
+    let mut segments: Vec<&str> = rows.iter().map(|(s, ..)| s.as_str()).collect();
+    segments.sort_unstable();
+    segments.dedup();
+
+    let stats_for = |segment: &str, variant: VariantType| {
+        rows.iter()
+            .find(|(s, v, _)| s == segment && *v == variant)
+            .map(|&(_, _, stats)| stats)
+            .unwrap_or_default()
+    };
+    let alpha = 1.0 - significance / 100.0;
+
+    let mut omitted = 0;
+    let mut outcomes = Vec::new();
+    for segment in segments {
+        let control = stats_for(segment, VariantType::Control);
+        let treatment = stats_for(segment, VariantType::Treatment);
+        let min_samples = min_sample_size.max(1);
+        if control.samples < min_samples || treatment.samples < min_samples {
+            omitted += 1;
+            continue;
+        }
+        let p_value = two_sample_p_value(control, treatment);
+        outcomes.push(SegmentOutcome {
+            segment: segment.to_string(),
+            control_samples: control.samples,
+            treatment_samples: treatment.samples,
+            control_mean: control.mean,
+            treatment_mean: treatment.mean,
+            lift_percent: (control.mean != 0.0)
+                .then(|| (treatment.mean - control.mean) / control.mean.abs() * 100.0),
+            p_value,
+            significant: p_value < alpha,
+        });
+    }
+    (outcomes, omitted)
+}
+
+/// Two-sided p-value of a two-sample z-test on the variants' means
+fn two_sample_p_value(control: MetricStats, treatment: MetricStats) -> f64 {
+    let variance = control.std_dev.powi(2) / control.samples as f64
+        + treatment.std_dev.powi(2) / treatment.samples as f64;
+    let diff = treatment.mean - control.mean;
+    if variance > 0.0 {
+        2.0 * (1.0 - normal_cdf((diff / variance.sqrt()).abs()))
+    } else if diff == 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// A guardrail regresses when the treatment's mean is higher than control's
+/// and a two-sample z-test puts the difference at `significance` percent or more.
+pub fn guardrail_outcome(
+    metric: &str,
+    control: MetricStats,
+    treatment: MetricStats,
+    significance: f64,
+) -> GuardrailOutcome {
+    let sampled = control.samples > 0 && treatment.samples > 0;
+    let p_value = sampled.then(|| two_sample_p_value(control, treatment));
+    let alpha = 1.0 - significance / 100.0;
+    let regressed = treatment.mean > control.mean && p_value.is_some_and(|p| p < alpha);
+
+    GuardrailOutcome {
+        metric: metric.to_string(),
+        control_samples: control.samples,
+        treatment_samples: treatment.samples,
+        control_mean: (control.samples > 0).then_some(control.mean),
+        treatment_mean: (treatment.samples > 0).then_some(treatment.mean),
+        p_value,
+        regressed,
+    }
+}
+
+/// Explanation of the first regressed guardrail, if any
+pub fn guardrail_violation(outcomes: &[GuardrailOutcome]) -> Option<String> {
+    outcomes.iter().find(|o| o.regressed).map(|o| {
+        format!(
+            "Treatment significantly increased guardrail metric '{}' ({:.4} vs {:.4} in control, p = {:.4})",
+            o.metric,
+            o.treatment_mean.unwrap_or_default(),
+            o.control_mean.unwrap_or_default(),
+            o.p_value.unwrap_or_default(),
+        )
+    })
+}
+
+/// Standard normal CDF, via the Abramowitz–Stegun erf approximation that
+/// `calculate_statistical_significance` also uses
+fn normal_cdf(x: f64) -> f64 {
+    let z = x / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * z.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    0.5 * (1.0 + erf.copysign(z))
+}
+
+/// Lowercase variant name as stored in the `variant_type` enum
+fn parse_variant(variant: &str) -> Result<&'static str, ApiError> {
+    match variant.trim().to_ascii_lowercase().as_str() {
+        "control" => Ok("control"),
+        "treatment" => Ok("treatment"),
+        _ => Err(ApiError::bad_request(
+            "InvalidVariant",
+            format!(
+                "variant must be `control` or `treatment`, got '{}'",
+                variant
+            ),
+        )),
+    }
+}
+
+fn push_metric_csv_row(out: &mut String, row: &MetricExportRow) {
+    out.push_str(&row.variant_type);
+    out.push(',');
+    push_csv_field(out, &row.metric_name);
+    out.push(',');
+    out.push_str(&row.metric_value.to_string());
+    out.push(',');
+    push_csv_field(out, row.user_address.as_deref().unwrap_or_default());
+    out.push(',');
+    out.push_str(&row.timestamp.to_rfc3339());
+    out.push('\n');
+}
+
+/// Quotes a field that contains a delimiter, quote or line break (RFC 4180)
+fn push_csv_field(out: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+fn require_assignment(
+    assignment: Option<AbTestAssignment>,
+    user_address: &str,
+) -> Result<AbTestAssignment, ApiError> {
+    assignment.ok_or_else(|| {
+        ApiError::not_found(
+            "AbTestAssignmentNotFound",
+            format!("User {} has not been assigned a variant in this test", user_address),
+        )
+    })
+}
+
+fn to_decimal(value: f64, field: &str) -> Result<Decimal, ApiError> {
+    Decimal::try_from(value).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidNumber",
+            format!("{} must be a finite number in range, got {}", field, value),
+        )
+    })
+}
+
 fn db_err(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = operation, error = ?err, "database operation failed");
-    ApiError::internal("An unexpected database error occurred")
+    ApiError::database(operation, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+    use chrono::Utc;
+    use shared::models::AbTestStatus;
+
+    fn assignment_for(user_address: &str) -> AbTestAssignment {
+        AbTestAssignment {
+            id: Uuid::new_v4(),
+            test_id: Uuid::new_v4(),
+            user_address: user_address.to_string(),
+            variant_type: VariantType::Treatment,
+            assigned_at: Utc::now(),
+        }
+    }
+
+    fn test_with(min_sample_size: i32, significance_threshold: i64) -> AbTest {
+        AbTest {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            name: "checkout".to_string(),
+            description: None,
+            status: AbTestStatus::Running,
+            traffic_split: Decimal::from(50),
+            variant_a_deployment_id: Uuid::new_v4(),
+            variant_b_deployment_id: Uuid::new_v4(),
+            primary_metric: "conversion".to_string(),
+            hypothesis: None,
+            significance_threshold: Decimal::from(significance_threshold),
+            min_sample_size,
+            started_at: None,
+            ended_at: None,
+            created_by: None,
+            created_at: Utc::now(),
+            auto_stop: false,
+            winner: None,
+            auto_stop_candidate: None,
+            auto_stop_streak: 0,
+            guardrail_metrics: Vec::new(),
+        }
+    }
+
+    fn result(
+        variant_type: VariantType,
+        sample_size: i32,
+        significance: i64,
+        is_winner: bool,
+    ) -> AbTestResult {
+        AbTestResult {
+            id: Uuid::new_v4(),
+            test_id: Uuid::nil(),
+            variant_type,
+            sample_size,
+            mean_value: None,
+            std_deviation: None,
+            confidence_interval_lower: None,
+            confidence_interval_upper: None,
+            p_value: Some(Decimal::new(1, 3)),
+            statistical_significance: Some(Decimal::from(significance)),
+            is_winner,
+            calculated_at: Utc::now(),
+            guardrail_outcomes: None,
+        }
+    }
+
+    #[test]
+    fn under_sampled_significant_result_declares_no_winner() {
+        let test = test_with(1000, 95);
+        let mut results = vec![
+            result(VariantType::Control, 12, 99, false),
+            result(VariantType::Treatment, 15, 99, true),
+        ];
+
+        let (outcome, winner) = decide_outcome(&test, &mut results);
+
+        assert_eq!(outcome, AbTestOutcome::InsufficientData);
+        assert!(winner.is_none());
+        assert!(results.iter().all(|r| !r.is_winner));
+    }
+
+    #[test]
+    fn sampled_but_insignificant_result_is_inconclusive() {
+        let test = test_with(100, 95);
+        let mut results = vec![
+            result(VariantType::Control, 500, 90, false),
+            result(VariantType::Treatment, 500, 90, true),
+        ];
+
+        let (outcome, winner) = decide_outcome(&test, &mut results);
+
+        assert_eq!(outcome, AbTestOutcome::Inconclusive);
+        assert!(winner.is_none());
+        assert!(!results[1].is_winner);
+    }
+
+    #[test]
+    fn sampled_significant_result_keeps_its_winner() {
+        let test = test_with(100, 95);
+        let mut results = vec![
+            result(VariantType::Control, 500, 99, false),
+            result(VariantType::Treatment, 500, 99, true),
+        ];
+
+        let (outcome, winner) = decide_outcome(&test, &mut results);
+
+        assert_eq!(outcome, AbTestOutcome::Winner);
+        assert!(matches!(winner, Some(VariantType::Treatment)));
+        assert!(results[1].is_winner);
+    }
+
+    fn stats(samples: i64, mean: f64, std_dev: f64) -> MetricStats {
+        MetricStats {
+            samples,
+            mean,
+            std_dev,
+        }
+    }
+
+    #[test]
+    fn significantly_higher_guardrail_is_a_violation() {
+        // errors per request: 5% in control, 8% in treatment, over 2000 samples each
+        let outcome = guardrail_outcome(
+            "error_rate",
+            stats(2000, 0.05, 0.218),
+            stats(2000, 0.08, 0.271),
+            95.0,
+        );
+
+        assert!(outcome.regressed);
+        assert!(outcome.p_value.unwrap() < 0.05);
+        let violation = guardrail_violation(&[outcome]).unwrap();
+        assert!(violation.contains("'error_rate'"), "{}", violation);
+    }
+
+    #[test]
+    fn guardrail_improvements_and_noise_do_not_veto() {
+        // Treatment lowered the guardrail: an improvement, never a violation
+        let improved = guardrail_outcome(
+            "latency_ms",
+            stats(2000, 120.0, 30.0),
+            stats(2000, 90.0, 30.0),
+            95.0,
+        );
+        assert!(!improved.regressed);
+
+        // Slightly higher, but well within noise
+        let noisy = guardrail_outcome(
+            "latency_ms",
+            stats(50, 120.0, 40.0),
+            stats(50, 122.0, 40.0),
+            95.0,
+        );
+        assert!(!noisy.regressed);
+        assert!(noisy.p_value.unwrap() > 0.05);
+
+        // No treatment samples yet
+        let unsampled = guardrail_outcome(
+            "latency_ms",
+            stats(50, 120.0, 40.0),
+            stats(0, 0.0, 0.0),
+            95.0,
+        );
+        assert_eq!(unsampled.p_value, None);
+        assert_eq!(guardrail_violation(&[improved, noisy, unsampled]), None);
+    }
+
+    #[test]
+    fn segments_report_lift_and_skip_small_ones() {
+        let row = |segment: &str, variant, stats| (segment.to_string(), variant, stats);
+        let rows = vec![
+            row("B", VariantType::Control, stats(200, 100.0, 10.0)),
+            row("B", VariantType::Treatment, stats(200, 110.0, 10.0)),
+            row("A", VariantType::Control, stats(150, 100.0, 40.0)),
+            row("A", VariantType::Treatment, stats(150, 101.0, 40.0)),
+            // Too few treatment samples to say anything
+            row("C", VariantType::Control, stats(300, 100.0, 10.0)),
+            row("C", VariantType::Treatment, stats(20, 150.0, 10.0)),
+        ];
+
+        let (segments, omitted) = segment_outcomes(&rows, 100, 95.0);
+
+        assert_eq!(omitted, 1);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].segment, "A");
+        assert!(!segments[0].significant);
+        assert_eq!(segments[1].segment, "B");
+        assert!(segments[1].significant);
+        assert!((segments[1].lift_percent.unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn metric_rows_are_written_as_csv() {
+        let row = MetricExportRow {
+            variant_type: "treatment".to_string(),
+            metric_name: "latency, p95".to_string(),
+            metric_value: Decimal::new(12345, 2),
+            user_address: None,
+            timestamp: "2026-01-02T03:04:05Z".parse().unwrap(),
+        };
+        let mut csv = String::from(METRICS_CSV_HEADER);
+        push_metric_csv_row(&mut csv, &row);
+
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("treatment,\"latency, p95\",123.45,,2026-01-02T03:04:05+00:00")
+        );
+        assert_eq!(parse_variant("Control").unwrap(), "control");
+        assert!(parse_variant("both").is_err());
+    }
+
+    #[test]
+    fn normal_cdf_matches_known_quantiles() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+        assert!((normal_cdf(-1.96) - 0.025).abs() < 1e-3);
+    }
+
+    #[test]
+    fn non_finite_values_are_rejected_not_zeroed() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, f64::MAX] {
+            let err = to_decimal(value, "metric_value").unwrap_err();
+            let response = err.into_response();
+            assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(to_decimal(12.5, "metric_value").unwrap(), Decimal::new(125, 1));
+    }
+
+    #[test]
+    fn recorded_user_assignment_is_returned() {
+        let recorded = assignment_for("GUSER");
+        let assignment = require_assignment(Some(recorded.clone()), "GUSER").unwrap();
+        assert_eq!(assignment.id, recorded.id);
+        assert_eq!(assignment.user_address, "GUSER");
+        assert!(matches!(assignment.variant_type, VariantType::Treatment));
+
+        let body = serde_json::to_value(UserAssignmentResponse {
+            assignment,
+            metric_count: 3,
+        })
+        .unwrap();
+        assert_eq!(body["user_address"], "GUSER");
+        assert_eq!(body["metric_count"], 3);
+    }
+
+    #[test]
+    fn unknown_user_yields_not_found() {
+        let err = require_assignment(None, "GUNKNOWN").unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -0,0 +1,138 @@
+// api/src/activity_events.rs
+// In-process fan-out of notable registry actions (publishes, verifications,
+// deprecations, canary transitions) to live activity feed sockets.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events a slow subscriber may fall behind by before it skips to the latest
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Publish,
+    Verify,
+    Deprecate,
+    CanaryTransition,
+}
+
+impl FromStr for ActivityKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "publish" => Ok(Self::Publish),
+            "verify" => Ok(Self::Verify),
+            "deprecate" => Ok(Self::Deprecate),
+            "canary_transition" => Ok(Self::CanaryTransition),
+            other => Err(format!(
+                "Unknown activity type '{}'; expected publish, verify, deprecate or canary_transition",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityEvent {
+    #[serde(rename = "type")]
+    pub kind: ActivityKind,
+    pub contract_id: Option<Uuid>,
+    pub occurred_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+impl ActivityEvent {
+    pub fn new(kind: ActivityKind, contract_id: Option<Uuid>, data: serde_json::Value) -> Self {
+        Self {
+            kind,
+            contract_id,
+            occurred_at: Utc::now(),
+            data,
+        }
+    }
+}
+
+/// A single broadcast channel shared by every activity feed subscriber
+pub struct ActivityEventHub {
+    sender: broadcast::Sender<ActivityEvent>,
+}
+
+impl Default for ActivityEventHub {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+}
+
+impl ActivityEventHub {
+    pub fn subscribe(&self) -> broadcast::Receiver<ActivityEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Send `event` to every connected subscriber. With nobody listening the
+    /// event is simply dropped.
+    pub fn publish(&self, event: ActivityEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+/// Parse a comma-separated `types` filter. An empty filter accepts every type.
+pub fn parse_activity_types(types: &str) -> Result<Vec<ActivityKind>, String> {
+    types
+        .split(',')
+        .filter(|t| !t.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+pub fn accepts(filter: &[ActivityKind], kind: ActivityKind) -> bool {
+    filter.is_empty() || filter.contains(&kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn types_filter_parses_and_rejects_unknown_names() {
+        assert_eq!(
+            parse_activity_types("publish, verify").unwrap(),
+            vec![ActivityKind::Publish, ActivityKind::Verify]
+        );
+        assert_eq!(parse_activity_types("").unwrap(), vec![]);
+        assert!(parse_activity_types("publish,launch").is_err());
+
+        let filter = parse_activity_types("canary_transition").unwrap();
+        assert!(accepts(&filter, ActivityKind::CanaryTransition));
+        assert!(!accepts(&filter, ActivityKind::Publish));
+        assert!(accepts(&[], ActivityKind::Deprecate));
+    }
+
+    #[tokio::test]
+    async fn slow_subscribers_skip_to_the_latest_events() {
+        let hub = ActivityEventHub::default();
+        let mut rx = hub.subscribe();
+
+        for n in 0..CHANNEL_CAPACITY + 10 {
+            hub.publish(ActivityEvent::new(
+                ActivityKind::Publish,
+                None,
+                json!({ "n": n }),
+            ));
+        }
+
+        assert!(matches!(
+            rx.recv().await,
+            Err(broadcast::error::RecvError::Lagged(10))
+        ));
+        let next = rx.recv().await.unwrap();
+        assert_eq!(next.data["n"], 10);
+    }
+}
@@ -1,4 +1,4 @@
-//! Activity feed handler — GET /api/activity-feed
+//! Activity feed handlers — GET /api/activity-feed and GET /api/activity-feed/ws
 //!
 //! Implements cursor-based pagination so clients can scroll through
 //! analytics events without the bugs described in issue #337:
@@ -6,15 +6,27 @@
 //!  • total      = real COUNT(*), not entries.len()
 //!  • page       = removed in favour of next_cursor
 //!  • next_cursor = created_at of the last returned entry
+//!
+//! The WebSocket endpoint pushes new activity as it happens instead.
 
 use axum::{
-    extract::{Query, State},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
     Json,
 };
 use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
 use shared::{ActivityFeedParams, AnalyticsEvent, CursorPaginatedResponse};
+use tokio::sync::broadcast;
 
-use crate::{error::AppError, state::AppState};
+use crate::{
+    activity_events::{accepts, parse_activity_types, ActivityEvent, ActivityKind},
+    error::{ApiError, ApiResult, AppError},
+    state::AppState,
+};
 
 /// GET /api/activity-feed
 ///
@@ -119,3 +131,57 @@ pub async fn get_activity_feed(
         next_cursor,
     )))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityFeedWsParams {
+    /// Comma-separated event types, e.g. `publish,verify`; omit for all
+    pub types: Option<String>,
+}
+
+/// GET /api/activity-feed/ws
+///
+/// Upgrades to a WebSocket that receives each new activity event as a JSON
+/// text message. A client that reads too slowly skips the events it missed
+/// and continues from the oldest one still buffered.
+pub async fn activity_feed_ws(
+    State(state): State<AppState>,
+    Query(params): Query<ActivityFeedWsParams>,
+    ws: WebSocketUpgrade,
+) -> ApiResult<Response> {
+    let types = parse_activity_types(params.types.as_deref().unwrap_or_default())
+        .map_err(|e| ApiError::bad_request("InvalidActivityType", e))?;
+
+    let events = state.activity_events.subscribe();
+    Ok(ws.on_upgrade(move |socket| stream_activity(socket, events, types)))
+}
+
+async fn stream_activity(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<ActivityEvent>,
+    types: Vec<ActivityKind>,
+) {
+    loop {
+        tokio::select! {
+            received = events.recv() => match received {
+                Ok(event) if accepts(&types, event.kind) => {
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "activity feed client lagged; skipping ahead");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            // Pings are answered by axum; anything else from the client is ignored
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
@@ -3,8 +3,13 @@ use crate::state::AppState;
 use axum::{routing::get, Router};
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route(
-        "/activity-feed",
-        get(activity_feed_handlers::get_activity_feed),
-    )
+    Router::new()
+        .route(
+            "/activity-feed",
+            get(activity_feed_handlers::get_activity_feed),
+        )
+        .route(
+            "/activity-feed/ws",
+            get(activity_feed_handlers::activity_feed_ws),
+        )
 }
@@ -2,39 +2,57 @@ use chrono::Timelike;
 use sqlx::PgPool;
 use std::time::Duration;
 
-/// Spawn the background aggregation task.
+use crate::background_jobs::JobScheduler;
+
+/// Register the aggregation job with the background scheduler.
 ///
 /// Runs every hour:
 ///   1. Aggregate raw events into daily summaries (yesterday + today).
 ///   2. Delete raw events older than 90 days.
-pub fn spawn_aggregation_task(pool: PgPool) {
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+pub fn spawn_aggregation_task(scheduler: &JobScheduler, pool: PgPool) {
+    scheduler.spawn_with_jitter(
+        "aggregation",
+        Duration::from_secs(3600),
+        Duration::from_secs(300),
+        move || {
+            let pool = pool.clone();
+            async move { run_hourly(&pool).await }
+        },
+    );
+}
 
-        loop {
-            interval.tick().await;
-            tracing::info!("aggregation: starting hourly run");
+/// One hourly run; each step is attempted even if an earlier one fails.
+async fn run_hourly(pool: &PgPool) -> anyhow::Result<()> {
+    tracing::info!("aggregation: starting hourly run");
+    let mut failed = Vec::new();
 
-            if let Err(err) = run_aggregation(&pool).await {
-                tracing::error!(error = ?err, "aggregation: run failed");
-            }
+    if let Err(err) = run_aggregation(pool).await {
+        tracing::error!(error = ?err, "aggregation: run failed");
+        failed.push("daily aggregation");
+    }
 
-            if let Err(err) = cleanup_old_events(&pool).await {
-                tracing::error!(error = ?err, "aggregation: retention cleanup failed");
-            }
+    if let Err(err) = cleanup_old_events(pool).await {
+        tracing::error!(error = ?err, "aggregation: retention cleanup failed");
+        failed.push("retention cleanup");
+    }
 
-            if let Err(err) = run_custom_metrics_aggregation(&pool).await {
-                tracing::error!(error = ?err, "aggregation: custom metrics aggregation failed");
-            }
+    if let Err(err) = run_custom_metrics_aggregation(pool).await {
+        tracing::error!(error = ?err, "aggregation: custom metrics aggregation failed");
+        failed.push("custom metrics aggregation");
+    }
 
-            // Daily contract health score update (runs at 2 AM UTC)
-            if chrono::Utc::now().hour() == 2 {
-                if let Err(err) = crate::health::update_all_health_scores(&pool).await {
-                    tracing::error!(error = ?err, "aggregation: health score update failed");
-                }
-            }
+    // Daily contract health score update (runs at 2 AM UTC)
+    if chrono::Utc::now().hour() == 2 {
+        if let Err(err) = crate::health::update_all_health_scores(pool).await {
+            tracing::error!(error = ?err, "aggregation: health score update failed");
+            failed.push("health score update");
         }
-    });
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!("{} failed", failed.join(", "));
+    }
+    Ok(())
 }
 
 /// Build daily aggregates from raw `analytics_events`.
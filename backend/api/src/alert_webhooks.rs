@@ -0,0 +1,258 @@
+// api/src/alert_webhooks.rs
+// Delivery of performance alert webhooks. The `check_performance_thresholds`
+// trigger queues an alert as `pending` when its config has a
+// `notify_webhook_url`; this job POSTs the alert details outside the insert,
+// retrying failures with exponential backoff until MAX_DELIVERY_ATTEMPTS, and
+// records the outcome on the alert so `list_alerts` can show it.
+//
+// Due alerts are claimed with `FOR UPDATE SKIP LOCKED` and leased for
+// DELIVERY_LEASE_SECS, so replicas running the job never deliver the same attempt
+// twice.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use shared::models::{AlertSeverity, MetricType};
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::background_jobs::JobScheduler;
+
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(15);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Alerts attempted per run
+const DISPATCH_BATCH_SIZE: i64 = 20;
+
+/// Attempts after which an alert's notification is marked `failed`
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+
+/// Delay before the first retry; doubled for every further attempt
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 60 * 60;
+
+/// How long a claimed alert is hidden from other replicas while it is sent;
+/// longer than a whole batch of timed-out deliveries
+const DELIVERY_LEASE_SECS: f64 = 300.0;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct PendingAlert {
+    id: Uuid,
+    contract_id: Uuid,
+    metric_type: MetricType,
+    threshold_type: String,
+    threshold_value: Decimal,
+    current_value: Decimal,
+    severity: AlertSeverity,
+    triggered_at: DateTime<Utc>,
+    message: Option<String>,
+    notify_webhook_url: String,
+    notification_attempts: i32,
+}
+
+/// Where an alert's notification stands after a delivery attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeliveryOutcome {
+    Delivered,
+    Retry { at: DateTime<Utc> },
+    Failed,
+}
+
+/// Register the webhook dispatcher with the background scheduler.
+pub fn spawn_alert_webhook_task(scheduler: &JobScheduler, pool: PgPool) {
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(error = %err, "alert_webhooks: could not build webhook client");
+            return;
+        }
+    };
+    scheduler.spawn("alert_webhooks", DISPATCH_INTERVAL, move || {
+        let pool = pool.clone();
+        let client = client.clone();
+        async move {
+            let delivered = dispatch_pending(&pool, &client).await?;
+            if delivered > 0 {
+                tracing::info!(delivered, "alert_webhooks: delivered alert notifications");
+            }
+            Ok(())
+        }
+    });
+}
+
+/// Attempt every due notification once, returning how many were delivered.
+pub async fn dispatch_pending(
+    pool: &PgPool,
+    client: &reqwest::Client,
+) -> Result<usize, sqlx::Error> {
+    let due: Vec<PendingAlert> = sqlx::query_as(
+        r#"
+        UPDATE performance_alerts
+        SET next_notification_at = NOW() + make_interval(secs => $2)
+        WHERE id IN (
+            SELECT id FROM performance_alerts
+            WHERE notification_status = 'pending'
+              AND next_notification_at <= NOW()
+            ORDER BY next_notification_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, contract_id, metric_type, threshold_type, threshold_value,
+                  current_value, severity, triggered_at, message, notify_webhook_url,
+                  notification_attempts
+        "#,
+    )
+    .bind(DISPATCH_BATCH_SIZE)
+    .bind(DELIVERY_LEASE_SECS)
+    .fetch_all(pool)
+    .await?;
+
+    let mut delivered = 0;
+    for alert in due {
+        let result = client
+            .post(&alert.notify_webhook_url)
+            .json(&alert_payload(&alert))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        let attempts = alert.notification_attempts + 1;
+        let error = result.as_ref().err().map(|err| err.to_string());
+        if let Some(ref error) = error {
+            tracing::warn!(alert_id = %alert.id, attempts, error, "alert_webhooks: delivery failed");
+        }
+
+        let outcome = delivery_outcome(result.is_ok(), attempts, Utc::now());
+        if outcome == DeliveryOutcome::Delivered {
+            delivered += 1;
+        }
+        record_outcome(pool, alert.id, attempts, &outcome, error.as_deref()).await?;
+    }
+
+    Ok(delivered)
+}
+
+async fn record_outcome(
+    pool: &PgPool,
+    alert_id: Uuid,
+    attempts: i32,
+    outcome: &DeliveryOutcome,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let (status, next_at) = match outcome {
+        DeliveryOutcome::Delivered => ("delivered", None),
+        DeliveryOutcome::Retry { at } => ("pending", Some(*at)),
+        DeliveryOutcome::Failed => ("failed", None),
+    };
+    sqlx::query(
+        r#"
+        UPDATE performance_alerts
+        SET notification_status = $2,
+            notification_attempts = $3,
+            next_notification_at = $4,
+            notification_error = $5,
+            notified_at = CASE WHEN $2 = 'delivered' THEN NOW() ELSE notified_at END
+        WHERE id = $1
+        "#,
+    )
+    .bind(alert_id)
+    .bind(status)
+    .bind(attempts)
+    .bind(next_at)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+fn alert_payload(alert: &PendingAlert) -> Value {
+    json!({
+        "event": "performance_alert_triggered",
+        "alert_id": alert.id,
+        "contract_id": alert.contract_id,
+        "metric_type": alert.metric_type,
+        "threshold_type": alert.threshold_type,
+        "threshold_value": alert.threshold_value,
+        "current_value": alert.current_value,
+        "severity": alert.severity,
+        "triggered_at": alert.triggered_at,
+        "message": alert.message,
+    })
+}
+
+/// Delay before retrying after `attempts` failed deliveries
+pub fn retry_backoff(attempts: i32) -> ChronoDuration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    ChronoDuration::seconds(
+        RETRY_BASE_SECS
+            .saturating_mul(1 << exponent)
+            .min(RETRY_MAX_SECS),
+    )
+}
+
+pub fn delivery_outcome(succeeded: bool, attempts: i32, now: DateTime<Utc>) -> DeliveryOutcome {
+    if succeeded {
+        DeliveryOutcome::Delivered
+    } else if attempts >= MAX_DELIVERY_ATTEMPTS {
+        DeliveryOutcome::Failed
+    } else {
+        DeliveryOutcome::Retry {
+            at: now + retry_backoff(attempts),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_back_off_exponentially_up_to_a_cap() {
+        assert_eq!(retry_backoff(1), ChronoDuration::seconds(30));
+        assert_eq!(retry_backoff(2), ChronoDuration::seconds(60));
+        assert_eq!(retry_backoff(4), ChronoDuration::seconds(240));
+        assert_eq!(retry_backoff(20), ChronoDuration::seconds(RETRY_MAX_SECS));
+    }
+
+    #[test]
+    fn notifications_fail_after_the_last_attempt() {
+        let now = Utc::now();
+
+        assert_eq!(delivery_outcome(true, 3, now), DeliveryOutcome::Delivered);
+        assert_eq!(
+            delivery_outcome(false, 1, now),
+            DeliveryOutcome::Retry {
+                at: now + ChronoDuration::seconds(30)
+            }
+        );
+        assert_eq!(
+            delivery_outcome(false, MAX_DELIVERY_ATTEMPTS, now),
+            DeliveryOutcome::Failed
+        );
+    }
+
+    #[test]
+    fn payload_carries_the_breached_threshold() {
+        let alert = PendingAlert {
+            id: Uuid::nil(),
+            contract_id: Uuid::nil(),
+            metric_type: MetricType::ExecutionTime,
+            threshold_type: "p95_exceeds".to_string(),
+            threshold_value: Decimal::from(250),
+            current_value: Decimal::from(410),
+            severity: AlertSeverity::Critical,
+            triggered_at: Utc::now(),
+            message: None,
+            notify_webhook_url: "https://hooks.example.com/alerts".to_string(),
+            notification_attempts: 0,
+        };
+
+        let payload = alert_payload(&alert);
+
+        assert_eq!(payload["event"], "performance_alert_triggered");
+        assert_eq!(payload["threshold_type"], "p95_exceeds");
+        assert_eq!(payload["threshold_value"], json!(Decimal::from(250)));
+        assert_eq!(payload["contract_id"], json!(Uuid::nil()));
+        assert!(payload.get("notify_webhook_url").is_none());
+    }
+}
@@ -1,7 +1,24 @@
-use shared::{AnalyticsEventType, Network};
+use chrono::{NaiveDate, Utc};
+use shared::{
+    AnalyticsEventType, ContractAnalyticsResponse, DeploymentStats, InteractorStats, Network,
+    TimelineEntry, TopUser,
+};
 use sqlx::PgPool;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+/// Days before today covered by the contract analytics timeline
+const TIMELINE_DAYS: i64 = 30;
+/// Callers listed in the contract analytics top users
+const TOP_USER_LIMIT: i64 = 10;
+
+/// Interactions with a contract on one day
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DailyInteractions {
+    pub date: NaiveDate,
+    pub count: i64,
+}
+
 /// Record an analytics event.
 ///
 /// This is intentionally fire-and-forget: callers should log errors but
@@ -39,3 +56,214 @@ pub async fn record_event(
 
     Ok(())
 }
+
+/// Recalculate a contract's interaction analytics and store them as its
+/// current snapshot.
+pub async fn recompute_contract_analytics(
+    pool: &PgPool,
+    contract_id: Uuid,
+) -> Result<ContractAnalyticsResponse, sqlx::Error> {
+    let computed_at = Utc::now();
+    let today = computed_at.date_naive();
+    let first_day = today - chrono::Duration::days(TIMELINE_DAYS);
+
+    let unique_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT user_address) FROM contract_interactions WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_one(pool)
+    .await?;
+
+    let top_users: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT user_address, COUNT(*) AS count
+        FROM contract_interactions
+        WHERE contract_id = $1 AND user_address IS NOT NULL
+        GROUP BY user_address
+        ORDER BY count DESC, user_address
+        LIMIT $2
+        "#,
+    )
+    .bind(contract_id)
+    .bind(TOP_USER_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let daily: Vec<DailyInteractions> = sqlx::query_as(
+        r#"
+        SELECT created_at::date AS date, COUNT(*) AS count
+        FROM contract_interactions
+        WHERE contract_id = $1 AND created_at::date >= $2
+        GROUP BY created_at::date
+        "#,
+    )
+    .bind(contract_id)
+    .bind(first_day)
+    .fetch_all(pool)
+    .await?;
+
+    let analytics = ContractAnalyticsResponse {
+        contract_id,
+        deployments: DeploymentStats {
+            count: 0,
+            unique_users: 0,
+            by_network: serde_json::json!({}),
+        },
+        interactors: InteractorStats {
+            unique_count,
+            top_users: top_users
+                .into_iter()
+                .map(|(address, count)| TopUser { address, count })
+                .collect(),
+        },
+        timeline: timeline(&daily, today),
+        computed_at,
+    };
+    let stored = serde_json::to_value(&analytics).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO contract_analytics_snapshots (contract_id, analytics, computed_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (contract_id)
+        DO UPDATE SET analytics = EXCLUDED.analytics, computed_at = EXCLUDED.computed_at
+        "#,
+    )
+    .bind(contract_id)
+    .bind(stored)
+    .bind(analytics.computed_at)
+    .execute(pool)
+    .await?;
+
+    Ok(analytics)
+}
+
+/// The last snapshot stored by `recompute_contract_analytics`, if any.
+pub async fn stored_contract_analytics(
+    pool: &PgPool,
+    contract_id: Uuid,
+) -> Result<Option<ContractAnalyticsResponse>, sqlx::Error> {
+    let stored: Option<serde_json::Value> = sqlx::query_scalar(
+        "SELECT analytics FROM contract_analytics_snapshots WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_optional(pool)
+    .await?;
+
+    stored
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+/// Daily totals for the `TIMELINE_DAYS` days up to and including `today`,
+/// with days that saw no interactions reported as zero.
+pub fn timeline(daily: &[DailyInteractions], today: NaiveDate) -> Vec<TimelineEntry> {
+    let first_day = today - chrono::Duration::days(TIMELINE_DAYS);
+    let mut per_day: BTreeMap<NaiveDate, i64> = first_day
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|day| (day, 0))
+        .collect();
+
+    for row in daily {
+        if let Some(total) = per_day.get_mut(&row.date) {
+            *total += row.count;
+        }
+    }
+
+    per_day
+        .into_iter()
+        .map(|(date, count)| TimelineEntry { date, count })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_fills_quiet_days_and_drops_older_ones() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let daily = [
+            DailyInteractions {
+                date: today,
+                count: 3,
+            },
+            DailyInteractions {
+                date: today - chrono::Duration::days(2),
+                count: 1,
+            },
+            DailyInteractions {
+                date: today - chrono::Duration::days(90),
+                count: 4,
+            },
+        ];
+
+        let timeline = timeline(&daily, today);
+
+        assert_eq!(timeline.len(), TIMELINE_DAYS as usize + 1);
+        assert_eq!(
+            timeline.first().unwrap().date,
+            today - chrono::Duration::days(30)
+        );
+        assert_eq!(timeline.last().unwrap().count, 3);
+        assert_eq!(timeline.iter().map(|e| e.count).sum::<i64>(), 4);
+    }
+
+    async fn interact(db: &PgPool, contract_id: Uuid, user: Option<&str>, days_ago: i64) {
+        sqlx::query(
+            "INSERT INTO contract_interactions \
+             (contract_id, user_address, interaction_type, interaction_timestamp, network, created_at) \
+             VALUES ($1, $2, 'invoke', NOW(), 'testnet', NOW() - make_interval(days => $3))",
+        )
+        .bind(contract_id)
+        .bind(user)
+        .bind(days_ago as i32)
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn recompute_reflects_new_interactions_and_advances_computed_at() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let contract_id = crate::test_support::seed_contract(&db, "analytics").await;
+        for _ in 0..3 {
+            interact(&db, contract_id, Some("GA"), 0).await;
+        }
+        interact(&db, contract_id, Some("GB"), 2).await;
+        for _ in 0..4 {
+            interact(&db, contract_id, Some("GA"), 90).await;
+        }
+
+        let before = recompute_contract_analytics(&db, contract_id)
+            .await
+            .unwrap();
+        assert_eq!(before.interactors.unique_count, 2);
+        assert_eq!(before.interactors.top_users[0].address, "GA");
+        assert_eq!(before.interactors.top_users[0].count, 7);
+        assert_eq!(before.timeline.last().unwrap().count, 3);
+
+        for _ in 0..10 {
+            interact(&db, contract_id, Some("GC"), 0).await;
+        }
+        interact(&db, contract_id, None, 0).await;
+        let after = recompute_contract_analytics(&db, contract_id)
+            .await
+            .unwrap();
+
+        assert!(after.computed_at > before.computed_at);
+        assert_eq!(after.interactors.unique_count, 3);
+        assert_eq!(after.interactors.top_users[0].address, "GC");
+        assert_eq!(after.timeline.last().unwrap().count, 14);
+
+        let stored = stored_contract_analytics(&db, contract_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.computed_at, after.computed_at);
+    }
+}
@@ -0,0 +1,162 @@
+// api/src/api_key_handlers.rs
+// Admin provisioning of publisher API keys. Write endpoints require a key
+// (see `auth::require_api_key`), so this is how a publisher gets its first
+// one; the key is returned once and only its hash is stored.
+
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::{generate_api_key, hash_api_key, API_KEY_PREFIX_LEN},
+    error::{ApiError, ApiResult},
+    handlers::{db_internal_error, map_json_rejection},
+    state::AppState,
+    validation::validators::validate_stellar_address,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct IssueApiKeyRequest {
+    pub stellar_address: String,
+    /// Label to tell a publisher's keys apart
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IssuedApiKey {
+    pub id: Uuid,
+    pub publisher_id: Uuid,
+    /// The key itself; it can't be retrieved again
+    pub key: String,
+    pub key_prefix: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// POST /api/admin/api-keys — issue an API key for the publisher with
+/// `stellar_address`, registering the publisher first if it is new
+pub async fn issue_api_key(
+    State(state): State<AppState>,
+    payload: Result<Json<IssueApiKeyRequest>, JsonRejection>,
+) -> ApiResult<(StatusCode, Json<IssuedApiKey>)> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+    let stellar_address = req.stellar_address.trim();
+    validate_stellar_address(stellar_address)
+        .map_err(|message| ApiError::bad_request("InvalidStellarAddress", message))?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin api key transaction", err))?;
+
+    let publisher_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO publishers (stellar_address) VALUES ($1)
+         ON CONFLICT (stellar_address) DO UPDATE SET stellar_address = EXCLUDED.stellar_address
+         RETURNING id",
+    )
+    .bind(stellar_address)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("upsert publisher for api key", err))?;
+
+    let key = generate_api_key();
+    let key_prefix = key[..API_KEY_PREFIX_LEN].to_string();
+    let (id, created_at): (Uuid, DateTime<Utc>) = sqlx::query_as(
+        "INSERT INTO api_keys (publisher_id, name, key_prefix, key_hash)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id, created_at",
+    )
+    .bind(publisher_id)
+    .bind(req.name.as_deref().map(str::trim))
+    .bind(&key_prefix)
+    .bind(hash_api_key(&key))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("insert api key", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit api key", err))?;
+
+    tracing::info!(publisher_id = %publisher_id, key_id = %id, "api key issued");
+    Ok((
+        StatusCode::CREATED,
+        Json(IssuedApiKey {
+            id,
+            publisher_id,
+            key,
+            key_prefix,
+            created_at,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{require_api_key, ApiKeyIdentity, API_KEY_HEADER};
+    use axum::{body::Body, extract::Request, middleware, routing::post, Extension, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn issued_keys_authenticate_as_their_publisher() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db);
+        let address =
+            format!("G{:A>55}", &Uuid::new_v4().simple().to_string()[..20]).to_ascii_uppercase();
+
+        let issue = |state: AppState| {
+            let address = address.clone();
+            async move {
+                let (status, Json(issued)) = issue_api_key(
+                    State(state),
+                    Ok(Json(IssueApiKeyRequest {
+                        stellar_address: address,
+                        name: Some("ci".to_string()),
+                    })),
+                )
+                .await
+                .unwrap();
+                assert_eq!(status, StatusCode::CREATED);
+                issued
+            }
+        };
+        let first = issue(state.clone()).await;
+        let second = issue(state.clone()).await;
+        assert_eq!(first.publisher_id, second.publisher_id);
+        assert_ne!(first.key, second.key);
+
+        let app = Router::new()
+            .route(
+                "/",
+                post(
+                    |Extension(identity): Extension<ApiKeyIdentity>| async move {
+                        identity.stellar_address
+                    },
+                ),
+            )
+            .route_layer(middleware::from_fn_with_state(state, require_api_key));
+        let response = app
+            .oneshot(
+                Request::post("/")
+                    .header(API_KEY_HEADER, &first.key)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, address.as_bytes());
+    }
+}
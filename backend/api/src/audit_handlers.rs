@@ -127,11 +127,12 @@ pub async fn create_security_audit(
 
         sqlx::query(
             r#"INSERT INTO audit_checks
-                   (audit_id, check_id, status, auto_detected, evidence)
-               VALUES ($1, $2, $3, $4, $5)"#,
+                   (audit_id, check_id, severity, status, auto_detected, evidence)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
         )
         .bind(audit.id)
         .bind(&item.id)
+        .bind(item.severity.to_string())
         .bind(&status)
         .bind(auto_detected)
         .bind(&evidence)
@@ -1,6 +1,7 @@
 use axum::{
-    extract::Request,
-    http::{header, StatusCode},
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
     middleware::Next,
     response::Response,
 };
@@ -9,11 +10,25 @@ use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use rand::{distributions::Alphanumeric, Rng};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::fmt;
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    handlers::db_internal_error,
+    state::AppState,
+};
 
 pub const MIN_JWT_SECRET_LEN: usize = 32;
 
+/// Header carrying a publisher API key on write requests
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// Leading characters of a key stored in clear to narrow the hash lookup
+pub const API_KEY_PREFIX_LEN: usize = 8;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthClaims {
     pub sub: String,
@@ -147,8 +162,8 @@ impl AuthManager {
     }
 }
 
-fn extract_bearer_token(req: &Request) -> Option<&str> {
-    req.headers()
+fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
         .get(header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
         .and_then(|v| v.strip_prefix("Bearer "))
@@ -160,21 +175,183 @@ fn is_admin(claims: &AuthClaims) -> bool {
     claims.admin || matches!(claims.role.as_deref(), Some("admin" | "ADMIN" | "Admin"))
 }
 
-pub async fn require_admin(req: Request, next: Next) -> Result<Response, StatusCode> {
-    let Some(token) = extract_bearer_token(&req) else {
-        return Err(StatusCode::UNAUTHORIZED);
+pub async fn require_admin(mut req: Request, next: Next) -> ApiResult<Response> {
+    let Some(token) = extract_bearer_token(req.headers()) else {
+        return Err(ApiError::unauthorized(
+            "MissingToken",
+            "An admin bearer token is required",
+        ));
     };
 
-    let auth = AuthManager::from_env().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let claims = auth.validate_jwt(token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let auth = AuthManager::from_env().map_err(|err| {
+        tracing::error!(error = %err, "admin authentication is not configured");
+        ApiError::internal("Authentication is not configured")
+    })?;
+    let claims = auth
+        .validate_jwt(token)
+        .map_err(|_| ApiError::unauthorized("InvalidToken", "Invalid or expired bearer token"))?;
 
     if !is_admin(&claims) {
-        return Err(StatusCode::FORBIDDEN);
+        return Err(ApiError::forbidden(
+            "AdminRequired",
+            "This endpoint requires an admin token",
+        ));
     }
 
+    req.extensions_mut().insert(claims);
     Ok(next.run(req).await)
 }
 
+/// Publisher an API key belongs to, attached to authenticated write requests
+#[derive(Debug, Clone)]
+pub struct ApiKeyIdentity {
+    pub key_id: Uuid,
+    pub publisher_id: Uuid,
+    pub stellar_address: String,
+}
+
+/// Requires a valid `X-API-Key`. Layered onto the write handlers only, so
+/// reads stay public. The resolved [`ApiKeyIdentity`] is added to the request
+/// extensions.
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    let invalid_key = || ApiError::unauthorized("InvalidApiKey", "Invalid or revoked API key");
+    let key = extract_api_key(req.headers()).ok_or_else(|| {
+        ApiError::unauthorized(
+            "MissingApiKey",
+            format!("An API key is required in the {} header", API_KEY_HEADER),
+        )
+    })?;
+    let prefix = key.get(..API_KEY_PREFIX_LEN).ok_or_else(invalid_key)?;
+
+    let candidates: Vec<(Uuid, Uuid, String, String)> = sqlx::query_as(
+        "SELECT k.id, k.publisher_id, p.stellar_address, k.key_hash \
+         FROM api_keys k JOIN publishers p ON p.id = k.publisher_id \
+         WHERE k.key_prefix = $1 AND k.revoked_at IS NULL",
+    )
+    .bind(prefix)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("look up api key", err))?;
+
+    let presented = hash_api_key(key);
+    let (key_id, publisher_id, stellar_address, _) = candidates
+        .into_iter()
+        .find(|(_, _, _, stored)| constant_time_eq(stored.as_bytes(), presented.as_bytes()))
+        .ok_or_else(invalid_key)?;
+
+    if let Err(err) = sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+        .bind(key_id)
+        .execute(&state.db)
+        .await
+    {
+        tracing::warn!(error = ?err, "failed to record api key use");
+    }
+
+    req.extensions_mut().insert(ApiKeyIdentity {
+        key_id,
+        publisher_id,
+        stellar_address,
+    });
+    Ok(next.run(req).await)
+}
+
+/// Who is making a request: the publisher behind its API key, if any, and
+/// whether it carries an admin bearer token
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub identity: Option<ApiKeyIdentity>,
+    pub admin: bool,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let admin = extract_bearer_token(&parts.headers)
+            .and_then(|token| AuthManager::from_env().ok()?.validate_jwt(token).ok())
+            .is_some_and(|claims| is_admin(&claims));
+
+        Ok(Self {
+            identity: parts.extensions.get::<ApiKeyIdentity>().cloned(),
+            admin,
+        })
+    }
+}
+
+/// Fails with 403 unless `caller` is an admin or the publisher of the contract
+/// with registry ID `contract_id`, and with 404 if there is no such contract.
+pub async fn assert_owns_contract(
+    state: &AppState,
+    caller: &Caller,
+    contract_id: Uuid,
+) -> ApiResult<()> {
+    let publisher_address: Option<String> = sqlx::query_scalar(
+        "SELECT p.stellar_address FROM contracts c \
+         JOIN publishers p ON p.id = c.publisher_id WHERE c.id = $1",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch contract publisher", err))?;
+
+    let Some(publisher_address) = publisher_address else {
+        return Err(ApiError::not_found(
+            "ContractNotFound",
+            format!("No contract found with ID: {}", contract_id),
+        ));
+    };
+
+    if !may_modify(caller, &publisher_address) {
+        return Err(ApiError::forbidden(
+            "NotContractOwner",
+            "Only the contract's publisher may modify it",
+        ));
+    }
+    Ok(())
+}
+
+fn may_modify(caller: &Caller, publisher_address: &str) -> bool {
+    caller.admin
+        || caller
+            .identity
+            .as_ref()
+            .is_some_and(|identity| identity.stellar_address == publisher_address)
+}
+
+fn extract_api_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+/// Hex SHA-256 of an API key, as stored in `api_keys.key_hash`
+/// A new random API key; the caller stores its prefix and hash and hands the
+/// key itself out once
+pub fn generate_api_key() -> String {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("sk_{}", secret)
+}
+
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Compares without short-circuiting so timing doesn't reveal how much matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 fn decode_hex_32(value: &str) -> Option<[u8; 32]> {
     let bytes = decode_hex(value)?;
     let mut out = [0u8; 32];
@@ -246,6 +423,110 @@ mod tests {
         assert!(second.is_err());
     }
 
+    #[test]
+    fn api_key_header_is_trimmed_and_required() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(extract_api_key(&headers), None);
+
+        headers.insert(API_KEY_HEADER, "   ".parse().unwrap());
+        assert_eq!(extract_api_key(&headers), None);
+
+        headers.insert(API_KEY_HEADER, " sk_live_abc123 ".parse().unwrap());
+        assert_eq!(extract_api_key(&headers), Some("sk_live_abc123"));
+    }
+
+    #[test]
+    fn api_key_hashes_compare_equal_only_for_the_same_key() {
+        let stored = hash_api_key("sk_live_abc123");
+        assert_eq!(stored.len(), 64);
+        assert!(constant_time_eq(
+            stored.as_bytes(),
+            hash_api_key("sk_live_abc123").as_bytes()
+        ));
+        assert!(!constant_time_eq(
+            stored.as_bytes(),
+            hash_api_key("sk_live_abc124").as_bytes()
+        ));
+        assert!(!constant_time_eq(
+            stored.as_bytes(),
+            &stored.as_bytes()[..63]
+        ));
+    }
+
+    #[test]
+    fn only_the_publisher_or_an_admin_may_modify_a_contract() {
+        let owner = "GOWNER";
+        let publisher = |address: &str| Caller {
+            identity: Some(ApiKeyIdentity {
+                key_id: Uuid::new_v4(),
+                publisher_id: Uuid::new_v4(),
+                stellar_address: address.to_string(),
+            }),
+            admin: false,
+        };
+
+        assert!(may_modify(&publisher(owner), owner));
+        assert!(!may_modify(&publisher("GOTHER"), owner));
+        assert!(!may_modify(
+            &Caller {
+                identity: None,
+                admin: false
+            },
+            owner
+        ));
+        assert!(may_modify(
+            &Caller {
+                identity: None,
+                admin: true
+            },
+            owner
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejected_requests_carry_error_codes() {
+        use axum::{body::Body, middleware, routing::post, Router};
+        use tower::ServiceExt;
+
+        let db = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let state = crate::test_support::test_state(db);
+        let send = |app: Router, request: Request| async move {
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            (status, body["error"]["code"].as_str().unwrap().to_string())
+        };
+
+        let admin = Router::new()
+            .route("/", post(|| async { "ok" }))
+            .route_layer(middleware::from_fn(require_admin));
+        let request = Request::post("/").body(Body::empty()).unwrap();
+        assert_eq!(
+            send(admin, request).await,
+            (StatusCode::UNAUTHORIZED, "MissingToken".to_string())
+        );
+
+        let writes = Router::new()
+            .route("/", post(|| async { "ok" }))
+            .route_layer(middleware::from_fn_with_state(state, require_api_key));
+        let request = Request::post("/").body(Body::empty()).unwrap();
+        assert_eq!(
+            send(writes.clone(), request).await,
+            (StatusCode::UNAUTHORIZED, "MissingApiKey".to_string())
+        );
+        let request = Request::post("/")
+            .header(API_KEY_HEADER, "short")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            send(writes, request).await,
+            (StatusCode::UNAUTHORIZED, "InvalidApiKey".to_string())
+        );
+    }
+
     #[test]
     fn jwt_secret_length_is_enforced() {
         let too_short = "a".repeat(MIN_JWT_SECRET_LEN - 1);
@@ -0,0 +1,510 @@
+// api/src/background_jobs.rs
+// Shared scheduler for periodic background jobs. Every job runs through one
+// semaphore so the jobs together never hold more than a configured number of
+// DB-heavy runs at once, and their first runs are staggered so they don't all
+// fire at startup. With leader election enabled, each run holds a Postgres
+// session advisory lock keyed by the job name, so a job never runs on two
+// replicas at once; replicas that lose the lock skip the tick. Under the lock
+// the run is also recorded in `background_job_runs`, and a replica only runs
+// a job when no replica has run it within (most of) its period, so the job
+// runs about once per period across the deployment rather than once per
+// replica.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, PgPool, Postgres};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+const DEFAULT_MAX_CONCURRENCY: usize = 2;
+const DEFAULT_STAGGER: Duration = Duration::from_secs(10);
+/// Share of a job's period that must pass before any replica runs it again
+const CLAIM_GAP_FRACTION: f64 = 0.9;
+
+#[derive(Debug, Clone)]
+pub struct JobSchedulerConfig {
+    /// Most job runs allowed in flight at once, across all jobs
+    pub max_concurrency: usize,
+    /// Delay between the first runs of consecutively registered jobs
+    pub stagger: Duration,
+}
+
+impl Default for JobSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            stagger: DEFAULT_STAGGER,
+        }
+    }
+}
+
+impl JobSchedulerConfig {
+    pub fn from_env() -> Self {
+        let max_concurrency = std::env::var("BACKGROUND_JOBS_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY);
+        let stagger = std::env::var("BACKGROUND_JOBS_STAGGER_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_STAGGER);
+
+        Self {
+            max_concurrency,
+            stagger,
+        }
+    }
+}
+
+/// Last-run status of a registered job, as reported by the admin endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    pub interval_secs: u64,
+    /// Most a run may be delayed past its tick
+    pub jitter_secs: u64,
+    pub start_offset_secs: u64,
+    pub running: bool,
+    pub runs: u64,
+    pub failures: u64,
+    /// Ticks skipped because another replica ran the job within its period
+    pub skipped: u64,
+    pub last_skipped_at: Option<DateTime<Utc>>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_duration_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct JobScheduler {
+    permits: Arc<Semaphore>,
+    stagger: Duration,
+    statuses: Arc<Mutex<BTreeMap<&'static str, JobStatus>>>,
+    /// Pool runs are locked and claimed on; `None` runs every job on every
+    /// replica
+    leader_pool: Option<PgPool>,
+}
+
+impl JobScheduler {
+    pub fn new(config: JobSchedulerConfig) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(config.max_concurrency.max(1))),
+            stagger: config.stagger,
+            statuses: Arc::new(Mutex::new(BTreeMap::new())),
+            leader_pool: None,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(JobSchedulerConfig::from_env())
+    }
+
+    /// Lock and claim each run on `pool` first, so a job runs on only one
+    /// replica at a time and once per period.
+    pub fn with_leader_election(mut self, pool: PgPool) -> Self {
+        self.leader_pool = Some(pool);
+        self
+    }
+
+    /// Run `job` every `period`, its first run offset by the stagger times the
+    /// number of jobs registered before it.
+    pub fn spawn<F, Fut>(&self, name: &'static str, period: Duration, job: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.spawn_with_jitter(name, period, Duration::ZERO, job);
+    }
+
+    /// Like [`spawn`](Self::spawn), but each run is delayed by a random amount
+    /// up to `jitter` so replicas don't all contend for the job at once.
+    /// `jitter` should be well below `period`.
+    pub fn spawn_with_jitter<F, Fut>(
+        &self,
+        name: &'static str,
+        period: Duration,
+        jitter: Duration,
+        job: F,
+    ) where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let offset = self.register(name, period, jitter);
+        let scheduler = self.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(offset).await;
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                if !jitter.is_zero() {
+                    tokio::time::sleep(jitter.mul_f64(rand::random::<f64>())).await;
+                }
+                scheduler.run_once(name, &job).await;
+            }
+        });
+    }
+
+    /// Snapshot of every registered job's status, ordered by name
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.lock().values().cloned().collect()
+    }
+
+    fn register(&self, name: &'static str, period: Duration, jitter: Duration) -> Duration {
+        let mut statuses = self.lock();
+        let offset = self.stagger * statuses.len() as u32;
+        statuses.insert(
+            name,
+            JobStatus {
+                name,
+                interval_secs: period.as_secs(),
+                jitter_secs: jitter.as_secs(),
+                start_offset_secs: offset.as_secs(),
+                running: false,
+                runs: 0,
+                failures: 0,
+                skipped: 0,
+                last_skipped_at: None,
+                last_started_at: None,
+                last_finished_at: None,
+                last_duration_ms: None,
+                last_error: None,
+            },
+        );
+        offset
+    }
+
+    async fn run_once<F, Fut>(&self, name: &'static str, job: &F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        // The semaphore is never closed, so acquiring can't fail
+        let Ok(_permit) = self.permits.acquire().await else {
+            return;
+        };
+
+        let leader = match &self.leader_pool {
+            Some(pool) => match lead_run(pool, name, self.claim_gap(name)).await {
+                Ok(Some(conn)) => Some(conn),
+                Ok(None) => {
+                    self.update(name, |status| {
+                        status.skipped += 1;
+                        status.last_skipped_at = Some(Utc::now());
+                    });
+                    return;
+                }
+                Err(err) => {
+                    tracing::error!(job = name, error = ?err, "failed to claim background job run");
+                    self.update(name, |status| {
+                        status.failures += 1;
+                        status.last_error = Some(format!("Failed to claim job run: {}", err));
+                    });
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        self.update(name, |status| {
+            status.running = true;
+            status.last_started_at = Some(Utc::now());
+        });
+        let started = Instant::now();
+
+        let result = job().await;
+        if let Some(conn) = leader {
+            release_run(conn, name).await;
+        }
+
+        if let Err(ref err) = result {
+            tracing::error!(job = name, error = ?err, "background job failed");
+        }
+        self.update(name, |status| {
+            status.running = false;
+            status.runs += 1;
+            status.last_finished_at = Some(Utc::now());
+            status.last_duration_ms = Some(started.elapsed().as_millis() as u64);
+            status.last_error = result.as_ref().err().map(|e| e.to_string());
+            if result.is_err() {
+                status.failures += 1;
+            }
+        });
+    }
+
+    /// Least time since the job's last run, on any replica, before this one
+    /// may run it again. Slightly under the period, less the jitter, so the
+    /// replica that claimed the last run isn't refused the next one because
+    /// its tick landed a little early.
+    fn claim_gap(&self, name: &str) -> Duration {
+        self.lock()
+            .get(name)
+            .map(|status| {
+                Duration::from_secs(status.interval_secs)
+                    .mul_f64(CLAIM_GAP_FRACTION)
+                    .saturating_sub(Duration::from_secs(status.jitter_secs))
+            })
+            .unwrap_or_default()
+    }
+
+    fn update(&self, name: &'static str, apply: impl FnOnce(&mut JobStatus)) {
+        if let Some(status) = self.lock().get_mut(name) {
+            apply(status);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<&'static str, JobStatus>> {
+        self.statuses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Take the advisory lock for `name` and claim the run, returning the
+/// connection that holds the lock, or `None` when another replica is running
+/// the job or ran it less than `min_gap` ago. The connection is closed rather
+/// than pooled once dropped, so the lock can't outlive a run that panics.
+async fn lead_run(
+    pool: &PgPool,
+    name: &str,
+    min_gap: Duration,
+) -> Result<Option<PoolConnection<Postgres>>, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    conn.close_on_drop();
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext($1))")
+        .bind(name)
+        .fetch_one(&mut *conn)
+        .await?;
+    if !locked || !claim_run(&mut conn, name, min_gap).await? {
+        return Ok(None);
+    }
+    Ok(Some(conn))
+}
+
+/// Release the advisory lock taken by [`lead_run`] so the next tick, on any
+/// replica, can take it straight away
+async fn release_run(mut conn: PoolConnection<Postgres>, name: &str) {
+    if let Err(err) = sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+        .bind(name)
+        .execute(&mut *conn)
+        .await
+    {
+        // Closing the connection on drop releases it instead
+        tracing::warn!(job = name, error = ?err, "failed to release background job lock");
+    }
+}
+
+/// Record a run of `name` in `background_job_runs` unless one was recorded
+/// less than `min_gap` ago, returning whether this replica got the run
+async fn claim_run(
+    conn: &mut PgConnection,
+    name: &str,
+    min_gap: Duration,
+) -> Result<bool, sqlx::Error> {
+    let claimed: Option<String> = sqlx::query_scalar(
+        "INSERT INTO background_job_runs (job_name, last_run_at) VALUES ($1, NOW())
+         ON CONFLICT (job_name) DO UPDATE SET last_run_at = NOW()
+         WHERE background_job_runs.last_run_at <= NOW() - make_interval(secs => $2)
+         RETURNING job_name",
+    )
+    .bind(name)
+    .bind(min_gap.as_secs_f64())
+    .fetch_optional(conn)
+    .await?;
+    Ok(claimed.is_some())
+}
+
+/// GET /api/admin/background-jobs — last-run status of every scheduled job
+pub async fn get_background_job_status(
+    axum::extract::State(state): axum::extract::State<crate::state::AppState>,
+) -> axum::Json<Vec<JobStatus>> {
+    axum::Json(state.background_jobs.statuses())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn tracked_job(
+        in_flight: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    ) -> impl Fn() -> std::pin::Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> {
+        move || {
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            Box::pin(async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn jobs_do_not_exceed_concurrency_limit() {
+        let scheduler = JobScheduler::new(JobSchedulerConfig {
+            max_concurrency: 1,
+            stagger: Duration::ZERO,
+        });
+        scheduler.register("first", Duration::from_secs(60), Duration::ZERO);
+        scheduler.register("second", Duration::from_secs(60), Duration::ZERO);
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let first = tracked_job(in_flight.clone(), peak.clone());
+        let second = tracked_job(in_flight, peak.clone());
+
+        tokio::join!(
+            scheduler.run_once("first", &first),
+            scheduler.run_once("second", &second),
+        );
+
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        assert!(scheduler
+            .statuses()
+            .iter()
+            .all(|s| s.runs == 1 && !s.running));
+    }
+
+    #[tokio::test]
+    async fn start_offsets_are_staggered_and_failures_recorded() {
+        let scheduler = JobScheduler::new(JobSchedulerConfig {
+            max_concurrency: 2,
+            stagger: Duration::from_secs(10),
+        });
+        assert_eq!(
+            scheduler.register("a", Duration::from_secs(60), Duration::ZERO),
+            Duration::ZERO
+        );
+        assert_eq!(
+            scheduler.register("b", Duration::from_secs(60), Duration::ZERO),
+            Duration::from_secs(10)
+        );
+
+        scheduler
+            .run_once("b", &|| async { anyhow::bail!("boom") })
+            .await;
+
+        let b = scheduler
+            .statuses()
+            .into_iter()
+            .find(|s| s.name == "b")
+            .unwrap();
+        assert_eq!((b.runs, b.failures), (1, 1));
+        assert_eq!(b.last_error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn runs_claimed_within_the_period_are_skipped() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let name: &'static str = Box::leak(format!("test_job_{}", uuid::Uuid::new_v4()).into());
+        // Two replicas sharing the database
+        let replicas: Vec<JobScheduler> = (0..2)
+            .map(|_| {
+                let scheduler = JobScheduler::new(JobSchedulerConfig::default())
+                    .with_leader_election(pool.clone());
+                scheduler.register(name, Duration::from_secs(3600), Duration::ZERO);
+                scheduler
+            })
+            .collect();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let job = || {
+            let runs = runs.clone();
+            async move {
+                runs.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        };
+
+        replicas[0].run_once(name, &job).await;
+        replicas[1].run_once(name, &job).await;
+        replicas[0].run_once(name, &job).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        let skipped: u64 = replicas
+            .iter()
+            .flat_map(|r| r.statuses())
+            .map(|s| s.skipped)
+            .sum();
+        assert_eq!(skipped, 2);
+
+        // Once the period has passed, the next tick runs again
+        sqlx::query(
+            "UPDATE background_job_runs SET last_run_at = NOW() - INTERVAL '1 hour'
+             WHERE job_name = $1",
+        )
+        .bind(name)
+        .execute(&pool)
+        .await
+        .unwrap();
+        replicas[1].run_once(name, &job).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn a_run_in_progress_blocks_other_replicas_past_its_period() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let name: &'static str = Box::leak(format!("test_job_{}", uuid::Uuid::new_v4()).into());
+        let replicas: Vec<JobScheduler> = (0..2)
+            .map(|_| {
+                let scheduler = JobScheduler::new(JobSchedulerConfig::default())
+                    .with_leader_election(pool.clone());
+                scheduler.register(name, Duration::from_secs(60), Duration::ZERO);
+                scheduler
+            })
+            .collect();
+        let started = Arc::new(tokio::sync::Notify::new());
+        let finish = Arc::new(tokio::sync::Notify::new());
+        let slow_job = || {
+            let started = started.clone();
+            let finish = finish.clone();
+            async move {
+                started.notify_one();
+                finish.notified().await;
+                Ok(())
+            }
+        };
+        let runs = Arc::new(AtomicUsize::new(0));
+        let job = || {
+            let runs = runs.clone();
+            async move {
+                runs.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        };
+
+        tokio::join!(replicas[0].run_once(name, &slow_job), async {
+            started.notified().await;
+            // The first run has outlasted its period, but it is still running
+            sqlx::query(
+                "UPDATE background_job_runs SET last_run_at = NOW() - INTERVAL '1 hour'
+                 WHERE job_name = $1",
+            )
+            .bind(name)
+            .execute(&pool)
+            .await
+            .unwrap();
+            replicas[1].run_once(name, &job).await;
+            finish.notify_one();
+        });
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        assert_eq!(replicas[1].statuses()[0].skipped, 1);
+
+        // Once it has finished, the lock is free again
+        replicas[1].run_once(name, &job).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+}
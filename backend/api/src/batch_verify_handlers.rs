@@ -1,12 +1,282 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
-use serde_json::json;
-
-pub async fn batch_verify_contracts() -> impl IntoResponse {
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        Json(json!({
-            "error": "not_implemented",
-            "message": "Batch verification endpoint is planned but not yet functional"
-        })),
-    )
+use axum::{
+    extract::{rejection::JsonRejection, State},
+    Json,
+};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shared::models::Contract;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    handlers::{db_internal_error, map_json_rejection, verify_deployed_wasm},
+    state::AppState,
+};
+
+/// Most contracts a single batch may verify
+const MAX_BATCH_VERIFY: usize = 50;
+
+/// Verifications in flight at once for one batch
+const BATCH_VERIFY_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchVerifyRequest {
+    pub contract_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchVerifyError {
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchVerifyResult {
+    pub contract_id: String,
+    /// `verified`, `failed` (source doesn't match the deployed WASM) or
+    /// `error` (the contract couldn't be verified; see `error`)
+    pub status: String,
+    pub cached: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchVerifyError>,
+}
+
+impl BatchVerifyResult {
+    fn verdict(contract_id: String, verification: Value, cached: bool) -> Self {
+        let verified = verification
+            .get("verified")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        Self {
+            contract_id,
+            status: if verified { "verified" } else { "failed" }.to_string(),
+            cached,
+            verification: Some(verification),
+            error: None,
+        }
+    }
+
+    fn error(contract_id: String, err: ApiError) -> Self {
+        Self {
+            contract_id,
+            status: "error".to_string(),
+            cached: false,
+            verification: None,
+            error: Some(BatchVerifyError {
+                code: err.code().to_string(),
+                message: err.message().to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchVerifyResponse {
+    pub results: Vec<BatchVerifyResult>,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl BatchVerifyResponse {
+    fn new(results: Vec<BatchVerifyResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.status == "verified").count();
+        Self {
+            total: results.len(),
+            failed: results.len() - succeeded,
+            succeeded,
+            results,
+        }
+    }
+}
+
+/// POST /api/contracts/batch-verify — verify up to [`MAX_BATCH_VERIFY`]
+/// registered contracts against the WASM deployed for them. Contracts with a
+/// cached passing verdict aren't re-verified; one contract failing doesn't
+/// fail the batch.
+pub async fn batch_verify_contracts(
+    State(state): State<AppState>,
+    payload: Result<Json<BatchVerifyRequest>, JsonRejection>,
+) -> ApiResult<Json<BatchVerifyResponse>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+    let contract_ids = normalize_batch(req.contract_ids)?;
+
+    let uuids: Vec<Uuid> = contract_ids
+        .iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect();
+    let contracts: Vec<Contract> = sqlx::query_as("SELECT * FROM contracts WHERE id = ANY($1)")
+        .bind(&uuids)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("fetch contracts for batch verification", err))?;
+    let mut contracts: HashMap<Uuid, Contract> = contracts.into_iter().map(|c| (c.id, c)).collect();
+
+    let jobs: Vec<(usize, String, Result<Contract, ApiError>)> = contract_ids
+        .into_iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let contract = match Uuid::parse_str(&id) {
+                Ok(uuid) => contracts.remove(&uuid).ok_or_else(|| {
+                    ApiError::not_found(
+                        "ContractNotFound",
+                        format!("No contract found with ID: {}", id),
+                    )
+                }),
+                Err(_) => Err(ApiError::bad_request(
+                    "InvalidContractId",
+                    format!("Invalid contract ID format: {}", id),
+                )),
+            };
+            (index, id, contract)
+        })
+        .collect();
+
+    let mut results: Vec<(usize, BatchVerifyResult)> = stream::iter(jobs)
+        .map(|(index, id, contract)| {
+            let state = &state;
+            async move {
+                let result = match contract {
+                    Ok(contract) => verify_one(state, id, &contract).await,
+                    Err(err) => BatchVerifyResult::error(id, err),
+                };
+                (index, result)
+            }
+        })
+        .buffer_unordered(BATCH_VERIFY_CONCURRENCY)
+        .collect()
+        .await;
+    results.sort_by_key(|(index, _)| *index);
+
+    Ok(Json(BatchVerifyResponse::new(
+        results.into_iter().map(|(_, result)| result).collect(),
+    )))
+}
+
+/// Verify one contract against the WASM under its stored `wasm_hash`
+async fn verify_one(
+    state: &AppState,
+    contract_id: String,
+    contract: &Contract,
+) -> BatchVerifyResult {
+    match verify_deployed_wasm(state, contract, &contract.wasm_hash).await {
+        Ok((verification, cached)) => BatchVerifyResult::verdict(contract_id, verification, cached),
+        Err(err) => BatchVerifyResult::error(contract_id, err),
+    }
+}
+
+/// Trim and de-duplicate the requested IDs, enforcing [`MAX_BATCH_VERIFY`]
+fn normalize_batch(contract_ids: Vec<String>) -> ApiResult<Vec<String>> {
+    if contract_ids.len() > MAX_BATCH_VERIFY {
+        return Err(ApiError::bad_request(
+            "BatchTooLarge",
+            format!(
+                "At most {} contracts can be verified in one batch, got {}",
+                MAX_BATCH_VERIFY,
+                contract_ids.len()
+            ),
+        ));
+    }
+
+    let mut ids: Vec<String> = Vec::with_capacity(contract_ids.len());
+    for id in contract_ids {
+        let id = id.trim();
+        if id.is_empty() {
+            return Err(ApiError::bad_request(
+                "InvalidContractId",
+                "contract_ids must not contain empty values",
+            ));
+        }
+        if !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+    if ids.is_empty() {
+        return Err(ApiError::bad_request(
+            "EmptyBatch",
+            "contract_ids must contain at least one contract",
+        ));
+    }
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn batches_are_deduplicated_and_capped() {
+        let ids = normalize_batch(vec![" a ".into(), "b".into(), "a".into()]).unwrap();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        let err = normalize_batch(vec![]).unwrap_err();
+        assert_eq!(err.code(), "EmptyBatch");
+
+        let too_many = (0..=MAX_BATCH_VERIFY).map(|i| i.to_string()).collect();
+        let err = normalize_batch(too_many).unwrap_err();
+        assert_eq!(err.code(), "BatchTooLarge");
+        assert_eq!(err.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn totals_count_only_verified_contracts_as_succeeded() {
+        let response = BatchVerifyResponse::new(vec![
+            BatchVerifyResult::verdict("a".into(), json!({ "verified": true }), true),
+            BatchVerifyResult::verdict("b".into(), json!({ "verified": false }), false),
+            BatchVerifyResult::error(
+                "c".into(),
+                ApiError::not_found("ContractNotFound", "missing"),
+            ),
+        ]);
+
+        assert_eq!(response.total, 3);
+        assert_eq!(response.succeeded, 1);
+        assert_eq!(response.failed, 2);
+        assert_eq!(response.results[1].status, "failed");
+        assert_eq!(
+            response.results[2].error.as_ref().unwrap().code,
+            "ContractNotFound"
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_verdicts_are_served_without_rpc() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let wasm_hash = format!("{:0>64}", Uuid::new_v4().simple());
+        let id = crate::test_support::seed_contract(&db, &wasm_hash).await;
+        let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        state
+            .cache
+            .put_verification(
+                &crate::cache::verification_key(id, &wasm_hash),
+                json!({ "verified": true }).to_string(),
+            )
+            .await;
+
+        // No RPC endpoint is configured for the test network, so only a cache
+        // hit can produce a verdict
+        let result = verify_one(&state, id.to_string(), &contract).await;
+        assert_eq!(result.status, "verified");
+        assert!(result.cached);
+
+        let is_verified: bool =
+            sqlx::query_scalar("SELECT is_verified FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert!(is_verified);
+    }
 }
@@ -1,12 +1,15 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
+use crate::cache::AbiLookup;
 use crate::error::{ApiError, ApiResult};
+use crate::simulation::abi_extractor::FunctionInfo;
 use crate::state::AppState;
 use crate::type_safety::parser::parse_json_spec;
 use crate::type_safety::types::{
@@ -78,6 +81,135 @@ pub async fn get_breaking_changes(
     }))
 }
 
+/// Smallest semver bump that covers a set of ABI changes
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SemverBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FunctionChange {
+    pub name: String,
+    pub breaking: bool,
+    pub changes: Vec<BreakingChange>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AbiDiff {
+    pub contract_id: String,
+    pub from: String,
+    pub to: String,
+    pub added_functions: Vec<String>,
+    pub removed_functions: Vec<String>,
+    /// Functions present in both versions whose signature changed
+    pub changed_functions: Vec<FunctionChange>,
+    pub type_changes: Vec<BreakingChange>,
+    pub breaking: bool,
+    pub suggested_bump: SemverBump,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AbiDiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+/// GET /api/contracts/:id/abi/diff?from=&to= — classify the ABI changes between two
+/// versions of a contract and suggest the minimum semver bump
+pub async fn get_abi_diff(
+    Path(contract_id): Path<String>,
+    Query(query): Query<AbiDiffQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<AbiDiff>> {
+    let from_selector = format!("{}@{}", contract_id, query.from);
+    let to_selector = format!("{}@{}", contract_id, query.to);
+
+    let from_abi = resolve_abi(&state, &from_selector).await?;
+    let to_abi = resolve_abi(&state, &to_selector).await?;
+
+    let from_spec = parse_json_spec(&from_abi, &from_selector).map_err(|e| {
+        ApiError::bad_request("InvalidABI", format!("Failed to parse 'from' ABI: {}", e))
+    })?;
+    let to_spec = parse_json_spec(&to_abi, &to_selector).map_err(|e| {
+        ApiError::bad_request("InvalidABI", format!("Failed to parse 'to' ABI: {}", e))
+    })?;
+
+    let diff = build_abi_diff(
+        contract_id,
+        query.from,
+        query.to,
+        diff_abi(&from_spec, &to_spec),
+    );
+    Ok(Json(diff))
+}
+
+/// Group `changes` into added, removed and changed functions plus type changes
+pub fn build_abi_diff(
+    contract_id: String,
+    from: String,
+    to: String,
+    changes: Vec<BreakingChange>,
+) -> AbiDiff {
+    let breaking = has_breaking_changes(&changes);
+    let suggested_bump = suggest_bump(&changes);
+
+    let mut added_functions = Vec::new();
+    let mut removed_functions = Vec::new();
+    let mut changed: BTreeMap<String, Vec<BreakingChange>> = BTreeMap::new();
+    let mut type_changes = Vec::new();
+
+    for change in changes {
+        match (change.category.as_str(), change.function.clone()) {
+            ("function_added", Some(name)) => added_functions.push(name),
+            ("function_removed", Some(name)) => removed_functions.push(name),
+            (_, Some(name)) => changed.entry(name).or_default().push(change),
+            (_, None) => type_changes.push(change),
+        }
+    }
+    added_functions.sort();
+    removed_functions.sort();
+
+    AbiDiff {
+        contract_id,
+        from,
+        to,
+        added_functions,
+        removed_functions,
+        changed_functions: changed
+            .into_iter()
+            .map(|(name, changes)| FunctionChange {
+                breaking: has_breaking_changes(&changes),
+                name,
+                changes,
+            })
+            .collect(),
+        type_changes,
+        breaking,
+        suggested_bump,
+    }
+}
+
+/// Breaking changes need a major bump and additions a minor one; anything else
+/// (e.g. renamed params) fits in a patch.
+pub fn suggest_bump(changes: &[BreakingChange]) -> SemverBump {
+    changes
+        .iter()
+        .map(|change| {
+            if change.severity == ChangeSeverity::Breaking {
+                SemverBump::Major
+            } else if change.category.ends_with("_added") {
+                SemverBump::Minor
+            } else {
+                SemverBump::Patch
+            }
+        })
+        .max()
+        .unwrap_or(SemverBump::Patch)
+}
+
 pub fn diff_abi(old: &ContractABI, new: &ContractABI) -> Vec<BreakingChange> {
     let mut changes = Vec::new();
 
@@ -126,7 +258,30 @@ fn diff_function(
     old_func: &ContractFunction,
     new_func: &ContractFunction,
 ) {
-    if old_func.params.len() != new_func.params.len() {
+    // Optional params appended after the existing ones keep old calls valid
+    let appended = new_func
+        .params
+        .get(old_func.params.len()..)
+        .unwrap_or_default();
+    let only_optional_appended = !appended.is_empty()
+        && appended
+            .iter()
+            .all(|p| matches!(p.param_type, SorobanType::Option { .. }));
+
+    if only_optional_appended {
+        for param in appended {
+            changes.push(BreakingChange {
+                severity: ChangeSeverity::NonBreaking,
+                category: "optional_param_added".to_string(),
+                message: format!(
+                    "Function '{}' gained optional param '{}'",
+                    old_func.name, param.name
+                ),
+                function: Some(old_func.name.clone()),
+                type_name: None,
+            });
+        }
+    } else if old_func.params.len() != new_func.params.len() {
         changes.push(BreakingChange {
             severity: ChangeSeverity::Breaking,
             category: "function_params_changed".to_string(),
@@ -364,8 +519,15 @@ fn diff_enum_variants(
 }
 
 pub(crate) async fn resolve_abi(state: &AppState, selector: &str) -> ApiResult<String> {
-    if let Some(cached) = state.cache.get_abi(selector).await {
-        return Ok(cached);
+    match state.cache.lookup_abi(selector).await {
+        AbiLookup::Hit(cached) => return Ok(cached),
+        AbiLookup::KnownAbsent => {
+            return Err(ApiError::not_found(
+                "AbiNotFound",
+                format!("No ABI available for '{}'", selector),
+            ))
+        }
+        AbiLookup::Miss => {}
     }
 
     let abi_result = if let Some((contract_id, version)) = selector.split_once('@') {
@@ -380,8 +542,12 @@ pub(crate) async fn resolve_abi(state: &AppState, selector: &str) -> ApiResult<S
         fetch_latest_abi_for_contract(state, selector).await
     };
 
-    if let Ok(abi) = &abi_result {
-        state.cache.put_abi(selector, abi.clone()).await;
+    match &abi_result {
+        Ok(abi) => state.cache.put_abi(selector, abi.clone()).await,
+        Err(err) if err.status() == StatusCode::NOT_FOUND => {
+            state.cache.mark_abi_absent(selector).await
+        }
+        Err(_) => {}
     }
 
     abi_result
@@ -422,7 +588,10 @@ async fn fetch_contract_uuid(state: &AppState, contract_id: &str) -> ApiResult<U
     Ok(uuid)
 }
 
-async fn fetch_latest_abi_for_contract(state: &AppState, contract_id: &str) -> ApiResult<String> {
+pub(crate) async fn fetch_latest_abi_for_contract(
+    state: &AppState,
+    contract_id: &str,
+) -> ApiResult<String> {
     let uuid = fetch_contract_uuid(state, contract_id).await?;
 
     if let Some(abi) = sqlx::query_scalar::<_, serde_json::Value>(
@@ -489,6 +658,56 @@ pub fn has_breaking_changes(changes: &[BreakingChange]) -> bool {
         .any(|c| c.severity == ChangeSeverity::Breaking)
 }
 
+/// Diff a registered ABI against functions extracted from an uploaded module.
+/// Extraction only recovers function names and parameter counts, so param
+/// and return types can't be compared.
+pub fn diff_extracted_abi(old: &ContractABI, new: &[FunctionInfo]) -> Vec<BreakingChange> {
+    let mut changes = Vec::new();
+
+    let new_funcs: HashMap<&str, &FunctionInfo> =
+        new.iter().map(|f| (f.name.as_str(), f)).collect();
+
+    for old_func in &old.functions {
+        match new_funcs.get(old_func.name.as_str()) {
+            None => changes.push(BreakingChange {
+                severity: ChangeSeverity::Breaking,
+                category: "function_removed".to_string(),
+                message: format!("Function '{}' was removed", old_func.name),
+                function: Some(old_func.name.clone()),
+                type_name: None,
+            }),
+            Some(new_func) if new_func.param_count as usize != old_func.params.len() => changes
+                .push(BreakingChange {
+                    severity: ChangeSeverity::Breaking,
+                    category: "function_params_changed".to_string(),
+                    message: format!(
+                        "Function '{}' parameter count changed from {} to {}",
+                        old_func.name,
+                        old_func.params.len(),
+                        new_func.param_count
+                    ),
+                    function: Some(old_func.name.clone()),
+                    type_name: None,
+                }),
+            Some(_) => {}
+        }
+    }
+
+    for new_func in new {
+        if !old.functions.iter().any(|f| f.name == new_func.name) {
+            changes.push(BreakingChange {
+                severity: ChangeSeverity::NonBreaking,
+                category: "function_added".to_string(),
+                message: format!("Function '{}' was added", new_func.name),
+                function: Some(new_func.name.clone()),
+                type_name: None,
+            });
+        }
+    }
+
+    changes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -570,4 +789,109 @@ mod tests {
             .iter()
             .any(|c| c.category == "function_added" && c.severity == ChangeSeverity::NonBreaking));
     }
+
+    #[test]
+    fn appended_optional_param_is_non_breaking() {
+        let mut old = ContractABI::new("Old".to_string());
+        old.functions.push(func(
+            "mint",
+            vec![param("to", SorobanType::Address)],
+            SorobanType::Void,
+        ));
+
+        let mut new = ContractABI::new("New".to_string());
+        new.functions.push(func(
+            "mint",
+            vec![
+                param("to", SorobanType::Address),
+                param(
+                    "memo",
+                    SorobanType::Option {
+                        value_type: Box::new(SorobanType::String),
+                    },
+                ),
+            ],
+            SorobanType::Void,
+        ));
+
+        let changes = diff_abi(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].category, "optional_param_added");
+        assert_eq!(suggest_bump(&changes), SemverBump::Minor);
+    }
+
+    #[test]
+    fn abi_diff_groups_changes_and_suggests_bump() {
+        let mut old = ContractABI::new("Old".to_string());
+        old.functions = vec![
+            func("burn", vec![], SorobanType::Void),
+            func("balance", vec![], SorobanType::U64),
+        ];
+
+        let mut new = ContractABI::new("New".to_string());
+        new.functions = vec![
+            func("balance", vec![], SorobanType::U128),
+            func("ping", vec![], SorobanType::Void),
+        ];
+
+        let diff = build_abi_diff(
+            "CABC".to_string(),
+            "1.0.0".to_string(),
+            "2.0.0".to_string(),
+            diff_abi(&old, &new),
+        );
+
+        assert_eq!(diff.added_functions, vec!["ping"]);
+        assert_eq!(diff.removed_functions, vec!["burn"]);
+        assert_eq!(diff.changed_functions.len(), 1);
+        assert_eq!(diff.changed_functions[0].name, "balance");
+        assert!(diff.changed_functions[0].breaking);
+        assert!(diff.breaking);
+        assert_eq!(diff.suggested_bump, SemverBump::Major);
+
+        let mut next = new.clone();
+        next.functions.push(func("pong", vec![], SorobanType::Void));
+        let additive = build_abi_diff(
+            "CABC".to_string(),
+            "2.0.0".to_string(),
+            "2.1.0".to_string(),
+            diff_abi(&new, &next),
+        );
+        assert!(!additive.breaking);
+        assert_eq!(additive.suggested_bump, SemverBump::Minor);
+        assert_eq!(suggest_bump(&[]), SemverBump::Patch);
+    }
+
+    #[test]
+    fn extracted_functions_are_diffed_by_name_and_arity() {
+        let extracted = |name: &str, param_count| FunctionInfo {
+            name: name.to_string(),
+            param_count,
+            return_type: None,
+            is_view: false,
+        };
+        let mut old = ContractABI::new("Old".to_string());
+        old.functions = vec![
+            func(
+                "balance",
+                vec![param("id", SorobanType::Address)],
+                SorobanType::I128,
+            ),
+            func("burn", vec![], SorobanType::Void),
+        ];
+
+        let changes = diff_extracted_abi(&old, &[extracted("balance", 1), extracted("mint", 2)]);
+        assert!(changes
+            .iter()
+            .any(|c| c.category == "function_removed" && c.function.as_deref() == Some("burn")));
+        assert!(changes
+            .iter()
+            .any(|c| c.category == "function_added" && c.function.as_deref() == Some("mint")));
+        assert_eq!(changes.len(), 2);
+
+        let changes = diff_extracted_abi(&old, &[extracted("balance", 2), extracted("burn", 0)]);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].category, "function_params_changed");
+        assert!(has_breaking_changes(&changes));
+    }
 }
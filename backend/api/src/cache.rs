@@ -1,14 +1,66 @@
+use async_trait::async_trait;
 use moka::future::Cache as MokaCache;
+use moka::notification::RemovalCause;
+use moka::Expiry;
 use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// TTL for cached ABIs
+pub const ABI_TTL: Duration = Duration::from_secs(24 * 3600);
+/// How long a confirmed "no ABI" answer is served from the cache
+pub const ABI_NEGATIVE_TTL: Duration = Duration::from_secs(60);
+/// Generic namespace holding the markers of ABIs known not to exist, so each
+/// marker expires on its own rather than with the ABI cache's TTL
+const ABI_ABSENT_NS: &str = "abi_absent";
+/// TTL for cached verification results
+pub const VERIFICATION_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+/// Default TTL for generic namespaced entries
+pub const GENERIC_TTL: Duration = Duration::from_secs(3600);
+
+/// Generic namespace and key the global dependency graph is cached under
+pub const DEPENDENCY_GRAPH_NS: &str = "system";
+pub const DEPENDENCY_GRAPH_KEY: &str = "global:dependency_graph";
+
+/// Namespaces that can hold data about a single contract, as accepted by
+/// [`CacheLayer::purge_contract`]
+pub const CONTRACT_CACHE_NAMESPACES: &[&str] = &[
+    "abi",
+    "verification",
+    "dependency_graph",
+    "dependency_updates",
+    "on_chain_status",
+];
+
+/// Every key a contract's cached data may be stored under
+#[derive(Debug, Clone, Default)]
+pub struct ContractCacheKeys {
+    /// The contract's row UUID and on-chain contract ID
+    pub ids: Vec<String>,
+    /// Versioned ABI selectors, e.g. `contract_id@1.2.0`
+    pub abi_selectors: Vec<String>,
+    /// WASM hashes of the contract and its versions
+    pub wasm_hashes: Vec<String>,
+}
+
+/// Which store backs the cache layer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheBackendKind {
+    /// In-process moka cache (per replica)
+    Moka,
+    /// Shared Redis instance (requires the `redis` feature)
+    Redis,
+}
 
 /// Cache configuration options
 #[derive(Clone, Debug)]
 pub struct CacheConfig {
     pub enabled: bool,
     pub max_capacity: u64,
+    pub backend: CacheBackendKind,
+    pub redis_url: Option<String>,
 }
 
 impl Default for CacheConfig {
@@ -16,6 +68,8 @@ impl Default for CacheConfig {
         Self {
             enabled: true,
             max_capacity: 10_000,
+            backend: CacheBackendKind::Moka,
+            redis_url: None,
         }
     }
 }
@@ -29,98 +83,531 @@ impl CacheConfig {
         }
 
         if let Ok(capacity_str) = std::env::var("CACHE_MAX_CAPACITY") {
-            if let Ok(capacity) = capacity_str.parse::<u64>() {
-                config.max_capacity = capacity;
-            } else {
-                // Support parsing like "10 GB" by just falling back to 10000 limit for elements if not
+            match parse_capacity(&capacity_str) {
+                Some(capacity) => config.max_capacity = capacity,
+                None => tracing::warn!(
+                    value = %capacity_str,
+                    default = config.max_capacity,
+                    "Invalid CACHE_MAX_CAPACITY (expected a number or a size like \"512MB\"), using default"
+                ),
+            }
+        }
+
+        if let Ok(backend_str) = std::env::var("CACHE_BACKEND") {
+            match backend_str.to_lowercase().as_str() {
+                "moka" | "memory" => config.backend = CacheBackendKind::Moka,
+                "redis" => config.backend = CacheBackendKind::Redis,
+                other => tracing::warn!(
+                    backend = other,
+                    "Unknown CACHE_BACKEND, falling back to in-process cache"
+                ),
             }
         }
 
+        config.redis_url = std::env::var("REDIS_URL").ok();
+
         tracing::info!(
-            "Cache config loaded: enabled={}, capacity={}",
+            "Cache config loaded: enabled={}, capacity={}, backend={:?}",
             config.enabled,
-            config.max_capacity
+            config.max_capacity,
+            config.backend
         );
 
         config
     }
 }
 
-pub struct CacheLayer {
+/// Parses a cache capacity: a bare number, or a size with a `KB`/`MB`/`GB`
+/// suffix converted to bytes (entries are weighed by their byte length).
+fn parse_capacity(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" => 1024,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+/// Entry count and weighted size of one cache, when the backend can report them cheaply
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheUsage {
+    pub entry_count: u64,
+    pub weighted_size: u64,
+}
+
+/// Outcome of looking an ABI up in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiLookup {
+    Hit(String),
+    /// A lookup within the last [`ABI_NEGATIVE_TTL`] found no ABI
+    KnownAbsent,
+    Miss,
+}
+
+/// Usage of every cache held by a backend
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BackendUsage {
+    pub abi: CacheUsage,
+    pub verification: CacheUsage,
+    pub generic: CacheUsage,
+}
+
+/// Storage behind [`CacheLayer`]. The layer handles the enabled flag and
+/// hit/miss metrics; backends only store and retrieve values.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn get_abi(&self, contract_id: &str) -> Option<String>;
+    async fn put_abi(&self, contract_id: &str, abi: String);
+    async fn invalidate_abi(&self, contract_id: &str);
+
+    async fn get_verification(&self, bytecode_hash: &str) -> Option<String>;
+    async fn put_verification(&self, bytecode_hash: &str, result: String);
+    async fn invalidate_verification(&self, bytecode_hash: &str);
+
+    async fn get(&self, ns: &str, key: &str) -> Option<String>;
+    async fn put(&self, ns: &str, key: &str, value: String, ttl: Option<Duration>);
+    async fn invalidate(&self, ns: &str, key: &str);
+    /// Drops every entry stored under `ns`, returning how many keys were invalidated
+    async fn invalidate_namespace(&self, ns: &str) -> usize;
+
+    fn usage(&self) -> BackendUsage {
+        BackendUsage::default()
+    }
+}
+
+/// Tracks which namespaced keys currently live in the generic cache so a
+/// whole namespace can be dropped at once.
+type NamespaceIndex = Arc<Mutex<HashMap<String, HashSet<String>>>>;
+
+/// The generic cache as seen by its own eviction listener. The listener is
+/// owned by the cache, so the backend clears this on drop to break the cycle.
+type GenericCacheHandle = Arc<Mutex<Option<MokaCache<String, GenericEntry>>>>;
+
+/// Joins a namespace and key in the generic cache. Namespaces never contain
+/// it, so the namespace is everything before its first occurrence even when
+/// the namespace or key contains ':'.
+const NAMESPACE_SEPARATOR: char = '\u{1f}';
+
+fn namespaced_key(ns: &str, key: &str) -> String {
+    debug_assert!(
+        !ns.contains(NAMESPACE_SEPARATOR),
+        "cache namespace {:?} contains the separator",
+        ns
+    );
+    format!("{}{}{}", ns, NAMESPACE_SEPARATOR, key)
+}
+
+/// Stop tracking `namespaced_key` unless `still_cached` says it has been
+/// stored again since it was removed. Checked under the index lock, and puts
+/// insert into the cache before tracking, so a re-added key stays tracked.
+fn untrack_key(
+    index: &NamespaceIndex,
+    namespaced_key: &str,
+    still_cached: impl FnOnce(&str) -> bool,
+) {
+    let Some((ns, _)) = namespaced_key.split_once(NAMESPACE_SEPARATOR) else {
+        return;
+    };
+    let mut index = index.lock().unwrap_or_else(|e| e.into_inner());
+    if still_cached(namespaced_key) {
+        return;
+    }
+    if let Some(keys) = index.get_mut(ns) {
+        keys.remove(namespaced_key);
+        if keys.is_empty() {
+            index.remove(ns);
+        }
+    }
+}
+
+/// A generic cache value and the deadline it was stored with. Entries
+/// without one live for [`GENERIC_TTL`].
+#[derive(Debug, Clone)]
+pub struct GenericEntry {
+    pub value: String,
+    pub expires_at: Option<Instant>,
+}
+
+impl GenericEntry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|deadline| deadline <= now)
+    }
+
+    fn time_to_live(&self, now: Instant) -> Duration {
+        self.expires_at.map_or(GENERIC_TTL, |deadline| {
+            deadline.saturating_duration_since(now)
+        })
+    }
+}
+
+/// Expires each generic entry at its own deadline
+struct GenericExpiry;
+
+impl Expiry<String, GenericEntry> for GenericExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        entry: &GenericEntry,
+        created_at: Instant,
+    ) -> Option<Duration> {
+        Some(entry.time_to_live(created_at))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        entry: &GenericEntry,
+        updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(entry.time_to_live(updated_at))
+    }
+}
+
+/// Default in-process backend built on moka
+pub struct MokaBackend {
     pub abi_cache: MokaCache<String, String>,
     pub verification_cache: MokaCache<String, String>,
-    pub generic_cache: MokaCache<String, String>,
-    config: CacheConfig,
+    pub generic_cache: MokaCache<String, GenericEntry>,
+    namespace_index: NamespaceIndex,
+    generic_handle: GenericCacheHandle,
 }
 
-impl CacheLayer {
-    pub fn new(config: CacheConfig) -> Self {
+impl MokaBackend {
+    pub fn new(max_capacity: u64) -> Self {
         // 24-hour TTL for ABI, max size configurable default 10GB but we use the config max_capacity
         let abi_cache = MokaCache::builder()
-            .max_capacity(config.max_capacity)
+            .max_capacity(max_capacity)
             .weigher(|_k, v: &String| -> u32 { v.len().try_into().unwrap_or(u32::MAX) })
-            .time_to_live(Duration::from_secs(24 * 3600))
+            .time_to_live(ABI_TTL)
             .build();
 
         // 7-day TTL for verification result cache, keyed by bytecode_hash
         let verification_cache = MokaCache::builder()
-            .max_capacity(config.max_capacity)
+            .max_capacity(max_capacity)
             .weigher(|_k, v: &String| -> u32 { v.len().try_into().unwrap_or(u32::MAX) })
-            .time_to_live(Duration::from_secs(7 * 24 * 3600))
+            .time_to_live(VERIFICATION_TTL)
             .build();
 
         // Generic cache for namespace-keyed entries (e.g., contract graphs)
         // Default 1-hour TTL, configurable per-entry
+        let namespace_index: NamespaceIndex = Arc::new(Mutex::new(HashMap::new()));
+        let generic_handle: GenericCacheHandle = Arc::new(Mutex::new(None));
+        let listener_index = namespace_index.clone();
+        let listener_handle = generic_handle.clone();
         let generic_cache = MokaCache::builder()
-            .max_capacity(config.max_capacity)
-            .weigher(|_k, v: &String| -> u32 { v.len().try_into().unwrap_or(u32::MAX) })
-            .time_to_live(Duration::from_secs(3600))
+            .max_capacity(max_capacity)
+            .weigher(|_k, v: &GenericEntry| -> u32 { v.value.len().try_into().unwrap_or(u32::MAX) })
+            .expire_after(GenericExpiry)
+            .eviction_listener(move |key: Arc<String>, _value, cause| {
+                // A replaced entry is still cached under the same key
+                if cause != RemovalCause::Replaced {
+                    untrack_key(&listener_index, &key, |key| {
+                        listener_handle
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .as_ref()
+                            .is_some_and(|cache| cache.contains_key(key))
+                    });
+                }
+            })
             .build();
+        *generic_handle.lock().unwrap_or_else(|e| e.into_inner()) = Some(generic_cache.clone());
 
         Self {
             abi_cache,
             verification_cache,
             generic_cache,
-            config,
+            namespace_index,
+            generic_handle,
+        }
+    }
+
+    /// Number of keys currently tracked for `ns`
+    pub fn namespace_key_count(&self, ns: &str) -> usize {
+        self.namespace_index
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(ns)
+            .map_or(0, HashSet::len)
+    }
+}
+
+impl Drop for MokaBackend {
+    fn drop(&mut self) {
+        self.generic_handle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MokaBackend {
+    async fn get_abi(&self, contract_id: &str) -> Option<String> {
+        self.abi_cache.get(contract_id).await
+    }
+
+    async fn put_abi(&self, contract_id: &str, abi: String) {
+        self.abi_cache.insert(contract_id.to_string(), abi).await;
+    }
+
+    async fn invalidate_abi(&self, contract_id: &str) {
+        self.abi_cache.invalidate(contract_id).await;
+    }
+
+    async fn get_verification(&self, bytecode_hash: &str) -> Option<String> {
+        self.verification_cache.get(bytecode_hash).await
+    }
+
+    async fn put_verification(&self, bytecode_hash: &str, result: String) {
+        self.verification_cache
+            .insert(bytecode_hash.to_string(), result)
+            .await;
+    }
+
+    async fn invalidate_verification(&self, bytecode_hash: &str) {
+        self.verification_cache.invalidate(bytecode_hash).await;
+    }
+
+    async fn get(&self, ns: &str, key: &str) -> Option<String> {
+        let namespaced_key = namespaced_key(ns, key);
+        self.generic_cache
+            .get(&namespaced_key)
+            .await
+            .filter(|entry| !entry.is_expired(Instant::now()))
+            .map(|entry| entry.value)
+    }
+
+    async fn put(&self, ns: &str, key: &str, value: String, ttl: Option<Duration>) {
+        let namespaced_key = namespaced_key(ns, key);
+        let entry = GenericEntry {
+            value,
+            expires_at: ttl.and_then(|ttl| Instant::now().checked_add(ttl)),
+        };
+
+        // Cached before tracked: see `untrack_key`
+        self.generic_cache
+            .insert(namespaced_key.clone(), entry)
+            .await;
+        self.namespace_index
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(ns.to_string())
+            .or_default()
+            .insert(namespaced_key);
+    }
+
+    async fn invalidate(&self, ns: &str, key: &str) {
+        let namespaced_key = namespaced_key(ns, key);
+        self.generic_cache.invalidate(&namespaced_key).await;
+        untrack_key(&self.namespace_index, &namespaced_key, |key| {
+            self.generic_cache.contains_key(key)
+        });
+    }
+
+    async fn invalidate_namespace(&self, ns: &str) -> usize {
+        let keys = self
+            .namespace_index
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(ns)
+            .unwrap_or_default();
+
+        for namespaced_key in &keys {
+            self.generic_cache.invalidate(namespaced_key).await;
         }
+
+        keys.len()
+    }
+
+    fn usage(&self) -> BackendUsage {
+        BackendUsage {
+            abi: CacheUsage {
+                entry_count: self.abi_cache.entry_count(),
+                weighted_size: self.abi_cache.weighted_size(),
+            },
+            verification: CacheUsage {
+                entry_count: self.verification_cache.entry_count(),
+                weighted_size: self.verification_cache.weighted_size(),
+            },
+            generic: CacheUsage {
+                entry_count: self.generic_cache.entry_count(),
+                weighted_size: self.generic_cache.weighted_size(),
+            },
+        }
+    }
+}
+
+/// Point-in-time statistics for a single cache
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CacheStats {
+    pub entry_count: u64,
+    pub weighted_size: u64,
+    pub max_capacity: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_ratio: f64,
+}
+
+impl CacheStats {
+    fn new(
+        entry_count: u64,
+        weighted_size: u64,
+        max_capacity: u64,
+        hits: u64,
+        misses: u64,
+    ) -> Self {
+        let lookups = hits + misses;
+        let hit_ratio = if lookups == 0 {
+            0.0
+        } else {
+            hits as f64 / lookups as f64
+        };
+        Self {
+            entry_count,
+            weighted_size,
+            max_capacity,
+            hits,
+            misses,
+            hit_ratio,
+        }
+    }
+}
+
+/// Statistics for every cache held by the [`CacheLayer`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CacheLayerStats {
+    pub enabled: bool,
+    pub abi: CacheStats,
+    pub verification: CacheStats,
+    pub generic: CacheStats,
+}
+
+pub struct CacheLayer {
+    backend: Arc<dyn CacheBackend>,
+    config: CacheConfig,
+}
+
+impl CacheLayer {
+    pub fn new(config: CacheConfig) -> Self {
+        let backend: Arc<dyn CacheBackend> = match config.backend {
+            CacheBackendKind::Moka => Arc::new(MokaBackend::new(config.max_capacity)),
+            CacheBackendKind::Redis => redis_backend(&config),
+        };
+        Self::with_backend(config, backend)
+    }
+
+    pub fn with_backend(config: CacheConfig, backend: Arc<dyn CacheBackend>) -> Self {
+        Self { backend, config }
     }
 
     pub fn config(&self) -> &CacheConfig {
         &self.config
     }
 
+    /// Snapshot of entry counts, sizes and cumulative hit/miss counters
+    pub fn stats(&self) -> CacheLayerStats {
+        let max_capacity = self.config.max_capacity;
+        let usage = self.backend.usage();
+        CacheLayerStats {
+            enabled: self.config.enabled,
+            abi: CacheStats::new(
+                usage.abi.entry_count,
+                usage.abi.weighted_size,
+                max_capacity,
+                crate::metrics::ABI_CACHE_HITS.get(),
+                crate::metrics::ABI_CACHE_MISSES.get(),
+            ),
+            verification: CacheStats::new(
+                usage.verification.entry_count,
+                usage.verification.weighted_size,
+                max_capacity,
+                crate::metrics::VERIFICATION_CACHE_HITS.get(),
+                crate::metrics::VERIFICATION_CACHE_MISSES.get(),
+            ),
+            generic: CacheStats::new(
+                usage.generic.entry_count,
+                usage.generic.weighted_size,
+                max_capacity,
+                crate::metrics::CACHE_HITS.get(),
+                crate::metrics::CACHE_MISSES.get(),
+            ),
+        }
+    }
+
     pub async fn get_abi(&self, contract_id: &str) -> Option<String> {
+        match self.lookup_abi(contract_id).await {
+            AbiLookup::Hit(abi) => Some(abi),
+            AbiLookup::KnownAbsent | AbiLookup::Miss => None,
+        }
+    }
+
+    /// Like [`Self::get_abi`], but reports a cached not-found marker as
+    /// [`AbiLookup::KnownAbsent`] so the caller can skip the database
+    pub async fn lookup_abi(&self, contract_id: &str) -> AbiLookup {
         if !self.config.enabled {
-            return None;
+            return AbiLookup::Miss;
         }
-        let result = self.abi_cache.get(contract_id).await;
-        if result.is_some() {
-            crate::metrics::ABI_CACHE_HITS.inc();
-        } else {
-            crate::metrics::ABI_CACHE_MISSES.inc();
+        let result = match self.backend.get_abi(contract_id).await {
+            Some(abi) => AbiLookup::Hit(abi),
+            None if self.backend.get(ABI_ABSENT_NS, contract_id).await.is_some() => {
+                AbiLookup::KnownAbsent
+            }
+            None => AbiLookup::Miss,
+        };
+        match result {
+            AbiLookup::Hit(_) => crate::metrics::ABI_CACHE_HITS.inc(),
+            AbiLookup::KnownAbsent => crate::metrics::ABI_CACHE_NEGATIVE_HITS.inc(),
+            AbiLookup::Miss => crate::metrics::ABI_CACHE_MISSES.inc(),
         }
         result
     }
 
+    /// Remember for [`ABI_NEGATIVE_TTL`] that `contract_id` has no ABI, until
+    /// `invalidate_abi` clears the marker
+    pub async fn mark_abi_absent(&self, contract_id: &str) {
+        if !self.config.enabled {
+            return;
+        }
+        self.backend
+            .put(
+                ABI_ABSENT_NS,
+                contract_id,
+                String::new(),
+                Some(ABI_NEGATIVE_TTL),
+            )
+            .await;
+    }
+
     pub async fn put_abi(&self, contract_id: &str, abi: String) {
         if !self.config.enabled {
             return;
         }
-        self.abi_cache.insert(contract_id.to_string(), abi).await;
+        self.backend.put_abi(contract_id, abi).await;
     }
 
     pub async fn invalidate_abi(&self, contract_id: &str) {
         if !self.config.enabled {
             return;
         }
-        self.abi_cache.invalidate(contract_id).await;
+        self.backend.invalidate_abi(contract_id).await;
+        self.backend.invalidate(ABI_ABSENT_NS, contract_id).await;
     }
 
     pub async fn get_verification(&self, bytecode_hash: &str) -> Option<String> {
         if !self.config.enabled {
             return None;
         }
-        let result = self.verification_cache.get(bytecode_hash).await;
+        let result = self.backend.get_verification(bytecode_hash).await;
         if result.is_some() {
             crate::metrics::VERIFICATION_CACHE_HITS.inc();
         } else {
@@ -133,16 +620,14 @@ impl CacheLayer {
         if !self.config.enabled {
             return;
         }
-        self.verification_cache
-            .insert(bytecode_hash.to_string(), result)
-            .await;
+        self.backend.put_verification(bytecode_hash, result).await;
     }
 
     pub async fn invalidate_verification(&self, bytecode_hash: &str) {
         if !self.config.enabled {
             return;
         }
-        self.verification_cache.invalidate(bytecode_hash).await;
+        self.backend.invalidate_verification(bytecode_hash).await;
     }
 
     // Generic cache methods with namespace support
@@ -150,17 +635,16 @@ impl CacheLayer {
         if !self.config.enabled {
             return (None, false);
         }
-        
-        let namespaced_key = format!("{}:{}", ns, key);
-        let result = self.generic_cache.get(&namespaced_key).await;
+
+        let result = self.backend.get(ns, key).await;
         let hit = result.is_some();
-        
+
         if hit {
             crate::metrics::CACHE_HITS.inc();
         } else {
             crate::metrics::CACHE_MISSES.inc();
         }
-        
+
         (result, hit)
     }
 
@@ -168,22 +652,57 @@ impl CacheLayer {
         if !self.config.enabled {
             return;
         }
-        
-        let namespaced_key = format!("{}:{}", ns, key);
-        
-        // Note: moka doesn't support per-entry TTL easily, so we use the cache-wide TTL
-        // For custom TTL support, we'd need to use entry_by_ref with expiration policy
-        // For now, we'll insert with the default TTL configured for generic_cache
-        self.generic_cache.insert(namespaced_key, value).await;
+        self.backend.put(ns, key, value, ttl).await;
     }
 
     pub async fn invalidate(&self, ns: &str, key: &str) {
         if !self.config.enabled {
             return;
         }
-        
-        let namespaced_key = format!("{}:{}", ns, key);
-        self.generic_cache.invalidate(&namespaced_key).await;
+        self.backend.invalidate(ns, key).await;
+    }
+
+    /// Drops every generic cache entry stored under `ns`, returning how many keys were invalidated
+    pub async fn invalidate_namespace(&self, ns: &str) -> usize {
+        if !self.config.enabled {
+            return 0;
+        }
+        self.backend.invalidate_namespace(ns).await
+    }
+
+    /// Drops everything cached about one contract in `namespaces` (a subset of
+    /// [`CONTRACT_CACHE_NAMESPACES`]), returning the namespaces cleared. The
+    /// dependency graph is global, so it is dropped whole.
+    pub async fn purge_contract(
+        &self,
+        keys: &ContractCacheKeys,
+        namespaces: &[&'static str],
+    ) -> Vec<&'static str> {
+        for ns in namespaces {
+            match *ns {
+                "abi" => {
+                    for selector in keys.ids.iter().chain(&keys.abi_selectors) {
+                        self.invalidate_abi(selector).await;
+                    }
+                }
+                // Formal verification reports are cached by contract UUID
+                "verification" => {
+                    for key in keys.ids.iter().chain(&keys.wasm_hashes) {
+                        self.invalidate_verification(key).await;
+                    }
+                }
+                "dependency_graph" => {
+                    self.invalidate(DEPENDENCY_GRAPH_NS, DEPENDENCY_GRAPH_KEY)
+                        .await;
+                }
+                generic => {
+                    for id in &keys.ids {
+                        self.invalidate(generic, id).await;
+                    }
+                }
+            }
+        }
+        namespaces.to_vec()
     }
 
     /// Starts an asynchronous startup warmup task querying the top 100 contracts
@@ -209,41 +728,206 @@ impl CacheLayer {
             .unwrap_or_default();
 
             for (id, contract_id, wasm_hash) in top_contracts {
-                if let Ok(Some(abi)) = sqlx::query_scalar::<_, serde_json::Value>(
-                    "SELECT abi FROM contract_abis WHERE contract_id = $1 ORDER BY created_at DESC LIMIT 1"
-                )
-                .bind(&id)
-                .fetch_optional(&pool).await {
-                    self.abi_cache.insert(contract_id.clone(), abi.to_string()).await;
-                }
-
-                if let Some(w_hash) = wasm_hash {
-                    if let Ok(Some(ver_res)) = sqlx::query_scalar::<_, String>(
-                        "SELECT status::text FROM formal_verification_results LIMIT 1", // fallback fake
-                    )
-                    .fetch_optional(&pool)
-                    .await
-                    {
-                        self.verification_cache
-                            .insert(w_hash.clone(), ver_res)
-                            .await;
-                    }
-                }
+                self.warm_contract(&pool, id, &contract_id, wasm_hash.as_deref())
+                    .await;
             }
             tracing::info!("Completed startup cache warmup.");
         });
     }
+
+    /// Loads one contract's latest ABI and, while the contract still runs
+    /// `wasm_hash`, its latest verification
+    async fn warm_contract(
+        &self,
+        pool: &PgPool,
+        id: uuid::Uuid,
+        contract_id: &str,
+        wasm_hash: Option<&str>,
+    ) {
+        if let Ok(Some(abi)) = sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT abi FROM contract_abis WHERE contract_id = $1 ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        {
+            self.backend.put_abi(contract_id, abi.to_string()).await;
+        }
+
+        let Some(wasm_hash) = wasm_hash else {
+            return;
+        };
+        let latest: Option<(shared::Network, String)> = sqlx::query_as(
+            r#"
+            SELECT c.network, v.status::text
+            FROM verifications v
+            JOIN contracts c ON c.id = v.contract_id
+            WHERE c.id = $1 AND c.wasm_hash = $2
+            ORDER BY v.created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(id)
+        .bind(wasm_hash)
+        .fetch_optional(pool)
+        .await
+        .unwrap_or_default();
+
+        if let Some(entry) = latest
+            .and_then(|(network, status)| warmed_verification(id, &network, wasm_hash, &status))
+        {
+            self.backend
+                .put_verification(&verification_key(id, wasm_hash), entry)
+                .await;
+        }
+    }
+}
+
+/// Verification cache key for `contract_uuid`'s verdict against the WASM with
+/// `wasm_hash`. The verdict depends on the contract's registered source as
+/// well as the bytecode, so contracts sharing a WASM don't share a verdict.
+pub fn verification_key(contract_uuid: uuid::Uuid, wasm_hash: &str) -> String {
+    format!("{}:{}", contract_uuid, wasm_hash)
+}
+
+/// Verification cache entry for a contract whose latest verification of
+/// `wasm_hash` has `status`, shaped like the on-chain verify response. Only a
+/// passed verification is warmed: a failed one lacks the compiled hash and
+/// message the response carries, so it is left to be recomputed.
+fn warmed_verification(
+    contract_uuid: uuid::Uuid,
+    network: &shared::Network,
+    wasm_hash: &str,
+    status: &str,
+) -> Option<String> {
+    if status != "verified" {
+        return None;
+    }
+    let entry = serde_json::json!({
+        "verified": true,
+        "status": "verified",
+        "contract_id": contract_uuid,
+        "network": network,
+        "compiled_wasm_hash": wasm_hash,
+        "deployed_wasm_hash": wasm_hash,
+        "message": null,
+    });
+    Some(entry.to_string())
+}
+
+#[cfg(feature = "redis")]
+fn redis_backend(config: &CacheConfig) -> Arc<dyn CacheBackend> {
+    let url = config.redis_url.as_deref().unwrap_or("redis://127.0.0.1:6379");
+    match crate::redis_cache::RedisBackend::new(url) {
+        Ok(backend) => Arc::new(backend),
+        Err(err) => {
+            tracing::error!(error = %err, "Invalid REDIS_URL, falling back to in-process cache");
+            Arc::new(MokaBackend::new(config.max_capacity))
+        }
+    }
+}
+
+#[cfg(not(feature = "redis"))]
+fn redis_backend(config: &CacheConfig) -> Arc<dyn CacheBackend> {
+    tracing::warn!(
+        "CACHE_BACKEND=redis requires the `redis` feature; falling back to in-process cache"
+    );
+    Arc::new(MokaBackend::new(config.max_capacity))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn capacity_accepts_bare_numbers() {
+        assert_eq!(parse_capacity("10000"), Some(10_000));
+    }
+
+    #[test]
+    fn capacity_accepts_size_suffixes() {
+        assert_eq!(parse_capacity("512MB"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_capacity("10 GB"), Some(10 * 1024 * 1024 * 1024));
+        assert_eq!(parse_capacity("64kb"), Some(64 * 1024));
+    }
+
+    #[test]
+    fn capacity_rejects_garbage() {
+        assert_eq!(parse_capacity("bogus"), None);
+        assert_eq!(parse_capacity("10 TB"), None);
+        assert_eq!(parse_capacity(""), None);
+    }
+
+    #[tokio::test]
+    async fn warm_up_caches_the_verification_of_the_contracts_own_hash() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let wasm_hash = uuid::Uuid::new_v4().simple().to_string();
+        let id = crate::test_support::seed_contract(&db, &wasm_hash).await;
+        let contract_id: String =
+            sqlx::query_scalar("SELECT contract_id FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        sqlx::query(
+            "INSERT INTO contract_abis (contract_id, version, abi) VALUES ($1, '1.0.0', '[]')",
+        )
+        .bind(id)
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO verifications (contract_id, status) VALUES ($1, 'verified')")
+            .bind(id)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let cache = CacheLayer::new(CacheConfig::default());
+        cache
+            .warm_contract(&db, id, &contract_id, Some(&wasm_hash))
+            .await;
+
+        assert_eq!(cache.get_abi(&contract_id).await, Some("[]".to_string()));
+        let cached: serde_json::Value = serde_json::from_str(
+            &cache
+                .get_verification(&verification_key(id, &wasm_hash))
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(cached["verified"], true);
+        assert_eq!(cached["deployed_wasm_hash"], wasm_hash);
+        assert_eq!(cached["compiled_wasm_hash"], wasm_hash);
+        assert_eq!(cached["contract_id"], id.to_string());
+        assert_eq!(cached["network"], "testnet");
+
+        // A verification of a WASM the contract no longer runs is not warmed
+        let stale = CacheLayer::new(CacheConfig::default());
+        stale
+            .warm_contract(&db, id, &contract_id, Some("old"))
+            .await;
+        assert!(stale
+            .get_verification(&verification_key(id, "old"))
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn warm_up_skips_unverified_contracts() {
+        let id = uuid::Uuid::new_v4();
+        for status in ["pending", "failed"] {
+            assert!(warmed_verification(id, &shared::Network::Mainnet, "ab", status).is_none());
+        }
+    }
+
     #[tokio::test]
     async fn test_abi_cache() {
         let config = CacheConfig {
             enabled: true,
             max_capacity: 100,
+            ..CacheConfig::default()
         };
         let cache = CacheLayer::new(config);
 
@@ -258,11 +942,50 @@ mod tests {
         assert!(val2.is_none());
     }
 
+    #[tokio::test]
+    async fn absent_abi_is_remembered_until_invalidated() {
+        let cache = CacheLayer::new(CacheConfig::default());
+
+        cache.mark_abi_absent("CNOABI").await;
+        assert_eq!(cache.lookup_abi("CNOABI").await, AbiLookup::KnownAbsent);
+        assert!(cache.get_abi("CNOABI").await.is_none());
+
+        cache.invalidate_abi("CNOABI").await;
+        assert_eq!(cache.lookup_abi("CNOABI").await, AbiLookup::Miss);
+
+        cache.put_abi("CNOABI", "[]".to_string()).await;
+        assert_eq!(
+            cache.lookup_abi("CNOABI").await,
+            AbiLookup::Hit("[]".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn absent_marker_expires_after_the_negative_ttl() {
+        let config = CacheConfig::default();
+        let backend = Arc::new(MokaBackend::new(config.max_capacity));
+        let cache = CacheLayer::with_backend(config, backend.clone());
+
+        let marked_at = Instant::now();
+        cache.mark_abi_absent("CNOABI").await;
+
+        let marker = backend
+            .generic_cache
+            .get(&namespaced_key(ABI_ABSENT_NS, "CNOABI"))
+            .await
+            .unwrap();
+        let expires_at = marker.expires_at.unwrap();
+        assert!(expires_at <= Instant::now() + ABI_NEGATIVE_TTL);
+        assert!(expires_at >= marked_at + ABI_NEGATIVE_TTL);
+        assert!(marker.is_expired(expires_at));
+    }
+
     #[tokio::test]
     async fn test_verification_cache() {
         let config = CacheConfig {
             enabled: true,
             max_capacity: 100,
+            ..CacheConfig::default()
         };
         let cache = CacheLayer::new(config);
 
@@ -284,6 +1007,7 @@ mod tests {
         let config = CacheConfig {
             enabled: false,
             max_capacity: 100,
+            ..CacheConfig::default()
         };
         let cache = CacheLayer::new(config);
 
@@ -301,6 +1025,7 @@ mod tests {
         let config = CacheConfig {
             enabled: true,
             max_capacity: 100,
+            ..CacheConfig::default()
         };
         let cache = CacheLayer::new(config);
 
@@ -325,11 +1050,60 @@ mod tests {
         assert!(!hit3);
     }
 
+    #[tokio::test]
+    async fn purging_a_contract_misses_in_every_namespace() {
+        let cache = CacheLayer::new(CacheConfig {
+            enabled: true,
+            max_capacity: 100,
+            ..CacheConfig::default()
+        });
+        let keys = ContractCacheKeys {
+            ids: vec!["uuid-1".to_string(), "CABC".to_string()],
+            abi_selectors: vec!["CABC@1.0.0".to_string()],
+            wasm_hashes: vec!["hash-1".to_string()],
+        };
+
+        for selector in ["uuid-1", "CABC", "CABC@1.0.0"] {
+            cache.put_abi(selector, "abi".to_string()).await;
+        }
+        cache.put_verification("uuid-1", "report".to_string()).await;
+        cache.put_verification("hash-1", "result".to_string()).await;
+        cache
+            .put("dependency_updates", "uuid-1", "updates".to_string(), None)
+            .await;
+        cache
+            .put("on_chain_status", "uuid-1", "status".to_string(), None)
+            .await;
+        cache
+            .put(
+                DEPENDENCY_GRAPH_NS,
+                DEPENDENCY_GRAPH_KEY,
+                "graph".to_string(),
+                None,
+            )
+            .await;
+        cache.put_abi("other", "abi".to_string()).await;
+
+        let cleared = cache.purge_contract(&keys, CONTRACT_CACHE_NAMESPACES).await;
+        assert_eq!(cleared, CONTRACT_CACHE_NAMESPACES);
+
+        for selector in ["uuid-1", "CABC", "CABC@1.0.0"] {
+            assert!(cache.get_abi(selector).await.is_none());
+        }
+        assert!(cache.get_verification("uuid-1").await.is_none());
+        assert!(cache.get_verification("hash-1").await.is_none());
+        assert!(!cache.get("dependency_updates", "uuid-1").await.1);
+        assert!(!cache.get("on_chain_status", "uuid-1").await.1);
+        assert!(!cache.get(DEPENDENCY_GRAPH_NS, DEPENDENCY_GRAPH_KEY).await.1);
+        assert!(cache.get_abi("other").await.is_some());
+    }
+
     #[tokio::test]
     async fn test_generic_cache_namespace_isolation() {
         let config = CacheConfig {
             enabled: true,
             max_capacity: 100,
+            ..CacheConfig::default()
         };
         let cache = CacheLayer::new(config);
 
@@ -357,11 +1131,169 @@ mod tests {
         assert_eq!(val2_after, Some("value_ns2".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_cache_stats_report_entries() {
+        let config = CacheConfig {
+            enabled: true,
+            max_capacity: 100,
+            ..CacheConfig::default()
+        };
+        let backend = Arc::new(MokaBackend::new(config.max_capacity));
+        let cache = CacheLayer::with_backend(config, backend.clone());
+
+        cache.put_abi("contract_1", "abi".to_string()).await;
+        backend.abi_cache.run_pending_tasks().await;
+
+        let stats = cache.stats();
+        assert!(stats.enabled);
+        assert_eq!(stats.abi.entry_count, 1);
+        assert_eq!(stats.abi.weighted_size, 3);
+        assert_eq!(stats.abi.max_capacity, 100);
+        assert_eq!(stats.generic.entry_count, 0);
+    }
+
+    #[test]
+    fn test_cache_stats_hit_ratio() {
+        assert_eq!(CacheStats::new(0, 0, 10, 0, 0).hit_ratio, 0.0);
+        assert_eq!(CacheStats::new(0, 0, 10, 3, 1).hit_ratio, 0.75);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_namespace() {
+        let config = CacheConfig {
+            enabled: true,
+            max_capacity: 100,
+            ..CacheConfig::default()
+        };
+        let backend = Arc::new(MokaBackend::new(config.max_capacity));
+        let cache = CacheLayer::with_backend(config, backend.clone());
+
+        cache.put("graph", "a", "1".to_string(), None).await;
+        cache.put("graph", "b", "2".to_string(), None).await;
+        cache.put("other", "a", "3".to_string(), None).await;
+        assert_eq!(backend.namespace_key_count("graph"), 2);
+
+        assert_eq!(cache.invalidate_namespace("graph").await, 2);
+        assert!(cache.get("graph", "a").await.0.is_none());
+        assert!(cache.get("graph", "b").await.0.is_none());
+        assert_eq!(cache.get("other", "a").await.0, Some("3".to_string()));
+        assert_eq!(backend.namespace_key_count("graph"), 0);
+        assert_eq!(cache.invalidate_namespace("graph").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_namespace_index_follows_evictions() {
+        let config = CacheConfig {
+            enabled: true,
+            max_capacity: 100,
+            ..CacheConfig::default()
+        };
+        let backend = Arc::new(MokaBackend::new(config.max_capacity));
+        let cache = CacheLayer::with_backend(config, backend.clone());
+
+        cache.put("graph", "a", "1".to_string(), None).await;
+        cache.put("graph", "a", "2".to_string(), None).await;
+        backend.generic_cache.run_pending_tasks().await;
+        assert_eq!(backend.namespace_key_count("graph"), 1);
+
+        // Removal that bypasses CacheLayer still updates the index via the listener
+        backend
+            .generic_cache
+            .invalidate(&namespaced_key("graph", "a"))
+            .await;
+        backend.generic_cache.run_pending_tasks().await;
+        assert_eq!(backend.namespace_key_count("graph"), 0);
+    }
+
+    #[tokio::test]
+    async fn namespaces_may_contain_colons() {
+        let backend = MokaBackend::new(100);
+        backend.put("graph:v2", "a", "1".to_string(), None).await;
+        backend.put("graph", "v2:a", "2".to_string(), None).await;
+        assert_eq!(backend.namespace_key_count("graph:v2"), 1);
+        assert_eq!(backend.namespace_key_count("graph"), 1);
+
+        backend.invalidate("graph:v2", "a").await;
+        backend.generic_cache.run_pending_tasks().await;
+        assert_eq!(backend.namespace_key_count("graph:v2"), 0);
+        assert_eq!(backend.namespace_key_count("graph"), 1);
+        assert_eq!(backend.invalidate_namespace("graph").await, 1);
+        assert!(backend.get("graph", "v2:a").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn keys_stored_again_stay_tracked_when_the_old_entry_expires() {
+        let backend = MokaBackend::new(100);
+        backend
+            .put(
+                "graph",
+                "a",
+                "1".to_string(),
+                Some(Duration::from_millis(50)),
+            )
+            .await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        backend.put("graph", "a", "2".to_string(), None).await;
+        backend.generic_cache.run_pending_tasks().await;
+        assert_eq!(backend.namespace_key_count("graph"), 1);
+        assert_eq!(backend.invalidate_namespace("graph").await, 1);
+        assert!(backend.get("graph", "a").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn generic_entries_expire_at_their_own_ttl() {
+        let cache = CacheLayer::new(CacheConfig::default());
+
+        cache
+            .put(
+                "dependency_graph",
+                "short",
+                "graph".to_string(),
+                Some(Duration::from_millis(50)),
+            )
+            .await;
+        cache
+            .put(
+                "dependency_graph",
+                "long",
+                "abi".to_string(),
+                Some(Duration::from_secs(10)),
+            )
+            .await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(cache.get("dependency_graph", "short").await, (None, false));
+        assert_eq!(
+            cache.get("dependency_graph", "long").await,
+            (Some("abi".to_string()), true)
+        );
+    }
+
+    #[test]
+    fn entries_without_a_deadline_use_the_generic_ttl() {
+        let now = Instant::now();
+        let entry = GenericEntry {
+            value: String::new(),
+            expires_at: None,
+        };
+        assert!(!entry.is_expired(now + GENERIC_TTL * 2));
+        assert_eq!(entry.time_to_live(now), GENERIC_TTL);
+
+        let entry = GenericEntry {
+            expires_at: Some(now),
+            ..entry
+        };
+        assert!(entry.is_expired(now));
+        assert_eq!(entry.time_to_live(now + GENERIC_TTL), Duration::ZERO);
+    }
+
     #[tokio::test]
     async fn test_generic_cache_disabled() {
         let config = CacheConfig {
             enabled: false,
             max_capacity: 100,
+            ..CacheConfig::default()
         };
         let cache = CacheLayer::new(config);
 
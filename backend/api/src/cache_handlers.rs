@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    cache::{CacheLayerStats, ContractCacheKeys, CONTRACT_CACHE_NAMESPACES},
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct PurgeContractCacheQuery {
+    /// Comma-separated subset of the contract cache namespaces; all when omitted
+    pub namespaces: Option<String>,
+}
+
+/// GET /api/admin/cache/stats — entry counts, sizes and hit/miss counters per cache
+pub async fn get_cache_stats(State(state): State<AppState>) -> Json<CacheLayerStats> {
+    Json(state.cache.stats())
+}
+
+/// POST /api/admin/cache/invalidate/:namespace — drop every generic cache entry in a namespace
+pub async fn invalidate_cache_namespace(
+    State(state): State<AppState>,
+    Path(namespace): Path<String>,
+) -> Json<Value> {
+    let invalidated = state.cache.invalidate_namespace(&namespace).await;
+    tracing::info!(namespace = %namespace, invalidated, "cache namespace invalidated");
+
+    Json(json!({
+        "namespace": namespace,
+        "invalidated": invalidated,
+    }))
+}
+
+/// POST /api/admin/contracts/:id/cache/purge — drop everything cached about a contract
+/// (ABIs, verification results, dependency graph and updates, on-chain status)
+pub async fn purge_contract_cache(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<PurgeContractCacheQuery>,
+) -> ApiResult<Json<Value>> {
+    let namespaces = parse_namespaces(query.namespaces.as_deref())?;
+
+    let (contract_uuid, contract_id, wasm_hash) =
+        sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+            "SELECT id, contract_id, wasm_hash FROM contracts WHERE id::text = $1 OR contract_id = $1",
+        )
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| {
+            ApiError::not_found("ContractNotFound", format!("Contract '{}' not found", id))
+        })?;
+
+    let versions = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT id, version, wasm_hash FROM contract_versions WHERE contract_id = $1",
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+
+    let mut keys = ContractCacheKeys {
+        ids: vec![contract_uuid.to_string(), contract_id.clone()],
+        abi_selectors: Vec::new(),
+        wasm_hashes: wasm_hash.into_iter().collect(),
+    };
+    for (version_id, version, version_hash) in versions {
+        keys.abi_selectors.extend([
+            version_id.to_string(),
+            format!("{}@{}", contract_id, version),
+            format!("{}@{}", contract_uuid, version),
+        ]);
+        keys.wasm_hashes.push(version_hash);
+    }
+    keys.wasm_hashes.sort();
+    keys.wasm_hashes.dedup();
+
+    let cleared = state.cache.purge_contract(&keys, &namespaces).await;
+    tracing::info!(contract_id = %contract_id, namespaces = ?cleared, "contract cache purged");
+
+    Ok(Json(json!({
+        "contract_id": contract_id,
+        "namespaces_cleared": cleared,
+    })))
+}
+
+fn parse_namespaces(raw: Option<&str>) -> ApiResult<Vec<&'static str>> {
+    let Some(raw) = raw else {
+        return Ok(CONTRACT_CACHE_NAMESPACES.to_vec());
+    };
+
+    raw.split(',')
+        .map(str::trim)
+        .filter(|ns| !ns.is_empty())
+        .map(|ns| {
+            CONTRACT_CACHE_NAMESPACES
+                .iter()
+                .find(|known| **known == ns)
+                .copied()
+                .ok_or_else(|| {
+                    ApiError::bad_request(
+                        "InvalidNamespace",
+                        format!(
+                            "Unknown cache namespace '{}'; expected one of {}",
+                            ns,
+                            CONTRACT_CACHE_NAMESPACES.join(", ")
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,118 @@
+// api/src/canary_events.rs
+// In-process fan-out of newly recorded canary metrics to live dashboard streams.
+
+use shared::models::CanaryMetric;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Metrics a slow subscriber may fall behind by before it starts skipping
+const CHANNEL_CAPACITY: usize = 64;
+
+/// One broadcast channel per canary that currently has subscribers
+#[derive(Default)]
+pub struct CanaryEventHub {
+    channels: Mutex<HashMap<Uuid, broadcast::Sender<CanaryMetric>>>,
+}
+
+impl CanaryEventHub {
+    /// Receive metrics published for the canary from now on. Channels left
+    /// without subscribers, by streams that ended, are dropped on the way.
+    pub fn subscribe(&self, canary_id: Uuid) -> broadcast::Receiver<CanaryMetric> {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        channels
+            .entry(canary_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Send `metric` to everyone watching its canary. Channels whose
+    /// subscribers have all gone away are dropped.
+    pub fn publish(&self, metric: &CanaryMetric) {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sender) = channels.get(&metric.canary_id) {
+            if sender.send(metric.clone()).is_err() {
+                channels.remove(&metric.canary_id);
+            }
+        }
+    }
+
+    /// End every stream for a canary, e.g. once it reaches a terminal status
+    pub fn close(&self, canary_id: Uuid) {
+        self.channels
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&canary_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    fn metric(canary_id: Uuid) -> CanaryMetric {
+        CanaryMetric {
+            id: Uuid::new_v4(),
+            canary_id,
+            timestamp: Utc::now(),
+            requests: 100,
+            errors: 2,
+            error_rate: Decimal::from(2),
+            avg_response_time_ms: None,
+            p95_response_time_ms: None,
+            p99_response_time_ms: None,
+            business_metrics: None,
+            baseline: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_receives_published_metrics() {
+        let hub = CanaryEventHub::default();
+        let canary_id = Uuid::new_v4();
+        let mut first = hub.subscribe(canary_id);
+        let mut second = hub.subscribe(canary_id);
+
+        let recorded = metric(canary_id);
+        hub.publish(&recorded);
+        hub.publish(&metric(Uuid::new_v4()));
+
+        assert_eq!(first.recv().await.unwrap().id, recorded.id);
+        assert_eq!(second.recv().await.unwrap().id, recorded.id);
+        assert!(first.try_recv().is_err());
+    }
+
+    #[test]
+    fn channels_without_subscribers_are_dropped() {
+        let hub = CanaryEventHub::default();
+        let (ended, watched) = (Uuid::new_v4(), Uuid::new_v4());
+        drop(hub.subscribe(ended));
+        let rx = hub.subscribe(watched);
+        assert_eq!(
+            hub.channels.lock().unwrap().keys().collect::<Vec<_>>(),
+            vec![&watched]
+        );
+
+        drop(rx);
+        hub.publish(&metric(watched));
+        assert!(hub.channels.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn closing_a_canary_ends_its_streams() {
+        let hub = CanaryEventHub::default();
+        let canary_id = Uuid::new_v4();
+        let mut rx = hub.subscribe(canary_id);
+
+        hub.close(canary_id);
+
+        assert!(matches!(
+            rx.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+}
@@ -1,17 +1,30 @@
 use axum::{
-    extract::{Json, Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{rejection::JsonRejection, Json, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
 };
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde_json::{json, Value};
 use shared::models::{
-    AdvanceCanaryRequest, CanaryMetric, CanaryRelease, CreateCanaryRequest,
-    RecordCanaryMetricRequest,
+    AdvanceCanaryRequest, CanaryBusinessMetric, CanaryMetric, CanaryRelease, CanaryStatus,
+    CanaryStageTransition, CreateCanaryRequest, RecordCanaryMetricRequest, RolloutStage,
 };
+use shared::pagination::{next_cursor, push_page, Cursor};
+use sqlx::{PgConnection, QueryBuilder};
+use std::{collections::BTreeMap, convert::Infallible, time::Duration};
+use tokio::sync::broadcast;
+use tokio_stream::Stream;
 use uuid::Uuid;
 
 use crate::{
+    activity_events::{ActivityEvent, ActivityKind},
     error::{ApiError, ApiResult},
+    handlers::map_json_rejection,
+    idempotency::{self, Begun},
     state::AppState,
 };
 
@@ -23,6 +36,8 @@ pub struct ListCanaryQuery {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Opaque keyset cursor from a previous page's `next_cursor`; takes precedence over `offset`
+    pub cursor: Option<String>,
     pub status: Option<String>,
 }
 
@@ -30,17 +45,176 @@ fn default_limit() -> i64 {
     20
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct CanaryHistoryQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+// ───────────────────── Responses ─────────────────────
+
+#[derive(Debug, serde::Serialize)]
+pub struct BusinessMetricSummary {
+    pub samples: usize,
+    pub mean: f64,
+    /// Mean of the control values, over the samples that reported one
+    pub baseline_mean: Option<f64>,
+    /// Change of `mean` relative to `baseline_mean`, in percent
+    pub relative_change_pct: Option<f64>,
+}
+
+/// A latency threshold exceeded by a recorded canary sample
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct LatencyBreach {
+    /// `p95` or `p99`
+    pub threshold: &'static str,
+    pub threshold_ms: f64,
+    pub observed_ms: f64,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RecordCanaryMetricResponse {
+    #[serde(flatten)]
+    pub metric: CanaryMetric,
+    /// Latency thresholds the sample exceeded while the canary was active
+    pub latency_breaches: Vec<LatencyBreach>,
+    /// Status the canary was moved to because of a breach, if any
+    pub canary_status: Option<CanaryStatus>,
+    /// Whether this sample pushed the canary's error rate over its threshold
+    /// and rolled it back
+    pub auto_rolled_back: bool,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CanaryAnalysis {
+    pub canary_id: Uuid,
+    pub samples: usize,
+    pub total_requests: i64,
+    pub total_errors: i64,
+    pub error_rate: f64,
+    pub avg_response_time_ms: Option<f64>,
+    pub business_metrics: BTreeMap<String, BusinessMetricSummary>,
+}
+
+/// Aggregated technical metrics over one time window
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct WindowStats {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub samples: usize,
+    pub total_requests: i64,
+    pub error_rate: Option<f64>,
+    pub avg_response_time_ms: Option<f64>,
+    pub p95_response_time_ms: Option<f64>,
+    pub p99_response_time_ms: Option<f64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MetricComparison {
+    pub metric: &'static str,
+    pub canary: Option<f64>,
+    pub baseline: Option<f64>,
+    /// `canary - baseline`
+    pub delta: Option<f64>,
+    /// `delta` relative to the baseline, in percent
+    pub delta_pct: Option<f64>,
+    /// `None` when either window has no data for the metric
+    pub passed: Option<bool>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct CanaryComparison {
+    pub canary_id: Uuid,
+    pub from_deployment_id: Uuid,
+    pub to_deployment_id: Uuid,
+    pub canary: WindowStats,
+    pub baseline: WindowStats,
+    /// Largest degradation, in percent of the baseline, a metric may show and still pass
+    pub tolerance_pct: f64,
+    pub metrics: Vec<MetricComparison>,
+    /// Whether p95 response time degraded beyond `tolerance_pct`
+    pub p95_regression: bool,
+    /// `pass`, `fail`, or `insufficient_data` when either window has no samples
+    pub verdict: &'static str,
+}
+
+/// Totals and rolled-up latency percentiles over a canary's window
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct CanaryMetricsSummary {
+    pub canary_id: Uuid,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub samples: i64,
+    pub total_requests: i64,
+    pub total_errors: i64,
+    /// Errors per request, in percent; `None` when no requests were recorded
+    pub error_rate: Option<f64>,
+    /// Mean of the recorded p95s, weighted by each sample's request count
+    pub avg_p95_response_time_ms: Option<f64>,
+    pub max_p95_response_time_ms: Option<f64>,
+    /// Mean of the recorded p99s, weighted by each sample's request count
+    pub avg_p99_response_time_ms: Option<f64>,
+    pub max_p99_response_time_ms: Option<f64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CanaryMetricsTotals {
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    samples: i64,
+    total_requests: i64,
+    total_errors: i64,
+    avg_p95_response_time_ms: Option<f64>,
+    max_p95_response_time_ms: Option<f64>,
+    avg_p99_response_time_ms: Option<f64>,
+    max_p99_response_time_ms: Option<f64>,
+}
+
+/// Default for `CANARY_REGRESSION_TOLERANCE_PCT`
+const DEFAULT_REGRESSION_TOLERANCE_PCT: f64 = 10.0;
+
+/// How much worse than the baseline a canary metric may be before it fails
+static REGRESSION_TOLERANCE_PCT: once_cell::sync::Lazy<f64> = once_cell::sync::Lazy::new(|| {
+    std::env::var("CANARY_REGRESSION_TOLERANCE_PCT")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .unwrap_or(DEFAULT_REGRESSION_TOLERANCE_PCT)
+});
+
+/// Default for `CANARY_ROLLBACK_MIN_REQUESTS`
+const DEFAULT_ROLLBACK_MIN_REQUESTS: i32 = 100;
+
+/// Requests a canary must have served before its error rate can roll it back
+static ROLLBACK_MIN_REQUESTS: once_cell::sync::Lazy<i32> = once_cell::sync::Lazy::new(|| {
+    std::env::var("CANARY_ROLLBACK_MIN_REQUESTS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_ROLLBACK_MIN_REQUESTS)
+});
+
 // ───────────────────── Handlers ─────────────────────
 
 /// POST /api/contracts/:id/canary — create a new canary release
 pub async fn create_canary(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
-    Json(req): Json<CreateCanaryRequest>,
+    payload: Result<Json<CreateCanaryRequest>, JsonRejection>,
 ) -> ApiResult<impl IntoResponse> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let to_deployment_uuid = parse_uuid(&req.to_deployment_id, "to_deployment")?;
-    let threshold = req.error_rate_threshold.unwrap_or(5.0);
+    let threshold = to_decimal(
+        req.error_rate_threshold.unwrap_or(5.0),
+        "error_rate_threshold",
+    )?;
+    let p95_threshold =
+        latency_threshold(req.p95_latency_threshold_ms, "p95_latency_threshold_ms")?;
+    let p99_threshold =
+        latency_threshold(req.p99_latency_threshold_ms, "p99_latency_threshold_ms")?;
 
     // Ensure no other active/pending canary for this contract
     let existing: Option<(Uuid,)> = sqlx::query_as(
@@ -60,15 +234,20 @@ pub async fn create_canary(
 
     let release: CanaryRelease = sqlx::query_as(
         r#"
-        INSERT INTO canary_releases (contract_id, to_deployment_id, error_rate_threshold, created_by)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO canary_releases
+            (contract_id, to_deployment_id, error_rate_threshold, created_by,
+             p95_latency_threshold_ms, p99_latency_threshold_ms, auto_rollback_on_latency)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING *
         "#,
     )
     .bind(contract_uuid)
     .bind(to_deployment_uuid)
-    .bind(rust_decimal::Decimal::try_from(threshold).unwrap_or_default())
+    .bind(threshold)
     .bind(req.created_by.as_deref())
+    .bind(p95_threshold)
+    .bind(p99_threshold)
+    .bind(req.auto_rollback_on_latency)
     .fetch_one(&state.db)
     .await
     .map_err(|e| db_err("create canary release", e))?;
@@ -84,53 +263,38 @@ pub async fn list_canaries(
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
-
-    let (releases, total): (Vec<CanaryRelease>, i64) = if let Some(ref status) = params.status {
-        let items: Vec<CanaryRelease> = sqlx::query_as(
-            "SELECT * FROM canary_releases WHERE contract_id = $1 AND status::text = $2 ORDER BY started_at DESC LIMIT $3 OFFSET $4",
-        )
-        .bind(contract_uuid)
-        .bind(status)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await
-        .map_err(|e| db_err("list canaries", e))?;
-
-        let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM canary_releases WHERE contract_id = $1 AND status::text = $2",
-        )
-        .bind(contract_uuid)
-        .bind(status)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| db_err("count canaries", e))?;
-
-        (items, count)
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
     } else {
-        let items: Vec<CanaryRelease> = sqlx::query_as(
-            "SELECT * FROM canary_releases WHERE contract_id = $1 ORDER BY started_at DESC LIMIT $2 OFFSET $3",
-        )
-        .bind(contract_uuid)
-        .bind(limit)
-        .bind(offset)
+        params.offset.max(0)
+    };
+
+    let mut query = QueryBuilder::new("SELECT * FROM canary_releases WHERE contract_id = ");
+    query.push_bind(contract_uuid);
+    if let Some(ref status) = params.status {
+        query.push(" AND status::text = ").push_bind(status);
+    }
+    push_page(&mut query, cursor.as_ref(), "started_at", limit, offset);
+    let releases: Vec<CanaryRelease> = query
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_err("list canaries", e))?;
 
-        let count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM canary_releases WHERE contract_id = $1",
-        )
-        .bind(contract_uuid)
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM canary_releases WHERE contract_id = ");
+    count.push_bind(contract_uuid);
+    if let Some(ref status) = params.status {
+        count.push(" AND status::text = ").push_bind(status);
+    }
+    let total: i64 = count
+        .build_query_scalar()
         .fetch_one(&state.db)
         .await
         .map_err(|e| db_err("count canaries", e))?;
 
-        (items, count)
-    };
-
     Ok(Json(json!({
+        "next_cursor": next_cursor(&releases, limit, |r| (r.started_at, r.id)),
         "items": releases,
         "total": total,
         "limit": limit,
@@ -165,8 +329,9 @@ pub async fn get_canary(
 pub async fn advance_canary(
     State(state): State<AppState>,
     Path(canary_id): Path<String>,
-    Json(req): Json<AdvanceCanaryRequest>,
+    payload: Result<Json<AdvanceCanaryRequest>, JsonRejection>,
 ) -> ApiResult<Json<CanaryRelease>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let canary_uuid = parse_uuid(&canary_id, "canary")?;
 
     let current: CanaryRelease =
@@ -219,13 +384,21 @@ pub async fn advance_canary(
         "#,
     )
     .bind(canary_uuid)
-    .bind(current.current_stage)
+    .bind(&current.current_stage)
     .bind(&updated.current_stage)
     .bind(current.current_percentage)
     .bind(next_percentage)
     .bind(req.advanced_by.as_deref())
     .execute(&state.db)
     .await;
+    publish_transition(
+        &state,
+        &updated,
+        &updated.status,
+        stage_name(&current.current_stage),
+        stage_name(&updated.current_stage),
+        req.advanced_by.as_deref(),
+    );
 
     Ok(Json(updated))
 }
@@ -255,6 +428,28 @@ pub async fn rollback_canary(
         ),
         _ => db_err("rollback canary", e),
     })?;
+    state.canary_events.close(canary_uuid);
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO canary_stage_history
+            (canary_id, from_stage, to_stage, from_percentage, to_percentage, transitioned_by)
+        VALUES ($1, $2, 'complete', $3, 0, 'manual-rollback')
+        "#,
+    )
+    .bind(canary_uuid)
+    .bind(&release.current_stage)
+    .bind(release.current_percentage)
+    .execute(&state.db)
+    .await;
+    publish_transition(
+        &state,
+        &release,
+        &release.status,
+        stage_name(&release.current_stage),
+        "complete",
+        Some("manual-rollback"),
+    );
 
     Ok(Json(release))
 }
@@ -284,6 +479,15 @@ pub async fn complete_canary(
         ),
         _ => db_err("complete canary", e),
     })?;
+    state.canary_events.close(canary_uuid);
+    publish_transition(
+        &state,
+        &release,
+        &release.status,
+        stage_name(&release.current_stage),
+        "complete",
+        None,
+    );
 
     Ok(Json(release))
 }
@@ -292,36 +496,119 @@ pub async fn complete_canary(
 pub async fn record_canary_metric(
     State(state): State<AppState>,
     Path(canary_id): Path<String>,
-    Json(req): Json<RecordCanaryMetricRequest>,
-) -> ApiResult<impl IntoResponse> {
+    headers: HeaderMap,
+    payload: Result<Json<RecordCanaryMetricRequest>, JsonRejection>,
+) -> ApiResult<Response> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let canary_uuid = parse_uuid(&canary_id, "canary")?;
+    let key = idempotency::key_from_headers(&headers)?;
+    let scope = format!("canary_metric:{}", canary_uuid);
+    let mut write = match idempotency::begin(&state.db, &scope, key).await? {
+        Begun::Replay(response) => return Ok(response),
+        Begun::Write(write) => write,
+    };
+    let (recorded, transition) = insert_canary_metric(write.conn(), canary_uuid, req).await?;
+    let response = write.commit(StatusCode::CREATED, &recorded).await?;
+
+    // Only announce what was committed
+    state.canary_events.publish(&recorded.metric);
+    if let Some(transition) = transition {
+        announce_gate_transition(&state, &transition);
+    }
+    Ok(response)
+}
+
+/// Record the sample, update the canary's totals and apply its gates, all on
+/// `conn`. Returns the response and any gate transition to announce.
+async fn insert_canary_metric(
+    conn: &mut PgConnection,
+    canary_uuid: Uuid,
+    req: RecordCanaryMetricRequest,
+) -> ApiResult<(RecordCanaryMetricResponse, Option<GateTransition>)> {
     let error_rate = if req.requests > 0 {
         (req.errors as f64 / req.requests as f64) * 100.0
     } else {
         0.0
     };
+    let error_rate = to_decimal(error_rate, "error_rate")?;
+    let avg_response_time_ms = req
+        .avg_response_time_ms
+        .map(|v| to_decimal(v, "avg_response_time_ms"))
+        .transpose()?;
+    let p95_response_time_ms = req
+        .p95_response_time_ms
+        .map(|v| to_decimal(v, "p95_response_time_ms"))
+        .transpose()?;
+    let p99_response_time_ms = req
+        .p99_response_time_ms
+        .map(|v| to_decimal(v, "p99_response_time_ms"))
+        .transpose()?;
+    if req
+        .business_metrics
+        .keys()
+        .any(|name| name.trim().is_empty())
+    {
+        return Err(ApiError::bad_request(
+            "InvalidBusinessMetric",
+            "Business metric names must not be empty",
+        ));
+    }
+    let business_metrics = (!req.business_metrics.is_empty())
+        .then(|| serde_json::to_value(&req.business_metrics))
+        .transpose()
+        .map_err(|e| ApiError::internal(format!("Failed to encode business metrics: {}", e)))?;
+
+    if req.baseline {
+        let from_deployment_id: Option<Option<Uuid>> =
+            sqlx::query_scalar("SELECT from_deployment_id FROM canary_releases WHERE id = $1")
+                .bind(canary_uuid)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| db_err("check canary baseline deployment", e))?;
+        if matches!(from_deployment_id, Some(None)) {
+            return Err(ApiError::unprocessable(
+                "NoBaselineDeployment",
+                "Canary has no from_deployment to record baseline samples for",
+            ));
+        }
+    }
 
     let metric: CanaryMetric = sqlx::query_as(
         r#"
         INSERT INTO canary_metrics
-            (canary_id, requests, errors, error_rate, avg_response_time_ms, p95_response_time_ms, p99_response_time_ms)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+            (canary_id, requests, errors, error_rate, avg_response_time_ms, p95_response_time_ms, p99_response_time_ms, business_metrics, baseline)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         RETURNING *
         "#,
     )
     .bind(canary_uuid)
     .bind(req.requests)
     .bind(req.errors)
-    .bind(rust_decimal::Decimal::try_from(error_rate).unwrap_or_default())
-    .bind(req.avg_response_time_ms.map(|v| rust_decimal::Decimal::try_from(v).unwrap_or_default()))
-    .bind(req.p95_response_time_ms.map(|v| rust_decimal::Decimal::try_from(v).unwrap_or_default()))
-    .bind(req.p99_response_time_ms.map(|v| rust_decimal::Decimal::try_from(v).unwrap_or_default()))
-    .fetch_one(&state.db)
+    .bind(error_rate)
+    .bind(avg_response_time_ms)
+    .bind(p95_response_time_ms)
+    .bind(p99_response_time_ms)
+    .bind(&business_metrics)
+    .bind(req.baseline)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| db_err("record canary metric", e))?;
 
-    // Update aggregate counts on the canary release
-    let _ = sqlx::query(
+    // Baseline samples describe the deployment being replaced, so they count
+    // towards neither the canary's totals nor its gates
+    if metric.baseline {
+        let response = RecordCanaryMetricResponse {
+            metric,
+            latency_breaches: Vec::new(),
+            canary_status: None,
+            auto_rolled_back: false,
+        };
+        return Ok((response, None));
+    }
+
+    // Update aggregate counts on the canary release; the error rate gate
+    // below reads the recomputed totals
+    let release: Option<CanaryRelease> = sqlx::query_as(
         r#"
         UPDATE canary_releases
         SET total_requests = total_requests + $2,
@@ -332,15 +619,40 @@ pub async fn record_canary_metric(
                 ELSE 0.0
             END
         WHERE id = $1
+        RETURNING *
         "#,
     )
     .bind(canary_uuid)
     .bind(req.requests)
     .bind(req.errors)
-    .execute(&state.db)
-    .await;
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| db_err("update canary totals", e))?;
+
+    let (latency_breaches, transition, auto_rolled_back) = match release {
+        Some(release) if matches!(release.status, CanaryStatus::Active) => {
+            let breaches = latency_breaches(&release, &metric);
+            if exceeds_error_rate(&release, *ROLLBACK_MIN_REQUESTS) {
+                let transition = rollback_on_error_rate(conn, release).await?;
+                let rolled_back = transition.is_some();
+                (breaches, transition, rolled_back)
+            } else if breaches.is_empty() {
+                (breaches, None, false)
+            } else {
+                let transition = gate_on_latency(conn, release, &breaches).await?;
+                (breaches, transition, false)
+            }
+        }
+        _ => (Vec::new(), None, false),
+    };
 
-    Ok((StatusCode::CREATED, Json(metric)))
+    let response = RecordCanaryMetricResponse {
+        metric,
+        latency_breaches,
+        canary_status: transition.as_ref().map(|t| t.status.clone()),
+        auto_rolled_back,
+    };
+    Ok((response, transition))
 }
 
 /// GET /api/canary/:canary_id/metrics — list canary metrics
@@ -351,17 +663,21 @@ pub async fn list_canary_metrics(
 ) -> ApiResult<Json<Value>> {
     let canary_uuid = parse_uuid(&canary_id, "canary")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.max(0)
+    };
 
-    let metrics: Vec<CanaryMetric> = sqlx::query_as(
-        "SELECT * FROM canary_metrics WHERE canary_id = $1 ORDER BY timestamp DESC LIMIT $2 OFFSET $3",
-    )
-    .bind(canary_uuid)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| db_err("list canary metrics", e))?;
+    let mut query = QueryBuilder::new("SELECT * FROM canary_metrics WHERE canary_id = ");
+    query.push_bind(canary_uuid);
+    push_page(&mut query, cursor.as_ref(), "timestamp", limit, offset);
+    let metrics: Vec<CanaryMetric> = query
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_err("list canary metrics", e))?;
 
     let total: i64 =
         sqlx::query_scalar("SELECT COUNT(*) FROM canary_metrics WHERE canary_id = $1")
@@ -371,6 +687,7 @@ pub async fn list_canary_metrics(
             .map_err(|e| db_err("count canary metrics", e))?;
 
     Ok(Json(json!({
+        "next_cursor": next_cursor(&metrics, limit, |m| (m.timestamp, m.id)),
         "items": metrics,
         "total": total,
         "limit": limit,
@@ -378,8 +695,751 @@ pub async fn list_canary_metrics(
     })))
 }
 
+/// GET /api/canary/:canary_id/metrics/summary — request and error totals plus
+/// the request-weighted average and max p95/p99 across the canary window
+pub async fn get_canary_metrics_summary(
+    State(state): State<AppState>,
+    Path(canary_id): Path<String>,
+) -> ApiResult<Json<CanaryMetricsSummary>> {
+    let canary_uuid = parse_uuid(&canary_id, "canary")?;
+
+    let totals: CanaryMetricsTotals = sqlx::query_as(
+        r#"
+        SELECT
+            r.started_at AS window_start,
+            COALESCE(r.completed_at, NOW()) AS window_end,
+            COUNT(m.id) AS samples,
+            COALESCE(SUM(m.requests), 0)::BIGINT AS total_requests,
+            COALESCE(SUM(m.errors), 0)::BIGINT AS total_errors,
+            (SUM(m.p95_response_time_ms * m.requests)
+                / NULLIF(SUM(m.requests) FILTER (WHERE m.p95_response_time_ms IS NOT NULL), 0)
+            )::DOUBLE PRECISION AS avg_p95_response_time_ms,
+            MAX(m.p95_response_time_ms)::DOUBLE PRECISION AS max_p95_response_time_ms,
+            (SUM(m.p99_response_time_ms * m.requests)
+                / NULLIF(SUM(m.requests) FILTER (WHERE m.p99_response_time_ms IS NOT NULL), 0)
+            )::DOUBLE PRECISION AS avg_p99_response_time_ms,
+            MAX(m.p99_response_time_ms)::DOUBLE PRECISION AS max_p99_response_time_ms
+        FROM canary_releases r
+        LEFT JOIN canary_metrics m
+            ON m.canary_id = r.id
+            AND NOT m.baseline
+            AND m.timestamp >= r.started_at
+            AND m.timestamp <= COALESCE(r.completed_at, NOW())
+        WHERE r.id = $1
+        GROUP BY r.id
+        "#,
+    )
+    .bind(canary_uuid)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("summarize canary metrics", e))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "CanaryNotFound",
+            format!("No canary release found with ID: {}", canary_id),
+        )
+    })?;
+
+    Ok(Json(metrics_summary(canary_uuid, totals)))
+}
+
+/// GET /api/canary/:canary_id/history — stage transitions in the order they
+/// happened, including automatic rollbacks and pauses
+pub async fn list_canary_history(
+    State(state): State<AppState>,
+    Path(canary_id): Path<String>,
+    Query(params): Query<CanaryHistoryQuery>,
+) -> ApiResult<Json<Value>> {
+    let canary_uuid = parse_uuid(&canary_id, "canary")?;
+    let limit = params.limit.clamp(1, 100);
+    let offset = params.offset.max(0);
+
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM canary_stage_history WHERE canary_id = $1")
+            .bind(canary_uuid)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| db_err("count canary stage history", e))?;
+
+    if total == 0 {
+        let exists: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM canary_releases WHERE id = $1")
+                .bind(canary_uuid)
+                .fetch_optional(&state.db)
+                .await
+                .map_err(|e| db_err("check canary for history", e))?;
+        if exists.is_none() {
+            return Err(ApiError::not_found(
+                "CanaryNotFound",
+                format!("No canary release found with ID: {}", canary_id),
+            ));
+        }
+    }
+
+    let transitions: Vec<CanaryStageTransition> = sqlx::query_as(
+        "SELECT * FROM canary_stage_history WHERE canary_id = $1 \
+         ORDER BY transitioned_at ASC, id ASC LIMIT $2 OFFSET $3",
+    )
+    .bind(canary_uuid)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list canary stage history", e))?;
+
+    Ok(Json(json!({
+        "items": transitions,
+        "total": total,
+        "limit": limit,
+        "offset": offset,
+    })))
+}
+
+/// GET /api/canary/:canary_id/analysis — technical and business metrics aggregated
+/// over every canary sample recorded for the canary
+pub async fn get_canary_analysis(
+    State(state): State<AppState>,
+    Path(canary_id): Path<String>,
+) -> ApiResult<Json<CanaryAnalysis>> {
+    let canary_uuid = parse_uuid(&canary_id, "canary")?;
+
+    let metrics: Vec<CanaryMetric> = sqlx::query_as(
+        "SELECT * FROM canary_metrics WHERE canary_id = $1 AND NOT baseline ORDER BY timestamp",
+    )
+    .bind(canary_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch canary metrics for analysis", e))?;
+
+    if metrics.is_empty() {
+        let exists: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM canary_releases WHERE id = $1")
+                .bind(canary_uuid)
+                .fetch_optional(&state.db)
+                .await
+                .map_err(|e| db_err("check canary for analysis", e))?;
+        if exists.is_none() {
+            return Err(ApiError::not_found(
+                "CanaryNotFound",
+                format!("No canary release found with ID: {}", canary_id),
+            ));
+        }
+    }
+
+    Ok(Json(analyze_canary(canary_uuid, &metrics)))
+}
+
+/// GET /api/canary/:canary_id/comparison — the canary window's metrics next to
+/// the baseline samples recorded for the deployment it replaces over the same
+/// window
+pub async fn get_canary_comparison(
+    State(state): State<AppState>,
+    Path(canary_id): Path<String>,
+) -> ApiResult<Json<CanaryComparison>> {
+    let canary_uuid = parse_uuid(&canary_id, "canary")?;
+
+    let release: CanaryRelease = sqlx::query_as("SELECT * FROM canary_releases WHERE id = $1")
+        .bind(canary_uuid)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ApiError::not_found(
+                "CanaryNotFound",
+                format!("No canary release found with ID: {}", canary_id),
+            ),
+            _ => db_err("fetch canary for comparison", e),
+        })?;
+    let Some(from_deployment_id) = release.from_deployment_id else {
+        return Err(ApiError::unprocessable(
+            "NoBaselineDeployment",
+            "Canary has no from_deployment to compare against",
+        ));
+    };
+
+    let canary_end = release.completed_at.unwrap_or_else(Utc::now);
+
+    // Both sides come from the same window, so load and time of day affect
+    // them alike
+    let metrics: Vec<CanaryMetric> = sqlx::query_as(
+        "SELECT * FROM canary_metrics \
+         WHERE canary_id = $1 AND timestamp >= $2 AND timestamp <= $3 ORDER BY timestamp",
+    )
+    .bind(canary_uuid)
+    .bind(release.started_at)
+    .bind(canary_end)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch canary metrics for comparison", e))?;
+    let (baseline_metrics, canary_metrics): (Vec<_>, Vec<_>) =
+        metrics.into_iter().partition(|m| m.baseline);
+
+    Ok(Json(compare_canary(
+        &release,
+        from_deployment_id,
+        window_stats(release.started_at, canary_end, &canary_metrics),
+        window_stats(release.started_at, canary_end, &baseline_metrics),
+        *REGRESSION_TOLERANCE_PCT,
+    )))
+}
+
+/// An automatic status change made by a metric gate, announced once the
+/// transaction that made it commits
+struct GateTransition {
+    release: CanaryRelease,
+    status: CanaryStatus,
+    to_stage: &'static str,
+    transitioned_by: &'static str,
+}
+
+/// Roll back or pause an active canary whose latest sample breached a latency
+/// threshold. `None` means another request changed the canary's status first.
+async fn gate_on_latency(
+    conn: &mut PgConnection,
+    release: CanaryRelease,
+    breaches: &[LatencyBreach],
+) -> ApiResult<Option<GateTransition>> {
+    let (update, transitioned_by, to_stage, to_percentage) = if release.auto_rollback_on_latency {
+        (
+            "UPDATE canary_releases SET status = 'rolled_back', completed_at = NOW() \
+             WHERE id = $1 AND status = 'active' RETURNING status",
+            "auto-rollback",
+            "complete",
+            0,
+        )
+    } else {
+        (
+            "UPDATE canary_releases SET status = 'paused' \
+             WHERE id = $1 AND status = 'active' RETURNING status",
+            "latency-gate",
+            stage_name(&release.current_stage),
+            release.current_percentage,
+        )
+    };
+
+    let status: Option<CanaryStatus> = sqlx::query_scalar(update)
+        .bind(release.id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| db_err("apply canary latency gate", e))?;
+    let Some(status) = status else {
+        return Ok(None);
+    };
+
+    tracing::warn!(
+        canary_id = %release.id,
+        status = ?status,
+        breaches = ?breaches,
+        "canary breached a latency threshold"
+    );
+    record_gate_history(
+        conn,
+        &release,
+        to_stage,
+        to_percentage,
+        transitioned_by,
+        json!({ "latency_breaches": breaches }),
+    )
+    .await?;
+    Ok(Some(GateTransition {
+        release,
+        status,
+        to_stage,
+        transitioned_by,
+    }))
+}
+
+/// Roll back an active canary whose error rate exceeded its threshold.
+/// `None` means another request changed the canary's status first.
+async fn rollback_on_error_rate(
+    conn: &mut PgConnection,
+    release: CanaryRelease,
+) -> ApiResult<Option<GateTransition>> {
+    let status: Option<CanaryStatus> = sqlx::query_scalar(
+        "UPDATE canary_releases SET status = 'rolled_back', completed_at = NOW() \
+         WHERE id = $1 AND status = 'active' RETURNING status",
+    )
+    .bind(release.id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| db_err("roll back canary on error rate", e))?;
+    let Some(status) = status else {
+        return Ok(None);
+    };
+
+    tracing::warn!(
+        canary_id = %release.id,
+        error_rate = ?release.current_error_rate,
+        threshold = %release.error_rate_threshold,
+        total_requests = release.total_requests,
+        "canary exceeded its error rate threshold"
+    );
+    record_gate_history(
+        conn,
+        &release,
+        "complete",
+        0,
+        "auto-rollback",
+        json!({
+            "error_rate": release.current_error_rate,
+            "error_rate_threshold": release.error_rate_threshold,
+            "total_requests": release.total_requests,
+            "error_count": release.error_count,
+        }),
+    )
+    .await?;
+    Ok(Some(GateTransition {
+        release,
+        status,
+        to_stage: "complete",
+        transitioned_by: "auto-rollback",
+    }))
+}
+
+/// Record an automatic move of `release` in its stage history
+async fn record_gate_history(
+    conn: &mut PgConnection,
+    release: &CanaryRelease,
+    to_stage: &str,
+    to_percentage: i32,
+    transitioned_by: &str,
+    metrics: Value,
+) -> ApiResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO canary_stage_history
+            (canary_id, from_stage, to_stage, from_percentage, to_percentage, transitioned_by, metrics_at_transition)
+        VALUES ($1, $2, $3::rollout_stage, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(release.id)
+    .bind(&release.current_stage)
+    .bind(to_stage)
+    .bind(release.current_percentage)
+    .bind(to_percentage)
+    .bind(transitioned_by)
+    .bind(metrics)
+    .execute(conn)
+    .await
+    .map_err(|e| db_err("record canary gate transition", e))?;
+    Ok(())
+}
+
+/// Announce a committed gate transition and end the canary's live stream
+/// when it is terminal
+fn announce_gate_transition(state: &AppState, transition: &GateTransition) {
+    let release = &transition.release;
+    publish_transition(
+        state,
+        release,
+        &transition.status,
+        stage_name(&release.current_stage),
+        transition.to_stage,
+        Some(transition.transitioned_by),
+    );
+
+    if is_terminal(&transition.status) {
+        state.canary_events.close(release.id);
+    }
+}
+
+/// Announce a canary stage or status change on the activity feed
+fn publish_transition(
+    state: &AppState,
+    release: &CanaryRelease,
+    status: &CanaryStatus,
+    from_stage: &str,
+    to_stage: &str,
+    transitioned_by: Option<&str>,
+) {
+    state.activity_events.publish(ActivityEvent::new(
+        ActivityKind::CanaryTransition,
+        Some(release.contract_id),
+        json!({
+            "canary_id": release.id,
+            "status": status,
+            "from_stage": from_stage,
+            "to_stage": to_stage,
+            "transitioned_by": transitioned_by,
+        }),
+    ));
+}
+
+/// Interval between release snapshots on a live canary stream
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+
+struct CanaryStream {
+    state: AppState,
+    canary_id: Uuid,
+    metrics: broadcast::Receiver<CanaryMetric>,
+    snapshots: tokio::time::Interval,
+    finished: bool,
+}
+
+/// GET /api/canary/:canary_id/stream — live canary metrics and release snapshots over SSE.
+/// The stream ends once the canary reaches a terminal status.
+pub async fn stream_canary(
+    State(state): State<AppState>,
+    Path(canary_id): Path<String>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let canary_uuid = parse_uuid(&canary_id, "canary")?;
+
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM canary_releases WHERE id = $1")
+        .bind(canary_uuid)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("check canary for stream", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "CanaryNotFound",
+                format!("No canary release found with ID: {}", canary_id),
+            )
+        })?;
+
+    // A metric recorded since the check is still reflected in the first
+    // snapshot, which the interval sends at once
+    let metrics = state.canary_events.subscribe(canary_uuid);
+    let stream = CanaryStream {
+        state,
+        canary_id: canary_uuid,
+        metrics,
+        snapshots: tokio::time::interval(SNAPSHOT_INTERVAL),
+        finished: false,
+    };
+    let events = futures::stream::unfold(stream, |mut stream| async move {
+        let event = next_canary_event(&mut stream).await?;
+        Some((Ok(event), stream))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn next_canary_event(stream: &mut CanaryStream) -> Option<Event> {
+    if stream.finished {
+        return None;
+    }
+    loop {
+        tokio::select! {
+            received = stream.metrics.recv() => match received {
+                Ok(metric) => return Some(json_event("metric", &metric)),
+                // A slow client skips the metrics it missed; the next snapshot catches it up
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                // Closed when the canary is completed or rolled back
+                Err(broadcast::error::RecvError::Closed) => {
+                    stream.finished = true;
+                    return Some(snapshot_event(stream).await);
+                }
+            },
+            _ = stream.snapshots.tick() => return Some(snapshot_event(stream).await),
+        }
+    }
+}
+
+async fn snapshot_event(stream: &mut CanaryStream) -> Event {
+    let release: Result<CanaryRelease, _> =
+        sqlx::query_as("SELECT * FROM canary_releases WHERE id = $1")
+            .bind(stream.canary_id)
+            .fetch_one(&stream.state.db)
+            .await;
+
+    match release {
+        Ok(release) => {
+            if is_terminal(&release.status) {
+                stream.finished = true;
+            }
+            json_event(
+                "snapshot",
+                &json!({
+                    "canary_id": release.id,
+                    "status": release.status,
+                    "current_stage": release.current_stage,
+                    "current_percentage": release.current_percentage,
+                    "current_error_rate": release.current_error_rate,
+                    "total_requests": release.total_requests,
+                    "error_count": release.error_count,
+                }),
+            )
+        }
+        Err(e) => {
+            tracing::error!(canary_id = %stream.canary_id, error = ?e, "canary stream snapshot failed");
+            stream.finished = true;
+            Event::default()
+                .event("error")
+                .data("Failed to load canary release")
+        }
+    }
+}
+
+fn json_event(name: &str, data: &impl serde::Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|_| Event::default().event("error"))
+}
+
 // ───────────────────── Helpers ─────────────────────
 
+fn analyze_canary(canary_id: Uuid, metrics: &[CanaryMetric]) -> CanaryAnalysis {
+    let total_requests: i64 = metrics.iter().map(|m| i64::from(m.requests)).sum();
+    let total_errors: i64 = metrics.iter().map(|m| i64::from(m.errors)).sum();
+    let error_rate = if total_requests > 0 {
+        total_errors as f64 / total_requests as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    let response_times: Vec<f64> = metrics
+        .iter()
+        .filter_map(|m| m.avg_response_time_ms.and_then(|v| v.to_f64()))
+        .collect();
+
+    let mut recorded: BTreeMap<String, Vec<CanaryBusinessMetric>> = BTreeMap::new();
+    for metric in metrics {
+        let Some(values) = metric.business_metrics.clone() else {
+            continue;
+        };
+        let Ok(values) = serde_json::from_value::<BTreeMap<String, CanaryBusinessMetric>>(values)
+        else {
+            continue;
+        };
+        for (name, value) in values {
+            recorded.entry(name).or_default().push(value);
+        }
+    }
+
+    let business_metrics = recorded
+        .into_iter()
+        .map(|(name, values)| {
+            let baseline_mean = mean(values.iter().filter_map(|v| v.baseline));
+            let canary_mean = mean(values.iter().map(|v| v.value)).unwrap_or(0.0);
+            let relative_change_pct = baseline_mean
+                .filter(|baseline| *baseline != 0.0)
+                .map(|baseline| (canary_mean - baseline) / baseline.abs() * 100.0);
+            let summary = BusinessMetricSummary {
+                samples: values.len(),
+                mean: canary_mean,
+                baseline_mean,
+                relative_change_pct,
+            };
+            (name, summary)
+        })
+        .collect();
+
+    CanaryAnalysis {
+        canary_id,
+        samples: metrics.len(),
+        total_requests,
+        total_errors,
+        error_rate,
+        avg_response_time_ms: mean(response_times.into_iter()),
+        business_metrics,
+    }
+}
+
+fn metrics_summary(canary_id: Uuid, totals: CanaryMetricsTotals) -> CanaryMetricsSummary {
+    CanaryMetricsSummary {
+        canary_id,
+        window_start: totals.window_start,
+        window_end: totals.window_end,
+        samples: totals.samples,
+        total_requests: totals.total_requests,
+        total_errors: totals.total_errors,
+        error_rate: (totals.total_requests > 0)
+            .then(|| totals.total_errors as f64 / totals.total_requests as f64 * 100.0),
+        avg_p95_response_time_ms: totals.avg_p95_response_time_ms,
+        max_p95_response_time_ms: totals.max_p95_response_time_ms,
+        avg_p99_response_time_ms: totals.avg_p99_response_time_ms,
+        max_p99_response_time_ms: totals.max_p99_response_time_ms,
+    }
+}
+
+/// Request-weighted error rate and mean response times over a window's samples
+fn window_stats(start: DateTime<Utc>, end: DateTime<Utc>, metrics: &[CanaryMetric]) -> WindowStats {
+    let total_requests: i64 = metrics.iter().map(|m| i64::from(m.requests)).sum();
+    let total_errors: i64 = metrics.iter().map(|m| i64::from(m.errors)).sum();
+    let response_time = |field: fn(&CanaryMetric) -> Option<Decimal>| {
+        mean(metrics.iter().filter_map(|m| field(m)?.to_f64()))
+    };
+
+    WindowStats {
+        start,
+        end,
+        samples: metrics.len(),
+        total_requests,
+        error_rate: (total_requests > 0)
+            .then(|| total_errors as f64 / total_requests as f64 * 100.0),
+        avg_response_time_ms: response_time(|m| m.avg_response_time_ms),
+        p95_response_time_ms: response_time(|m| m.p95_response_time_ms),
+        p99_response_time_ms: response_time(|m| m.p99_response_time_ms),
+    }
+}
+
+/// Compare one lower-is-better metric; it fails when the canary is more than
+/// `tolerance_pct` percent worse than the baseline.
+fn compare_metric(
+    metric: &'static str,
+    canary: Option<f64>,
+    baseline: Option<f64>,
+    tolerance_pct: f64,
+) -> MetricComparison {
+    let (delta, delta_pct, passed) = match (canary, baseline) {
+        (Some(canary), Some(baseline)) => {
+            let delta = canary - baseline;
+            let delta_pct = (baseline != 0.0).then(|| delta / baseline.abs() * 100.0);
+            let passed = canary <= baseline + baseline.abs() * tolerance_pct / 100.0;
+            (Some(delta), delta_pct, Some(passed))
+        }
+        _ => (None, None, None),
+    };
+
+    MetricComparison {
+        metric,
+        canary,
+        baseline,
+        delta,
+        delta_pct,
+        passed,
+    }
+}
+
+fn compare_canary(
+    release: &CanaryRelease,
+    from_deployment_id: Uuid,
+    canary: WindowStats,
+    baseline: WindowStats,
+    tolerance_pct: f64,
+) -> CanaryComparison {
+    let metrics = vec![
+        compare_metric(
+            "error_rate",
+            canary.error_rate,
+            baseline.error_rate,
+            tolerance_pct,
+        ),
+        compare_metric(
+            "avg_response_time_ms",
+            canary.avg_response_time_ms,
+            baseline.avg_response_time_ms,
+            tolerance_pct,
+        ),
+        compare_metric(
+            "p95_response_time_ms",
+            canary.p95_response_time_ms,
+            baseline.p95_response_time_ms,
+            tolerance_pct,
+        ),
+        compare_metric(
+            "p99_response_time_ms",
+            canary.p99_response_time_ms,
+            baseline.p99_response_time_ms,
+            tolerance_pct,
+        ),
+    ];
+
+    let p95_regression = metrics
+        .iter()
+        .any(|m| m.metric == "p95_response_time_ms" && m.passed == Some(false));
+    let verdict = if canary.samples == 0 || baseline.samples == 0 {
+        "insufficient_data"
+    } else if metrics.iter().any(|m| m.passed == Some(false)) {
+        "fail"
+    } else {
+        "pass"
+    };
+
+    CanaryComparison {
+        canary_id: release.id,
+        from_deployment_id,
+        to_deployment_id: release.to_deployment_id,
+        canary,
+        baseline,
+        tolerance_pct,
+        metrics,
+        p95_regression,
+        verdict,
+    }
+}
+
+/// Whether `release` has served at least `min_requests` requests and its
+/// cumulative error rate is above its threshold
+fn exceeds_error_rate(release: &CanaryRelease, min_requests: i32) -> bool {
+    release.total_requests >= min_requests
+        && release
+            .current_error_rate
+            .is_some_and(|rate| rate > release.error_rate_threshold)
+}
+
+/// Latency thresholds configured on `release` that `metric` exceeds
+fn latency_breaches(release: &CanaryRelease, metric: &CanaryMetric) -> Vec<LatencyBreach> {
+    [
+        (
+            "p95",
+            release.p95_latency_threshold_ms,
+            metric.p95_response_time_ms,
+        ),
+        (
+            "p99",
+            release.p99_latency_threshold_ms,
+            metric.p99_response_time_ms,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(threshold, limit, observed)| {
+        let (limit, observed) = (limit?.to_f64()?, observed?.to_f64()?);
+        (observed > limit).then_some(LatencyBreach {
+            threshold,
+            threshold_ms: limit,
+            observed_ms: observed,
+        })
+    })
+    .collect()
+}
+
+fn latency_threshold(value: Option<f64>, field: &str) -> Result<Option<Decimal>, ApiError> {
+    value
+        .map(|v| {
+            if v <= 0.0 {
+                return Err(ApiError::bad_request(
+                    "InvalidThreshold",
+                    format!("{} must be greater than zero", field),
+                ));
+            }
+            to_decimal(v, field)
+        })
+        .transpose()
+}
+
+/// Database name of a rollout stage
+fn stage_name(stage: &RolloutStage) -> &'static str {
+    match stage {
+        RolloutStage::Stage1 => "stage_1",
+        RolloutStage::Stage2 => "stage_2",
+        RolloutStage::Stage3 => "stage_3",
+        RolloutStage::Stage4 => "stage_4",
+        RolloutStage::Complete => "complete",
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Whether a canary has finished rolling out, one way or another
+fn is_terminal(status: &CanaryStatus) -> bool {
+    matches!(
+        status,
+        CanaryStatus::Completed | CanaryStatus::RolledBack | CanaryStatus::Failed
+    )
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, ApiError> {
+    cursor
+        .map(|c| {
+            Cursor::decode(c).map_err(|e| ApiError::bad_request("InvalidCursor", e.to_string()))
+        })
+        .transpose()
+}
+
 fn parse_uuid(id: &str, label: &str) -> Result<Uuid, ApiError> {
     Uuid::parse_str(id).map_err(|_| {
         ApiError::bad_request(
@@ -389,9 +1449,17 @@ fn parse_uuid(id: &str, label: &str) -> Result<Uuid, ApiError> {
     })
 }
 
+fn to_decimal(value: f64, field: &str) -> Result<Decimal, ApiError> {
+    Decimal::try_from(value).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidNumber",
+            format!("{} must be a finite number in range, got {}", field, value),
+        )
+    })
+}
+
 fn db_err(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = operation, error = ?err, "database operation failed");
-    ApiError::internal("An unexpected database error occurred")
+    ApiError::database(operation, err)
 }
 
 fn advance_stage(
@@ -410,3 +1478,499 @@ fn advance_stage(
         _ => ("stage_2", target_override.unwrap_or(10)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample(requests: i32, errors: i32, business_metrics: Option<Value>) -> CanaryMetric {
+        CanaryMetric {
+            id: Uuid::new_v4(),
+            canary_id: Uuid::nil(),
+            timestamp: Utc::now(),
+            requests,
+            errors,
+            error_rate: Decimal::ZERO,
+            avg_response_time_ms: Some(Decimal::from(100)),
+            p95_response_time_ms: None,
+            p99_response_time_ms: None,
+            business_metrics,
+            baseline: false,
+        }
+    }
+
+    #[test]
+    fn business_metrics_appear_in_analysis() {
+        let metrics = vec![
+            sample(
+                100,
+                1,
+                Some(json!({ "conversion_rate": { "value": 0.12, "baseline": 0.10 } })),
+            ),
+            sample(
+                100,
+                3,
+                Some(json!({ "conversion_rate": { "value": 0.14, "baseline": 0.10 } })),
+            ),
+            sample(50, 0, None),
+        ];
+
+        let analysis = analyze_canary(Uuid::nil(), &metrics);
+
+        assert_eq!(analysis.total_requests, 250);
+        assert_eq!(analysis.total_errors, 4);
+        let conversion = &analysis.business_metrics["conversion_rate"];
+        assert_eq!(conversion.samples, 2);
+        assert!((conversion.mean - 0.13).abs() < 1e-9);
+        assert_eq!(conversion.baseline_mean, Some(0.10));
+        assert!((conversion.relative_change_pct.unwrap() - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn business_metric_without_baseline_has_no_change() {
+        let metrics = vec![sample(10, 0, Some(json!({ "signups": { "value": 4.0 } })))];
+
+        let signups = &analyze_canary(Uuid::nil(), &metrics).business_metrics["signups"];
+
+        assert_eq!(signups.mean, 4.0);
+        assert_eq!(signups.baseline_mean, None);
+        assert_eq!(signups.relative_change_pct, None);
+    }
+
+    fn latency_sample(requests: i32, errors: i32, p95: i64) -> CanaryMetric {
+        CanaryMetric {
+            p95_response_time_ms: Some(Decimal::from(p95)),
+            ..sample(requests, errors, None)
+        }
+    }
+
+    fn release() -> CanaryRelease {
+        CanaryRelease {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            from_deployment_id: Some(Uuid::new_v4()),
+            to_deployment_id: Uuid::new_v4(),
+            status: CanaryStatus::Active,
+            current_stage: RolloutStage::Stage2,
+            current_percentage: 10,
+            target_percentage: 100,
+            error_rate_threshold: Decimal::from(5),
+            current_error_rate: None,
+            total_requests: 0,
+            error_count: 0,
+            started_at: Utc::now(),
+            completed_at: None,
+            created_by: None,
+            p95_latency_threshold_ms: None,
+            p99_latency_threshold_ms: None,
+            auto_rollback_on_latency: false,
+        }
+    }
+
+    fn compare(canary: &[CanaryMetric], baseline: &[CanaryMetric]) -> CanaryComparison {
+        let release = release();
+        let now = Utc::now();
+        compare_canary(
+            &release,
+            release.from_deployment_id.unwrap(),
+            window_stats(now, now, canary),
+            window_stats(now, now, baseline),
+            10.0,
+        )
+    }
+
+    #[test]
+    fn window_error_rate_is_weighted_by_requests() {
+        let now = Utc::now();
+        let stats = window_stats(now, now, &[sample(900, 9, None), sample(100, 11, None)]);
+
+        assert_eq!(stats.total_requests, 1000);
+        assert_eq!(stats.error_rate, Some(2.0));
+        assert_eq!(stats.avg_response_time_ms, Some(100.0));
+        assert_eq!(stats.p95_response_time_ms, None);
+    }
+
+    #[test]
+    fn p95_degradation_beyond_tolerance_is_a_regression() {
+        let baseline = [latency_sample(100, 1, 200), latency_sample(100, 1, 200)];
+
+        // 5% slower is within the 10% tolerance
+        let within = compare(&[latency_sample(100, 1, 210)], &baseline);
+        assert!(!within.p95_regression);
+        assert_eq!(within.verdict, "pass");
+
+        let degraded = compare(&[latency_sample(100, 1, 240)], &baseline);
+        let p95 = degraded
+            .metrics
+            .iter()
+            .find(|m| m.metric == "p95_response_time_ms")
+            .unwrap();
+        assert_eq!(p95.delta, Some(40.0));
+        assert_eq!(p95.delta_pct, Some(20.0));
+        assert_eq!(p95.passed, Some(false));
+        assert!(degraded.p95_regression);
+        assert_eq!(degraded.verdict, "fail");
+    }
+
+    #[test]
+    fn latency_above_a_threshold_is_reported() {
+        let mut release = release();
+        release.p95_latency_threshold_ms = Some(Decimal::from(250));
+        release.p99_latency_threshold_ms = Some(Decimal::from(500));
+        let metric = CanaryMetric {
+            p99_response_time_ms: Some(Decimal::from(450)),
+            ..latency_sample(100, 0, 300)
+        };
+
+        assert_eq!(
+            latency_breaches(&release, &metric),
+            vec![LatencyBreach {
+                threshold: "p95",
+                threshold_ms: 250.0,
+                observed_ms: 300.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn latency_gate_ignores_missing_thresholds_and_samples() {
+        let mut release = release();
+        // No thresholds configured: nothing can trip
+        assert!(latency_breaches(&release, &latency_sample(100, 0, 10_000)).is_empty());
+
+        // A threshold with no matching measurement in the sample can't trip either
+        release.p99_latency_threshold_ms = Some(Decimal::from(500));
+        assert!(latency_breaches(&release, &latency_sample(100, 0, 10_000)).is_empty());
+
+        // Exactly at the threshold is still within it
+        release.p95_latency_threshold_ms = Some(Decimal::from(300));
+        assert!(latency_breaches(&release, &latency_sample(100, 0, 300)).is_empty());
+    }
+
+    fn release_with_errors(total_requests: i32, error_rate: i64) -> CanaryRelease {
+        CanaryRelease {
+            total_requests,
+            current_error_rate: Some(Decimal::from(error_rate)),
+            ..release()
+        }
+    }
+
+    #[test]
+    fn error_rate_below_threshold_does_not_roll_back() {
+        // Threshold is 5%
+        assert!(!exceeds_error_rate(&release_with_errors(1_000, 2), 100));
+        assert!(!exceeds_error_rate(&release_with_errors(1_000, 5), 100));
+        assert!(!exceeds_error_rate(&release(), 100));
+    }
+
+    #[test]
+    fn error_rate_above_threshold_rolls_back_with_enough_samples() {
+        assert!(exceeds_error_rate(&release_with_errors(100, 6), 100));
+        assert!(exceeds_error_rate(&release_with_errors(5_000, 50), 100));
+    }
+
+    #[test]
+    fn error_rate_above_threshold_waits_for_minimum_sample() {
+        // A single early error is a 100% error rate
+        assert!(!exceeds_error_rate(&release_with_errors(1, 100), 100));
+        assert!(!exceeds_error_rate(&release_with_errors(99, 50), 100));
+        assert!(exceeds_error_rate(&release_with_errors(99, 50), 50));
+    }
+
+    /// An active canary on a freshly seeded contract
+    async fn seed_active_canary(db: &sqlx::PgPool) -> Uuid {
+        let contract_id = crate::test_support::seed_contract(db, "canary").await;
+        let deployment_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO contract_deployments (contract_id, environment, wasm_hash)
+             VALUES ($1, 'green', 'canary') RETURNING id",
+        )
+        .bind(contract_id)
+        .fetch_one(db)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO canary_releases (contract_id, to_deployment_id, status, error_rate_threshold)
+             VALUES ($1, $2, 'active', 5.0) RETURNING id",
+        )
+        .bind(contract_id)
+        .bind(deployment_id)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    /// Record a sample outside any idempotent request
+    async fn insert(
+        db: &sqlx::PgPool,
+        canary_id: Uuid,
+        req: RecordCanaryMetricRequest,
+    ) -> ApiResult<RecordCanaryMetricResponse> {
+        let mut conn = db.acquire().await.unwrap();
+        insert_canary_metric(&mut conn, canary_id, req)
+            .await
+            .map(|(response, _)| response)
+    }
+
+    fn metric_request(requests: i32, errors: i32) -> RecordCanaryMetricRequest {
+        RecordCanaryMetricRequest {
+            canary_id: String::new(),
+            requests,
+            errors,
+            avg_response_time_ms: None,
+            p95_response_time_ms: None,
+            p99_response_time_ms: None,
+            business_metrics: Default::default(),
+            baseline: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn comparison_uses_baseline_samples_from_the_canary_window() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let canary_id = seed_active_canary(&db).await;
+        let baseline = RecordCanaryMetricRequest {
+            baseline: true,
+            ..metric_request(100, 1)
+        };
+
+        // Without a from-deployment there is nothing to sample
+        let err = insert(&db, canary_id, baseline.clone()).await.unwrap_err();
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        sqlx::query(
+            "UPDATE canary_releases SET from_deployment_id = \
+             (SELECT to_deployment_id FROM canary_releases WHERE id = $1) WHERE id = $1",
+        )
+        .bind(canary_id)
+        .execute(&db)
+        .await
+        .unwrap();
+        insert(&db, canary_id, metric_request(50, 1)).await.unwrap();
+        let response = insert(&db, canary_id, baseline).await.unwrap();
+        assert!(response.metric.baseline);
+
+        let Json(comparison) = get_canary_comparison(State(state), Path(canary_id.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            (comparison.canary.samples, comparison.canary.total_requests),
+            (1, 50)
+        );
+        assert_eq!(
+            (
+                comparison.baseline.samples,
+                comparison.baseline.total_requests
+            ),
+            (1, 100)
+        );
+        assert_eq!(comparison.baseline.start, comparison.canary.start);
+
+        // Baseline traffic does not count towards the canary's own totals
+        let total_requests: i32 =
+            sqlx::query_scalar("SELECT total_requests FROM canary_releases WHERE id = $1")
+                .bind(canary_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(total_requests, 50);
+    }
+
+    #[tokio::test]
+    async fn recorded_errors_over_threshold_roll_the_canary_back() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let canary_id = seed_active_canary(&db).await;
+
+        // Too few requests so far: the totals move but nothing rolls back
+        let response = insert(&db, canary_id, metric_request(10, 5)).await.unwrap();
+        assert!(!response.auto_rolled_back);
+        assert!(response.canary_status.is_none());
+
+        let response = insert(&db, canary_id, metric_request(110, 7))
+            .await
+            .unwrap();
+        assert!(response.auto_rolled_back);
+        assert!(matches!(
+            response.canary_status,
+            Some(CanaryStatus::RolledBack)
+        ));
+
+        let release: CanaryRelease = sqlx::query_as("SELECT * FROM canary_releases WHERE id = $1")
+            .bind(canary_id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert!(matches!(release.status, CanaryStatus::RolledBack));
+        assert!(release.completed_at.is_some());
+        assert_eq!((release.total_requests, release.error_count), (120, 12));
+        let transitioned_by: Vec<Option<String>> = sqlx::query_scalar(
+            "SELECT transitioned_by FROM canary_stage_history WHERE canary_id = $1",
+        )
+        .bind(canary_id)
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(transitioned_by, vec![Some("auto-rollback".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn recorded_errors_under_threshold_keep_the_canary_active() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let canary_id = seed_active_canary(&db).await;
+
+        let response = insert(&db, canary_id, metric_request(200, 4))
+            .await
+            .unwrap();
+        assert!(!response.auto_rolled_back);
+
+        let release: CanaryRelease = sqlx::query_as("SELECT * FROM canary_releases WHERE id = $1")
+            .bind(canary_id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert!(matches!(release.status, CanaryStatus::Active));
+        assert_eq!(release.current_error_rate, Some(Decimal::from(2)));
+    }
+
+    #[test]
+    fn latency_thresholds_must_be_positive() {
+        assert!(latency_threshold(Some(0.0), "p95_latency_threshold_ms").is_err());
+        assert!(latency_threshold(Some(f64::NAN), "p95_latency_threshold_ms").is_err());
+        assert_eq!(
+            latency_threshold(Some(250.0), "p95_latency_threshold_ms").unwrap(),
+            Some(Decimal::from(250))
+        );
+        assert_eq!(
+            latency_threshold(None, "p95_latency_threshold_ms").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_baseline_window_is_insufficient_data() {
+        let comparison = compare(&[latency_sample(100, 0, 200)], &[]);
+
+        assert_eq!(comparison.verdict, "insufficient_data");
+        assert!(comparison.metrics.iter().all(|m| m.passed.is_none()));
+        assert!(!comparison.p95_regression);
+    }
+
+    #[test]
+    fn summary_error_rate_needs_requests() {
+        let totals = |total_requests, total_errors| CanaryMetricsTotals {
+            window_start: Utc::now(),
+            window_end: Utc::now(),
+            samples: 2,
+            total_requests,
+            total_errors,
+            avg_p95_response_time_ms: Some(180.0),
+            max_p95_response_time_ms: Some(240.0),
+            avg_p99_response_time_ms: None,
+            max_p99_response_time_ms: None,
+        };
+
+        let summary = metrics_summary(Uuid::nil(), totals(400, 6));
+        assert_eq!(summary.error_rate, Some(1.5));
+        assert_eq!(summary.max_p95_response_time_ms, Some(240.0));
+        assert_eq!(metrics_summary(Uuid::nil(), totals(0, 0)).error_rate, None);
+    }
+
+    #[tokio::test]
+    async fn replayed_idempotency_key_counts_the_sample_once() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let canary_id = seed_active_canary(&db).await;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            idempotency::IDEMPOTENCY_KEY_HEADER,
+            "retry-1".parse().unwrap(),
+        );
+
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let response = record_canary_metric(
+                State(state.clone()),
+                Path(canary_id.to_string()),
+                headers.clone(),
+                Ok(Json(metric_request(40, 1))),
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            bodies.push(serde_json::from_slice::<Value>(&body).unwrap());
+        }
+        assert_eq!(bodies[0], bodies[1]);
+
+        let samples: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM canary_metrics WHERE canary_id = $1")
+                .bind(canary_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        let totals: (i32, i32) =
+            sqlx::query_as("SELECT total_requests, error_count FROM canary_releases WHERE id = $1")
+                .bind(canary_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(samples, 1);
+        assert_eq!(totals, (40, 1));
+    }
+
+    #[tokio::test]
+    async fn metric_pages_follow_the_cursor() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let canary_id = seed_active_canary(&db).await;
+        for requests in 1..=3 {
+            insert(&db, canary_id, metric_request(requests, 0))
+                .await
+                .unwrap();
+        }
+
+        let page = |cursor: Option<String>| {
+            let state = state.clone();
+            async move {
+                let Json(page) = list_canary_metrics(
+                    State(state),
+                    Path(canary_id.to_string()),
+                    Query(ListCanaryQuery {
+                        limit: 2,
+                        offset: 0,
+                        cursor,
+                        status: None,
+                    }),
+                )
+                .await
+                .unwrap();
+                page
+            }
+        };
+        let first = page(None).await;
+        assert_eq!(first["items"].as_array().unwrap().len(), 2);
+        let cursor = first["next_cursor"].as_str().unwrap().to_string();
+
+        let second = page(Some(cursor)).await;
+        let items = second["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["requests"], 1);
+        assert!(second["next_cursor"].is_null());
+    }
+}
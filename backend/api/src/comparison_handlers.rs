@@ -0,0 +1,339 @@
+// api/src/comparison_handlers.rs
+//
+// Side-by-side comparison of two different contracts (as opposed to two
+// versions of one): ABI functions, inferred capabilities, and trust signals.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+use crate::breaking_changes::{diff_abi, resolve_abi, BreakingChange};
+use crate::error::{ApiError, ApiResult};
+use crate::state::AppState;
+use crate::trust::{compute_trust_score, load_trust_input};
+use crate::type_safety::parser::parse_json_spec;
+use crate::type_safety::types::ContractABI;
+
+/// Capabilities inferred from the functions a contract exposes. A capability
+/// applies when every listed function is present.
+const CAPABILITIES: &[(&str, &[&str])] = &[
+    ("fungible_token", &["transfer", "balance", "decimals"]),
+    ("non_fungible_token", &["owner_of", "token_uri"]),
+    ("transferable", &["transfer"]),
+    ("allowances", &["approve", "allowance"]),
+    ("mintable", &["mint"]),
+    ("burnable", &["burn"]),
+    ("token_metadata", &["name", "symbol"]),
+    ("admin_controlled", &["set_admin"]),
+    ("pausable", &["pause", "unpause"]),
+    ("upgradeable", &["upgrade"]),
+];
+
+#[derive(Debug, Deserialize)]
+pub struct CompareQuery {
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct SetComparison<T> {
+    pub shared: Vec<T>,
+    pub only_in_a: Vec<T>,
+    pub only_in_b: Vec<T>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionComparison {
+    #[serde(flatten)]
+    pub names: SetComparison<String>,
+    /// Differences in parameters or return types of functions both contracts expose
+    pub signature_differences: Vec<BreakingChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractTrustSummary {
+    pub contract_id: String,
+    pub name: String,
+    pub is_verified: bool,
+    pub trust_score: f64,
+    pub trust_badge: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractComparison {
+    pub a: ContractTrustSummary,
+    pub b: ContractTrustSummary,
+    pub functions: FunctionComparison,
+    pub capabilities: SetComparison<&'static str>,
+    /// `"a"` or `"b"`, whichever has the higher trust score; absent on a tie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub more_trusted: Option<&'static str>,
+}
+
+/// GET /api/contracts/compare?a=&b= — compare two contracts' ABIs, capabilities and trust
+pub async fn compare_contracts(
+    Query(query): Query<CompareQuery>,
+    State(state): State<AppState>,
+) -> ApiResult<Json<ContractComparison>> {
+    let abi_a = parse_abi(&state, &query.a).await?;
+    let abi_b = parse_abi(&state, &query.b).await?;
+
+    let a = fetch_trust_summary(&state, &query.a).await?;
+    let b = fetch_trust_summary(&state, &query.b).await?;
+
+    let more_trusted = if a.trust_score > b.trust_score {
+        Some("a")
+    } else if b.trust_score > a.trust_score {
+        Some("b")
+    } else {
+        None
+    };
+
+    Ok(Json(ContractComparison {
+        a,
+        b,
+        functions: compare_functions(&abi_a, &abi_b),
+        capabilities: compare_capabilities(&abi_a, &abi_b),
+        more_trusted,
+    }))
+}
+
+async fn parse_abi(state: &AppState, selector: &str) -> ApiResult<ContractABI> {
+    let abi = resolve_abi(state, selector).await?;
+    parse_json_spec(&abi, selector).map_err(|e| {
+        ApiError::bad_request(
+            "InvalidABI",
+            format!("Failed to parse ABI for '{}': {}", selector, e),
+        )
+    })
+}
+
+async fn fetch_trust_summary(state: &AppState, selector: &str) -> ApiResult<ContractTrustSummary> {
+    let contract_id = selector.split_once('@').map_or(selector, |(id, _)| id);
+
+    let (id, stellar_id, name) = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT id, contract_id, name FROM contracts \
+         WHERE contract_id = $1 OR id::text = $1 LIMIT 1",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            "ContractNotFound",
+            format!("Contract '{}' not found", contract_id),
+        )
+    })?;
+
+    // Scored exactly as /trust-score scores it
+    let input = load_trust_input(&state.db, id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ContractNotFound",
+                format!("Contract '{}' not found", contract_id),
+            )
+        })?;
+    let trust = compute_trust_score(&input);
+
+    Ok(ContractTrustSummary {
+        contract_id: stellar_id,
+        name,
+        is_verified: input.is_verified,
+        trust_score: trust.score,
+        trust_badge: trust.badge,
+    })
+}
+
+pub fn compare_functions(a: &ContractABI, b: &ContractABI) -> FunctionComparison {
+    let names_a: BTreeSet<String> = a.functions.iter().map(|f| f.name.clone()).collect();
+    let names_b: BTreeSet<String> = b.functions.iter().map(|f| f.name.clone()).collect();
+    let names = split_sets(names_a, names_b);
+
+    let mut signature_differences: Vec<BreakingChange> = diff_abi(a, b)
+        .into_iter()
+        .filter(|change| {
+            change
+                .function
+                .as_ref()
+                .is_some_and(|f| names.shared.contains(f))
+        })
+        .collect();
+    signature_differences.sort_by(|x, y| x.function.cmp(&y.function));
+
+    FunctionComparison {
+        names,
+        signature_differences,
+    }
+}
+
+pub fn compare_capabilities(a: &ContractABI, b: &ContractABI) -> SetComparison<&'static str> {
+    split_sets(infer_capabilities(a), infer_capabilities(b))
+}
+
+pub fn infer_capabilities(abi: &ContractABI) -> BTreeSet<&'static str> {
+    CAPABILITIES
+        .iter()
+        .filter(|(_, required)| required.iter().all(|name| abi.has_function(name)))
+        .map(|(capability, _)| *capability)
+        .collect()
+}
+
+fn split_sets<T: Ord + Clone>(a: BTreeSet<T>, b: BTreeSet<T>) -> SetComparison<T> {
+    SetComparison {
+        shared: a.intersection(&b).cloned().collect(),
+        only_in_a: a.difference(&b).cloned().collect(),
+        only_in_b: b.difference(&a).cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_safety::types::{
+        ContractFunction, FunctionParam, FunctionVisibility, SorobanType,
+    };
+
+    fn func(
+        name: &str,
+        params: &[(&str, SorobanType)],
+        return_type: SorobanType,
+    ) -> ContractFunction {
+        ContractFunction {
+            name: name.to_string(),
+            visibility: FunctionVisibility::Public,
+            params: params
+                .iter()
+                .map(|(name, ty)| FunctionParam {
+                    name: name.to_string(),
+                    param_type: ty.clone(),
+                    doc: None,
+                })
+                .collect(),
+            return_type,
+            doc: None,
+            is_mutable: true,
+        }
+    }
+
+    fn token() -> ContractABI {
+        let mut abi = ContractABI::new("Token".to_string());
+        abi.functions = vec![
+            func(
+                "transfer",
+                &[
+                    ("from", SorobanType::Address),
+                    ("to", SorobanType::Address),
+                    ("amount", SorobanType::I128),
+                ],
+                SorobanType::Void,
+            ),
+            func(
+                "balance",
+                &[("id", SorobanType::Address)],
+                SorobanType::I128,
+            ),
+            func("decimals", &[], SorobanType::U32),
+            func("name", &[], SorobanType::String),
+            func("symbol", &[], SorobanType::String),
+            func(
+                "approve",
+                &[
+                    ("spender", SorobanType::Address),
+                    ("amount", SorobanType::I128),
+                ],
+                SorobanType::Void,
+            ),
+            func(
+                "allowance",
+                &[
+                    ("owner", SorobanType::Address),
+                    ("spender", SorobanType::Address),
+                ],
+                SorobanType::I128,
+            ),
+            func(
+                "mint",
+                &[("to", SorobanType::Address), ("amount", SorobanType::I128)],
+                SorobanType::Void,
+            ),
+        ];
+        abi
+    }
+
+    fn nft() -> ContractABI {
+        let mut abi = ContractABI::new("Nft".to_string());
+        abi.functions = vec![
+            func(
+                "transfer",
+                &[
+                    ("from", SorobanType::Address),
+                    ("to", SorobanType::Address),
+                    ("token_id", SorobanType::U64),
+                ],
+                SorobanType::Void,
+            ),
+            func(
+                "balance",
+                &[("owner", SorobanType::Address)],
+                SorobanType::U64,
+            ),
+            func(
+                "owner_of",
+                &[("token_id", SorobanType::U64)],
+                SorobanType::Address,
+            ),
+            func(
+                "token_uri",
+                &[("token_id", SorobanType::U64)],
+                SorobanType::String,
+            ),
+            func("name", &[], SorobanType::String),
+            func("symbol", &[], SorobanType::String),
+            func("mint", &[("to", SorobanType::Address)], SorobanType::U64),
+        ];
+        abi
+    }
+
+    #[test]
+    fn token_and_nft_report_shared_and_distinct_functions() {
+        let functions = compare_functions(&token(), &nft());
+
+        assert_eq!(
+            functions.names.shared,
+            vec!["balance", "mint", "name", "symbol", "transfer"]
+        );
+        assert_eq!(
+            functions.names.only_in_a,
+            vec!["allowance", "approve", "decimals"]
+        );
+        assert_eq!(functions.names.only_in_b, vec!["owner_of", "token_uri"]);
+
+        let differing: BTreeSet<_> = functions
+            .signature_differences
+            .iter()
+            .filter_map(|c| c.function.as_deref())
+            .collect();
+        assert!(differing.contains("transfer"));
+        assert!(differing.contains("mint"));
+        assert!(!differing.contains("name"));
+    }
+
+    #[test]
+    fn token_and_nft_report_shared_and_distinct_capabilities() {
+        let capabilities = compare_capabilities(&token(), &nft());
+
+        assert_eq!(
+            capabilities.shared,
+            vec!["mintable", "token_metadata", "transferable"]
+        );
+        assert_eq!(capabilities.only_in_a, vec!["allowances", "fungible_token"]);
+        assert_eq!(capabilities.only_in_b, vec!["non_fungible_token"]);
+    }
+}
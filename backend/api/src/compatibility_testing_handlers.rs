@@ -2,7 +2,7 @@
 // Handlers for the SDK/Wasm/Network contract compatibility testing matrix (Issue #261).
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{rejection::JsonRejection, Path, Query, State},
     Json,
 };
 use chrono::{DateTime, Utc};
@@ -11,10 +11,17 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 use crate::{
+    breaking_changes::{diff_abi, resolve_abi, BreakingChange, ChangeSeverity},
     error::{ApiError, ApiResult},
+    handlers::map_json_rejection,
     state::AppState,
+    type_safety::parser::parse_json_spec,
 };
 
+/// Generic cache namespace for pairwise version checks. Entries are keyed by
+/// WASM hashes, so a result never goes stale while its hashes stay the same.
+const VERSION_PAIR_CACHE_NS: &str = "version_compatibility";
+
 // ─────────────────────────────────────────────────────────
 // SDK / Wasm / Network Compatibility Testing Models
 // ─────────────────────────────────────────────────────────
@@ -234,8 +241,9 @@ pub async fn get_compatibility_matrix(
 pub async fn run_compatibility_test(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
-    Json(body): Json<RunCompatibilityTestRequest>,
+    payload: Result<Json<RunCompatibilityTestRequest>, JsonRejection>,
 ) -> ApiResult<Json<CompatibilityTestEntry>> {
+    let Json(body) = payload.map_err(map_json_rejection)?;
     // Verify contract exists
     let exists: bool = sqlx::query_scalar("SELECT COUNT(*) > 0 FROM contracts WHERE id = $1")
         .bind(contract_id)
@@ -367,6 +375,136 @@ pub async fn run_compatibility_test(
     Ok(Json(entry))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct VersionPairQuery {
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionPairStatus {
+    Compatible,
+    Breaking,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionPairCompatibility {
+    pub contract_id: Uuid,
+    pub a: String,
+    pub b: String,
+    pub a_wasm_hash: String,
+    pub b_wasm_hash: String,
+    pub status: VersionPairStatus,
+    /// Breaking ABI changes a consumer of `a` hits after upgrading to `b`
+    pub incompatibilities: Vec<BreakingChange>,
+    pub non_breaking_change_count: usize,
+}
+
+/// Split an `a` → `b` ABI diff into its verdict, the breaking changes and
+/// the number of non-breaking ones.
+pub fn classify_version_pair(
+    changes: Vec<BreakingChange>,
+) -> (VersionPairStatus, Vec<BreakingChange>, usize) {
+    let total = changes.len();
+    let incompatibilities: Vec<BreakingChange> = changes
+        .into_iter()
+        .filter(|c| c.severity == ChangeSeverity::Breaking)
+        .collect();
+    let status = if incompatibilities.is_empty() {
+        VersionPairStatus::Compatible
+    } else {
+        VersionPairStatus::Breaking
+    };
+    let non_breaking = total - incompatibilities.len();
+    (status, incompatibilities, non_breaking)
+}
+
+/// The check is directional (upgrading from `a` to `b`), so the key is too
+fn version_pair_cache_key(a_wasm_hash: &str, b_wasm_hash: &str) -> String {
+    format!("{}..{}", a_wasm_hash, b_wasm_hash)
+}
+
+/// GET /api/contracts/:id/compatibility-matrix/check?a=<version>&b=<version>
+///
+/// Whether a consumer built against version `a` keeps working on version `b`,
+/// using the same ABI-diff classification as the breaking-changes report.
+pub async fn check_version_compatibility(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Query(query): Query<VersionPairQuery>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let hashes: Vec<(String, String)> = sqlx::query_as(
+        "SELECT version, wasm_hash FROM contract_versions \
+         WHERE contract_id = $1 AND version IN ($2, $3)",
+    )
+    .bind(contract_id)
+    .bind(&query.a)
+    .bind(&query.b)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("DB error: {e}")))?;
+
+    let wasm_hash = |version: &str| {
+        hashes
+            .iter()
+            .find(|(v, _)| v == version)
+            .map(|(_, hash)| hash.clone())
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "VersionNotFound",
+                    format!("Contract has no version '{}'", version),
+                )
+            })
+    };
+    let a_wasm_hash = wasm_hash(&query.a)?;
+    let b_wasm_hash = wasm_hash(&query.b)?;
+
+    let cache_key = version_pair_cache_key(&a_wasm_hash, &b_wasm_hash);
+    if let (Some(cached), true) = state.cache.get(VERSION_PAIR_CACHE_NS, &cache_key).await {
+        if let Ok(mut result) = serde_json::from_str::<serde_json::Value>(&cached) {
+            // Another contract or version label may share these hashes
+            result["contract_id"] = serde_json::json!(contract_id);
+            result["a"] = serde_json::json!(query.a);
+            result["b"] = serde_json::json!(query.b);
+            return Ok(Json(result));
+        }
+    }
+
+    let mut specs = Vec::with_capacity(2);
+    for version in [&query.a, &query.b] {
+        let selector = format!("{}@{}", contract_id, version);
+        let abi = resolve_abi(&state, &selector).await?;
+        specs.push(parse_json_spec(&abi, &selector).map_err(|e| {
+            ApiError::bad_request(
+                "InvalidABI",
+                format!("Failed to parse ABI for version '{}': {}", version, e),
+            )
+        })?);
+    }
+
+    let (status, incompatibilities, non_breaking_change_count) =
+        classify_version_pair(diff_abi(&specs[0], &specs[1]));
+    let result = serde_json::to_value(VersionPairCompatibility {
+        contract_id,
+        a: query.a,
+        b: query.b,
+        a_wasm_hash,
+        b_wasm_hash,
+        status,
+        incompatibilities,
+        non_breaking_change_count,
+    })
+    .map_err(|e| ApiError::internal(format!("Failed to encode result: {e}")))?;
+
+    state
+        .cache
+        .put(VERSION_PAIR_CACHE_NS, &cache_key, result.to_string(), None)
+        .await;
+
+    Ok(Json(result))
+}
+
 /// GET /api/contracts/:id/compatibility-matrix/history
 ///
 /// Returns historical compatibility changes for trend analysis.
@@ -553,3 +691,44 @@ fn simulate_compatibility_test(
         (CompatibilityStatus::Compatible, None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(severity: ChangeSeverity, category: &str) -> BreakingChange {
+        BreakingChange {
+            severity,
+            category: category.to_string(),
+            message: category.to_string(),
+            function: Some("transfer".to_string()),
+            type_name: None,
+        }
+    }
+
+    #[test]
+    fn only_breaking_changes_are_incompatibilities() {
+        let (status, incompatibilities, non_breaking) = classify_version_pair(vec![
+            change(ChangeSeverity::NonBreaking, "function_added"),
+            change(ChangeSeverity::Breaking, "function_removed"),
+        ]);
+
+        assert_eq!(status, VersionPairStatus::Breaking);
+        assert_eq!(incompatibilities.len(), 1);
+        assert_eq!(incompatibilities[0].category, "function_removed");
+        assert_eq!(non_breaking, 1);
+
+        let (status, incompatibilities, _) =
+            classify_version_pair(vec![change(ChangeSeverity::NonBreaking, "function_added")]);
+        assert_eq!(status, VersionPairStatus::Compatible);
+        assert!(incompatibilities.is_empty());
+    }
+
+    #[test]
+    fn cache_key_follows_upgrade_direction() {
+        assert_ne!(
+            version_pair_cache_key("aa", "bb"),
+            version_pair_cache_key("bb", "aa")
+        );
+    }
+}
@@ -611,6 +611,5 @@ async fn verify_contract_exists(state: &AppState, contract_id: Uuid) -> ApiResul
 }
 
 fn db_err(op: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = op, error = ?err, "database error");
-    ApiError::internal("An unexpected database error occurred")
+    ApiError::database(op, err)
 }
@@ -1,22 +1,26 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{rejection::JsonRejection, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
-use shared::{CustomMetric, CustomMetricAggregate, CustomMetricType, RecordCustomMetricRequest};
-use sqlx::{QueryBuilder, Row};
+use shared::{
+    CustomMetric, CustomMetricAggregate, CustomMetricType, HistogramBucket,
+    RecordCustomMetricRequest,
+};
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::collections::BTreeMap;
 
 use crate::{
     error::{ApiError, ApiResult},
+    handlers::map_json_rejection,
     state::AppState,
 };
 
 fn db_error(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation, error = ?err, "database operation failed");
-    ApiError::internal("Database operation failed")
+    ApiError::database(operation, err)
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,6 +30,68 @@ pub struct MetricQuery {
     pub from: Option<String>,
     pub to: Option<String>,
     pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Longest metric name accepted as a filter
+const MAX_METRIC_NAME_LEN: usize = 255;
+/// Most labels one sample may carry
+const MAX_LABELS: usize = 16;
+/// Longest label key or value
+const MAX_LABEL_LEN: usize = 128;
+/// Most buckets one histogram sample may carry
+const MAX_HISTOGRAM_BUCKETS: usize = 64;
+/// Percentiles reported when a histogram query names none
+const DEFAULT_PERCENTILES: [f64; 3] = [50.0, 95.0, 99.0];
+
+#[derive(Debug, Deserialize)]
+pub struct MetricGroupQuery {
+    pub metric: Option<String>,
+    /// Label key to group samples by; samples without it form their own group
+    pub group_by: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Comma-separated percentiles for histogram queries, e.g. `50,90,99.9`
+    pub percentiles: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct LabelAggregate {
+    /// Value of the `group_by` label, `None` for samples without it
+    pub label_value: Option<String>,
+    pub sample_count: i64,
+    pub sum_value: Option<f64>,
+    pub avg_value: Option<f64>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MetricAggregateResponse {
+    pub contract_id: String,
+    pub metric_name: String,
+    pub group_by: Option<String>,
+    pub groups: Vec<LabelAggregate>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HistogramGroup {
+    /// Value of the `group_by` label, `None` for samples without it
+    pub label_value: Option<String>,
+    /// Histogram samples merged into this group
+    pub sample_count: i64,
+    /// Per-bucket counts summed across the samples
+    pub buckets: Vec<HistogramBucket>,
+    /// Percentile (e.g. `"p95"`) to estimated value
+    pub percentiles: BTreeMap<String, Option<f64>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MetricHistogramResponse {
+    pub contract_id: String,
+    pub metric_name: String,
+    pub group_by: Option<String>,
+    pub groups: Vec<HistogramGroup>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -40,6 +106,10 @@ pub struct MetricSeriesResponse {
     pub metric_type: Option<CustomMetricType>,
     pub resolution: String,
     pub points: Vec<MetricSeriesPoint>,
+    /// Buckets matching the filters, across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -63,6 +133,10 @@ pub struct MetricSampleResponse {
     pub metric_type: Option<CustomMetricType>,
     pub resolution: String,
     pub samples: Vec<MetricSample>,
+    /// Samples matching the filters, across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -126,74 +200,35 @@ pub async fn get_contract_metrics(
     Path(contract_id): Path<String>,
     Query(query): Query<MetricQuery>,
 ) -> ApiResult<impl IntoResponse> {
-    let metric_name = match query.metric {
-        Some(name) if !name.trim().is_empty() => name,
-        _ => {
-            return Err(ApiError::bad_request(
-                "MissingMetric",
-                "Query parameter 'metric' is required", // e.g. ?metric=custom_trades_volume
-            ));
-        }
-    };
+    let metric_name = required_metric_name(query.metric)?;
 
     let resolution = query.resolution.as_deref().unwrap_or("hour").to_lowercase();
 
-    let from_ts = query
-        .from
-        .as_deref()
-        .and_then(|raw| raw.parse::<DateTime<Utc>>().ok());
-    let to_ts = query
-        .to
-        .as_deref()
-        .and_then(|raw| raw.parse::<DateTime<Utc>>().ok());
+    let (from_ts, to_ts) = parse_range(query.from.as_deref(), query.to.as_deref())?;
 
     let limit = query.limit.unwrap_or(500).clamp(1, 5000);
+    let offset = query.offset.unwrap_or(0).max(0);
 
-    if resolution == "raw" {
-        let mut qb = QueryBuilder::new(
-            "SELECT id, contract_id, metric_name, metric_type, value, unit, metadata, ledger_sequence, \
-             transaction_hash, timestamp, network, created_at \
-             FROM contract_custom_metrics WHERE contract_id = ",
-        );
-        qb.push_bind(&contract_id);
-        qb.push(" AND metric_name = ");
-        qb.push_bind(&metric_name);
-
-        if let Some(from_ts) = from_ts {
-            qb.push(" AND timestamp >= ");
-            qb.push_bind(from_ts);
-        }
-
-        if let Some(to_ts) = to_ts {
-            qb.push(" AND timestamp <= ");
-            qb.push_bind(to_ts);
-        }
-
-        qb.push(" ORDER BY timestamp DESC LIMIT ");
-        qb.push_bind(limit);
+    let filter = MetricFilter {
+        contract_id: contract_id.clone(),
+        metric_name: metric_name.clone(),
+        from: from_ts,
+        to: to_ts,
+    };
 
-        let samples = qb
+    if resolution == "raw" {
+        let source = MetricSource::RAW;
+        let samples = page_query(&source, &filter, limit, offset)
             .build_query_as::<CustomMetric>()
             .fetch_all(&state.db)
             .await
             .map_err(|e| db_error("fetch raw metrics", e))?;
+        let total = count_matching(&state, &source, &filter).await?;
 
-        if samples.is_empty() {
-            let metric_type = fetch_metric_type(&state, &contract_id, &metric_name).await?;
-            return Ok((
-                StatusCode::OK,
-                Json(MetricSampleResponse {
-                    contract_id,
-                    metric_name,
-                    metric_type,
-                    resolution,
-                    samples: Vec::new(),
-                }),
-            )
-                .into_response());
-        }
-
-        let metric_type = Some(samples[0].metric_type.clone());
+        let metric_type = match samples.first() {
+            Some(sample) => Some(sample.metric_type.clone()),
+            None => fetch_metric_type(&state, &contract_id, &metric_name).await?,
+        };
         let series = MetricSampleResponse {
             contract_id,
             metric_name,
@@ -208,67 +243,30 @@ pub async fn get_contract_metrics(
                     metadata: row.metadata,
                 })
                 .collect(),
+            total,
+            limit,
+            offset,
         };
 
         return Ok((StatusCode::OK, Json(series)).into_response());
     }
 
-    let (table, bucket_column) = match resolution.as_str() {
-        "day" | "daily" => ("contract_custom_metrics_daily", "bucket_start"),
-        _ => ("contract_custom_metrics_hourly", "bucket_start"),
+    let source = match resolution.as_str() {
+        "day" | "daily" => MetricSource::DAILY,
+        _ => MetricSource::HOURLY,
     };
 
-    let mut qb = QueryBuilder::new(format!(
-        "SELECT contract_id, metric_name, metric_type, bucket_start, bucket_end, sample_count, \
-         sum_value, avg_value, min_value, max_value, p50_value, p95_value, p99_value \
-         FROM {} WHERE contract_id = ",
-        table
-    ));
-    qb.push_bind(&contract_id);
-    qb.push(" AND metric_name = ");
-    qb.push_bind(&metric_name);
-
-    if let Some(from_ts) = from_ts {
-        qb.push(" AND ");
-        qb.push(bucket_column);
-        qb.push(" >= ");
-        qb.push_bind(from_ts);
-    }
-
-    if let Some(to_ts) = to_ts {
-        qb.push(" AND ");
-        qb.push(bucket_column);
-        qb.push(" <= ");
-        qb.push_bind(to_ts);
-    }
-
-    qb.push(" ORDER BY ");
-    qb.push(bucket_column);
-    qb.push(" DESC LIMIT ");
-    qb.push_bind(limit);
-
-    let points = qb
+    let points = page_query(&source, &filter, limit, offset)
         .build_query_as::<CustomMetricAggregate>()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_error("fetch aggregated metrics", e))?;
+    let total = count_matching(&state, &source, &filter).await?;
 
-    if points.is_empty() {
-        let metric_type = fetch_metric_type(&state, &contract_id, &metric_name).await?;
-        return Ok((
-            StatusCode::OK,
-            Json(MetricSeriesResponse {
-                contract_id,
-                metric_name,
-                metric_type,
-                resolution,
-                points: Vec::new(),
-            }),
-        )
-            .into_response());
-    }
-
-    let metric_type = Some(points[0].metric_type.clone());
+    let metric_type = match points.first() {
+        Some(point) => Some(point.metric_type.clone()),
+        None => fetch_metric_type(&state, &contract_id, &metric_name).await?,
+    };
     let series = MetricSeriesResponse {
         contract_id,
         metric_name,
@@ -303,11 +301,428 @@ pub async fn get_contract_metrics(
                     .map(|v| v.to_string().parse::<f64>().unwrap_or(0.0)),
             })
             .collect(),
+        total,
+        limit,
+        offset,
     };
 
     Ok((StatusCode::OK, Json(series)).into_response())
 }
 
+pub async fn get_metric_aggregate(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    Query(query): Query<MetricGroupQuery>,
+) -> ApiResult<Json<MetricAggregateResponse>> {
+    let metric_name = required_metric_name(query.metric)?;
+    let group_by = optional_label_key(query.group_by)?;
+    let (from, to) = parse_range(query.from.as_deref(), query.to.as_deref())?;
+    let filter = MetricFilter {
+        contract_id: contract_id.clone(),
+        metric_name: metric_name.clone(),
+        from,
+        to,
+    };
+
+    let rows = label_aggregate_query(&filter, group_by.as_deref())
+        .build()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_error("aggregate metrics by label", e))?;
+
+    let groups = rows
+        .into_iter()
+        .map(|row| LabelAggregate {
+            label_value: row.get("label_value"),
+            sample_count: row.get("sample_count"),
+            sum_value: row.get("sum_value"),
+            avg_value: row.get("avg_value"),
+            min_value: row.get("min_value"),
+            max_value: row.get("max_value"),
+        })
+        .collect();
+
+    Ok(Json(MetricAggregateResponse {
+        contract_id,
+        metric_name,
+        group_by,
+        groups,
+    }))
+}
+
+pub async fn get_metric_histogram(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    Query(query): Query<MetricGroupQuery>,
+) -> ApiResult<Json<MetricHistogramResponse>> {
+    let metric_name = required_metric_name(query.metric)?;
+    let group_by = optional_label_key(query.group_by)?;
+    let (from, to) = parse_range(query.from.as_deref(), query.to.as_deref())?;
+    let percentiles = parse_percentiles(query.percentiles.as_deref())?;
+    let filter = MetricFilter {
+        contract_id: contract_id.clone(),
+        metric_name: metric_name.clone(),
+        from,
+        to,
+    };
+
+    let rows = histogram_samples_query(&filter, group_by.as_deref())
+        .build_query_as::<(Option<String>, sqlx::types::Json<Vec<HistogramBucket>>)>()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_error("fetch histogram samples", e))?;
+
+    let mut grouped: BTreeMap<Option<String>, Vec<Vec<HistogramBucket>>> = BTreeMap::new();
+    for (label_value, buckets) in rows {
+        grouped.entry(label_value).or_default().push(buckets.0);
+    }
+
+    let groups = grouped
+        .into_iter()
+        .map(|(label_value, samples)| {
+            let sample_count = samples.len() as i64;
+            let buckets = merge_buckets(samples.iter().map(Vec::as_slice));
+            let percentiles = percentiles
+                .iter()
+                .map(|&p| (percentile_label(p), bucket_percentile(&buckets, p)))
+                .collect();
+            HistogramGroup {
+                label_value,
+                sample_count,
+                buckets,
+                percentiles,
+            }
+        })
+        .collect();
+
+    Ok(Json(MetricHistogramResponse {
+        contract_id,
+        metric_name,
+        group_by,
+        groups,
+    }))
+}
+
+/// Most histogram samples merged for one query, newest first
+const MAX_HISTOGRAM_SAMPLES: i64 = 10_000;
+
+/// Selects the label value a row groups under, or NULL when not grouping
+fn push_group_column<'a>(qb: &mut QueryBuilder<'a, Postgres>, group_by: Option<&'a str>) {
+    match group_by {
+        Some(key) => {
+            qb.push("labels ->> ");
+            qb.push_bind(key);
+        }
+        None => {
+            qb.push("NULL::text");
+        }
+    }
+    qb.push(" AS label_value");
+}
+
+fn label_aggregate_query<'a>(
+    filter: &'a MetricFilter,
+    group_by: Option<&'a str>,
+) -> QueryBuilder<'a, Postgres> {
+    let mut qb = QueryBuilder::new("SELECT ");
+    push_group_column(&mut qb, group_by);
+    qb.push(
+        ", COUNT(*) AS sample_count, SUM(value)::float8 AS sum_value, \
+         AVG(value)::float8 AS avg_value, MIN(value)::float8 AS min_value, \
+         MAX(value)::float8 AS max_value FROM contract_custom_metrics",
+    );
+    push_filters(&mut qb, &MetricSource::RAW, filter);
+    qb.push(" GROUP BY 1 ORDER BY 1 NULLS LAST");
+    qb
+}
+
+fn histogram_samples_query<'a>(
+    filter: &'a MetricFilter,
+    group_by: Option<&'a str>,
+) -> QueryBuilder<'a, Postgres> {
+    let mut qb = QueryBuilder::new("SELECT ");
+    push_group_column(&mut qb, group_by);
+    qb.push(", buckets FROM contract_custom_metrics");
+    push_filters(&mut qb, &MetricSource::RAW, filter);
+    qb.push(" AND metric_type = 'histogram' AND buckets IS NOT NULL");
+    qb.push(" ORDER BY timestamp DESC, id DESC LIMIT ");
+    qb.push_bind(MAX_HISTOGRAM_SAMPLES);
+    qb
+}
+
+/// Sums per-bucket counts across samples; samples may use different bounds,
+/// in which case the result carries the union of them.
+fn merge_buckets<'a>(
+    samples: impl IntoIterator<Item = &'a [HistogramBucket]>,
+) -> Vec<HistogramBucket> {
+    let mut all: Vec<HistogramBucket> = samples.into_iter().flatten().cloned().collect();
+    all.sort_by(|a, b| a.le.total_cmp(&b.le));
+
+    let mut merged: Vec<HistogramBucket> = Vec::new();
+    for bucket in all {
+        match merged.last_mut() {
+            Some(last) if last.le == bucket.le => last.count += bucket.count,
+            _ => merged.push(bucket),
+        }
+    }
+    merged
+}
+
+/// Estimates the `p`th percentile (0-100) by interpolating linearly inside
+/// the bucket holding the target rank. The first bucket is assumed to start
+/// at zero, or at its own bound when that is negative.
+fn bucket_percentile(buckets: &[HistogramBucket], p: f64) -> Option<f64> {
+    let total: u64 = buckets.iter().map(|b| b.count).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let rank = (p / 100.0) * total as f64;
+    let mut seen = 0u64;
+    let mut lower = buckets.first().map_or(0.0, |b| b.le.min(0.0));
+    for bucket in buckets {
+        if bucket.count > 0 && (seen + bucket.count) as f64 >= rank {
+            let within = (rank - seen as f64).max(0.0) / bucket.count as f64;
+            return Some(lower + (bucket.le - lower) * within);
+        }
+        seen += bucket.count;
+        lower = bucket.le;
+    }
+    buckets.last().map(|b| b.le)
+}
+
+fn percentile_label(p: f64) -> String {
+    format!("p{}", p)
+}
+
+fn parse_percentiles(raw: Option<&str>) -> ApiResult<Vec<f64>> {
+    let Some(raw) = raw.filter(|raw| !raw.trim().is_empty()) else {
+        return Ok(DEFAULT_PERCENTILES.to_vec());
+    };
+    raw.split(',')
+        .map(|part| match part.trim().parse::<f64>() {
+            Ok(p) if (0.0..=100.0).contains(&p) => Ok(p),
+            _ => Err(ApiError::bad_request(
+                "InvalidPercentile",
+                format!("'{}' is not a percentile between 0 and 100", part.trim()),
+            )),
+        })
+        .collect()
+}
+
+fn required_metric_name(metric: Option<String>) -> ApiResult<String> {
+    let metric_name = match metric {
+        Some(name) if !name.trim().is_empty() => name,
+        _ => {
+            return Err(ApiError::bad_request(
+                "MissingMetric",
+                "Query parameter 'metric' is required", // e.g. ?metric=custom_trades_volume
+            ));
+        }
+    };
+    if metric_name.len() > MAX_METRIC_NAME_LEN {
+        return Err(ApiError::bad_request(
+            "InvalidMetric",
+            format!(
+                "Metric name must be at most {} characters",
+                MAX_METRIC_NAME_LEN
+            ),
+        ));
+    }
+    Ok(metric_name)
+}
+
+fn optional_label_key(group_by: Option<String>) -> ApiResult<Option<String>> {
+    match group_by {
+        Some(key) if key.trim().is_empty() => Ok(None),
+        Some(key) if key.len() > MAX_LABEL_LEN => Err(ApiError::bad_request(
+            "InvalidGroupBy",
+            format!("Label keys must be at most {} characters", MAX_LABEL_LEN),
+        )),
+        other => Ok(other),
+    }
+}
+
+/// Optional `from` / `to` bounds of a metric query
+type TimeRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+fn parse_range(from: Option<&str>, to: Option<&str>) -> ApiResult<TimeRange> {
+    let from_ts = parse_timestamp(from, "from")?;
+    let to_ts = parse_timestamp(to, "to")?;
+    if let (Some(from), Some(to)) = (from_ts, to_ts) {
+        if from > to {
+            return Err(ApiError::bad_request(
+                "InvalidRange",
+                "'from' must not be after 'to'",
+            ));
+        }
+    }
+    Ok((from_ts, to_ts))
+}
+
+/// Checks labels and histogram buckets before a sample is stored
+fn validate_metric(metric: &RecordCustomMetricRequest) -> ApiResult<()> {
+    if metric.labels.len() > MAX_LABELS {
+        return Err(ApiError::bad_request(
+            "TooManyLabels",
+            format!("A metric may carry at most {} labels", MAX_LABELS),
+        ));
+    }
+    if metric
+        .labels
+        .iter()
+        .any(|(k, v)| k.is_empty() || k.len() > MAX_LABEL_LEN || v.len() > MAX_LABEL_LEN)
+    {
+        return Err(ApiError::bad_request(
+            "InvalidLabel",
+            format!(
+                "Label keys must be non-empty and keys and values at most {} characters",
+                MAX_LABEL_LEN
+            ),
+        ));
+    }
+
+    match (&metric.metric_type, &metric.buckets) {
+        (CustomMetricType::Histogram, None) => Err(ApiError::bad_request(
+            "MissingBuckets",
+            "Histogram metrics must include 'buckets'",
+        )),
+        (CustomMetricType::Histogram, Some(buckets)) => {
+            if buckets.is_empty() || buckets.len() > MAX_HISTOGRAM_BUCKETS {
+                return Err(ApiError::bad_request(
+                    "InvalidBuckets",
+                    format!(
+                        "Histograms must have between 1 and {} buckets",
+                        MAX_HISTOGRAM_BUCKETS
+                    ),
+                ));
+            }
+            let ascending = buckets.iter().all(|b| b.le.is_finite())
+                && buckets.windows(2).all(|w| w[0].le < w[1].le);
+            if !ascending {
+                return Err(ApiError::bad_request(
+                    "InvalidBuckets",
+                    "Bucket bounds must be finite and strictly increasing",
+                ));
+            }
+            Ok(())
+        }
+        (_, Some(_)) => Err(ApiError::bad_request(
+            "UnexpectedBuckets",
+            "Only histogram metrics may include 'buckets'",
+        )),
+        (_, None) => Ok(()),
+    }
+}
+
+const RAW_COLUMNS: &str = "id, contract_id, metric_name, metric_type, value, unit, metadata, \
+     ledger_sequence, transaction_hash, timestamp, network, created_at, labels, buckets";
+const AGGREGATE_COLUMNS: &str = "contract_id, metric_name, metric_type, bucket_start, bucket_end, \
+     sample_count, sum_value, avg_value, min_value, max_value, p50_value, p95_value, p99_value";
+
+/// Table a metric listing reads from, with its columns, time column and ordering
+struct MetricSource {
+    table: &'static str,
+    columns: &'static str,
+    time_column: &'static str,
+    /// Total order so pages never overlap or skip rows
+    order_by: &'static str,
+}
+
+impl MetricSource {
+    const RAW: Self = Self {
+        table: "contract_custom_metrics",
+        columns: RAW_COLUMNS,
+        time_column: "timestamp",
+        order_by: "timestamp DESC, id DESC",
+    };
+    const HOURLY: Self = Self::aggregate("contract_custom_metrics_hourly");
+    const DAILY: Self = Self::aggregate("contract_custom_metrics_daily");
+
+    const fn aggregate(table: &'static str) -> Self {
+        Self {
+            table,
+            columns: AGGREGATE_COLUMNS,
+            time_column: "bucket_start",
+            order_by: "bucket_start DESC, bucket_end DESC",
+        }
+    }
+}
+
+struct MetricFilter {
+    contract_id: String,
+    metric_name: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+/// Appends the WHERE clause shared by a listing and its count; every
+/// caller-supplied value is bound, never interpolated.
+fn push_filters<'a>(
+    qb: &mut QueryBuilder<'a, Postgres>,
+    source: &MetricSource,
+    filter: &'a MetricFilter,
+) {
+    qb.push(" WHERE contract_id = ");
+    qb.push_bind(&filter.contract_id);
+    qb.push(" AND metric_name = ");
+    qb.push_bind(&filter.metric_name);
+
+    if let Some(from) = filter.from {
+        qb.push(format_args!(" AND {} >= ", source.time_column));
+        qb.push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        qb.push(format_args!(" AND {} <= ", source.time_column));
+        qb.push_bind(to);
+    }
+}
+
+fn page_query<'a>(
+    source: &MetricSource,
+    filter: &'a MetricFilter,
+    limit: i64,
+    offset: i64,
+) -> QueryBuilder<'a, Postgres> {
+    let mut qb = QueryBuilder::new(format!("SELECT {} FROM {}", source.columns, source.table));
+    push_filters(&mut qb, source, filter);
+    qb.push(format_args!(" ORDER BY {} LIMIT ", source.order_by));
+    qb.push_bind(limit);
+    qb.push(" OFFSET ");
+    qb.push_bind(offset);
+    qb
+}
+
+fn count_query<'a>(source: &MetricSource, filter: &'a MetricFilter) -> QueryBuilder<'a, Postgres> {
+    let mut qb = QueryBuilder::new(format!("SELECT COUNT(*) FROM {}", source.table));
+    push_filters(&mut qb, source, filter);
+    qb
+}
+
+async fn count_matching(
+    state: &AppState,
+    source: &MetricSource,
+    filter: &MetricFilter,
+) -> ApiResult<i64> {
+    count_query(source, filter)
+        .build_query_scalar::<i64>()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_error("count metrics", e))
+}
+
+fn parse_timestamp(raw: Option<&str>, field: &str) -> ApiResult<Option<DateTime<Utc>>> {
+    raw.map(|raw| {
+        raw.parse::<DateTime<Utc>>().map_err(|_| {
+            ApiError::bad_request(
+                "InvalidTimestamp",
+                format!("'{}' must be an RFC 3339 timestamp", field),
+            )
+        })
+    })
+    .transpose()
+}
+
 async fn fetch_metric_type(
     state: &AppState,
     contract_id: &str,
@@ -330,24 +745,27 @@ async fn fetch_metric_type(
 pub async fn record_contract_metric(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
-    Json(payload): Json<RecordCustomMetricRequest>,
+    payload: Result<Json<RecordCustomMetricRequest>, JsonRejection>,
 ) -> ApiResult<Json<CustomMetric>> {
+    let Json(payload) = payload.map_err(map_json_rejection)?;
     if payload.contract_id != contract_id {
         return Err(ApiError::bad_request(
             "ContractMismatch",
             "Contract ID in payload does not match path",
         ));
     }
+    validate_metric(&payload)?;
 
     let timestamp = payload.timestamp.unwrap_or_else(Utc::now);
     let network = payload.network.unwrap_or(shared::Network::Testnet);
 
     let metric = sqlx::query_as::<_, CustomMetric>(
         "INSERT INTO contract_custom_metrics \
-         (contract_id, metric_name, metric_type, value, unit, metadata, ledger_sequence, transaction_hash, timestamp, network) \
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+         (contract_id, metric_name, metric_type, value, unit, metadata, ledger_sequence, transaction_hash, timestamp, network, \
+          labels, buckets) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) \
          RETURNING id, contract_id, metric_name, metric_type, value, unit, metadata, ledger_sequence, transaction_hash, \
-                   timestamp, network, created_at",
+                   timestamp, network, created_at, labels, buckets",
     )
     .bind(&payload.contract_id)
     .bind(&payload.metric_name)
@@ -359,6 +777,8 @@ pub async fn record_contract_metric(
     .bind(&payload.transaction_hash)
     .bind(timestamp)
     .bind(network)
+    .bind(sqlx::types::Json(&payload.labels))
+    .bind(payload.buckets.as_ref().map(sqlx::types::Json))
     .fetch_one(&state.db)
     .await
     .map_err(|e| db_error("insert custom metric", e))?;
@@ -369,8 +789,9 @@ pub async fn record_contract_metric(
 pub async fn record_metrics_batch(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
-    Json(payload): Json<Vec<RecordCustomMetricRequest>>,
+    payload: Result<Json<Vec<RecordCustomMetricRequest>>, JsonRejection>,
 ) -> ApiResult<Json<serde_json::Value>> {
+    let Json(payload) = payload.map_err(map_json_rejection)?;
     if payload.is_empty() {
         return Ok(Json(serde_json::json!({
             "inserted": 0,
@@ -383,7 +804,7 @@ pub async fn record_metrics_batch(
     let mut errors = 0u64;
 
     for metric in payload {
-        if metric.contract_id != contract_id {
+        if metric.contract_id != contract_id || validate_metric(&metric).is_err() {
             errors += 1;
             continue;
         }
@@ -392,8 +813,9 @@ pub async fn record_metrics_batch(
 
         let result = sqlx::query(
             "INSERT INTO contract_custom_metrics \
-             (contract_id, metric_name, metric_type, value, unit, metadata, ledger_sequence, transaction_hash, timestamp, network) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+             (contract_id, metric_name, metric_type, value, unit, metadata, ledger_sequence, transaction_hash, timestamp, network, \
+              labels, buckets) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
         )
         .bind(&metric.contract_id)
         .bind(&metric.metric_name)
@@ -405,6 +827,8 @@ pub async fn record_metrics_batch(
         .bind(&metric.transaction_hash)
         .bind(timestamp)
         .bind(network)
+        .bind(sqlx::types::Json(&metric.labels))
+        .bind(metric.buckets.as_ref().map(sqlx::types::Json))
         .execute(&state.db)
         .await;
 
@@ -423,3 +847,163 @@ pub async fn record_metrics_batch(
         "total": inserted + errors
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(metric_name: &str) -> MetricFilter {
+        MetricFilter {
+            contract_id: "CABC".to_string(),
+            metric_name: metric_name.to_string(),
+            from: Some(Utc::now() - chrono::Duration::days(1)),
+            to: None,
+        }
+    }
+
+    #[test]
+    fn malicious_metric_name_is_bound_not_interpolated() {
+        let name = "x'; DROP TABLE contract_custom_metrics; --";
+        let filter = filter(name);
+
+        for source in [MetricSource::RAW, MetricSource::HOURLY, MetricSource::DAILY] {
+            let page = page_query(&source, &filter, 50, 100);
+            let count = count_query(&source, &filter);
+            for sql in [page.sql(), count.sql()] {
+                assert!(!sql.contains("DROP"), "{}", sql);
+                assert!(sql.contains("metric_name = $2"), "{}", sql);
+            }
+        }
+    }
+
+    #[test]
+    fn total_counts_every_page_with_the_same_filters() {
+        let filter = filter("custom_trades_volume");
+        let source = MetricSource::RAW;
+
+        let page = page_query(&source, &filter, 50, 100);
+        let count = count_query(&source, &filter);
+
+        assert!(page
+            .sql()
+            .ends_with("ORDER BY timestamp DESC, id DESC LIMIT $4 OFFSET $5"));
+        // The count shares the WHERE clause but is not limited to one page
+        let (_, where_clause) = count.sql().split_once(" WHERE ").unwrap();
+        assert!(page.sql().contains(where_clause));
+        assert!(!count.sql().contains("LIMIT"));
+    }
+
+    fn bucket(le: f64, count: u64) -> HistogramBucket {
+        HistogramBucket { le, count }
+    }
+
+    fn histogram(buckets: Option<Vec<HistogramBucket>>) -> RecordCustomMetricRequest {
+        RecordCustomMetricRequest {
+            contract_id: "CABC".to_string(),
+            metric_name: "swap_latency_ms".to_string(),
+            metric_type: CustomMetricType::Histogram,
+            value: 0.0,
+            unit: None,
+            metadata: None,
+            ledger_sequence: None,
+            transaction_hash: None,
+            timestamp: None,
+            network: None,
+            labels: Default::default(),
+            buckets,
+        }
+    }
+
+    #[test]
+    fn percentiles_interpolate_within_the_target_bucket() {
+        let buckets = [bucket(10.0, 50), bucket(100.0, 40), bucket(1000.0, 10)];
+
+        // 50 of 100 observations sit at or below 10
+        assert_eq!(bucket_percentile(&buckets, 50.0), Some(10.0));
+        // p70 is halfway through the (10, 100] bucket
+        assert_eq!(bucket_percentile(&buckets, 70.0), Some(55.0));
+        // the first bucket interpolates up from zero
+        assert_eq!(bucket_percentile(&buckets, 25.0), Some(5.0));
+        assert_eq!(bucket_percentile(&buckets, 100.0), Some(1000.0));
+        assert_eq!(bucket_percentile(&[bucket(10.0, 0)], 50.0), None);
+    }
+
+    #[test]
+    fn merging_sums_matching_bounds_and_keeps_the_rest() {
+        let a = [bucket(10.0, 1), bucket(100.0, 2)];
+        let b = [bucket(10.0, 3), bucket(50.0, 4)];
+
+        let merged = merge_buckets([&a[..], &b[..]]);
+
+        assert_eq!(
+            merged,
+            vec![bucket(10.0, 4), bucket(50.0, 4), bucket(100.0, 2)]
+        );
+    }
+
+    #[test]
+    fn histogram_buckets_are_validated() {
+        assert!(validate_metric(&histogram(Some(vec![bucket(1.0, 1), bucket(5.0, 2)]))).is_ok());
+        assert!(validate_metric(&histogram(None)).is_err());
+        assert!(validate_metric(&histogram(Some(vec![]))).is_err());
+        assert!(validate_metric(&histogram(Some(vec![bucket(5.0, 1), bucket(1.0, 2)]))).is_err());
+        assert!(validate_metric(&histogram(Some(vec![bucket(f64::INFINITY, 1)]))).is_err());
+
+        let mut gauge = histogram(Some(vec![bucket(1.0, 1)]));
+        gauge.metric_type = CustomMetricType::Gauge;
+        assert!(validate_metric(&gauge).is_err());
+        gauge.buckets = None;
+        gauge
+            .labels
+            .insert("function".to_string(), "swap".to_string());
+        assert!(validate_metric(&gauge).is_ok());
+        gauge.labels.insert(String::new(), "x".to_string());
+        assert!(validate_metric(&gauge).is_err());
+    }
+
+    #[test]
+    fn group_by_label_key_is_bound_not_interpolated() {
+        let filter = filter("custom_trades_volume");
+        let key = "function'; DROP TABLE contract_custom_metrics; --";
+
+        for qb in [
+            label_aggregate_query(&filter, Some(key)),
+            histogram_samples_query(&filter, Some(key)),
+        ] {
+            let sql = qb.sql();
+            assert!(!sql.contains("DROP"), "{}", sql);
+            assert!(
+                sql.starts_with("SELECT labels ->> $1 AS label_value"),
+                "{}",
+                sql
+            );
+            assert!(sql.contains("metric_name = $3"), "{}", sql);
+        }
+        assert!(label_aggregate_query(&filter, None)
+            .sql()
+            .starts_with("SELECT NULL::text AS label_value"));
+    }
+
+    #[test]
+    fn percentile_list_is_parsed_and_bounded() {
+        assert_eq!(
+            parse_percentiles(None).unwrap(),
+            DEFAULT_PERCENTILES.to_vec()
+        );
+        assert_eq!(
+            parse_percentiles(Some("90, 99.9")).unwrap(),
+            vec![90.0, 99.9]
+        );
+        assert!(parse_percentiles(Some("101")).is_err());
+        assert!(parse_percentiles(Some("p95")).is_err());
+    }
+
+    #[test]
+    fn unparsable_timestamp_is_rejected() {
+        assert!(parse_timestamp(Some("yesterday"), "from").is_err());
+        assert!(parse_timestamp(None, "from").unwrap().is_none());
+        assert!(parse_timestamp(Some("2026-01-01T00:00:00Z"), "to")
+            .unwrap()
+            .is_some());
+    }
+}
@@ -1,9 +1,76 @@
 use crate::cache::CacheLayer;
 use crate::metrics;
+use serde::Serialize;
+use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::time::Duration;
 
+/// Default for `DB_ACQUIRE_TIMEOUT_SECS`
+const DEFAULT_ACQUIRE_TIMEOUT_SECS: u64 = 30;
+/// Default for `DB_IDLE_TIMEOUT_SECS`
+const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
+
+/// Connection pool settings, read from the environment at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbPoolConfig {
+    pub max_connections: u32,
+    pub acquire_timeout: Duration,
+    pub idle_timeout: Duration,
+}
+
+impl DbPoolConfig {
+    /// `default_max_connections` applies when neither `DB_MAX_CONNECTIONS` nor
+    /// the older `DB_MAX_POOL_SIZE` is set.
+    pub fn from_env(default_max_connections: u32) -> Self {
+        Self::from_lookup(default_max_connections, |name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(default_max_connections: u32, var: impl Fn(&str) -> Option<String>) -> Self {
+        let positive = |name: &str| {
+            var(name)
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        Self {
+            max_connections: positive("DB_MAX_CONNECTIONS")
+                .or_else(|| positive("DB_MAX_POOL_SIZE"))
+                .map(|v| v.min(u32::MAX as u64) as u32)
+                .unwrap_or(default_max_connections),
+            acquire_timeout: Duration::from_secs(
+                positive("DB_ACQUIRE_TIMEOUT_SECS").unwrap_or(DEFAULT_ACQUIRE_TIMEOUT_SECS),
+            ),
+            idle_timeout: Duration::from_secs(
+                positive("DB_IDLE_TIMEOUT_SECS").unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS),
+            ),
+        }
+    }
+
+    pub fn pool_options(&self) -> PgPoolOptions {
+        PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+    }
+}
+
+/// Pool occupancy reported by the health check
+#[derive(Debug, Serialize)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: u32,
+    pub max_connections: u32,
+}
+
+pub fn pool_stats(pool: &PgPool) -> PoolStats {
+    PoolStats {
+        size: pool.size(),
+        idle: pool.num_idle() as u32,
+        max_connections: pool.options().get_max_connections(),
+    }
+}
+
 pub fn spawn_db_monitoring_task(pool: PgPool, cache: Arc<CacheLayer>) {
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(5));
@@ -41,11 +108,12 @@ pub fn spawn_db_monitoring_task(pool: PgPool, cache: Arc<CacheLayer>) {
                 );
             }
 
-            // Moka Cache Metrics
-            let abi_entries = cache.abi_cache.entry_count();
-            let abi_size = cache.abi_cache.weighted_size();
-            let ver_entries = cache.verification_cache.entry_count();
-            let ver_size = cache.verification_cache.weighted_size();
+            // Cache Metrics
+            let stats = cache.stats();
+            let abi_entries = stats.abi.entry_count;
+            let abi_size = stats.abi.weighted_size;
+            let ver_entries = stats.verification.entry_count;
+            let ver_size = stats.verification.weighted_size;
 
             metrics::CACHE_ENTRIES.set(abi_entries.saturating_add(ver_entries) as i64);
             metrics::CACHE_SIZE_BYTES.set(abi_size.saturating_add(ver_size) as i64);
@@ -86,3 +154,33 @@ pub async fn acquire_with_metrics(
 
     res
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_config_reads_env_and_falls_back_to_defaults() {
+        let config = DbPoolConfig::from_lookup(10, |name| match name {
+            "DB_MAX_CONNECTIONS" => Some("25".into()),
+            "DB_MAX_POOL_SIZE" => Some("40".into()),
+            "DB_IDLE_TIMEOUT_SECS" => Some("0".into()),
+            _ => None,
+        });
+
+        assert_eq!(config.max_connections, 25);
+        assert_eq!(
+            config.acquire_timeout,
+            Duration::from_secs(DEFAULT_ACQUIRE_TIMEOUT_SECS)
+        );
+        assert_eq!(
+            config.idle_timeout,
+            Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS)
+        );
+
+        let legacy =
+            DbPoolConfig::from_lookup(10, |name| (name == "DB_MAX_POOL_SIZE").then(|| "40".into()));
+        assert_eq!(legacy.max_connections, 40);
+        assert_eq!(DbPoolConfig::from_lookup(10, |_| None).max_connections, 10);
+    }
+}
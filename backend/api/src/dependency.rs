@@ -1,11 +1,13 @@
 use crate::error::ApiError;
 use anyhow::Result;
+use semver::{Version, VersionReq};
+use serde::Serialize;
 use shared::{
-    ContractDependency, DependencyDeclaration, DependencyTreeNode, GraphEdge, GraphNode,
-    GraphResponse,
+    Contract, ContractDependency, DependencyDeclaration, DependencyTreeNode, GraphEdge, GraphNode,
+    GraphResponse, ImpactLevel,
 };
 use sqlx::PgPool;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use uuid::Uuid;
 
 /// Detect dependencies from a contract ABI JSON
@@ -72,33 +74,79 @@ pub async fn get_transitive_dependencies(pool: &PgPool, root_id: Uuid) -> Result
     Ok(result)
 }
 
-/// Calculate transitive closure of dependents (all contracts affected by this one)
-pub async fn get_transitive_dependents(pool: &PgPool, root_id: Uuid) -> Result<Vec<Uuid>> {
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back(root_id);
-    visited.insert(root_id);
+/// Most dependency paths one impact analysis may walk before it stops
+pub const IMPACT_TRAVERSAL_LIMIT: i64 = 10_000;
 
-    let mut result = Vec::new();
-
-    while let Some(current_id) = queue.pop_front() {
-        let dependents: Vec<Uuid> = sqlx::query_scalar(
-            "SELECT contract_id FROM contract_dependencies WHERE dependency_contract_id = $1",
+/// Contracts that depend on `root_id`, directly or through up to `max_depth`
+/// levels of dependents, each with its shortest distance from the root.
+/// Returns `true` alongside when the walk hit `IMPACT_TRAVERSAL_LIMIT` and the
+/// result may be incomplete. The root appears in the result only when it
+/// depends on itself through a cycle.
+pub async fn get_dependents_within(
+    pool: &PgPool,
+    root_id: Uuid,
+    max_depth: u32,
+) -> Result<(Vec<(Uuid, i32)>, bool)> {
+    // Paths exclude revisits so cycles terminate; traversal stops at the root.
+    // The outer LIMIT halts the recursion itself once enough rows are produced.
+    let rows: Vec<(Uuid, i32, i64)> = sqlx::query_as(
+        r#"
+        WITH RECURSIVE impacted(contract_id, distance, path) AS (
+            SELECT d.contract_id, 1, ARRAY[d.contract_id]
+            FROM contract_dependencies d
+            WHERE d.dependency_contract_id = $1
+          UNION ALL
+            SELECT d.contract_id, i.distance + 1, i.path || d.contract_id
+            FROM impacted i
+            JOIN contract_dependencies d ON d.dependency_contract_id = i.contract_id
+            WHERE i.distance < $2
+              AND i.contract_id <> $1
+              AND NOT d.contract_id = ANY(i.path)
+        ),
+        walked AS (
+            SELECT contract_id, distance FROM impacted LIMIT $3
         )
-        .bind(current_id)
-        .fetch_all(pool)
-        .await?;
+        SELECT contract_id, MIN(distance), (SELECT COUNT(*) FROM walked)
+        FROM walked
+        GROUP BY contract_id
+        ORDER BY MIN(distance), contract_id
+        "#,
+    )
+    .bind(root_id)
+    .bind(max_depth as i32)
+    .bind(IMPACT_TRAVERSAL_LIMIT)
+    .fetch_all(pool)
+    .await?;
 
-        for dep_id in dependents {
-            if !visited.contains(&dep_id) {
-                visited.insert(dep_id);
-                queue.push_back(dep_id);
-                result.push(dep_id);
-            }
+    let truncated = rows
+        .first()
+        .is_some_and(|(_, _, walked)| *walked >= IMPACT_TRAVERSAL_LIMIT);
+    Ok((
+        rows.into_iter()
+            .map(|(id, distance, _)| (id, distance))
+            .collect(),
+        truncated,
+    ))
+}
+
+/// Group impacted contracts by their distance from the changed contract,
+/// nearest first. Distances without a fetched contract are left out.
+pub fn group_by_distance(distances: &[(Uuid, i32)], contracts: Vec<Contract>) -> Vec<ImpactLevel> {
+    let mut by_id: HashMap<Uuid, Contract> = contracts.into_iter().map(|c| (c.id, c)).collect();
+    let mut levels: BTreeMap<i32, Vec<Contract>> = BTreeMap::new();
+    for (id, distance) in distances {
+        if let Some(contract) = by_id.remove(id) {
+            levels.entry(*distance).or_default().push(contract);
         }
     }
 
-    Ok(result)
+    levels
+        .into_iter()
+        .map(|(distance, contracts)| ImpactLevel {
+            distance,
+            contracts,
+        })
+        .collect()
 }
 
 /// Detect if adding a dependency would create a cycle
@@ -128,12 +176,74 @@ pub async fn build_dependency_graph(pool: &PgPool) -> Result<GraphResponse> {
     .fetch_all(pool)
     .await?;
 
+    let cycles = find_cycles(&edges);
     Ok(GraphResponse {
         nodes: contracts,
         edges,
+        cycles,
     })
 }
 
+/// Circular dependencies among `edges`, found by depth-first search: one
+/// cycle per back edge, listed from the contract the loop returns to.
+/// A contract depending on itself is a cycle of one.
+pub fn find_cycles(edges: &[GraphEdge]) -> Vec<Vec<Uuid>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        OnPath,
+        Done,
+    }
+
+    // Ordered so the cycles reported are stable between calls
+    let mut adjacency: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    for edge in edges {
+        adjacency.entry(edge.source).or_default().push(edge.target);
+    }
+    for targets in adjacency.values_mut() {
+        targets.sort();
+        targets.dedup();
+    }
+
+    let mut visits: HashMap<Uuid, Visit> = HashMap::new();
+    let mut cycles = Vec::new();
+
+    for &root in adjacency.keys() {
+        if visits.contains_key(&root) {
+            continue;
+        }
+        // Iterative DFS; each entry is a node on the current path and the
+        // index of the next dependency of it to explore
+        let mut path: Vec<(Uuid, usize)> = vec![(root, 0)];
+        visits.insert(root, Visit::OnPath);
+
+        while let Some(&(node, next)) = path.last() {
+            let targets = adjacency.get(&node).map(Vec::as_slice).unwrap_or(&[]);
+            let Some(&target) = targets.get(next) else {
+                visits.insert(node, Visit::Done);
+                path.pop();
+                continue;
+            };
+            if let Some(top) = path.last_mut() {
+                top.1 += 1;
+            }
+
+            match visits.get(&target) {
+                None => {
+                    visits.insert(target, Visit::OnPath);
+                    path.push((target, 0));
+                }
+                Some(Visit::OnPath) => {
+                    let start = path.iter().position(|(n, _)| *n == target).unwrap_or(0);
+                    cycles.push(path[start..].iter().map(|(n, _)| *n).collect());
+                }
+                Some(Visit::Done) => {}
+            }
+        }
+    }
+
+    cycles
+}
+
 /// Resolve a dependency name/id to a contract UUID if it exists in the registry
 pub async fn resolve_contract_id(pool: &PgPool, identifier: &str) -> Result<Option<Uuid>> {
     // Try UUID first
@@ -160,34 +270,66 @@ pub async fn resolve_contract_id(pool: &PgPool, identifier: &str) -> Result<Opti
     Ok(id)
 }
 
-/// Save dependencies for a contract, resolving them if possible
+/// A declared dependency that would put the contract declaring it on a cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCycle {
+    pub dependency: String,
+}
+
+impl std::fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dependency on {} would create a cycle", self.dependency)
+    }
+}
+
+impl std::error::Error for DependencyCycle {}
+
+impl From<DependencyCycle> for ApiError {
+    fn from(cycle: DependencyCycle) -> Self {
+        ApiError::conflict("DependencyCycle", cycle.to_string())
+    }
+}
+
+/// Resolve a contract's declared dependencies to registry contracts where
+/// possible, failing with `DependencyCycle` if any of them already depends on
+/// the contract
+pub async fn resolve_dependencies<'a>(
+    pool: &PgPool,
+    contract_id: Uuid,
+    decls: &'a [DependencyDeclaration],
+) -> Result<Vec<(&'a DependencyDeclaration, Option<Uuid>)>> {
+    let mut resolved = Vec::with_capacity(decls.len());
+    for decl in decls {
+        let dep_contract_id = resolve_contract_id(pool, &decl.name).await?;
+        if let Some(dep_id) = dep_contract_id {
+            if detect_cycle(pool, contract_id, dep_id).await? {
+                return Err(DependencyCycle {
+                    dependency: decl.name.clone(),
+                }
+                .into());
+            }
+        }
+        resolved.push((decl, dep_contract_id));
+    }
+    Ok(resolved)
+}
+
+/// Save dependencies for a contract, resolving them if possible. Leaves the
+/// existing dependencies in place rather than record a cycle.
 pub async fn save_dependencies(
     pool: &PgPool,
     contract_id: Uuid,
     decls: &[DependencyDeclaration],
 ) -> Result<()> {
+    let resolved = resolve_dependencies(pool, contract_id, decls).await?;
+
     // Clear existing dependencies (optional, depends on if we want to merge or replace)
     sqlx::query("DELETE FROM contract_dependencies WHERE contract_id = $1")
         .bind(contract_id)
         .execute(pool)
         .await?;
 
-    for decl in decls {
-        let dep_contract_id = resolve_contract_id(pool, &decl.name).await?;
-
-        if let Some(dep_id) = dep_contract_id {
-            if detect_cycle(pool, contract_id, dep_id)
-                .await
-                .unwrap_or(false)
-            {
-                tracing::warn!(
-                    "Circular dependency detected: contract {} -> {}",
-                    contract_id,
-                    dep_id
-                );
-            }
-        }
-
+    for (decl, dep_contract_id) in resolved {
         sqlx::query(
             "INSERT INTO contract_dependencies (contract_id, dependency_name, dependency_contract_id, version_constraint) 
              VALUES ($1, $2, $3, $4)
@@ -206,6 +348,108 @@ pub async fn save_dependencies(
     Ok(())
 }
 
+/// How far a dependency's latest version is ahead of the one its constraint
+/// resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateType {
+    Patch,
+    Minor,
+    Major,
+}
+
+/// A newer version of one of a contract's registry dependencies
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DependencyUpdate {
+    pub name: String,
+    pub dependency_contract_id: Uuid,
+    pub current_requirement: String,
+    /// Highest published version the requirement allows
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_type: UpdateType,
+    /// A security patch has been applied to the dependency for `current_version`
+    pub is_security: bool,
+}
+
+/// The version `requirement` resolves to among `published`, and the latest
+/// published version when it is newer. Unparseable versions are ignored, and
+/// nothing is reported when no published version satisfies the requirement.
+pub fn available_update(requirement: &str, published: &[String]) -> Option<(Version, Version)> {
+    let req = VersionReq::parse(requirement).ok()?;
+    let versions: Vec<Version> = published
+        .iter()
+        .filter_map(|v| Version::parse(v).ok())
+        .collect();
+    let current = versions.iter().filter(|v| req.matches(v)).max()?;
+    let latest = versions.iter().max()?;
+    (latest > current).then(|| (current.clone(), latest.clone()))
+}
+
+pub fn update_type(current: &Version, latest: &Version) -> UpdateType {
+    if current.major != latest.major {
+        UpdateType::Major
+    } else if current.minor != latest.minor {
+        UpdateType::Minor
+    } else {
+        UpdateType::Patch
+    }
+}
+
+/// Check each of the contract's resolved dependencies for a newer published
+/// version, the same comparison the scheduled update monitor makes.
+pub async fn check_dependency_updates(
+    pool: &PgPool,
+    contract_id: Uuid,
+) -> Result<Vec<DependencyUpdate>> {
+    let deps: Vec<(String, Uuid, String, Vec<String>)> = sqlx::query_as(
+        r#"
+        SELECT d.dependency_name, d.dependency_contract_id, d.version_constraint,
+               COALESCE(array_agg(v.version) FILTER (WHERE v.version IS NOT NULL), '{}')
+        FROM contract_dependencies d
+        LEFT JOIN contract_versions v ON v.contract_id = d.dependency_contract_id
+        WHERE d.contract_id = $1 AND d.dependency_contract_id IS NOT NULL
+        GROUP BY d.id
+        ORDER BY d.dependency_name
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await?;
+
+    let dep_ids: Vec<Uuid> = deps.iter().map(|(_, id, ..)| *id).collect();
+    let patched: Vec<(Uuid, String)> = sqlx::query_as(
+        r#"
+        SELECT pa.contract_id, sp.target_version
+        FROM patch_audits pa
+        JOIN security_patches sp ON sp.id = pa.patch_id
+        WHERE pa.contract_id = ANY($1)
+        "#,
+    )
+    .bind(&dep_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deps
+        .into_iter()
+        .filter_map(|(name, dep_id, requirement, published)| {
+            let (current, latest) = available_update(&requirement, &published)?;
+            let current_version = current.to_string();
+            Some(DependencyUpdate {
+                is_security: patched
+                    .iter()
+                    .any(|(id, version)| *id == dep_id && *version == current_version),
+                update_type: update_type(&current, &latest),
+                name,
+                dependency_contract_id: dep_id,
+                current_requirement: requirement,
+                current_version,
+                latest_version: latest.to_string(),
+            })
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +473,141 @@ mod tests {
         assert_eq!(deps[0].name, "TokenInterface");
     }
 
+    fn declaration(name: &str) -> DependencyDeclaration {
+        DependencyDeclaration {
+            name: name.to_string(),
+            version_constraint: "*".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn mutual_dependencies_are_rejected_before_saving() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let a = crate::test_support::seed_contract(&db, "cycle-a").await;
+        let b = crate::test_support::seed_contract(&db, "cycle-b").await;
+        save_dependencies(&db, b, &[declaration(&a.to_string())])
+            .await
+            .unwrap();
+
+        let err = save_dependencies(&db, a, &[declaration(&b.to_string())])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DependencyCycle>(),
+            Some(&DependencyCycle {
+                dependency: b.to_string()
+            })
+        );
+        let saved: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM contract_dependencies WHERE contract_id = $1")
+                .bind(a)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(saved, 0);
+    }
+
+    fn edge(source: Uuid, target: Uuid) -> GraphEdge {
+        GraphEdge {
+            source,
+            target,
+            dependency_type: "calls".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_find_cycles_self_dependency() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        let cycles = find_cycles(&[edge(a, a), edge(b, a)]);
+        assert_eq!(cycles, vec![vec![a]]);
+    }
+
+    #[test]
+    fn test_find_cycles_mutual_dependency() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+
+        let cycles = find_cycles(&[edge(a, b), edge(b, a), edge(c, a)]);
+        assert_eq!(cycles.len(), 1);
+        let mut members = cycles[0].clone();
+        members.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(members, expected);
+    }
+
+    #[test]
+    fn test_find_cycles_acyclic_graph() {
+        let (a, b, c, d) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+
+        // Diamond: d is reached twice but there is no loop
+        let edges = [edge(a, b), edge(a, c), edge(b, d), edge(c, d)];
+        assert!(find_cycles(&edges).is_empty());
+
+        let mut looped = edges.to_vec();
+        looped.push(edge(d, a));
+        let cycles = find_cycles(&looped);
+        assert!(!cycles.is_empty());
+        assert!(cycles
+            .iter()
+            .all(|cycle| cycle.contains(&d) && cycle.contains(&a)));
+    }
+
+    fn contract(id: Uuid, name: &str) -> Contract {
+        Contract {
+            id,
+            contract_id: format!("C{}", name.to_uppercase()),
+            wasm_hash: "a".repeat(64),
+            name: name.to_string(),
+            description: None,
+            publisher_id: Uuid::nil(),
+            network: shared::Network::Testnet,
+            is_verified: false,
+            category: None,
+            tags: vec![],
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            health_score: 0,
+            is_maintenance: false,
+            logical_id: None,
+            network_configs: None,
+        }
+    }
+
+    #[test]
+    fn test_group_by_distance() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let distances = [(a, 1), (c, 1), (b, 2)];
+        let contracts = vec![
+            contract(b, "vault"),
+            contract(a, "token"),
+            contract(c, "dex"),
+        ];
+
+        let levels = group_by_distance(&distances, contracts);
+
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].distance, 1);
+        let names: Vec<&str> = levels[0]
+            .contracts
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["token", "dex"]);
+        assert_eq!(levels[1].distance, 2);
+        assert_eq!(levels[1].contracts[0].id, b);
+    }
+
     #[test]
     fn test_detect_dependencies_duplicate() {
         let abi = json!([
@@ -239,4 +618,22 @@ mod tests {
         let deps = detect_dependencies_from_abi(&abi);
         assert_eq!(deps.len(), 1);
     }
+
+    #[test]
+    fn updates_compare_against_the_resolved_version() {
+        let published: Vec<String> = ["1.0.0", "1.2.3", "2.0.0", "not-a-version"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        let (current, latest) = available_update("^1.0", &published).unwrap();
+        assert_eq!(current.to_string(), "1.2.3");
+        assert_eq!(latest.to_string(), "2.0.0");
+        assert_eq!(update_type(&current, &latest), UpdateType::Major);
+
+        // Already on the latest, or nothing satisfies the requirement
+        assert_eq!(available_update("*", &published), None);
+        assert_eq!(available_update("^3", &published), None);
+        assert_eq!(available_update("not a requirement", &published), None);
+    }
 }
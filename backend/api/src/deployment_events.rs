@@ -0,0 +1,108 @@
+// api/src/deployment_events.rs
+// Wake-ups for long-polling deployment status watchers. Watchers re-read the
+// status periodically, which is what picks up changes today: nothing in the
+// API writes `contract_deployments` yet, so `notify` has no callers. A writer
+// added here should call it so watchers on this replica answer at once.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// One `Notify` per contract that currently has watchers
+#[derive(Default)]
+pub struct DeploymentEventHub {
+    watchers: Mutex<HashMap<Uuid, Arc<Notify>>>,
+}
+
+impl DeploymentEventHub {
+    /// Register interest in a contract's deployment status until the
+    /// returned guard is dropped.
+    pub fn watch(&self, contract_id: Uuid) -> DeploymentWatch<'_> {
+        let notify = self
+            .watchers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(contract_id)
+            .or_default()
+            .clone();
+        DeploymentWatch {
+            hub: self,
+            contract_id,
+            notify: Some(notify),
+        }
+    }
+
+    /// Wake everyone watching the contract's deployments.
+    pub fn notify(&self, contract_id: Uuid) {
+        let notify = self
+            .watchers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&contract_id)
+            .cloned();
+        if let Some(notify) = notify {
+            notify.notify_waiters();
+        }
+    }
+
+    /// Drop a watcher's handle, forgetting the contract once nobody watches it.
+    fn release(&self, contract_id: Uuid, handle: Arc<Notify>) {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|e| e.into_inner());
+        drop(handle);
+        if watchers
+            .get(&contract_id)
+            .is_some_and(|notify| Arc::strong_count(notify) == 1)
+        {
+            watchers.remove(&contract_id);
+        }
+    }
+}
+
+/// A registered watcher, released when dropped, including when the request
+/// holding it is cancelled
+pub struct DeploymentWatch<'a> {
+    hub: &'a DeploymentEventHub,
+    contract_id: Uuid,
+    notify: Option<Arc<Notify>>,
+}
+
+impl DeploymentWatch<'_> {
+    pub fn notify(&self) -> &Notify {
+        self.notify.as_deref().expect("present until dropped")
+    }
+}
+
+impl Drop for DeploymentWatch<'_> {
+    fn drop(&mut self) {
+        if let Some(notify) = self.notify.take() {
+            self.hub.release(self.contract_id, notify);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn watchers_are_woken_and_forgotten() {
+        let hub = DeploymentEventHub::default();
+        let contract_id = Uuid::new_v4();
+        let first = hub.watch(contract_id);
+        let second = hub.watch(contract_id);
+
+        let woken = first.notify().notified();
+        hub.notify(Uuid::new_v4());
+        hub.notify(contract_id);
+        tokio::time::timeout(Duration::from_secs(1), woken)
+            .await
+            .expect("watcher was not woken");
+
+        drop(first);
+        assert!(hub.watchers.lock().unwrap().contains_key(&contract_id));
+        drop(second);
+        assert!(hub.watchers.lock().unwrap().is_empty());
+    }
+}
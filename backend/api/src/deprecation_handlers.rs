@@ -1,12 +1,15 @@
 use axum::{
-    extract::{Path, State},
+    extract::{rejection::JsonRejection, Path, State},
     Json,
 };
 use chrono::{DateTime, Utc};
-use shared::{DeprecateContractRequest, DeprecationInfo, DeprecationStatus};
+use shared::{DeprecateContractRequest, DeprecationInfo, DeprecationReason, DeprecationStatus};
 use uuid::Uuid;
 
+use crate::activity_events::{ActivityEvent, ActivityKind};
+use crate::auth::{assert_owns_contract, Caller};
 use crate::error::{ApiError, ApiResult};
+use crate::handlers::map_json_rejection;
 use crate::state::AppState;
 
 pub async fn get_deprecation_info(
@@ -20,12 +23,16 @@ pub async fn get_deprecation_info(
         (
             DateTime<Utc>,
             DateTime<Utc>,
+            Option<DeprecationReason>,
             Option<Uuid>,
             Option<String>,
             Option<String>,
+            Option<DateTime<Utc>>,
+            Option<DateTime<Utc>>,
         ),
     >(
-        "SELECT deprecated_at, retirement_at, replacement_contract_id, migration_guide_url, notes \
+        "SELECT deprecated_at, retirement_at, reason, replacement_contract_id, migration_guide_url, notes, \
+                sunset_at, retired_at \
          FROM contract_deprecations WHERE contract_id = $1",
     )
     .bind(contract_uuid)
@@ -41,30 +48,36 @@ pub async fn get_deprecation_info(
     .await
     .map_err(|err| db_internal_error("count notifications", err))?;
 
-    if let Some((deprecated_at, retirement_at, replacement_id, guide_url, notes)) = record {
+    if let Some((
+        deprecated_at,
+        retirement_at,
+        reason,
+        replacement_id,
+        guide_url,
+        notes,
+        sunset_at,
+        retired_at,
+    )) = record
+    {
         let now = Utc::now();
-        let status = if now >= retirement_at {
-            DeprecationStatus::Retired
-        } else {
-            DeprecationStatus::Deprecated
-        };
-        let days_remaining = if retirement_at > now {
-            Some((retirement_at - now).num_days())
-        } else {
-            Some(0)
-        };
+        let status = deprecation_status(now, retirement_at, retired_at);
+        let days_remaining = Some(days_until(now, retirement_at));
+        let days_until_sunset = sunset_at.map(|at| days_until(now, at));
 
         let replacement_contract_id = replacement_id.map(|id| id.to_string());
 
         return Ok(Json(DeprecationInfo {
             contract_id,
             status,
+            reason,
             deprecated_at: Some(deprecated_at),
             retirement_at: Some(retirement_at),
             replacement_contract_id,
             migration_guide_url: guide_url,
             notes,
             days_remaining,
+            sunset_at,
+            days_until_sunset,
             dependents_notified,
         }));
     }
@@ -72,12 +85,15 @@ pub async fn get_deprecation_info(
     Ok(Json(DeprecationInfo {
         contract_id,
         status: DeprecationStatus::Active,
+        reason: None,
         deprecated_at: None,
         retirement_at: None,
         replacement_contract_id: None,
         migration_guide_url: None,
         notes: None,
         days_remaining: None,
+        sunset_at: None,
+        days_until_sunset: None,
         dependents_notified,
     }))
 }
@@ -85,9 +101,12 @@ pub async fn get_deprecation_info(
 pub async fn deprecate_contract(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<DeprecateContractRequest>,
+    caller: Caller,
+    payload: Result<Json<DeprecateContractRequest>, JsonRejection>,
 ) -> ApiResult<Json<DeprecationInfo>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let (contract_uuid, contract_id) = fetch_contract_identity(&state, &id).await?;
+    assert_owns_contract(&state, &caller, contract_uuid).await?;
 
     if req.migration_guide_url.is_none() && req.replacement_contract_id.is_none() {
         return Err(ApiError::bad_request(
@@ -103,32 +122,70 @@ pub async fn deprecate_contract(
         ));
     }
 
-    let replacement_uuid = if let Some(ref selector) = req.replacement_contract_id {
-        Some(fetch_contract_uuid(&state, selector).await?)
+    if req.sunset_at.is_some_and(|at| at <= Utc::now()) {
+        return Err(ApiError::bad_request(
+            "InvalidSunsetDate",
+            "sunset_at must be in the future",
+        ));
+    }
+
+    let replacement = if let Some(ref selector) = req.replacement_contract_id {
+        let (replacement_uuid, replacement_contract_id) =
+            fetch_contract_identity(&state, selector).await?;
+        let replacement_deprecated: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM contract_deprecations WHERE contract_id = $1)",
+        )
+        .bind(replacement_uuid)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("check replacement deprecation", err))?;
+
+        validate_replacement(contract_uuid, replacement_uuid, replacement_deprecated)?;
+        Some((replacement_uuid, replacement_contract_id))
     } else {
         None
     };
 
     sqlx::query(
-        "INSERT INTO contract_deprecations (contract_id, retirement_at, replacement_contract_id, migration_guide_url, notes) \
-         VALUES ($1, $2, $3, $4, $5) \
+        "INSERT INTO contract_deprecations (contract_id, retirement_at, reason, replacement_contract_id, migration_guide_url, notes, sunset_at) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) \
          ON CONFLICT (contract_id) DO UPDATE SET \
            retirement_at = EXCLUDED.retirement_at, \
+           reason = EXCLUDED.reason, \
            replacement_contract_id = EXCLUDED.replacement_contract_id, \
            migration_guide_url = EXCLUDED.migration_guide_url, \
            notes = EXCLUDED.notes, \
+           sunset_at = EXCLUDED.sunset_at, \
+           retired_at = NULL, \
            updated_at = NOW()",
     )
     .bind(contract_uuid)
     .bind(req.retirement_at)
-    .bind(replacement_uuid)
+    .bind(req.reason)
+    .bind(replacement.as_ref().map(|(uuid, _)| *uuid))
     .bind(&req.migration_guide_url)
     .bind(&req.notes)
+    .bind(req.sunset_at)
     .execute(&state.db)
     .await
     .map_err(|err| db_internal_error("upsert deprecation", err))?;
 
-    notify_dependents(&state, contract_uuid, &contract_id, req.retirement_at).await?;
+    let message = deprecation_message(
+        &contract_id,
+        req.retirement_at,
+        req.reason,
+        replacement.as_ref().map(|(_, id)| id.as_str()),
+    );
+    notify_dependents(&state, contract_uuid, &contract_id, &message).await?;
+    state.activity_events.publish(ActivityEvent::new(
+        ActivityKind::Deprecate,
+        Some(contract_uuid),
+        serde_json::json!({
+            "contract_id": contract_id,
+            "retirement_at": req.retirement_at,
+            "replacement_contract_id": replacement.as_ref().map(|(_, id)| id),
+        }),
+    ));
 
     get_deprecation_info(State(state), Path(contract_id)).await
 }
@@ -137,7 +194,7 @@ async fn notify_dependents(
     state: &AppState,
     deprecated_id: Uuid,
     contract_id: &str,
-    retirement_at: DateTime<Utc>,
+    message: &str,
 ) -> ApiResult<()> {
     let has_dep_contract_id =
         column_exists(state, "contract_dependencies", "dependency_contract_id").await?;
@@ -178,12 +235,6 @@ async fn notify_dependents(
     }
 
     for dependent in dependents {
-        let message = format!(
-            "Contract {} has been deprecated and will retire on {}",
-            contract_id,
-            retirement_at.to_rfc3339()
-        );
-
         let _ = sqlx::query(
             "INSERT INTO contract_deprecation_notifications (contract_id, deprecated_contract_id, message) \
              VALUES ($1, $2, $3) \
@@ -191,7 +242,7 @@ async fn notify_dependents(
         )
         .bind(dependent)
         .bind(deprecated_id)
-        .bind(&message)
+        .bind(message)
         .execute(&state.db)
         .await
         .map_err(|err| db_internal_error("insert notification", err))?;
@@ -233,29 +284,73 @@ async fn fetch_contract_identity(state: &AppState, id: &str) -> ApiResult<(Uuid,
     })
 }
 
-async fn fetch_contract_uuid(state: &AppState, contract_id: &str) -> ApiResult<Uuid> {
-    if let Ok(uuid) = Uuid::parse_str(contract_id) {
-        return Ok(uuid);
+/// Retired once the sunset job has run or the retirement date has passed.
+fn deprecation_status(
+    now: DateTime<Utc>,
+    retirement_at: DateTime<Utc>,
+    retired_at: Option<DateTime<Utc>>,
+) -> DeprecationStatus {
+    if retired_at.is_some() || now >= retirement_at {
+        DeprecationStatus::Retired
+    } else {
+        DeprecationStatus::Deprecated
     }
+}
 
-    let uuid = sqlx::query_scalar::<_, Uuid>("SELECT id FROM contracts WHERE contract_id = $1")
-        .bind(contract_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|err| db_internal_error("fetch contract", err))?
-        .ok_or_else(|| {
-            ApiError::not_found(
-                "ContractNotFound",
-                format!("Contract '{}' not found", contract_id),
-            )
-        })?;
+/// Whole days from `now` until `at`, or 0 once it has passed
+fn days_until(now: DateTime<Utc>, at: DateTime<Utc>) -> i64 {
+    if at > now {
+        (at - now).num_days()
+    } else {
+        0
+    }
+}
+
+/// A replacement must be a different contract that is not itself on its way out.
+fn validate_replacement(
+    contract_uuid: Uuid,
+    replacement_uuid: Uuid,
+    replacement_deprecated: bool,
+) -> ApiResult<()> {
+    if replacement_uuid == contract_uuid {
+        return Err(ApiError::bad_request(
+            "InvalidReplacementContract",
+            "A contract cannot be its own replacement",
+        ));
+    }
+
+    if replacement_deprecated {
+        return Err(ApiError::unprocessable(
+            "DeprecatedReplacementContract",
+            "replacement_contract_id points to a contract that is itself deprecated",
+        ));
+    }
 
-    Ok(uuid)
+    Ok(())
+}
+
+fn deprecation_message(
+    contract_id: &str,
+    retirement_at: DateTime<Utc>,
+    reason: Option<DeprecationReason>,
+    replacement_contract_id: Option<&str>,
+) -> String {
+    let mut message = format!(
+        "Contract {} has been deprecated and will retire on {}",
+        contract_id,
+        retirement_at.to_rfc3339()
+    );
+    if let Some(reason) = reason {
+        message.push_str(&format!(" (reason: {})", reason));
+    }
+    if let Some(replacement) = replacement_contract_id {
+        message.push_str(&format!("; migrate to {}", replacement));
+    }
+    message
 }
 
 fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = operation, error = ?err, "database operation failed");
-    ApiError::internal("Database operation failed")
+    ApiError::database(operation, err)
 }
 
 async fn column_exists(state: &AppState, table: &str, column: &str) -> ApiResult<bool> {
@@ -270,3 +365,91 @@ async fn column_exists(state: &AppState, table: &str, column: &str) -> ApiResult
 
     Ok(exists)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    #[test]
+    fn replacement_link_round_trips() {
+        let replacement = Uuid::new_v4();
+        let req: DeprecateContractRequest = serde_json::from_value(serde_json::json!({
+            "retirement_at": "2030-01-01T00:00:00Z",
+            "reason": "superseded",
+            "replacement_contract_id": replacement.to_string(),
+        }))
+        .unwrap();
+        assert_eq!(req.reason, Some(DeprecationReason::Superseded));
+        assert!(validate_replacement(Uuid::new_v4(), replacement, false).is_ok());
+
+        let info = DeprecationInfo {
+            contract_id: "CDEPRECATED".to_string(),
+            status: DeprecationStatus::Deprecated,
+            reason: req.reason,
+            deprecated_at: Some(Utc::now()),
+            retirement_at: Some(req.retirement_at),
+            replacement_contract_id: req.replacement_contract_id.clone(),
+            migration_guide_url: None,
+            notes: None,
+            days_remaining: Some(10),
+            sunset_at: None,
+            days_until_sunset: None,
+            dependents_notified: 0,
+        };
+        let body = serde_json::to_value(&info).unwrap();
+        assert_eq!(body["reason"], "superseded");
+        assert_eq!(body["replacement_contract_id"], replacement.to_string());
+    }
+
+    #[test]
+    fn deprecated_replacement_is_rejected() {
+        let err = validate_replacement(Uuid::new_v4(), Uuid::new_v4(), true).unwrap_err();
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[test]
+    fn self_replacement_is_rejected() {
+        let id = Uuid::new_v4();
+        let err = validate_replacement(id, id, false).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn notification_message_points_to_replacement() {
+        let message = deprecation_message(
+            "COLD",
+            Utc::now(),
+            Some(DeprecationReason::Vulnerable),
+            Some("CNEW"),
+        );
+        assert!(message.contains("reason: vulnerable"));
+        assert!(message.ends_with("migrate to CNEW"));
+    }
+
+    #[test]
+    fn sunset_countdown_and_retired_status() {
+        let now = Utc::now();
+        let retirement_at = now + chrono::Duration::days(60);
+        let sunset_at = now + chrono::Duration::days(30) + chrono::Duration::hours(1);
+
+        assert_eq!(days_until(now, sunset_at), 30);
+        assert_eq!(days_until(now, now - chrono::Duration::days(2)), 0);
+        assert_eq!(
+            deprecation_status(now, retirement_at, None),
+            DeprecationStatus::Deprecated
+        );
+        assert_eq!(
+            deprecation_status(now, retirement_at, Some(now)),
+            DeprecationStatus::Retired
+        );
+        assert_eq!(
+            deprecation_status(retirement_at, retirement_at, None),
+            DeprecationStatus::Retired
+        );
+    }
+}
@@ -0,0 +1,60 @@
+// api/src/deprecation_sunset.rs
+// Periodic retirement of deprecated contracts whose `sunset_at` has passed.
+// Each transition is recorded in `contract_audit_log` in the same statement,
+// so a contract is never marked retired without its audit entry.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::background_jobs::JobScheduler;
+
+const SUNSET_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Recorded as `changed_by` on the audit entries this job writes
+const SUNSET_ACTOR: &str = "system:deprecation_sunset";
+
+/// Register the sunset job with the background scheduler.
+pub fn spawn_sunset_task(scheduler: &JobScheduler, pool: PgPool) {
+    scheduler.spawn("deprecation_sunset", SUNSET_INTERVAL, move || {
+        let pool = pool.clone();
+        async move {
+            let retired = retire_sunset_contracts(&pool, Utc::now()).await?;
+            if retired > 0 {
+                tracing::info!(retired, "deprecation_sunset: retired contracts");
+            }
+            Ok(())
+        }
+    });
+}
+
+/// Mark every not-yet-retired deprecation whose sunset is at or before `now`
+/// as retired, returning how many contracts were transitioned.
+pub async fn retire_sunset_contracts(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        WITH retired AS (
+            UPDATE contract_deprecations
+            SET retired_at = $1, updated_at = NOW()
+            WHERE sunset_at <= $1 AND retired_at IS NULL
+            RETURNING contract_id, sunset_at
+        )
+        INSERT INTO contract_audit_log (action_type, contract_id, old_value, new_value, changed_by)
+        SELECT 'contract_retired',
+               contract_id,
+               jsonb_build_object('status', 'deprecated'),
+               jsonb_build_object('status', 'retired', 'sunset_at', sunset_at),
+               $2
+        FROM retired
+        "#,
+    )
+    .bind(now)
+    .bind(SUNSET_ACTOR)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
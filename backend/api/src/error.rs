@@ -20,11 +20,18 @@ impl std::fmt::Display for ApiError {
     }
 }
 
+/// The `error` object every error response carries. `code` is a stable,
+/// machine-readable name clients can branch on; see docs/ERROR_CODES.md.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: String,
+    pub message: String,
+}
+
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
-    error: String,
-    message: String,
-    code: u16,
+    error: ErrorBody,
+    status: u16,
     timestamp: String,
     correlation_id: String,
 }
@@ -42,6 +49,14 @@ impl ApiError {
         Self::new(StatusCode::BAD_REQUEST, error, message)
     }
 
+    pub fn unauthorized(error: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, error, message)
+    }
+
+    pub fn forbidden(error: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, error, message)
+    }
+
     pub fn not_found(error: impl Into<String>, message: impl Into<String>) -> Self {
         Self::new(StatusCode::NOT_FOUND, error, message)
     }
@@ -65,15 +80,98 @@ impl ApiError {
     pub fn db_error(message: impl Into<String>) -> Self {
         Self::new(StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError", message)
     }
+
+    /// Log a failed database `operation` and map it to a client-facing error,
+    /// keeping constraint violations and outages distinguishable.
+    pub fn database(operation: &str, err: sqlx::Error) -> Self {
+        let (status, code, message) = classify_db_error(&err);
+        if status.is_server_error() {
+            tracing::error!(operation, error = ?err, "database operation failed");
+        } else {
+            tracing::debug!(operation, error = ?err, "database operation rejected");
+        }
+        Self::new(status, code, message)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn code(&self) -> &str {
+        &self.error
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Status, code and message for a database error. Constraint violations are
+/// the client's doing; connection problems are reported as unavailability.
+fn classify_db_error(err: &sqlx::Error) -> (StatusCode, &'static str, &'static str) {
+    match err {
+        sqlx::Error::RowNotFound => (
+            StatusCode::NOT_FOUND,
+            "NotFound",
+            "The requested resource was not found",
+        ),
+        sqlx::Error::Database(db) => match db.code().as_deref() {
+            Some("23505") => (
+                StatusCode::CONFLICT,
+                "DuplicateResource",
+                "A resource with the same unique key already exists",
+            ),
+            Some("23503") => (
+                StatusCode::CONFLICT,
+                "ReferenceViolation",
+                "The request references a missing resource, or the resource is still referenced",
+            ),
+            Some("23502" | "23514" | "22001" | "22003" | "22P02") => (
+                StatusCode::BAD_REQUEST,
+                "ConstraintViolation",
+                "The request violates a data constraint",
+            ),
+            Some("40001" | "40P01") => (
+                StatusCode::CONFLICT,
+                "TransactionConflict",
+                "The request conflicted with a concurrent change; retry it",
+            ),
+            Some("57014" | "57P01" | "53300") => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "DatabaseUnavailable",
+                "The database is temporarily unavailable",
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DatabaseError",
+                "An unexpected database error occurred",
+            ),
+        },
+        sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "DatabaseUnavailable",
+            "The database is temporarily unavailable",
+        ),
+        _ => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "DatabaseError",
+            "An unexpected database error occurred",
+        ),
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let correlation_id = Uuid::new_v4().to_string();
         let payload = ErrorResponse {
-            error: self.error,
-            message: self.message,
-            code: self.status.as_u16(),
+            error: ErrorBody {
+                code: self.error,
+                message: self.message,
+            },
+            status: self.status.as_u16(),
             timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
             correlation_id: correlation_id.clone(),
         };
@@ -93,7 +191,94 @@ pub type AppError = ApiError;
 
 impl From<sqlx::Error> for ApiError {
     fn from(e: sqlx::Error) -> Self {
-        tracing::error!(err = %e, "database error");
-        ApiError::internal("Database error")
+        ApiError::database("query", e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[derive(Debug)]
+    struct PgError(&'static str);
+
+    impl std::fmt::Display for PgError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SQLSTATE {}", self.0)
+        }
+    }
+
+    impl std::error::Error for PgError {}
+
+    impl sqlx::error::DatabaseError for PgError {
+        fn message(&self) -> &str {
+            "simulated"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.0))
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    fn pg(sqlstate: &'static str) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(PgError(sqlstate)))
+    }
+
+    #[test]
+    fn database_failures_keep_distinct_codes() {
+        let cases = [
+            (pg("23505"), StatusCode::CONFLICT, "DuplicateResource"),
+            (pg("23503"), StatusCode::CONFLICT, "ReferenceViolation"),
+            (pg("23514"), StatusCode::BAD_REQUEST, "ConstraintViolation"),
+            (
+                pg("XX000"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DatabaseError",
+            ),
+            (
+                sqlx::Error::PoolTimedOut,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "DatabaseUnavailable",
+            ),
+            (sqlx::Error::RowNotFound, StatusCode::NOT_FOUND, "NotFound"),
+        ];
+
+        for (err, status, code) in cases {
+            let api_error = ApiError::from(err);
+            assert_eq!((api_error.status(), api_error.code()), (status, code));
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_nest_code_and_message_under_error() {
+        let response = ApiError::database("insert contract", pg("23505")).into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(response.headers().contains_key("x-correlation-id"));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "DuplicateResource");
+        assert!(body["error"]["message"].is_string());
+        assert_eq!(body["status"], 409);
+        assert!(body["correlation_id"].is_string());
     }
 }
@@ -12,8 +12,7 @@ use sqlx::Row;
 use crate::{error::{ApiError, ApiResult}, state::AppState};
 
 fn db_error(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation, error = ?err, "database operation failed");
-    ApiError::internal("Database operation failed")
+    ApiError::database(operation, err)
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,384 @@
+// api/src/gas_history.rs
+// Recorded deployment gas estimates and their per-publisher summary.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use shared::{models::GasDelta, Network};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    handlers::db_internal_error,
+    simulation::GasEstimationResult,
+    state::AppState,
+    telemetry::db_span,
+};
+
+/// Most contracts a gas summary covers, most expensive first
+const SUMMARY_CONTRACT_LIMIT: i64 = 500;
+
+/// Newest gas estimate of one of a publisher's contracts
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LatestGasEstimate {
+    pub contract_id: Uuid,
+    pub name: String,
+    pub total_cost_stroops: i64,
+    pub complexity_factor: f64,
+    pub estimated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContractGasCost {
+    pub contract_id: Uuid,
+    pub name: String,
+    pub total_cost_stroops: i64,
+    pub estimated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublisherGasSummary {
+    pub publisher_id: Uuid,
+    /// Contracts with a recorded estimate that the summary covers
+    pub contracts_estimated: usize,
+    pub avg_total_cost_stroops: Option<i64>,
+    pub max_total_cost_stroops: Option<i64>,
+    pub most_expensive: Option<ContractGasCost>,
+    pub avg_complexity_factor: Option<f64>,
+    /// "low", "medium" or "high" for the average complexity factor
+    pub avg_complexity_rating: Option<&'static str>,
+    /// Set when the publisher has more estimated contracts than a summary covers
+    pub truncated: bool,
+}
+
+/// Hex SHA-256 of a WASM module, as stored in `contracts.wasm_hash`
+pub fn module_hash(wasm: &[u8]) -> String {
+    hex::encode(Sha256::digest(wasm))
+}
+
+/// Store `estimate` of the module `wasm_hash` against the registered contract
+/// `contract_id` on `network`. Nothing is recorded for contracts that aren't
+/// in the registry.
+pub async fn record_estimate(
+    pool: &PgPool,
+    contract_id: &str,
+    network: &Network,
+    wasm_hash: &str,
+    estimate: &GasEstimationResult,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO contract_gas_estimates
+            (contract_id, network, wasm_hash, total_cost_stroops, deployment_cost_stroops,
+             storage_cost_stroops, wasm_size_kb, complexity_factor)
+        SELECT id, network, $3, $4, $5, $6, $7, $8
+        FROM contracts WHERE contract_id = $1 AND network = $2
+        "#,
+    )
+    .bind(contract_id)
+    .bind(network)
+    .bind(wasm_hash)
+    .bind(estimate.total_cost_stroops)
+    .bind(estimate.deployment_cost_stroops)
+    .bind(estimate.storage_cost_stroops)
+    .bind(estimate.wasm_size_kb)
+    .bind(estimate.complexity_factor)
+    .execute(pool)
+    .instrument(db_span("record gas estimate"))
+    .await?;
+
+    Ok(())
+}
+
+/// Estimate to compare a simulation of the module `wasm_hash` with: the
+/// newest one of the version deployed for the registered contract
+/// `contract_id` on `network`, or failing that the newest one of any other
+/// module. Earlier runs of the same module are never the baseline.
+pub async fn previous_estimate(
+    pool: &PgPool,
+    contract_id: &str,
+    network: &Network,
+    wasm_hash: &str,
+) -> Result<Option<(i64, DateTime<Utc>)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT g.total_cost_stroops, g.estimated_at
+        FROM contract_gas_estimates g
+        JOIN contracts c ON c.id = g.contract_id
+        WHERE c.contract_id = $1 AND c.network = $2
+          AND g.wasm_hash IS DISTINCT FROM $3
+        ORDER BY g.wasm_hash = c.wasm_hash DESC NULLS LAST, g.estimated_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(contract_id)
+    .bind(network)
+    .bind(wasm_hash)
+    .fetch_optional(pool)
+    .instrument(db_span("fetch previous gas estimate"))
+    .await
+}
+
+pub fn gas_delta(
+    current_cost_stroops: i64,
+    previous_cost_stroops: i64,
+    previous_estimated_at: DateTime<Utc>,
+) -> GasDelta {
+    let delta_stroops = current_cost_stroops.saturating_sub(previous_cost_stroops);
+    GasDelta {
+        previous_total_cost_stroops: previous_cost_stroops,
+        previous_estimated_at,
+        delta_stroops,
+        delta_percent: (previous_cost_stroops != 0)
+            .then(|| delta_stroops as f64 / previous_cost_stroops as f64 * 100.0),
+    }
+}
+
+/// GET /api/publishers/:id/gas-summary — aggregate of the latest gas estimate
+/// of each of the publisher's contracts
+pub async fn get_publisher_gas_summary(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<PublisherGasSummary>> {
+    let publisher_id = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidPublisherId",
+            format!("Invalid publisher ID format: {}", id),
+        )
+    })?;
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM publishers WHERE id = $1)")
+        .bind(publisher_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("check publisher exists", err))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "PublisherNotFound",
+            format!("No publisher found with ID: {}", id),
+        ));
+    }
+
+    // One past the limit so truncation can be detected
+    let rows: Vec<LatestGasEstimate> = sqlx::query_as(
+        r#"
+        SELECT * FROM (
+            SELECT DISTINCT ON (g.contract_id)
+                g.contract_id, c.name, g.total_cost_stroops, g.complexity_factor, g.estimated_at
+            FROM contract_gas_estimates g
+            JOIN contracts c ON c.id = g.contract_id
+            WHERE c.publisher_id = $1
+            ORDER BY g.contract_id, g.estimated_at DESC
+        ) latest
+        ORDER BY total_cost_stroops DESC, contract_id
+        LIMIT $2
+        "#,
+    )
+    .bind(publisher_id)
+    .bind(SUMMARY_CONTRACT_LIMIT + 1)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch latest gas estimates", err))?;
+
+    Ok(Json(summarize(
+        publisher_id,
+        rows,
+        SUMMARY_CONTRACT_LIMIT as usize,
+    )))
+}
+
+/// Summarize the newest estimate of each contract in `rows`, keeping at most
+/// `limit` contracts with the highest cost.
+pub fn summarize(
+    publisher_id: Uuid,
+    rows: Vec<LatestGasEstimate>,
+    limit: usize,
+) -> PublisherGasSummary {
+    let mut latest: HashMap<Uuid, LatestGasEstimate> = HashMap::new();
+    for row in rows {
+        match latest.get(&row.contract_id) {
+            Some(seen) if seen.estimated_at >= row.estimated_at => {}
+            _ => {
+                latest.insert(row.contract_id, row);
+            }
+        }
+    }
+
+    let mut estimates: Vec<LatestGasEstimate> = latest.into_values().collect();
+    estimates.sort_by(|a, b| {
+        b.total_cost_stroops
+            .cmp(&a.total_cost_stroops)
+            .then(a.contract_id.cmp(&b.contract_id))
+    });
+    let truncated = estimates.len() > limit;
+    estimates.truncate(limit);
+
+    let count = estimates.len();
+    let avg_total_cost_stroops = (count > 0).then(|| {
+        let sum: i128 = estimates.iter().map(|e| e.total_cost_stroops as i128).sum();
+        (sum / count as i128) as i64
+    });
+    let avg_complexity_factor = (count > 0)
+        .then(|| estimates.iter().map(|e| e.complexity_factor).sum::<f64>() / count as f64);
+
+    PublisherGasSummary {
+        publisher_id,
+        contracts_estimated: count,
+        avg_total_cost_stroops,
+        max_total_cost_stroops: estimates.first().map(|e| e.total_cost_stroops),
+        most_expensive: estimates.first().map(|e| ContractGasCost {
+            contract_id: e.contract_id,
+            name: e.name.clone(),
+            total_cost_stroops: e.total_cost_stroops,
+            estimated_at: e.estimated_at,
+        }),
+        avg_complexity_factor,
+        avg_complexity_rating: avg_complexity_factor.map(complexity_rating),
+        truncated,
+    }
+}
+
+fn complexity_rating(factor: f64) -> &'static str {
+    if factor >= 0.66 {
+        "high"
+    } else if factor >= 0.33 {
+        "medium"
+    } else {
+        "low"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(
+        contract_id: Uuid,
+        name: &str,
+        cost: i64,
+        complexity: f64,
+        days_ago: i64,
+    ) -> LatestGasEstimate {
+        LatestGasEstimate {
+            contract_id,
+            name: name.to_string(),
+            total_cost_stroops: cost,
+            complexity_factor: complexity,
+            estimated_at: Utc::now() - chrono::Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn summary_uses_latest_estimate_per_contract() {
+        let token = Uuid::new_v4();
+        let vault = Uuid::new_v4();
+        let rows = vec![
+            // The token used to be the most expensive, but its latest estimate is cheaper
+            estimate(token, "token", 900_000, 0.9, 10),
+            estimate(token, "token", 200_000, 0.2, 1),
+            estimate(vault, "vault", 400_000, 0.5, 5),
+            estimate(vault, "vault", 600_000, 0.6, 2),
+        ];
+
+        let summary = summarize(Uuid::new_v4(), rows, 10);
+
+        assert_eq!(summary.contracts_estimated, 2);
+        assert_eq!(summary.avg_total_cost_stroops, Some(400_000));
+        assert_eq!(summary.max_total_cost_stroops, Some(600_000));
+        let most_expensive = summary.most_expensive.unwrap();
+        assert_eq!(
+            (most_expensive.contract_id, most_expensive.name.as_str()),
+            (vault, "vault")
+        );
+        assert!((summary.avg_complexity_factor.unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(summary.avg_complexity_rating, Some("medium"));
+        assert!(!summary.truncated);
+    }
+
+    #[test]
+    fn summary_is_capped_to_the_most_expensive_contracts() {
+        let rows = (0..3)
+            .map(|i| estimate(Uuid::new_v4(), "c", 100 * (i + 1), 0.1, 0))
+            .collect();
+
+        let summary = summarize(Uuid::new_v4(), rows, 2);
+
+        assert!(summary.truncated);
+        assert_eq!(summary.contracts_estimated, 2);
+        assert_eq!(summary.avg_total_cost_stroops, Some(250));
+    }
+
+    #[test]
+    fn delta_is_relative_to_the_previous_estimate() {
+        let at = Utc::now();
+
+        let delta = gas_delta(112_000, 100_000, at);
+        assert_eq!(delta.delta_stroops, 12_000);
+        assert!((delta.delta_percent.unwrap() - 12.0).abs() < 1e-9);
+        assert_eq!(delta.previous_estimated_at, at);
+
+        assert_eq!(gas_delta(80, 100, at).delta_stroops, -20);
+        assert_eq!(gas_delta(50, 0, at).delta_percent, None);
+    }
+
+    #[tokio::test]
+    async fn deltas_compare_with_the_deployed_version_or_another_build() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let id = crate::test_support::seed_contract(&pool, "deployed").await;
+        let contract_id: String =
+            sqlx::query_scalar("SELECT contract_id FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        for (hours_ago, hash, cost) in
+            [(3, "deployed", 100), (2, "draft", 200), (1, "upgrade", 300)]
+        {
+            sqlx::query(
+                "INSERT INTO contract_gas_estimates
+                     (contract_id, network, wasm_hash, total_cost_stroops, deployment_cost_stroops,
+                      storage_cost_stroops, wasm_size_kb, complexity_factor, estimated_at)
+                 VALUES ($1, 'testnet', $2, $3, 0, 0, 1, 0.1, NOW() - make_interval(hours => $4))",
+            )
+            .bind(id)
+            .bind(hash)
+            .bind(cost as i64)
+            .bind(hours_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let previous_cost = |hash: &'static str| {
+            let (pool, contract_id) = (pool.clone(), contract_id.clone());
+            async move {
+                previous_estimate(&pool, &contract_id, &Network::Testnet, hash)
+                    .await
+                    .unwrap()
+                    .map(|(cost, _)| cost)
+            }
+        };
+
+        // A re-run of the upgrade is compared with what's deployed, not itself
+        assert_eq!(previous_cost("upgrade").await, Some(100));
+        assert_eq!(previous_cost("fresh").await, Some(100));
+        // The deployed module itself is compared with the newest other build
+        assert_eq!(previous_cost("deployed").await, Some(300));
+    }
+
+    #[test]
+    fn publisher_without_estimates_has_empty_summary() {
+        let summary = summarize(Uuid::new_v4(), vec![], 10);
+        assert_eq!(summary.contracts_estimated, 0);
+        assert!(summary.most_expensive.is_none());
+        assert!(summary.avg_complexity_rating.is_none());
+    }
+}
@@ -13,14 +13,13 @@ use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use serde_json::{json, Value};
 use shared::{
     pagination::Cursor, AnalyticsEventType, AuditActionType, ChangePublisherRequest, Contract,
-    ContractAnalyticsResponse, ContractChangelogEntry, ContractChangelogResponse,
+    ContractAnalyticsResponse, ContractAuditLog, ContractChangelogEntry, ContractChangelogResponse,
     ContractGetResponse, ContractInteractionResponse, ContractSearchParams, ContractVersion,
     CreateContractVersionRequest, CreateInteractionBatchRequest, CreateInteractionRequest,
-    ContractAuditLog,
-    DeploymentStats, InteractionTimeSeriesPoint, InteractionTimeSeriesResponse,
-    InteractionsListResponse, InteractionsQueryParams, InteractorStats, Network, NetworkConfig,
-    PaginatedResponse, PublishRequest, Publisher, SemVer, TimelineEntry, TopUser, TrendingParams,
-    UpdateContractMetadataRequest, UpdateContractStatusRequest, VerifyRequest,
+    DependencyDeclaration, InteractionTimeSeriesPoint, InteractionTimeSeriesResponse,
+    InteractionsListResponse, InteractionsQueryParams, Network, NetworkConfig, PaginatedResponse,
+    PublishRequest, Publisher, RankedContract, TrendingParams, UpdateContractMetadataRequest,
+    UpdateContractStatusRequest, VerifyOnChainRequest, VerifyRequest,
 };
 use std::time::Duration;
 use uuid::Uuid;
@@ -32,21 +31,22 @@ pub struct GetContractQuery {
 }
 
 use crate::{
+    activity_events::{ActivityEvent, ActivityKind},
     analytics,
+    auth::{assert_owns_contract, Caller},
     breaking_changes::{diff_abi, has_breaking_changes, resolve_abi},
+    cache::{AbiLookup, CacheLayer, ContractCacheKeys, DEPENDENCY_GRAPH_KEY, DEPENDENCY_GRAPH_NS},
     dependency,
     error::{ApiError, ApiResult},
     state::AppState,
-    type_safety::parser::parse_json_spec,
-    type_safety::{generate_openapi, to_json, to_yaml},
+    trust,
 };
 
 pub(crate) fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = operation, error = ?err, "database operation failed");
-    ApiError::internal("An unexpected database error occurred")
+    ApiError::database(operation, err)
 }
 
-fn map_json_rejection(err: JsonRejection) -> ApiError {
+pub(crate) fn map_json_rejection(err: JsonRejection) -> ApiError {
     ApiError::bad_request(
         "InvalidRequest",
         format!("Invalid JSON payload: {}", err.body_text()),
@@ -66,6 +66,115 @@ pub struct AuditLogQuery {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Comma-separated action types, e.g. `publish,deprecate`
+    pub action: Option<String>,
+    /// Only entries at or after this time
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only entries before this time
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only entries made by this user (`changed_by`)
+    pub actor: Option<String>,
+}
+
+/// Validated audit log filters; every value is bound, never interpolated
+#[derive(Debug, Default)]
+struct AuditLogFilter {
+    contract_id: Option<Uuid>,
+    actions: Vec<String>,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    actor: Option<String>,
+}
+
+impl AuditLogFilter {
+    fn from_query(contract_id: Option<Uuid>, params: &AuditLogQuery) -> ApiResult<Self> {
+        if let (Some(from), Some(to)) = (params.from, params.to) {
+            if from >= to {
+                return Err(ApiError::bad_request(
+                    "InvalidDateRange",
+                    "`from` must be earlier than `to`",
+                ));
+            }
+        }
+
+        let actions = params
+            .action
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(|a| parse_audit_action(a).map(|action| action.to_string()))
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        Ok(Self {
+            contract_id,
+            actions,
+            from: params.from,
+            to: params.to,
+            actor: params
+                .actor
+                .as_deref()
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+/// Accepts the stored action names plus the shorter names reviewers search by
+fn parse_audit_action(action: &str) -> ApiResult<AuditActionType> {
+    let parsed = match action.to_ascii_lowercase().as_str() {
+        "publish" | "contract_published" => AuditActionType::ContractPublished,
+        "metadata" | "metadata_updated" => AuditActionType::MetadataUpdated,
+        "status_change" | "verify" | "verification_changed" => AuditActionType::VerificationChanged,
+        "publisher_change" | "publisher_changed" => AuditActionType::PublisherChanged,
+        "version" | "version_created" => AuditActionType::VersionCreated,
+        "rollback" => AuditActionType::Rollback,
+        "deprecate" | "retire" | "contract_retired" => AuditActionType::ContractRetired,
+        _ => {
+            return Err(ApiError::bad_request(
+                "InvalidAuditAction",
+                format!("Unknown audit action '{}'", action),
+            ))
+        }
+    };
+    Ok(parsed)
+}
+
+fn audit_log_query(
+    filter: &AuditLogFilter,
+    limit: i64,
+    offset: i64,
+) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
+    let mut qb = sqlx::QueryBuilder::new(
+        r#"SELECT id, contract_id, action_type, old_value, new_value, changed_by, "timestamp",
+               previous_hash, hash, signature
+          FROM contract_audit_log
+         WHERE TRUE"#,
+    );
+    if let Some(contract_id) = filter.contract_id {
+        qb.push(" AND contract_id = ").push_bind(contract_id);
+    }
+    if !filter.actions.is_empty() {
+        qb.push(" AND action_type::text = ANY(")
+            .push_bind(&filter.actions)
+            .push(")");
+    }
+    if let Some(from) = filter.from {
+        qb.push(r#" AND "timestamp" >= "#).push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        qb.push(r#" AND "timestamp" < "#).push_bind(to);
+    }
+    if let Some(actor) = &filter.actor {
+        qb.push(" AND changed_by = ").push_bind(actor);
+    }
+    qb.push(r#" ORDER BY "timestamp" DESC LIMIT "#)
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    qb
 }
 
 fn default_audit_limit() -> i64 {
@@ -304,6 +413,9 @@ struct ContractInteractionInsert<'a> {
     network: &'a Network,
 }
 
+/// How long the health check waits for the database before reporting it down
+const HEALTH_DB_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
 pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
     let uptime = state.started_at.elapsed().as_secs();
     let now = chrono::Utc::now().to_rfc3339();
@@ -324,10 +436,15 @@ pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Va
         );
     }
 
-    let db_ok = sqlx::query_scalar::<_, i32>("SELECT 1")
-        .fetch_one(&state.db)
-        .await
-        .is_ok();
+    let db_ok = matches!(
+        tokio::time::timeout(
+            HEALTH_DB_PROBE_TIMEOUT,
+            sqlx::query_scalar::<_, i32>("SELECT 1").fetch_one(&state.db),
+        )
+        .await,
+        Ok(Ok(_))
+    );
+    let pool = crate::db_monitoring::pool_stats(&state.db);
 
     if db_ok {
         tracing::info!(uptime_secs = uptime, "health check passed");
@@ -337,12 +454,15 @@ pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Va
                 "status": "ok",
                 "version": "0.1.0",
                 "timestamp": now,
-                "uptime_secs": uptime
+                "uptime_secs": uptime,
+                "database": { "reachable": true, "pool": pool }
             })),
         )
     } else {
         tracing::warn!(
             uptime_secs = uptime,
+            pool_size = pool.size,
+            pool_idle = pool.idle,
             "health check degraded — db unreachable"
         );
         (
@@ -351,7 +471,8 @@ pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Va
                 "status": "degraded",
                 "version": "0.1.0",
                 "timestamp": now,
-                "uptime_secs": uptime
+                "uptime_secs": uptime,
+                "database": { "reachable": false, "pool": pool }
             })),
         )
     }
@@ -381,175 +502,334 @@ pub async fn get_stats(State(state): State<AppState>) -> ApiResult<Json<Value>>
     })))
 }
 
-/// List and search contracts
-pub async fn list_contracts(
-    State(state): State<AppState>,
-    params: Result<Query<ContractSearchParams>, QueryRejection>,
-) -> axum::response::Response {
-    let Query(params) = match params {
-        Ok(q) => q,
-        Err(err) => return map_query_rejection(err).into_response(),
-    };
+/// Verification statuses accepted by the listing's `status` filter
+const CONTRACT_STATUSES: &[&str] = &["pending", "verified", "failed"];
+
+/// Contract listing filters, validated once and applied to both the page and
+/// the count query
+#[derive(Debug, Default)]
+struct ContractListFilter<'a> {
+    /// Full-text search over name, tags and description
+    search: Option<&'a str>,
+    /// Substring match on name or description
+    query: Option<&'a str>,
+    verified: Option<bool>,
+    category: Option<&'a str>,
+    networks: Vec<String>,
+    status: Option<String>,
+    publisher_address: Option<&'a str>,
+    tag: Option<&'a str>,
+}
 
-    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
 
-    // Cursor logic
-    let cursor = params.cursor.as_ref().and_then(|c| Cursor::decode(c).ok());
+impl<'a> ContractListFilter<'a> {
+    fn from_params(params: &'a ContractSearchParams) -> ApiResult<Self> {
+        let status = non_blank(params.status.as_deref())
+            .map(|status| {
+                let status = status.to_ascii_lowercase();
+                if CONTRACT_STATUSES.contains(&status.as_str()) {
+                    Ok(status)
+                } else {
+                    Err(ApiError::bad_request(
+                        "InvalidStatus",
+                        format!("status must be one of: {}", CONTRACT_STATUSES.join(", ")),
+                    ))
+                }
+            })
+            .transpose()?;
 
-    let (page, offset) = if cursor.is_some() {
-        (1, 0) // Ignore page/offset if cursor is present
-    } else {
-        let p = params.page.unwrap_or(1).max(1);
-        (p, (p - 1).max(0) * limit)
-    };
+        // Filter by network(s) (Issue #43)
+        let networks = params
+            .networks
+            .as_ref()
+            .filter(|n| !n.is_empty())
+            .cloned()
+            .or_else(|| params.network.clone().map(|n| vec![n]))
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        Ok(Self {
+            search: non_blank(params.search.as_deref()),
+            query: non_blank(params.query.as_deref()),
+            // `verified_only=false` has always meant "no filter"
+            verified: params.verified.or(params.verified_only.filter(|v| *v)),
+            category: non_blank(params.category.as_deref()),
+            networks,
+            status,
+            publisher_address: non_blank(params.publisher_address.as_deref()),
+            tag: non_blank(params.tag.as_deref()),
+        })
+    }
+}
 
-    let sort_by = params.sort_by.clone().unwrap_or_else(|| {
-        if params.query.is_some() {
-            shared::SortBy::Relevance
-        } else {
-            shared::SortBy::CreatedAt
-        }
-    });
-    let sort_order = params.sort_order.clone().unwrap_or(shared::SortOrder::Desc);
+fn push_contract_list_filters<'a>(
+    qb: &mut sqlx::QueryBuilder<'a, sqlx::Postgres>,
+    filter: &ContractListFilter<'a>,
+) {
+    if let Some(search) = filter.search {
+        qb.push(" AND c.search_vector @@ contracts_build_tsquery(")
+            .push_bind(search)
+            .push(")");
+    }
+    if let Some(query) = filter.query {
+        let pattern = format!("%{}%", query);
+        qb.push(" AND (c.name ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR c.description ILIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+    if let Some(verified) = filter.verified {
+        qb.push(" AND c.is_verified = ").push_bind(verified);
+    }
+    if let Some(category) = filter.category {
+        qb.push(" AND c.category = ").push_bind(category);
+    }
+    if !filter.networks.is_empty() {
+        qb.push(" AND c.network::text = ANY(")
+            .push_bind(filter.networks.clone())
+            .push(")");
+    }
+    if let Some(status) = &filter.status {
+        // Contracts never submitted for verification count as pending
+        qb.push(
+            " AND COALESCE((SELECT v.status::text FROM verifications v \
+             WHERE v.contract_id = c.id ORDER BY v.created_at DESC LIMIT 1), 'pending') = ",
+        )
+        .push_bind(status.clone());
+    }
+    if let Some(address) = filter.publisher_address {
+        qb.push(" AND c.publisher_id IN (SELECT id FROM publishers WHERE stellar_address = ")
+            .push_bind(address)
+            .push(")");
+    }
+    if let Some(tag) = filter.tag {
+        qb.push(" AND ").push_bind(tag).push(" = ANY(c.tags)");
+    }
+}
 
-    let is_timestamp_sort = matches!(sort_by, shared::SortBy::CreatedAt);
+/// What the listing is ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContractListOrder {
+    CreatedAt,
+    UpdatedAt,
+    Name,
+    TrustScore,
+    Interactions,
+    Deployments,
+    Relevance,
+}
 
-    // Build dynamic query with aggregations
-    let mut query = String::from(
-        "SELECT c.*
-         FROM contracts c
-         LEFT JOIN contract_interactions ci ON c.id = ci.contract_id
-         LEFT JOIN contract_versions cv ON c.id = cv.contract_id
-         WHERE 1=1",
-    );
-    let mut count_query = String::from("SELECT COUNT(*) FROM contracts WHERE 1=1");
+impl ContractListOrder {
+    /// `sort`/`order` take precedence over the older `sort_by`/`sort_order`
+    fn from_params(params: &ContractSearchParams, filter: &ContractListFilter) -> (Self, bool) {
+        if let Some(sort) = params.sort {
+            let order = match sort {
+                shared::ContractSort::PublishedAt => Self::CreatedAt,
+                shared::ContractSort::Name => Self::Name,
+                shared::ContractSort::TrustScore => Self::TrustScore,
+            };
+            let ascending = match &params.order {
+                Some(order) => *order == shared::SortOrder::Asc,
+                None => order == Self::Name,
+            };
+            return (order, ascending);
+        }
 
-    if let Some(ref q) = params.query {
-        let search_clause = format!(
-            " AND (c.name ILIKE '%{}%' OR c.description ILIKE '%{}%')",
-            q, q
-        );
-        query.push_str(&search_clause);
-        count_query.push_str(&search_clause);
+        let sort_by = params.sort_by.clone().unwrap_or_else(|| {
+            if filter.query.is_some() || filter.search.is_some() {
+                shared::SortBy::Relevance
+            } else {
+                shared::SortBy::CreatedAt
+            }
+        });
+        let order = match sort_by {
+            shared::SortBy::CreatedAt => Self::CreatedAt,
+            shared::SortBy::UpdatedAt => Self::UpdatedAt,
+            shared::SortBy::Popularity | shared::SortBy::Interactions => Self::Interactions,
+            shared::SortBy::Deployments => Self::Deployments,
+            shared::SortBy::Relevance => Self::Relevance,
+        };
+        (order, params.sort_order == Some(shared::SortOrder::Asc))
     }
+}
 
-    if let Some(verified) = params.verified_only {
-        if verified {
-            query.push_str(" AND c.is_verified = true");
-            count_query.push_str(" AND is_verified = true");
-        }
+/// One page of the contract listing. The cursor only applies when ordering by
+/// creation time.
+fn contract_list_query<'a>(
+    filter: &ContractListFilter<'a>,
+    order: ContractListOrder,
+    ascending: bool,
+    cursor: Option<&Cursor>,
+    limit: i64,
+    offset: i64,
+) -> sqlx::QueryBuilder<'a, sqlx::Postgres> {
+    let mut qb = sqlx::QueryBuilder::new("SELECT c.*");
+    if let Some(search) = filter.search {
+        qb.push(", ts_rank(c.search_vector, contracts_build_tsquery(")
+            .push_bind(search)
+            .push(")) AS search_rank");
     }
-
-    if let Some(ref category) = params.category {
-        let category_clause = format!(" AND c.category = '{}'", category);
-        query.push_str(&category_clause);
-        count_query.push_str(&category_clause);
+    qb.push(" FROM contracts c WHERE TRUE");
+    push_contract_list_filters(&mut qb, filter);
+
+    let direction = if ascending { "ASC" } else { "DESC" };
+    if let (Some(cursor), ContractListOrder::CreatedAt) = (cursor, order) {
+        let op = if ascending { ">" } else { "<" };
+        qb.push(format!(" AND (c.created_at {} ", op))
+            .push_bind(cursor.timestamp)
+            .push(" OR (c.created_at = ")
+            .push_bind(cursor.timestamp)
+            .push(format!(" AND c.id {} ", op))
+            .push_bind(cursor.id)
+            .push("))");
     }
 
-    // Filter by network(s) (Issue #43)
-    let network_list = params
-        .networks
-        .as_ref()
-        .filter(|n| !n.is_empty())
-        .cloned()
-        .or_else(|| params.network.map(|n| vec![n]));
-    if let Some(ref nets) = network_list {
-        let net_list: Vec<String> = nets.iter().map(|n| n.to_string()).collect();
-        let in_clause = net_list
-            .iter()
-            .map(|s| format!("'{}'", s.replace('\'', "''")))
-            .collect::<Vec<_>>()
-            .join(", ");
-        let network_clause = format!(" AND c.network IN ({})", in_clause);
-        query.push_str(&network_clause);
-        count_query.push_str(&network_clause);
-    }
-
-    // Apply cursor filter if available and sorting by timestamp
-    if let Some(cursor) = cursor {
-        if is_timestamp_sort {
-            let direction_op = if sort_order == shared::SortOrder::Asc {
-                ">"
-            } else {
-                "<"
-            };
-            let cursor_clause = format!(
-                " AND (c.created_at {} '{}' OR (c.created_at = '{}' AND c.id {} '{}'))",
-                direction_op,
-                cursor.timestamp.to_rfc3339(),
-                cursor.timestamp.to_rfc3339(),
-                direction_op,
-                cursor.id
-            );
-            query.push_str(&cursor_clause);
+    qb.push(" ORDER BY ");
+    match order {
+        ContractListOrder::CreatedAt => {
+            qb.push("c.created_at");
+        }
+        ContractListOrder::UpdatedAt => {
+            qb.push("c.updated_at");
+        }
+        ContractListOrder::Name => {
+            qb.push("c.name");
+        }
+        ContractListOrder::TrustScore => {
+            qb.push(crate::trust::trust_score_sql());
         }
+        ContractListOrder::Interactions => {
+            qb.push("(SELECT COUNT(*) FROM contract_interactions ci WHERE ci.contract_id = c.id)");
+        }
+        ContractListOrder::Deployments => {
+            qb.push("(SELECT COUNT(*) FROM contract_versions cv WHERE cv.contract_id = c.id)");
+        }
+        ContractListOrder::Relevance => match (filter.search, filter.query) {
+            (Some(search), _) => {
+                qb.push("ts_rank(c.search_vector, contracts_build_tsquery(")
+                    .push_bind(search)
+                    .push("))");
+            }
+            (None, Some(query)) => {
+                qb.push("CASE WHEN c.name ILIKE ")
+                    .push_bind(query)
+                    .push(" THEN 0 WHEN c.name ILIKE ")
+                    .push_bind(format!("%{}%", query))
+                    .push(" THEN 1 ELSE 2 END");
+            }
+            (None, None) => {
+                qb.push("c.created_at");
+            }
+        },
     }
+    qb.push(format!(" {}, c.id DESC LIMIT ", direction))
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    qb
+}
 
-    query.push_str(" GROUP BY c.id");
+fn contract_count_query<'a>(
+    filter: &ContractListFilter<'a>,
+) -> sqlx::QueryBuilder<'a, sqlx::Postgres> {
+    let mut qb = sqlx::QueryBuilder::new("SELECT COUNT(*) FROM contracts c WHERE TRUE");
+    push_contract_list_filters(&mut qb, filter);
+    qb
+}
 
-    // Sorting logic using aggregations in ORDER BY
-    let order_by = match sort_by {
-        shared::SortBy::CreatedAt => "c.created_at".to_string(),
-        shared::SortBy::UpdatedAt => "c.updated_at".to_string(),
-        shared::SortBy::Popularity | shared::SortBy::Interactions => {
-            "COUNT(DISTINCT ci.id)".to_string()
-        }
-        shared::SortBy::Deployments => "COUNT(DISTINCT cv.id)".to_string(),
-        shared::SortBy::Relevance => {
-            if let Some(ref q) = params.query {
-                format!(
-                    "CASE WHEN c.name ILIKE '{}' THEN 0
-                          WHEN c.name ILIKE '%{}%' THEN 1
-                          ELSE 2 END",
-                    q, q
-                )
-            } else {
-                "c.created_at".to_string()
-            }
-        }
+/// List and search contracts
+pub async fn list_contracts(
+    State(state): State<AppState>,
+    params: Result<Query<ContractSearchParams>, QueryRejection>,
+) -> axum::response::Response {
+    let Query(params) = match params {
+        Ok(q) => q,
+        Err(err) => return map_query_rejection(err).into_response(),
     };
-
-    let direction = if sort_order == shared::SortOrder::Asc {
-        "ASC"
-    } else {
-        "DESC"
+    let filter = match ContractListFilter::from_params(&params) {
+        Ok(filter) => filter,
+        Err(err) => return err.into_response(),
     };
+    let (order, ascending) = ContractListOrder::from_params(&params, &filter);
 
-    query.push_str(&format!(
-        " ORDER BY {} {}, c.id DESC LIMIT {} OFFSET {}",
-        order_by, direction, limit, offset
-    ));
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    // Keyset cursors only exist for the creation-time ordering; any other
+    // order pages by offset
+    let cursor = params
+        .cursor
+        .as_ref()
+        .filter(|_| order == ContractListOrder::CreatedAt)
+        .and_then(|c| Cursor::decode(c).ok());
 
-    let contracts: Vec<Contract> = match sqlx::query_as(&query).fetch_all(&state.db).await {
-        Ok(rows) => rows,
-        Err(err) => return db_internal_error("list contracts", err).into_response(),
+    let offset = if cursor.is_some() {
+        0 // Ignore page/offset if cursor is present
+    } else if let Some(offset) = params.offset {
+        offset.max(0)
+    } else {
+        (params.page.unwrap_or(1).max(1) - 1) * limit
     };
 
-    let total: i64 = match sqlx::query_scalar(&count_query).fetch_one(&state.db).await {
+    let contracts: Vec<RankedContract> =
+        match contract_list_query(&filter, order, ascending, cursor.as_ref(), limit, offset)
+            .build_query_as()
+            .fetch_all(&state.db)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(err) => return db_internal_error("list contracts", err).into_response(),
+        };
+
+    let total: i64 = match contract_count_query(&filter)
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+    {
         Ok(v) => v,
         Err(err) => return db_internal_error("count filtered contracts", err).into_response(),
     };
 
-    let mut response = PaginatedResponse::new(contracts, total, page, limit);
+    let keyset = order == ContractListOrder::CreatedAt;
 
     // Generate next cursor if we have full page
-    if response.items.len() >= limit as usize {
-        if let Some(last) = response.items.last() {
-            let next_cursor = Cursor::new(last.created_at, last.id).encode();
-            response.next_cursor = Some(next_cursor);
-        }
-    }
+    let next_cursor = if keyset && contracts.len() >= limit as usize {
+        contracts
+            .last()
+            .map(|last| Cursor::new(last.contract.created_at, last.contract.id).encode())
+    } else {
+        None
+    };
 
     // Generate prev cursor if we have items and are not on the first page
-    // (Simplification: if we have a cursor, or page > 1)
-    if params.cursor.is_some() || page > 1 {
-        if let Some(first) = response.items.first() {
-            let prev_cursor = Cursor::new(first.created_at, first.id).encode();
-            response.prev_cursor = Some(prev_cursor);
-        }
-    }
+    let prev_cursor = if keyset && (cursor.is_some() || offset > 0) {
+        contracts
+            .first()
+            .map(|first| Cursor::new(first.contract.created_at, first.contract.id).encode())
+    } else {
+        None
+    };
 
-    (StatusCode::OK, Json(response)).into_response()
+    (
+        StatusCode::OK,
+        Json(json!({
+            "items": contracts,
+            "total": total,
+            "page": offset / limit + 1,
+            "page_size": limit,
+            "total_pages": (total + limit - 1) / limit,
+            "limit": limit,
+            "offset": offset,
+            "next_cursor": next_cursor,
+            "prev_cursor": prev_cursor,
+        })),
+    )
+        .into_response()
 }
 
 /// Get a specific contract by ID. Optional ?network= returns network-specific config (Issue #43).
@@ -694,12 +974,75 @@ pub async fn get_contract_changelog(
     }))
 }
 
+/// Drop cached ABIs a publish may have made stale: the latest ABI under both
+/// contract identifiers, the selectors for `version` if one was published,
+/// and the global dependency graph.
+async fn invalidate_published_abi(
+    cache: &CacheLayer,
+    contract_uuid: Uuid,
+    contract_id: &str,
+    version: Option<&str>,
+) {
+    let ids = vec![contract_uuid.to_string(), contract_id.to_string()];
+    let abi_selectors = version
+        .map(|v| ids.iter().map(|id| format!("{}@{}", id, v)).collect())
+        .unwrap_or_default();
+    let keys = ContractCacheKeys {
+        ids,
+        abi_selectors,
+        wasm_hashes: Vec::new(),
+    };
+    cache
+        .purge_contract(&keys, &["abi", "dependency_graph"])
+        .await;
+}
+
+/// Highest of a contract's stored versions by semver precedence
+fn latest_semver(versions: &[String]) -> ApiResult<Option<semver::Version>> {
+    let mut latest: Option<semver::Version> = None;
+    for version in versions {
+        let parsed = semver::Version::parse(version).map_err(|_| {
+            ApiError::unprocessable(
+                "InvalidExistingVersion",
+                format!("Existing version '{}' is not valid semver", version),
+            )
+        })?;
+        if latest
+            .as_ref()
+            .is_none_or(|l| parsed.cmp_precedence(l) == std::cmp::Ordering::Greater)
+        {
+            latest = Some(parsed);
+        }
+    }
+    Ok(latest)
+}
+
+/// Reject a new version that does not sort strictly after the current latest.
+/// Build metadata is ignored, so `1.2.3+build.2` does not follow `1.2.3`.
+fn ensure_version_increases(
+    new_version: &semver::Version,
+    latest: &semver::Version,
+) -> ApiResult<()> {
+    if new_version.cmp_precedence(latest) != std::cmp::Ordering::Greater {
+        return Err(ApiError::conflict(
+            "VersionNotIncreasing",
+            format!(
+                "Version {} must be greater than the latest version {}",
+                new_version, latest
+            ),
+        ));
+    }
+    Ok(())
+}
+
 pub async fn create_contract_version(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    caller: Caller,
     ValidatedJson(req): ValidatedJson<CreateContractVersionRequest>,
 ) -> ApiResult<Json<ContractVersion>> {
     let (contract_uuid, contract_id) = fetch_contract_identity(&state, &id).await?;
+    assert_owns_contract(&state, &caller, contract_uuid).await?;
     if !req.contract_id.trim().is_empty() && req.contract_id != contract_id {
         return Err(ApiError::bad_request(
             "ContractMismatch",
@@ -707,10 +1050,10 @@ pub async fn create_contract_version(
         ));
     }
 
-    let new_version = SemVer::parse(&req.version).ok_or_else(|| {
+    let new_version = semver::Version::parse(&req.version).map_err(|e| {
         ApiError::bad_request(
             "InvalidVersion",
-            "Version must be valid semver (e.g. 1.2.3)",
+            format!("Version must be valid semver (e.g. 1.2.3): {}", e),
         )
     })?;
 
@@ -807,20 +1150,11 @@ pub async fn create_contract_version(
             .map_err(|err| db_internal_error("fetch contract versions", err))?;
 
     if !existing_versions.is_empty() {
-        let mut parsed: Vec<SemVer> = Vec::with_capacity(existing_versions.len());
-        for version in &existing_versions {
-            let parsed_version = SemVer::parse(version).ok_or_else(|| {
-                ApiError::unprocessable(
-                    "InvalidExistingVersion",
-                    format!("Existing version '{}' is not valid semver", version),
-                )
-            })?;
-            parsed.push(parsed_version);
-        }
-        parsed.sort();
-        let latest_version = parsed.last().cloned();
+        let latest_version = latest_semver(&existing_versions)?;
 
         if let Some(old_version) = latest_version {
+            ensure_version_increases(&new_version, &old_version)?;
+
             let old_selector = format!("{}@{}", contract_id, old_version);
             let old_abi = resolve_abi(&state, &old_selector).await?;
             let old_spec = crate::type_safety::parser::parse_json_spec(&old_abi, &contract_id)
@@ -850,6 +1184,9 @@ pub async fn create_contract_version(
         }
     }
 
+    let detected_deps = dependency::detect_dependencies_from_abi(&req.abi);
+    reject_dependency_cycles(&state, contract_uuid, &detected_deps).await?;
+
     let mut tx = state
         .db
         .begin()
@@ -900,15 +1237,7 @@ pub async fn create_contract_version(
         .await
         .map_err(|err| db_internal_error("commit contract version", err))?;
 
-    state.cache.invalidate_abi(&contract_id).await;
-    state.cache.invalidate_abi(&contract_uuid.to_string()).await;
-    state
-        .cache
-        .invalidate_abi(&format!("{}@{}", contract_id, req.version))
-        .await;
-
     // Post-commit dependency analysis
-    let detected_deps = dependency::detect_dependencies_from_abi(&req.abi);
     if !detected_deps.is_empty() {
         if let Err(e) =
             dependency::save_dependencies(&state.db, contract_uuid, &detected_deps).await
@@ -919,12 +1248,14 @@ pub async fn create_contract_version(
                 e
             );
         }
-        // Invalidate global graph cache
-        state
-            .cache
-            .invalidate("system", "global:dependency_graph")
-            .await;
     }
+    invalidate_published_abi(
+        &state.cache,
+        contract_uuid,
+        &contract_id,
+        Some(&req.version),
+    )
+    .await;
 
     let _ = analytics::record_event(
         &state.db,
@@ -1016,6 +1347,24 @@ async fn fetch_contract_network(
     })
 }
 
+/// Refuse dependencies that would put the contract on a dependency cycle
+async fn reject_dependency_cycles(
+    state: &AppState,
+    contract_uuid: Uuid,
+    decls: &[DependencyDeclaration],
+) -> ApiResult<()> {
+    match dependency::resolve_dependencies(&state.db, contract_uuid, decls).await {
+        Ok(_) => Ok(()),
+        Err(e) => match e.downcast::<dependency::DependencyCycle>() {
+            Ok(cycle) => Err(cycle.into()),
+            Err(e) => Err(ApiError::internal(format!(
+                "Failed to resolve dependencies: {}",
+                e
+            ))),
+        },
+    }
+}
+
 pub async fn publish_contract(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -1093,7 +1442,8 @@ pub async fn publish_contract(
         .await
         .map_err(|err| db_internal_error("fetch contract after insert", err))?;
 
-    // Save dependencies if provided
+    // Save dependencies if provided. Validation already refused the contract
+    // naming itself, the only cycle a contract can join before others depend on it.
     if !req.dependencies.is_empty() {
         if let Err(e) =
             dependency::save_dependencies(&state.db, contract.id, &req.dependencies).await
@@ -1104,12 +1454,8 @@ pub async fn publish_contract(
                 e
             );
         }
-        // Invalidate global graph cache
-        state
-            .cache
-            .invalidate("system", "global:dependency_graph")
-            .await;
     }
+    invalidate_published_abi(&state.cache, contract.id, &contract.contract_id, None).await;
 
     let creation_changes = json!({
         "contract_id": { "before": Value::Null, "after": contract.contract_id },
@@ -1160,6 +1506,15 @@ pub async fn publish_contract(
         Some(json!({ "name": contract.name })),
     )
     .await;
+    state.activity_events.publish(ActivityEvent::new(
+        ActivityKind::Publish,
+        Some(contract.id),
+        json!({
+            "contract_id": contract.contract_id,
+            "name": contract.name,
+            "network": contract.network,
+        }),
+    ));
 
     Ok(Json(contract))
 }
@@ -1270,6 +1625,42 @@ pub struct ContractAbiQuery {
     pub version: Option<String>,
 }
 
+/// Most contracts one `POST /api/contracts/abi/batch` call may request
+const MAX_ABI_BATCH: usize = 100;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct BatchAbiRequest {
+    /// Contract UUIDs or on-chain contract IDs
+    pub contract_ids: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct OpenApiQuery {
+    pub version: Option<String>,
+    /// Embed example request/response bodies synthesized from the ABI types
+    #[serde(default)]
+    pub examples: bool,
+}
+
+/// Build the OpenAPI document for a contract's ABI
+async fn contract_openapi_doc(
+    state: &AppState,
+    id: &str,
+    query: &OpenApiQuery,
+) -> ApiResult<contract_abi::OpenApiDoc> {
+    let abi_json = resolve_contract_abi(state, id, query.version.as_deref()).await?;
+    let abi = contract_abi::parse_json_spec(&abi_json, id)
+        .map_err(|e| ApiError::bad_request("InvalidABI", format!("Failed to parse ABI: {}", e)))?;
+    let options = contract_abi::OpenApiOptions {
+        include_examples: query.examples,
+    };
+    Ok(contract_abi::generate_openapi_with_options(
+        &abi,
+        Some("/invoke"),
+        &options,
+    ))
+}
+
 /// Fetch ABI JSON string for contract (by id or id@version)
 async fn resolve_contract_abi(
     state: &AppState,
@@ -1283,46 +1674,192 @@ async fn resolve_contract_abi(
     resolve_abi(state, &selector).await
 }
 
+/// Strong ETag for an ABI, derived from a SHA-256 of its JSON
+fn abi_etag(abi_json: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("\"{}\"", hex::encode(Sha256::digest(abi_json.as_bytes())))
+}
+
+/// Whether an `If-None-Match` header value matches `etag` (weak comparison, per RFC 9110)
+fn if_none_match_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
 // Contract ABI and OpenAPI endpoints
 pub async fn get_contract_abi(
     State(state): State<AppState>,
     Path(id): Path<String>,
     Query(query): Query<ContractAbiQuery>,
-) -> ApiResult<Json<Value>> {
+    headers: HeaderMap,
+) -> ApiResult<Response> {
     let abi_json = resolve_contract_abi(&state, &id, query.version.as_deref()).await?;
+    let etag = abi_etag(&abi_json);
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| if_none_match_matches(v, &etag));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
     let abi: Value = serde_json::from_str(&abi_json)
         .map_err(|e| ApiError::internal(format!("Invalid ABI JSON: {}", e)))?;
-    Ok(Json(json!({ "abi": abi })))
+    Ok(([(header::ETAG, etag)], Json(json!({ "abi": abi }))).into_response())
 }
 
-pub async fn get_contract_openapi_yaml(
+/// POST /api/contracts/abi/batch — latest ABI of several contracts in one call.
+/// Cached ABIs are served from the ABI cache; the rest are loaded with a single
+/// query. Unknown or ABI-less contracts are listed under `not_found`.
+pub async fn get_contract_abis_batch(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-    Query(query): Query<ContractAbiQuery>,
-) -> ApiResult<Response> {
-    let abi_json = resolve_contract_abi(&state, &id, query.version.as_deref()).await?;
-    let abi = parse_json_spec(&abi_json, &id)
-        .map_err(|e| ApiError::bad_request("InvalidABI", format!("Failed to parse ABI: {}", e)))?;
-    let doc = generate_openapi(&abi, Some("/invoke"));
-    let yaml = to_yaml(&doc).map_err(|e| ApiError::internal(format!("OpenAPI YAML: {}", e)))?;
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, "application/x-yaml")
-        .body(axum::body::Body::from(yaml))
-        .map_err(|_| ApiError::internal("Failed to build response"))
-}
+    payload: Result<Json<BatchAbiRequest>, JsonRejection>,
+) -> ApiResult<Json<Value>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+    let selectors = normalize_abi_batch(req.contract_ids)?;
+
+    let mut abis = serde_json::Map::new();
+    let mut not_found = Vec::new();
+    let mut pending = Vec::new();
+    for selector in selectors {
+        match state.cache.lookup_abi(&selector).await {
+            AbiLookup::Hit(cached) => match serde_json::from_str::<Value>(&cached) {
+                Ok(abi) => {
+                    abis.insert(selector, abi);
+                }
+                Err(_) => pending.push(selector),
+            },
+            AbiLookup::KnownAbsent => not_found.push(selector),
+            AbiLookup::Miss => pending.push(selector),
+        }
+    }
 
-pub async fn get_contract_openapi_json(
-    State(state): State<AppState>,
-    Path(id): Path<String>,
-    Query(query): Query<ContractAbiQuery>,
-) -> ApiResult<Response> {
-    let abi_json = resolve_contract_abi(&state, &id, query.version.as_deref()).await?;
-    let abi = parse_json_spec(&abi_json, &id)
-        .map_err(|e| ApiError::bad_request("InvalidABI", format!("Failed to parse ABI: {}", e)))?;
-    let doc = generate_openapi(&abi, Some("/invoke"));
-    let json = to_json(&doc).map_err(|e| ApiError::internal(format!("OpenAPI JSON: {}", e)))?;
-    Response::builder()
+    if !pending.is_empty() {
+        let uuids: Vec<Uuid> = pending
+            .iter()
+            .filter_map(|s| Uuid::parse_str(s).ok())
+            .collect();
+        let rows: Vec<(Uuid, String, Option<Value>)> = sqlx::query_as(
+            r#"
+            SELECT c.id, c.contract_id,
+                   COALESCE(
+                       (SELECT a.abi FROM contract_abis a
+                        WHERE a.contract_id = c.id
+                        ORDER BY a.created_at DESC LIMIT 1),
+                       c.abi
+                   )
+            FROM contracts c
+            WHERE c.id = ANY($1) OR c.contract_id = ANY($2)
+            ORDER BY c.created_at DESC
+            "#,
+        )
+        .bind(&uuids)
+        .bind(&pending)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("batch fetch contract abis", err))?;
+
+        let (found, missing) = match_abi_rows(pending, &rows);
+        for (selector, abi) in found {
+            state.cache.put_abi(&selector, abi.to_string()).await;
+            abis.insert(selector, abi);
+        }
+        for selector in &missing {
+            state.cache.mark_abi_absent(selector).await;
+        }
+        not_found.extend(missing);
+    }
+
+    Ok(Json(json!({ "abis": abis, "not_found": not_found })))
+}
+
+/// Trim and de-duplicate requested contract IDs, enforcing [`MAX_ABI_BATCH`]
+fn normalize_abi_batch(contract_ids: Vec<String>) -> ApiResult<Vec<String>> {
+    let mut selectors: Vec<String> = Vec::with_capacity(contract_ids.len());
+    for id in contract_ids {
+        let id = id.trim();
+        if id.is_empty() {
+            return Err(ApiError::bad_request(
+                "InvalidContractId",
+                "contract_ids must not contain empty values",
+            ));
+        }
+        if !selectors.iter().any(|s| s == id) {
+            selectors.push(id.to_string());
+        }
+    }
+
+    if selectors.is_empty() {
+        return Err(ApiError::bad_request(
+            "EmptyBatch",
+            "contract_ids must contain at least one contract",
+        ));
+    }
+    if selectors.len() > MAX_ABI_BATCH {
+        return Err(ApiError::bad_request(
+            "BatchTooLarge",
+            format!(
+                "At most {} contracts can be requested at once",
+                MAX_ABI_BATCH
+            ),
+        ));
+    }
+    Ok(selectors)
+}
+
+/// Pair each selector with the ABI of the first row whose UUID or contract ID
+/// it names. Rows come newest first, so a contract ID registered on several
+/// networks resolves to its latest registration, as the single-ABI endpoint does.
+fn match_abi_rows(
+    selectors: Vec<String>,
+    rows: &[(Uuid, String, Option<Value>)],
+) -> (Vec<(String, Value)>, Vec<String>) {
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    for selector in selectors {
+        let abi = rows
+            .iter()
+            .find(|(id, contract_id, _)| {
+                *contract_id == selector || id.to_string() == selector.to_ascii_lowercase()
+            })
+            .and_then(|(_, _, abi)| abi.clone())
+            .filter(|abi| !abi.is_null());
+        match abi {
+            Some(abi) => found.push((selector, abi)),
+            None => missing.push(selector),
+        }
+    }
+    (found, missing)
+}
+
+pub async fn get_contract_openapi_yaml(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<OpenApiQuery>,
+) -> ApiResult<Response> {
+    let doc = contract_openapi_doc(&state, &id, &query).await?;
+    let yaml = contract_abi::to_yaml(&doc)
+        .map_err(|e| ApiError::internal(format!("OpenAPI YAML: {}", e)))?;
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-yaml")
+        .body(axum::body::Body::from(yaml))
+        .map_err(|_| ApiError::internal("Failed to build response"))
+}
+
+pub async fn get_contract_openapi_json(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<OpenApiQuery>,
+) -> ApiResult<Response> {
+    let doc = contract_openapi_doc(&state, &id, &query).await?;
+    let json = contract_abi::to_json(&doc)
+        .map_err(|e| ApiError::internal(format!("OpenAPI JSON: {}", e)))?;
+    Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, "application/json")
         .body(axum::body::Body::from(json))
@@ -1330,13 +1867,11 @@ pub async fn get_contract_openapi_json(
 }
 
 // Stubs for upstream added endpoints
-fn planned_not_implemented_response() -> (StatusCode, Json<Value>) {
-    (
+fn planned_not_implemented_response() -> ApiError {
+    ApiError::new(
         StatusCode::NOT_IMPLEMENTED,
-        Json(json!({
-            "error": "not_implemented",
-            "message": "This endpoint is planned but not yet functional"
-        })),
+        "NotImplemented",
+        "This endpoint is planned but not yet functional",
     )
 }
 
@@ -1349,90 +1884,99 @@ pub async fn update_contract_state() -> impl IntoResponse {
 }
 
 /// GET /api/contracts/:id/analytics — timeline and top users from contract_interactions (Issue #46).
+///
+/// Serves the stored snapshot; the first read computes one if none exists yet.
 pub async fn get_contract_analytics(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> ApiResult<Json<ContractAnalyticsResponse>> {
-    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
-        ApiError::bad_request(
-            "InvalidContractId",
-            format!("Invalid contract ID format: {}", id),
-        )
-    })?;
-
+    let contract_uuid = parse_analytics_contract_id(&id)?;
     ensure_contract_exists(&state, contract_uuid, &id, "get contract for analytics").await?;
 
-    let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
+    let stored = analytics::stored_contract_analytics(&state.db, contract_uuid)
+        .await
+        .map_err(|e| db_internal_error("load stored analytics", e))?;
+    let analytics = match stored {
+        Some(analytics) => analytics,
+        None => analytics::recompute_contract_analytics(&state.db, contract_uuid)
+            .await
+            .map_err(|e| db_internal_error("compute analytics", e))?,
+    };
 
-    let unique_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(DISTINCT user_address) FROM contract_interactions \
-         WHERE contract_id = $1 AND user_address IS NOT NULL",
-    )
-    .bind(contract_uuid)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e| db_internal_error("analytics unique interactors", e))?;
+    Ok(Json(analytics))
+}
 
-    let top_user_rows: Vec<(Option<String>, i64)> = sqlx::query_as(
-        "SELECT user_address, COUNT(*) AS cnt FROM contract_interactions \
-         WHERE contract_id = $1 AND user_address IS NOT NULL \
-         GROUP BY user_address ORDER BY cnt DESC LIMIT 10",
-    )
-    .bind(contract_uuid)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| db_internal_error("analytics top users", e))?;
+/// POST /api/contracts/:id/analytics/recompute — recalculate and store a contract's analytics
+pub async fn recompute_contract_analytics(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ContractAnalyticsResponse>> {
+    let contract_uuid = parse_analytics_contract_id(&id)?;
+    ensure_contract_exists(&state, contract_uuid, &id, "get contract for analytics").await?;
 
-    let top_users: Vec<TopUser> = top_user_rows
-        .into_iter()
-        .filter_map(|(addr, count)| addr.map(|a| TopUser { address: a, count }))
-        .collect();
+    let analytics = analytics::recompute_contract_analytics(&state.db, contract_uuid)
+        .await
+        .map_err(|e| db_internal_error("recompute analytics", e))?;
 
-    let timeline_rows: Vec<(chrono::NaiveDate, i64)> = sqlx::query_as(
-        r#"
-        SELECT d::date AS date, COALESCE(e.cnt, 0)::bigint AS count
-        FROM generate_series(
-            ($1::timestamptz)::date,
-            CURRENT_DATE,
-            '1 day'::interval
-        ) d
-        LEFT JOIN (
-            SELECT created_at::date AS event_date, COUNT(*) AS cnt
-            FROM contract_interactions
-            WHERE contract_id = $2 AND created_at >= $1
-            GROUP BY created_at::date
-        ) e ON d::date = e.event_date
-        ORDER BY d::date
-        "#,
-    )
-    .bind(thirty_days_ago)
-    .bind(contract_uuid)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| db_internal_error("analytics timeline", e))?;
+    Ok(Json(analytics))
+}
 
-    let timeline: Vec<TimelineEntry> = timeline_rows
-        .into_iter()
-        .map(|(date, count)| TimelineEntry { date, count })
-        .collect();
+fn parse_analytics_contract_id(id: &str) -> ApiResult<Uuid> {
+    Uuid::parse_str(id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidContractId",
+            format!("Invalid contract ID format: {}", id),
+        )
+    })
+}
 
-    Ok(Json(ContractAnalyticsResponse {
-        contract_id: contract_uuid,
-        deployments: DeploymentStats {
-            count: 0,
-            unique_users: 0,
-            by_network: serde_json::json!({}),
-        },
-        interactors: InteractorStats {
-            unique_count,
-            top_users,
-        },
-        timeline,
-    }))
+pub async fn get_trust_score(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Value>> {
+    let (contract_uuid, trust) = compute_contract_trust_score(&state, &id).await?;
+    Ok(Json(json!({
+        "contract_id": contract_uuid,
+        "score": trust.score,
+        "badge": trust.badge,
+        "badge_icon": trust.badge_icon,
+        "summary": trust.summary,
+    })))
 }
 
-pub async fn get_trust_score() -> impl IntoResponse {
-    planned_not_implemented_response()
+/// The trust score split into its weighted factors, so publishers can see
+/// what to improve and consumers can audit the number.
+pub async fn get_trust_score_breakdown(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Value>> {
+    let (contract_uuid, trust) = compute_contract_trust_score(&state, &id).await?;
+    Ok(Json(json!({
+        "contract_id": contract_uuid,
+        "score": trust.score,
+        "max_score": trust.factors.iter().map(|f| f.points_max).sum::<f64>(),
+        "badge": trust.badge,
+        "factors": trust.factors,
+    })))
+}
+
+async fn compute_contract_trust_score(
+    state: &AppState,
+    id: &str,
+) -> ApiResult<(Uuid, trust::TrustScore)> {
+    let contract_uuid = Uuid::parse_str(id)
+        .map_err(|_| ApiError::bad_request("InvalidContractId", format!("Invalid ID: {}", id)))?;
+
+    let input = trust::load_trust_input(&state.db, contract_uuid)
+        .await
+        .map_err(|e| db_internal_error("compute_contract_trust_score", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ContractNotFound",
+                format!("No contract found with ID: {}", id),
+            )
+        })?;
+    Ok((contract_uuid, trust::compute_trust_score(&input)))
 }
 
 pub async fn get_contract_dependencies(
@@ -1452,6 +1996,60 @@ pub async fn get_contract_dependencies(
     Ok(Json(json!({ "dependencies": deps })))
 }
 
+/// Cache namespace for on-demand dependency update checks, keyed by contract UUID
+const DEPENDENCY_UPDATES_NS: &str = "dependency_updates";
+const DEPENDENCY_UPDATES_TTL: Duration = Duration::from_secs(60);
+
+/// GET /api/contracts/:id/dependency-updates — check the contract's
+/// dependencies for newer versions now, without sending any notification
+pub async fn get_dependency_updates(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Value>> {
+    let contract_uuid = Uuid::parse_str(&id)
+        .map_err(|_| ApiError::bad_request("InvalidContractId", format!("Invalid ID: {}", id)))?;
+    let cache_key = contract_uuid.to_string();
+
+    if let (Some(cached), true) = state.cache.get(DEPENDENCY_UPDATES_NS, &cache_key).await {
+        if let Ok(result) = serde_json::from_str(&cached) {
+            return Ok(Json(result));
+        }
+    }
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contracts WHERE id = $1)")
+        .bind(contract_uuid)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_internal_error("check contract exists", e))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "ContractNotFound",
+            format!("No contract found with ID: {}", id),
+        ));
+    }
+
+    let updates = dependency::check_dependency_updates(&state.db, contract_uuid)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to check dependency updates: {}", e)))?;
+
+    let result = json!({
+        "contract_id": contract_uuid,
+        "updates": updates,
+        "checked_at": chrono::Utc::now(),
+    });
+    state
+        .cache
+        .put(
+            DEPENDENCY_UPDATES_NS,
+            &cache_key,
+            result.to_string(),
+            Some(DEPENDENCY_UPDATES_TTL),
+        )
+        .await;
+
+    Ok(Json(result))
+}
+
 pub async fn get_contract_dependents(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -1473,8 +2071,8 @@ pub async fn get_contract_graph(
     State(state): State<AppState>,
 ) -> ApiResult<Json<shared::GraphResponse>> {
     // Try cache first
-    let cache_key = "global:dependency_graph";
-    if let (Some(cached), true) = state.cache.get("system", cache_key).await {
+    let cache_key = DEPENDENCY_GRAPH_KEY;
+    if let (Some(cached), true) = state.cache.get(DEPENDENCY_GRAPH_NS, cache_key).await {
         if let Ok(graph) = serde_json::from_str(&cached) {
             return Ok(Json(graph));
         }
@@ -1489,7 +2087,7 @@ pub async fn get_contract_graph(
         state
             .cache
             .put(
-                "system",
+                DEPENDENCY_GRAPH_NS,
                 cache_key,
                 serialized,
                 Some(Duration::from_secs(300)),
@@ -1500,9 +2098,23 @@ pub async fn get_contract_graph(
     Ok(Json(graph))
 }
 
+/// Default for `IMPACT_ANALYSIS_MAX_DEPTH`
+const DEFAULT_IMPACT_MAX_DEPTH: u32 = 10;
+
+/// Deepest `depth` an impact analysis may request
+static IMPACT_MAX_DEPTH: once_cell::sync::Lazy<u32> = once_cell::sync::Lazy::new(|| {
+    std::env::var("IMPACT_ANALYSIS_MAX_DEPTH")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_IMPACT_MAX_DEPTH)
+});
+
 #[derive(Debug, serde::Deserialize)]
 pub struct ImpactQuery {
     pub change: Option<String>,
+    /// Levels of dependents to walk; 1 (the default) is direct dependents only
+    pub depth: Option<u32>,
 }
 
 pub async fn get_impact_analysis(
@@ -1513,12 +2125,27 @@ pub async fn get_impact_analysis(
     let contract_uuid = Uuid::parse_str(&id)
         .map_err(|_| ApiError::bad_request("InvalidContractId", format!("Invalid ID: {}", id)))?;
 
-    let affected_ids = dependency::get_transitive_dependents(&state.db, contract_uuid)
-        .await
-        .map_err(|e| ApiError::internal(format!("Failed to get impact: {}", e)))?;
+    let max_depth = *IMPACT_MAX_DEPTH;
+    let depth = query.depth.unwrap_or(1);
+    if depth == 0 || depth > max_depth {
+        return Err(ApiError::bad_request(
+            "InvalidDepth",
+            format!("depth must be between 1 and {}", max_depth),
+        ));
+    }
 
-    // Check for cycles involving this contract
-    let has_cycles = affected_ids.contains(&contract_uuid);
+    let (distances, truncated) =
+        dependency::get_dependents_within(&state.db, contract_uuid, depth)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to get impact: {}", e)))?;
+
+    // The contract reaching itself means it sits on a dependency cycle
+    let has_cycles = distances.iter().any(|(id, _)| *id == contract_uuid);
+    let distances: Vec<(Uuid, i32)> = distances
+        .into_iter()
+        .filter(|(id, _)| *id != contract_uuid)
+        .collect();
+    let affected_ids: Vec<Uuid> = distances.iter().map(|(id, _)| *id).collect();
 
     // Fetch details for affected contracts
     let affected_contracts: Vec<shared::Contract> = if !affected_ids.is_empty() {
@@ -1531,12 +2158,18 @@ pub async fn get_impact_analysis(
         Vec::new()
     };
 
+    let impacted_by_distance =
+        dependency::group_by_distance(&distances, affected_contracts.clone());
+
     Ok(Json(shared::ImpactAnalysisResponse {
         contract_id: contract_uuid,
         change_type: query.change,
+        depth,
         affected_count: affected_ids.len(),
         affected_contracts,
+        impacted_by_distance,
         has_cycles,
+        truncated,
     }))
 }
 
@@ -1789,6 +2422,15 @@ pub async fn verify_contract(
                 Some(json!({ "verification_id": verification_id })),
             )
             .await;
+            state.activity_events.publish(ActivityEvent::new(
+                ActivityKind::Verify,
+                Some(contract.id),
+                json!({
+                    "contract_id": contract.contract_id,
+                    "verification_id": verification_id,
+                    "network": contract.network,
+                }),
+            ));
 
             Ok(Json(json!({
                 "verified": true,
@@ -1914,9 +2556,187 @@ pub async fn verify_contract(
     }
 }
 
+/// POST /api/contracts/verify-on-chain — verify an already-deployed contract
+/// against its registered source using the WASM installed on its network,
+/// so the binary doesn't have to be uploaded again. Given a contract address,
+/// the WASM is the one its on-chain instance currently runs, which differs
+/// from the registered hash once the contract has been upgraded.
+///
+/// Verdicts are cached in the verification cache under the fetched bytecode hash.
+pub async fn verify_contract_on_chain(
+    State(state): State<AppState>,
+    ValidatedJson(req): ValidatedJson<VerifyOnChainRequest>,
+) -> ApiResult<Json<Value>> {
+    let contract: Contract = sqlx::query_as(
+        "SELECT * FROM contracts
+         WHERE ($1::text IS NULL OR contract_id = $1)
+           AND ($2::text IS NULL OR wasm_hash = $2)
+           AND ($3::network_type IS NULL OR network = $3)
+         ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(&req.contract_id)
+    .bind(&req.wasm_hash)
+    .bind(&req.network)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch contract for on-chain verification", err))?
+    .ok_or_else(|| {
+        let identifier = req.contract_id.as_deref().or(req.wasm_hash.as_deref());
+        ApiError::not_found(
+            "ContractNotFound",
+            format!(
+                "No registered contract matches {}",
+                identifier.unwrap_or_default()
+            ),
+        )
+    })?;
+
+    let wasm_hash = match req.wasm_hash {
+        Some(wasm_hash) => wasm_hash,
+        None => rpc_client(&contract.network)?
+            .installed_wasm_hash(&contract.contract_id)
+            .await
+            .map_err(rpc_error)?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "WasmNotOnChain",
+                    format!(
+                        "Contract {} has no WASM instance on {}",
+                        contract.contract_id, contract.network
+                    ),
+                )
+            })?,
+    };
+    let (response, _cached) = verify_deployed_wasm(&state, &contract, &wasm_hash).await?;
+    Ok(Json(response))
+}
+
+fn rpc_client(network: &Network) -> ApiResult<crate::stellar::StellarRpcClient> {
+    crate::stellar::StellarRpcClient::for_network(network).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "RpcNotConfigured",
+            format!("No Stellar RPC endpoint is configured for {}", network),
+        )
+    })
+}
+
+fn rpc_error(err: crate::stellar::StellarRpcError) -> ApiError {
+    ApiError::new(StatusCode::BAD_GATEWAY, "RpcError", err.to_string())
+}
+
+/// Verify `contract`'s registered source against the WASM installed under
+/// `wasm_hash` on its network. Returns the verdict and whether it came from
+/// the verification cache; passing verdicts are cached per contract and
+/// bytecode hash. A WASM hash is the sha256 of the code, so a cached verdict
+/// is served before the WASM is fetched, without calling RPC.
+pub(crate) async fn verify_deployed_wasm(
+    state: &AppState,
+    contract: &Contract,
+    wasm_hash: &str,
+) -> ApiResult<(Value, bool)> {
+    let cache_key = crate::cache::verification_key(contract.id, wasm_hash);
+    if let Some(cached) = state.cache.get_verification(&cache_key).await {
+        if let Ok(result) = serde_json::from_str::<Value>(&cached) {
+            // The flag may have been reset since the verdict was cached
+            if result.get("verified").and_then(Value::as_bool) == Some(true) {
+                mark_contract_verified(state, contract.id).await?;
+            }
+            return Ok((result, true));
+        }
+    }
+
+    let wasm = rpc_client(&contract.network)?
+        .fetch_wasm(wasm_hash)
+        .await
+        .map_err(rpc_error)?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "WasmNotOnChain",
+                format!(
+                    "WASM hash {} is not installed on {}",
+                    wasm_hash, contract.network
+                ),
+            )
+        })?;
+
+    let bytecode_hash = verifier::hash_wasm(&wasm);
+    let source: Option<(String, Value, String)> = sqlx::query_as(
+        "SELECT source_code, build_params, compiler_version FROM verifications
+         WHERE contract_id = $1 AND source_code <> ''
+         ORDER BY (status = 'verified') DESC, created_at DESC
+         LIMIT 1",
+    )
+    .bind(contract.id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("fetch registered source", err))?;
+    let (source_code, build_params, compiler_version) = source.ok_or_else(|| {
+        ApiError::unprocessable(
+            "NoRegisteredSource",
+            format!(
+                "Contract {} has no registered source to verify against",
+                contract.contract_id
+            ),
+        )
+    })?;
+
+    let result = verifier::verify_contract(
+        &source_code,
+        &bytecode_hash,
+        Some(&compiler_version),
+        Some(&build_params),
+    )
+    .await
+    .map_err(|err| ApiError::unprocessable("VerificationFailed", err.to_string()))?;
+
+    if result.verified {
+        mark_contract_verified(state, contract.id).await?;
+        state.activity_events.publish(ActivityEvent::new(
+            ActivityKind::Verify,
+            Some(contract.id),
+            json!({
+                "contract_id": contract.contract_id,
+                "network": contract.network,
+                "on_chain": true,
+            }),
+        ));
+    }
+
+    let response = json!({
+        "verified": result.verified,
+        "status": if result.verified { "verified" } else { "failed" },
+        "contract_id": contract.id,
+        "network": contract.network,
+        "compiled_wasm_hash": result.compiled_wasm_hash,
+        "deployed_wasm_hash": result.deployed_wasm_hash,
+        "message": result.message,
+    });
+    // Failures aren't cached, so a corrected source is picked up on the
+    // next attempt
+    if result.verified {
+        state
+            .cache
+            .put_verification(&cache_key, response.to_string())
+            .await;
+    }
+
+    Ok((response, false))
+}
+
+async fn mark_contract_verified(state: &AppState, contract_id: Uuid) -> ApiResult<()> {
+    sqlx::query("UPDATE contracts SET is_verified = true, updated_at = NOW() WHERE id = $1")
+        .bind(contract_id)
+        .execute(&state.db)
+        .await
+        .map_err(|err| db_internal_error("mark contract verified", err))?;
+    Ok(())
+}
+
 pub async fn update_contract_metadata(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    caller: Caller,
     headers: HeaderMap,
     ValidatedJson(req): ValidatedJson<UpdateContractMetadataRequest>,
 ) -> ApiResult<Json<Contract>> {
@@ -1937,6 +2757,7 @@ pub async fn update_contract_metadata(
             format!("Invalid contract ID format: {}", id),
         )
     })?;
+    assert_owns_contract(&state, &caller, contract_uuid).await?;
 
     let before: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
         .bind(contract_uuid)
@@ -2025,6 +2846,7 @@ pub async fn update_contract_metadata(
 pub async fn change_contract_publisher(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    caller: Caller,
     headers: HeaderMap,
     ValidatedJson(req): ValidatedJson<ChangePublisherRequest>,
 ) -> ApiResult<Json<Contract>> {
@@ -2034,6 +2856,7 @@ pub async fn change_contract_publisher(
             format!("Invalid contract ID format: {}", id),
         )
     })?;
+    assert_owns_contract(&state, &caller, contract_uuid).await?;
 
     let before: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
         .bind(contract_uuid)
@@ -2101,6 +2924,7 @@ pub async fn change_contract_publisher(
 pub async fn update_contract_status(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    caller: Caller,
     headers: HeaderMap,
     ValidatedJson(req): ValidatedJson<UpdateContractStatusRequest>,
 ) -> ApiResult<Json<Value>> {
@@ -2121,6 +2945,7 @@ pub async fn update_contract_status(
             format!("Invalid contract ID format: {}", id),
         )
     })?;
+    assert_owns_contract(&state, &caller, contract_uuid).await?;
 
     let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
         .bind(contract_uuid)
@@ -2234,6 +3059,7 @@ pub async fn get_contract_audit_log(
     })?;
     let limit = params.limit.clamp(1, 500);
     let offset = params.offset.max(0);
+    let filter = AuditLogFilter::from_query(Some(contract_uuid), &params)?;
 
     let _contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
         .bind(contract_uuid)
@@ -2254,22 +3080,11 @@ pub async fn get_contract_audit_log(
     )
     .await?;
 
-    let logs: Vec<ContractAuditLog> = sqlx::query_as(
-        r#"
-        SELECT id, contract_id, action_type, old_value, new_value, changed_by, "timestamp",
-               previous_hash, hash, signature
-          FROM contract_audit_log
-         WHERE contract_id = $1
-         ORDER BY "timestamp" DESC
-         LIMIT $2 OFFSET $3
-        "#,
-    )
-    .bind(contract_uuid)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|err| db_internal_error("fetch contract audit logs", err))?;
+    let logs: Vec<ContractAuditLog> = audit_log_query(&filter, limit, offset)
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("fetch contract audit logs", err))?;
 
     Ok(Json(logs))
 }
@@ -2281,26 +3096,168 @@ pub async fn get_all_audit_logs(
     let limit = params.limit.clamp(1, 500);
     let offset = params.offset.max(0);
 
-    let logs: Vec<ContractAuditLog> = sqlx::query_as(
-        r#"
-        SELECT id, contract_id, action_type, old_value, new_value, changed_by, "timestamp",
-               previous_hash, hash, signature
-          FROM contract_audit_log
-         ORDER BY "timestamp" DESC
-         LIMIT $1 OFFSET $2
-        "#,
-    )
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|err| db_internal_error("fetch all audit logs", err))?;
+    let filter = AuditLogFilter::from_query(None, &params)?;
+    let logs: Vec<ContractAuditLog> = audit_log_query(&filter, limit, offset)
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("fetch all audit logs", err))?;
 
     Ok(Json(logs))
 }
 
-pub async fn get_deployment_status() -> impl IntoResponse {
-    planned_not_implemented_response()
+/// GET /api/contracts/:id/deployment-status — blue/green deployments recorded
+/// in the registry alongside whether the contract's WASM is installed on-chain.
+///
+/// `on_chain` is null when no RPC endpoint is configured for the contract's
+/// network or the RPC check failed (see `on_chain_error`).
+pub async fn get_deployment_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Value>> {
+    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidContractId",
+            format!("Invalid contract ID format: {}", id),
+        )
+    })?;
+
+    let (network, wasm_hash): (Network, String) =
+        sqlx::query_as("SELECT network, wasm_hash FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract for deployment status", err))?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "ContractNotFound",
+                    format!("No contract found with ID: {}", id),
+                )
+            })?;
+
+    let deployments = load_deployments(&state, contract_uuid).await?;
+
+    let status =
+        crate::stellar::cached_wasm_status(&state.cache, contract_uuid, &network, &wasm_hash).await;
+    let (on_chain, on_chain_error) = match status {
+        Ok(status) => (status, None),
+        Err(err) => {
+            tracing::warn!(contract_id = %contract_uuid, error = %err, "on-chain status check failed");
+            (None, Some(err.to_string()))
+        }
+    };
+
+    Ok(Json(json!({
+        "contract_id": contract_uuid,
+        "network": network,
+        "wasm_hash": wasm_hash,
+        "deployments": deployments,
+        "on_chain": on_chain.as_ref().map(|s| s.on_chain),
+        "ledger_sequence": on_chain.as_ref().and_then(|s| s.ledger_sequence),
+        "latest_ledger": on_chain.as_ref().map(|s| s.latest_ledger),
+        "checked_at": on_chain.as_ref().map(|s| s.checked_at),
+        "on_chain_error": on_chain_error,
+    })))
+}
+
+async fn load_deployments(
+    state: &AppState,
+    contract_uuid: Uuid,
+) -> ApiResult<Vec<shared::models::ContractDeployment>> {
+    sqlx::query_as("SELECT * FROM contract_deployments WHERE contract_id = $1 ORDER BY environment")
+        .bind(contract_uuid)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("list contract deployments", err))
+}
+
+/// Version token for a set of deployments, derived from a SHA-256 of their JSON
+fn deployments_etag(deployments: &[shared::models::ContractDeployment]) -> String {
+    use sha2::{Digest, Sha256};
+    let json = serde_json::to_vec(deployments).unwrap_or_default();
+    hex::encode(Sha256::digest(json))
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct DeploymentWatchQuery {
+    /// `etag` from the caller's last response
+    pub since: Option<String>,
+}
+
+/// Time left between a watch answering unchanged and the global request
+/// timeout cutting it off
+const DEPLOYMENT_WATCH_MARGIN: Duration = Duration::from_secs(5);
+/// How often a held watch re-reads the deployments. Nothing notifies
+/// watchers of writes yet, so this is how changes are noticed.
+const DEPLOYMENT_WATCH_RECHECK: Duration = Duration::from_secs(5);
+
+/// Longest a watch is held open: [`DEPLOYMENT_WATCH_MARGIN`] short of the
+/// request timeout, or half of it when that is too short for the margin
+fn deployment_watch_timeout(request_timeout: Duration) -> Duration {
+    request_timeout
+        .checked_sub(DEPLOYMENT_WATCH_MARGIN)
+        .filter(|timeout| !timeout.is_zero())
+        .unwrap_or(request_timeout / 2)
+}
+
+/// GET /api/contracts/:id/deployments/status/watch?since=<etag> — long-poll
+/// for a change in the contract's deployments.
+///
+/// Answers at once when the deployments no longer match `since` (or it is
+/// omitted); otherwise re-reads them every DEPLOYMENT_WATCH_RECHECK until they
+/// change or the watch times out just ahead of REQUEST_TIMEOUT_SECS, and then
+/// reports `changed: false`.
+pub async fn watch_deployment_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<DeploymentWatchQuery>,
+) -> ApiResult<Response> {
+    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidContractId",
+            format!("Invalid contract ID format: {}", id),
+        )
+    })?;
+    ensure_contract_exists(
+        &state,
+        contract_uuid,
+        &id,
+        "get contract for deployment watch",
+    )
+    .await?;
+
+    let since = query.since.as_deref().map(|s| s.trim().trim_matches('"'));
+    let watch = state.deployment_events.watch(contract_uuid);
+    let deadline = tokio::time::Instant::now()
+        + deployment_watch_timeout(crate::request_timeout::request_timeout_from_env());
+    let (deployments, etag, changed) = loop {
+        // Registered before reading, so a change made meanwhile still wakes us
+        let notified = watch.notify().notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        let deployments = load_deployments(&state, contract_uuid).await?;
+        let etag = deployments_etag(&deployments);
+        if since != Some(etag.as_str()) {
+            break (deployments, etag, true);
+        }
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            break (deployments, etag, false);
+        }
+        let _ = tokio::time::timeout(DEPLOYMENT_WATCH_RECHECK.min(deadline - now), notified).await;
+    };
+
+    Ok((
+        [(header::ETAG, format!("\"{}\"", etag))],
+        Json(json!({
+            "contract_id": contract_uuid,
+            "etag": etag,
+            "changed": changed,
+            "deployments": deployments,
+        })),
+    )
+        .into_response())
 }
 
 pub async fn deploy_green() -> impl IntoResponse {
@@ -2623,10 +3580,7 @@ pub async fn post_contract_interactions_batch(
 }
 
 pub async fn route_not_found() -> impl IntoResponse {
-    (
-        StatusCode::NOT_FOUND,
-        Json(json!({"error": "Route not found"})),
-    )
+    ApiError::not_found("RouteNotFound", "Route not found")
 }
 
 #[cfg(test)]
@@ -2634,6 +3588,7 @@ mod tests {
     use super::*;
     use prometheus::Registry;
     use sqlx::postgres::PgPoolOptions;
+    use std::collections::HashMap;
     use std::sync::atomic::AtomicBool;
     use std::sync::Arc;
 
@@ -2655,6 +3610,319 @@ mod tests {
         assert_eq!(value["status"], "shutting_down");
     }
 
+    /// Cache-aside read mirroring `resolve_abi`, with `stored` standing in for the database
+    async fn read_abi(
+        cache: &CacheLayer,
+        stored: &HashMap<String, String>,
+        selector: &str,
+    ) -> String {
+        if let Some(cached) = cache.get_abi(selector).await {
+            return cached;
+        }
+        let abi = stored[selector].clone();
+        cache.put_abi(selector, abi.clone()).await;
+        abi
+    }
+
+    #[tokio::test]
+    async fn publishing_a_version_replaces_the_cached_abi() {
+        let cache = CacheLayer::new(crate::cache::CacheConfig::default());
+        let contract_uuid = Uuid::new_v4();
+        let contract_id = "CTOKEN";
+        let mut stored = HashMap::from([(contract_id.to_string(), "abi-v1".to_string())]);
+
+        assert_eq!(read_abi(&cache, &stored, contract_id).await, "abi-v1");
+
+        stored.insert(contract_id.to_string(), "abi-v2".to_string());
+        stored.insert(format!("{}@1.1.0", contract_id), "abi-v2".to_string());
+        assert_eq!(read_abi(&cache, &stored, contract_id).await, "abi-v1");
+
+        cache
+            .put(
+                DEPENDENCY_GRAPH_NS,
+                DEPENDENCY_GRAPH_KEY,
+                "{}".to_string(),
+                None,
+            )
+            .await;
+        invalidate_published_abi(&cache, contract_uuid, contract_id, Some("1.1.0")).await;
+
+        assert_eq!(read_abi(&cache, &stored, contract_id).await, "abi-v2");
+        assert_eq!(
+            read_abi(&cache, &stored, &format!("{}@1.1.0", contract_id)).await,
+            "abi-v2"
+        );
+        assert!(!cache.get(DEPENDENCY_GRAPH_NS, DEPENDENCY_GRAPH_KEY).await.1);
+    }
+
+    fn versions(list: &[&str]) -> Vec<String> {
+        list.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn latest_semver_orders_by_precedence() {
+        let latest = latest_semver(&versions(&["1.2.0", "1.10.0", "1.10.0-rc.1", "1.9.9"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.to_string(), "1.10.0");
+        assert!(latest_semver(&[]).unwrap().is_none());
+        assert!(latest_semver(&versions(&["1.0.0", "latest"])).is_err());
+    }
+
+    #[test]
+    fn new_version_must_exceed_latest() {
+        let latest = semver::Version::parse("1.2.3").unwrap();
+        let v = |s: &str| semver::Version::parse(s).unwrap();
+
+        assert!(ensure_version_increases(&v("1.2.4"), &latest).is_ok());
+        assert!(ensure_version_increases(&v("2.0.0-alpha"), &latest).is_ok());
+        assert!(ensure_version_increases(&v("1.2.3"), &latest).is_err());
+        assert!(ensure_version_increases(&v("1.2.3+build.7"), &latest).is_err());
+        assert!(ensure_version_increases(&v("1.2.3-rc.1"), &latest).is_err());
+        assert!(ensure_version_increases(&v("1.1.0"), &latest).is_err());
+    }
+
+    fn audit_query(action: Option<&str>) -> AuditLogQuery {
+        AuditLogQuery {
+            limit: 100,
+            offset: 0,
+            action: action.map(str::to_string),
+            from: None,
+            to: None,
+            actor: None,
+        }
+    }
+
+    #[test]
+    fn audit_actions_accept_short_and_stored_names() {
+        let filter = AuditLogFilter::from_query(
+            None,
+            &audit_query(Some("publish, deprecate,verification_changed")),
+        )
+        .unwrap();
+        assert_eq!(
+            filter.actions,
+            versions(&[
+                "contract_published",
+                "contract_retired",
+                "verification_changed"
+            ])
+        );
+
+        let err = AuditLogFilter::from_query(None, &audit_query(Some("publish,'; DROP TABLE x")))
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn audit_date_range_must_be_ordered() {
+        let mut params = audit_query(None);
+        params.from = Some(chrono::Utc::now());
+        params.to = params.from.map(|from| from - chrono::Duration::days(1));
+        assert!(AuditLogFilter::from_query(None, &params).is_err());
+    }
+
+    #[test]
+    fn audit_filters_are_bound_not_interpolated() {
+        let mut params = audit_query(Some("deprecate"));
+        params.from = Some(chrono::Utc::now() - chrono::Duration::days(31));
+        params.to = Some(chrono::Utc::now());
+        params.actor = Some("' OR 1=1 --".to_string());
+        let filter = AuditLogFilter::from_query(Some(Uuid::new_v4()), &params).unwrap();
+
+        let qb = audit_log_query(&filter, 50, 0);
+        let sql = qb.sql();
+        assert!(sql.contains("contract_id = $1"));
+        assert!(sql.contains("action_type::text = ANY($2)"));
+        assert!(sql.contains(r#""timestamp" >= $3"#));
+        assert!(sql.contains(r#""timestamp" < $4"#));
+        assert!(sql.contains("changed_by = $5"));
+        assert!(sql.ends_with("LIMIT $6 OFFSET $7"));
+        assert!(!sql.contains("OR 1=1"));
+    }
+
+    fn search_params(params: Value) -> ContractSearchParams {
+        serde_json::from_value(params).unwrap()
+    }
+
+    #[test]
+    fn contract_list_filters_are_bound_not_interpolated() {
+        let params = search_params(json!({
+            "status": "Verified",
+            "publisher_address": "GABC' OR 1=1 --",
+            "verified": false,
+            "tag": "defi",
+            "category": "'; DROP TABLE contracts; --",
+        }));
+        let filter = ContractListFilter::from_params(&params).unwrap();
+        assert_eq!(filter.status.as_deref(), Some("verified"));
+
+        let qb = contract_list_query(&filter, ContractListOrder::CreatedAt, false, None, 20, 40);
+        let sql = qb.sql();
+        assert!(sql.contains("c.is_verified = $1"));
+        assert!(sql.contains("c.category = $2"));
+        assert!(sql.contains("LIMIT 1), 'pending') = $3"));
+        assert!(sql.contains("stellar_address = $4)"));
+        assert!(sql.contains("$5 = ANY(c.tags)"));
+        assert!(sql.ends_with("ORDER BY c.created_at DESC, c.id DESC LIMIT $6 OFFSET $7"));
+        assert!(!sql.contains("DROP TABLE") && !sql.contains("OR 1=1"));
+
+        let count = contract_count_query(&filter);
+        assert!(count.sql().ends_with("$5 = ANY(c.tags)"));
+    }
+
+    #[test]
+    fn contract_list_rejects_unknown_status() {
+        let params = search_params(json!({ "status": "archived" }));
+        let err = ContractListFilter::from_params(&params).unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn contract_list_sort_overrides_sort_by() {
+        let params = search_params(json!({ "sort": "name", "sort_by": "popularity" }));
+        let filter = ContractListFilter::from_params(&params).unwrap();
+        assert_eq!(
+            ContractListOrder::from_params(&params, &filter),
+            (ContractListOrder::Name, true)
+        );
+
+        let params = search_params(json!({ "sort": "trust_score", "order": "asc" }));
+        let filter = ContractListFilter::from_params(&params).unwrap();
+        let (order, ascending) = ContractListOrder::from_params(&params, &filter);
+        assert_eq!((order, ascending), (ContractListOrder::TrustScore, true));
+        let qb = contract_list_query(&filter, order, ascending, None, 20, 0);
+        assert!(qb.sql().contains("WHEN c.is_verified THEN"));
+
+        assert!(serde_json::from_value::<ContractSearchParams>(json!({ "sort": "size" })).is_err());
+    }
+
+    #[tokio::test]
+    async fn contract_list_pages_and_cursors_follow_the_order() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let tag = format!("list-{}", Uuid::new_v4().simple());
+        for _ in 0..3 {
+            let id = crate::test_support::seed_contract(&db, "listing").await;
+            sqlx::query("UPDATE contracts SET tags = ARRAY[$1] WHERE id = $2")
+                .bind(&tag)
+                .bind(id)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+        let state = crate::test_support::test_state(db);
+        let list = |query: String| {
+            let state = state.clone();
+            async move {
+                let params = Query::try_from_uri(&format!("/?{}", query).parse().unwrap());
+                let response = list_contracts(State(state), params).await;
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&body).unwrap()
+            }
+        };
+
+        let first = list(format!("tag={}&limit=2", tag)).await;
+        assert_eq!(
+            (first["total"].as_i64(), first["total_pages"].as_i64()),
+            (Some(3), Some(2))
+        );
+        assert_eq!(first["page"], 1);
+        let cursor = first["next_cursor"].as_str().unwrap().to_string();
+        let second = list(format!("tag={}&limit=2&cursor={}", tag, cursor)).await;
+        assert_eq!(second["items"].as_array().unwrap().len(), 1);
+        assert!(second["prev_cursor"].is_string());
+
+        // Other orders page by offset, so they hand out no cursor and ignore one
+        let by_name = list(format!("tag={}&limit=2&sort=name", tag)).await;
+        assert!(by_name["next_cursor"].is_null());
+        let page_two = list(format!(
+            "tag={}&limit=2&sort=name&page=2&cursor={}",
+            tag, cursor
+        ))
+        .await;
+        assert_eq!(
+            (page_two["page"].as_i64(), page_two["offset"].as_i64()),
+            (Some(2), Some(2))
+        );
+        assert_eq!(page_two["items"].as_array().unwrap().len(), 1);
+        assert!(page_two["prev_cursor"].is_null());
+    }
+
+    #[test]
+    fn abi_batch_is_deduplicated_and_capped() {
+        let ids = versions(&[" CA ", "CB", "CA"]);
+        assert_eq!(normalize_abi_batch(ids).unwrap(), versions(&["CA", "CB"]));
+
+        assert!(normalize_abi_batch(vec![]).is_err());
+        assert!(normalize_abi_batch(versions(&["CA", " "])).is_err());
+
+        let too_many = (0..=MAX_ABI_BATCH).map(|i| format!("C{}", i)).collect();
+        assert!(normalize_abi_batch(too_many).is_err());
+    }
+
+    #[test]
+    fn abi_rows_match_by_uuid_or_contract_id() {
+        let with_abi = Uuid::new_v4();
+        let without_abi = Uuid::new_v4();
+        let rows = vec![
+            (
+                with_abi,
+                "CTOKEN".to_string(),
+                Some(json!({ "functions": [] })),
+            ),
+            (without_abi, "CEMPTY".to_string(), None),
+        ];
+
+        let (found, missing) = match_abi_rows(
+            vec![
+                "CTOKEN".to_string(),
+                with_abi.to_string().to_uppercase(),
+                "CEMPTY".to_string(),
+                "CUNKNOWN".to_string(),
+            ],
+            &rows,
+        );
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, "CTOKEN");
+        assert_eq!(found[1].1, json!({ "functions": [] }));
+        assert_eq!(missing, versions(&["CEMPTY", "CUNKNOWN"]));
+    }
+
+    #[test]
+    fn deployment_watches_end_before_the_request_timeout() {
+        let secs = Duration::from_secs;
+        assert_eq!(deployment_watch_timeout(secs(30)), secs(25));
+        assert_eq!(deployment_watch_timeout(secs(120)), secs(115));
+        assert_eq!(
+            deployment_watch_timeout(secs(5)),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(deployment_watch_timeout(secs(2)), secs(1));
+    }
+
+    #[test]
+    fn abi_etag_changes_with_content() {
+        let etag = abi_etag(r#"{"functions":[]}"#);
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+        assert_eq!(etag, abi_etag(r#"{"functions":[]}"#));
+        assert_ne!(etag, abi_etag(r#"{"functions":[{"name":"mint"}]}"#));
+    }
+
+    #[test]
+    fn if_none_match_accepts_lists_weak_tags_and_wildcard() {
+        let etag = abi_etag("{}");
+        assert!(if_none_match_matches(&etag, &etag));
+        assert!(if_none_match_matches(&format!("\"stale\", W/{}", etag), &etag));
+        assert!(if_none_match_matches("*", &etag));
+        assert!(!if_none_match_matches("\"stale\"", &etag));
+    }
+
     #[test]
     fn split_audit_changes_extracts_before_after() {
         let changes = json!({
@@ -2691,4 +3959,47 @@ mod tests {
         assert_eq!(new["verification_id"], "abc123");
         assert_eq!(new["_ip_address"], "unknown");
     }
+
+    #[tokio::test]
+    async fn only_the_publisher_may_set_verification_status() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let id = crate::test_support::seed_contract(&db, &"ab".repeat(32)).await;
+        let stranger = Caller {
+            identity: Some(crate::auth::ApiKeyIdentity {
+                key_id: Uuid::new_v4(),
+                publisher_id: Uuid::new_v4(),
+                stellar_address: "GSTRANGER".to_string(),
+            }),
+            admin: false,
+        };
+
+        let err = update_contract_status(
+            State(state),
+            Path(id.to_string()),
+            stranger,
+            HeaderMap::new(),
+            ValidatedJson(UpdateContractStatusRequest {
+                status: "verified".to_string(),
+                error_message: None,
+                user_id: None,
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+
+        let (is_verified, verifications): (bool, i64) = sqlx::query_as(
+            "SELECT c.is_verified, (SELECT COUNT(*) FROM verifications WHERE contract_id = c.id)
+             FROM contracts c WHERE c.id = $1",
+        )
+        .bind(id)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert!(!is_verified);
+        assert_eq!(verifications, 0);
+    }
 }
@@ -8,6 +8,8 @@ use tokio::sync::RwLock;
 use tokio::time;
 use tracing::{error, info, warn};
 
+use crate::background_jobs::JobStatus;
+use crate::metrics_retention::{RetentionStatus, RetentionStatusResponse};
 use crate::state::AppState;
 
 const MAX_TOTAL_HEALTH_SCORE: i32 = 100;
@@ -36,6 +38,8 @@ pub struct HealthMonitorStatus {
     pub total_failures: Arc<AtomicU64>,
     pub contracts_checked: Arc<AtomicU64>,
     pub contracts_failed: Arc<AtomicU64>,
+    /// Last run of the metrics retention job, reported alongside the checks
+    pub metrics_retention: RetentionStatus,
 }
 
 impl Default for HealthMonitorStatus {
@@ -48,6 +52,7 @@ impl Default for HealthMonitorStatus {
             total_failures: Arc::new(AtomicU64::new(0)),
             contracts_checked: Arc::new(AtomicU64::new(0)),
             contracts_failed: Arc::new(AtomicU64::new(0)),
+            metrics_retention: RetentionStatus::default(),
         }
     }
 }
@@ -63,6 +68,7 @@ pub struct HealthMonitorStatusResponse {
     pub total_failures: u64,
     pub contracts_checked: u64,
     pub contracts_failed: u64,
+    pub metrics_retention: RetentionStatusResponse,
 }
 
 impl HealthMonitorStatus {
@@ -79,6 +85,7 @@ impl HealthMonitorStatus {
             total_failures: self.total_failures.load(Ordering::Relaxed),
             contracts_checked: self.contracts_checked.load(Ordering::Relaxed),
             contracts_failed: self.contracts_failed.load(Ordering::Relaxed),
+            metrics_retention: self.metrics_retention.snapshot().await,
         }
     }
 }
@@ -344,13 +351,25 @@ async fn upsert_contract_health(pool: &PgPool, health: &ContractHealth) -> Resul
     Ok(())
 }
 
+/// Health monitor status together with the scheduled background jobs
+#[derive(Debug, serde::Serialize)]
+pub struct HealthMonitorReport {
+    #[serde(flatten)]
+    pub monitor: HealthMonitorStatusResponse,
+    pub background_jobs: Vec<JobStatus>,
+}
+
 /// Handler: GET /api/health-monitor/status
 ///
-/// Returns the current status of the health monitor background task.
+/// Returns the current status of the health monitor background task and the
+/// last run of every scheduled background job.
 pub async fn get_health_monitor_status(
     axum::extract::State(state): axum::extract::State<crate::state::AppState>,
-) -> axum::Json<HealthMonitorStatusResponse> {
-    axum::Json(state.health_monitor_status.snapshot().await)
+) -> axum::Json<HealthMonitorReport> {
+    axum::Json(HealthMonitorReport {
+        monitor: state.health_monitor_status.snapshot().await,
+        background_jobs: state.background_jobs.statuses(),
+    })
 }
 
 #[cfg(test)]
@@ -579,6 +598,8 @@ mod tests {
         assert_eq!(snapshot.total_failures, 0);
         assert_eq!(snapshot.contracts_checked, 0);
         assert_eq!(snapshot.contracts_failed, 0);
+        assert!(snapshot.metrics_retention.last_run.is_none());
+        assert_eq!(snapshot.metrics_retention.last_rows_deleted, 0);
     }
 
     #[tokio::test]
@@ -0,0 +1,274 @@
+// api/src/idempotency.rs
+// `Idempotency-Key` support for the metric-recording endpoints. The first
+// request with a key claims it in `metric_idempotency_keys` inside the same
+// transaction as the handler's writes and stores its response there, so the
+// claim and the writes commit or roll back together. Retries within the TTL
+// replay the stored response without writing again, so aggregate counters are
+// only incremented once.
+
+use axum::{
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+
+use crate::error::{ApiError, ApiResult};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+
+/// Default for `IDEMPOTENCY_KEY_TTL_SECS`
+const DEFAULT_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// How long a processed key is remembered
+pub static IDEMPOTENCY_TTL_SECS: once_cell::sync::Lazy<i64> = once_cell::sync::Lazy::new(|| {
+    std::env::var("IDEMPOTENCY_KEY_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_TTL_SECS)
+});
+
+/// The request's `Idempotency-Key`, if it sent one
+pub fn key_from_headers(headers: &HeaderMap) -> ApiResult<Option<String>> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|k| !k.is_empty() && k.len() <= MAX_KEY_LEN)
+        .ok_or_else(|| {
+            ApiError::bad_request(
+                "InvalidIdempotencyKey",
+                format!(
+                    "Idempotency-Key must be 1 to {} visible ASCII characters",
+                    MAX_KEY_LEN
+                ),
+            )
+        })?;
+    Ok(Some(key.to_string()))
+}
+
+/// Outcome of [`begin`]
+pub enum Begun {
+    /// An earlier request with the same key already committed this response
+    Replay(Response),
+    /// A transaction for the handler's writes, holding the key's claim
+    Write(IdempotentWrite),
+}
+
+/// The handler's writes plus, when the request has a key, its claim. Dropping
+/// it without [`IdempotentWrite::commit`] rolls both back, so a failed request
+/// leaves nothing behind for a retry to trip over.
+pub struct IdempotentWrite {
+    tx: Transaction<'static, Postgres>,
+    claim: Option<(String, String)>,
+}
+
+/// Start a write that runs at most once per `(scope, key)`. A concurrent
+/// request with the same key waits on the claim and then replays the response
+/// it committed.
+pub async fn begin(db: &PgPool, scope: &str, key: Option<String>) -> ApiResult<Begun> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| db_error("begin idempotent write", e))?;
+
+    let Some(key) = key else {
+        return Ok(Begun::Write(IdempotentWrite { tx, claim: None }));
+    };
+    if let Some((status, body)) = claim(&mut tx, scope, &key).await? {
+        let mut response = (status, Json(body)).into_response();
+        response
+            .headers_mut()
+            .insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+        return Ok(Begun::Replay(response));
+    }
+
+    Ok(Begun::Write(IdempotentWrite {
+        tx,
+        claim: Some((scope.to_string(), key)),
+    }))
+}
+
+impl IdempotentWrite {
+    /// Connection the handler's writes go through
+    pub fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    /// Store the response against the key and commit it with the writes
+    pub async fn commit<T: Serialize>(
+        mut self,
+        status: StatusCode,
+        body: &T,
+    ) -> ApiResult<Response> {
+        let body = serde_json::to_value(body)
+            .map_err(|e| ApiError::internal(format!("Failed to encode response: {}", e)))?;
+
+        if let Some((scope, key)) = &self.claim {
+            sqlx::query(
+                "UPDATE metric_idempotency_keys SET status_code = $3, response = $4 \
+                 WHERE scope = $1 AND idempotency_key = $2",
+            )
+            .bind(scope)
+            .bind(key)
+            .bind(status.as_u16() as i16)
+            .bind(&body)
+            .execute(&mut *self.tx)
+            .await
+            .map_err(|e| db_error("store idempotent response", e))?;
+        }
+        self.tx
+            .commit()
+            .await
+            .map_err(|e| db_error("commit idempotent write", e))?;
+
+        Ok((status, Json(body)).into_response())
+    }
+}
+
+/// Claim `key` for this transaction, or return the response stored by the
+/// request that already holds it
+async fn claim(
+    conn: &mut PgConnection,
+    scope: &str,
+    key: &str,
+) -> ApiResult<Option<(StatusCode, serde_json::Value)>> {
+    // Expired keys are taken over in place
+    let claimed: Option<String> = sqlx::query_scalar(
+        r#"
+        INSERT INTO metric_idempotency_keys (scope, idempotency_key)
+        VALUES ($1, $2)
+        ON CONFLICT (scope, idempotency_key) DO UPDATE
+            SET created_at = NOW(), status_code = NULL, response = NULL
+            WHERE metric_idempotency_keys.created_at < NOW() - make_interval(secs => $3)
+        RETURNING scope
+        "#,
+    )
+    .bind(scope)
+    .bind(key)
+    .bind(*IDEMPOTENCY_TTL_SECS as f64)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| db_error("claim idempotency key", e))?;
+    if claimed.is_some() {
+        return Ok(None);
+    }
+
+    let stored: Option<(Option<i16>, Option<serde_json::Value>)> = sqlx::query_as(
+        "SELECT status_code, response FROM metric_idempotency_keys \
+         WHERE scope = $1 AND idempotency_key = $2",
+    )
+    .bind(scope)
+    .bind(key)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| db_error("fetch idempotency key", e))?;
+
+    match stored {
+        Some((Some(status), Some(body))) => Ok(Some((
+            StatusCode::from_u16(status as u16).unwrap_or(StatusCode::OK),
+            body,
+        ))),
+        _ => Err(ApiError::conflict(
+            "IdempotencyKeyInProgress",
+            "A request with this Idempotency-Key is still being processed; retry shortly",
+        )),
+    }
+}
+
+fn db_error(operation: &str, err: sqlx::Error) -> ApiError {
+    ApiError::database(operation, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_str(key).unwrap());
+        headers
+    }
+
+    #[test]
+    fn key_is_optional_and_trimmed() {
+        assert_eq!(key_from_headers(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(
+            key_from_headers(&headers(" retry-7f3a "))
+                .unwrap()
+                .as_deref(),
+            Some("retry-7f3a")
+        );
+    }
+
+    #[test]
+    fn blank_or_oversized_keys_are_rejected() {
+        assert!(key_from_headers(&headers("   ")).is_err());
+        assert!(key_from_headers(&headers(&"k".repeat(MAX_KEY_LEN + 1))).is_err());
+    }
+
+    /// Stands in for a metric handler: records a row, then fails when asked
+    async fn record(
+        db: &PgPool,
+        scope: &str,
+        key: Option<&str>,
+        fail: bool,
+    ) -> ApiResult<Response> {
+        let mut write = match begin(db, scope, key.map(str::to_string)).await? {
+            Begun::Replay(response) => return Ok(response),
+            Begun::Write(write) => write,
+        };
+        sqlx::query(
+            "INSERT INTO metric_idempotency_keys (scope, idempotency_key) VALUES ($1, 'row')",
+        )
+        .bind(format!("{}:rows:{}", scope, uuid::Uuid::new_v4()))
+        .execute(write.conn())
+        .await
+        .unwrap();
+        if fail {
+            return Err(ApiError::internal("later step failed"));
+        }
+        write
+            .commit(StatusCode::CREATED, &serde_json::json!({ "ok": true }))
+            .await
+    }
+
+    async fn rows(db: &PgPool, scope: &str) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM metric_idempotency_keys WHERE scope LIKE $1")
+            .bind(format!("{}:rows:%", scope))
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_failed_write_rolls_back_with_its_claim() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let scope = format!("test:{}", uuid::Uuid::new_v4());
+
+        assert!(record(&db, &scope, Some("k"), true).await.is_err());
+        assert_eq!(rows(&db, &scope).await, 0);
+
+        // The retry isn't blocked by the failed attempt, and its replay
+        // doesn't write again
+        let first = record(&db, &scope, Some("k"), false).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(REPLAYED_HEADER).is_none());
+        let replay = record(&db, &scope, Some("k"), false).await.unwrap();
+        assert_eq!(replay.status(), StatusCode::CREATED);
+        assert!(replay.headers().get(REPLAYED_HEADER).is_some());
+        assert_eq!(rows(&db, &scope).await, 1);
+
+        record(&db, &scope, None, false).await.unwrap();
+        assert_eq!(rows(&db, &scope).await, 2);
+    }
+}
@@ -1,14 +1,24 @@
 #![allow(dead_code, unused)]
 
+pub mod activity_events;
+pub mod background_jobs;
 pub mod backup_handlers;
 pub mod backup_routes;
 pub mod cache;
+pub mod canary_events;
+pub mod deployment_events;
 pub mod disaster_recovery_models;
 pub mod error;
 pub mod health_monitor;
+pub mod idempotency;
 pub mod metrics;
+pub mod metrics_retention;
 pub mod notification_handlers;
 pub mod notification_routes;
 pub mod post_incident_handlers;
 pub mod post_incident_routes;
+#[cfg(feature = "redis")]
+pub mod redis_cache;
 pub mod state;
+#[cfg(test)]
+mod test_support;
@@ -1,55 +1,81 @@
 #![allow(dead_code, unused)]
 
+mod ab_test_auto_stop;
+mod ab_test_cleanup;
 mod ab_test_handlers;
 mod aggregation;
+mod alert_webhooks;
 mod analytics;
+mod api_key_handlers;
 mod auth;
+mod background_jobs;
 mod batch_verify_handlers;
 mod breaking_changes;
 mod cache;
+mod cache_handlers;
+mod canary_events;
 mod canary_handlers;
 mod compatibility_testing_handlers;
+mod comparison_handlers;
 mod db_monitoring;
+mod deployment_events;
 
+mod activity_events;
 mod activity_feed_handlers;
 mod activity_feed_routes;
 mod custom_metrics_handlers;
 mod dependency;
 mod deprecation_handlers;
+mod deprecation_sunset;
 mod error;
+mod gas_history;
 mod handlers;
 mod health;
 pub mod health_monitor;
 #[cfg(test)]
 mod health_tests;
+mod idempotency;
 mod metrics;
 mod metrics_handler;
+mod metrics_retention;
 mod migration_handlers;
+mod monitor;
+mod notifier;
 mod performance_handlers;
 mod rate_limit;
+#[cfg(feature = "redis")]
+mod redis_cache;
 mod release_notes_handlers;
 mod release_notes_routes;
+mod request_timeout;
 pub mod request_tracing;
 mod routes;
 pub mod security_log;
 pub mod signing_handlers;
 mod state;
+mod stellar;
+mod telemetry;
+#[cfg(test)]
+mod test_support;
+mod trust;
 mod type_safety;
 mod validation;
 mod simulation;
 mod simulation_handlers;
 
 use anyhow::Result;
+use axum::error_handling::HandleErrorLayer;
 use axum::extract::{Request, State};
 use axum::http::{header, HeaderValue, Method, StatusCode};
 use axum::response::Response;
 use axum::{middleware, Router};
 use dotenv::dotenv;
 use prometheus::Registry;
-use sqlx::postgres::PgPoolOptions;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use tower::timeout::TimeoutLayer;
+use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -91,6 +117,11 @@ async fn main() -> Result<()> {
         ));
     }
 
+    // Fail fast when the default gas network is not a known network.
+    let default_gas_network = simulation::gas_estimator::default_gas_network_from_env()
+        .map_err(|err| anyhow::anyhow!("Invalid gas network configuration: {}", err))?;
+    tracing::info!(network = %default_gas_network, "Default gas network configured");
+
     // Database connection with dynamic pool size
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 
@@ -99,20 +130,18 @@ async fn main() -> Result<()> {
         .unwrap_or(4);
 
     let default_max_pool = (logical_cores * 2).max(10);
-    let max_pool_size = std::env::var("DB_MAX_POOL_SIZE")
-        .ok()
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap_or(default_max_pool as u32);
+    let pool_config = db_monitoring::DbPoolConfig::from_env(default_max_pool as u32);
 
     tracing::info!(
-        max_pool_size = max_pool_size,
+        max_pool_size = pool_config.max_connections,
+        acquire_timeout_secs = pool_config.acquire_timeout.as_secs(),
+        idle_timeout_secs = pool_config.idle_timeout.as_secs(),
         logical_cores = logical_cores,
         "Initializing database connection pool"
     );
 
-    let pool = PgPoolOptions::new()
-        .max_connections(max_pool_size)
-        .acquire_timeout(std::time::Duration::from_secs(30))
+    let pool = pool_config
+        .pool_options()
         .connect(&database_url)
         .await?;
 
@@ -126,9 +155,6 @@ async fn main() -> Result<()> {
     // Check migration versioning state on startup (Issue #252)
     migration_handlers::check_migrations_on_startup(&pool).await;
 
-    // Spawn the hourly analytics aggregation background task
-    aggregation::spawn_aggregation_task(pool.clone());
-
     // Create prometheus registry for metrics
     let registry = Registry::new();
     if let Err(e) = crate::metrics::register_all(&registry) {
@@ -137,7 +163,33 @@ async fn main() -> Result<()> {
 
     // Create app state
     let is_shutting_down = Arc::new(AtomicBool::new(false));
-    let state = AppState::new(pool.clone(), registry, is_shutting_down.clone());
+    let mut state = AppState::new(pool.clone(), registry, is_shutting_down.clone());
+    state.default_gas_network = default_gas_network;
+
+    // Schedule the hourly analytics aggregation background job
+    aggregation::spawn_aggregation_task(&state.background_jobs, pool.clone());
+
+    // Schedule removal of stale A/B test variants, assignments and metrics
+    ab_test_cleanup::spawn_ab_test_cleanup_task(&state.background_jobs, pool.clone());
+
+    // Schedule evaluation of A/B tests created with auto_stop
+    ab_test_auto_stop::spawn_ab_test_auto_stop_task(&state.background_jobs, pool.clone());
+
+    // Schedule delivery of performance alert webhooks
+    alert_webhooks::spawn_alert_webhook_task(&state.background_jobs, pool.clone());
+
+    // Schedule retirement of deprecated contracts past their sunset date
+    deprecation_sunset::spawn_sunset_task(&state.background_jobs, pool.clone());
+
+    // Schedule the dependency update check that notifies publishers
+    monitor::spawn_update_monitor_task(&state.background_jobs, pool.clone());
+
+    // Schedule pruning of raw metrics older than METRICS_RETENTION_DAYS
+    metrics_retention::spawn_metrics_retention_task(
+        &state.background_jobs,
+        pool.clone(),
+        state.health_monitor_status.metrics_retention.clone(),
+    );
 
     // Spawn the background DB and cache monitoring task
     db_monitoring::spawn_db_monitoring_task(pool.clone(), state.cache.clone());
@@ -180,24 +232,42 @@ async fn main() -> Result<()> {
             Method::DELETE,
             Method::OPTIONS,
         ])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::HeaderName::from_static(auth::API_KEY_HEADER),
+        ]);
+
+    let request_timeout = request_timeout::request_timeout_from_env();
+    tracing::info!(
+        timeout_secs = request_timeout.as_secs(),
+        "Request timeout configured"
+    );
 
     // Build router
     let app = Router::new()
-        .merge(routes::contract_routes())
-        .merge(routes::publisher_routes())
+        .merge(routes::contract_routes(&state))
+        .merge(routes::publisher_routes(&state))
         .merge(routes::health_routes())
         .merge(routes::health_monitor_routes())
         .merge(routes::admin_routes())
         .merge(routes::compatibility_dashboard_routes())
-        .merge(routes::canary_routes())
-        .merge(routes::ab_test_routes())
-        .merge(routes::performance_routes())
+        .merge(routes::canary_routes(&state))
+        .merge(routes::ab_test_routes(&state))
+        .merge(routes::performance_routes(&state))
         .merge(routes::observability_routes())
         .merge(release_notes_routes::release_notes_routes())
         .nest("/api", activity_feed_routes::routes())
         .fallback(handlers::route_not_found)
+        // Innermost, so a timed-out request still passes through the
+        // in-flight tracking and metrics middleware as a 503
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(request_timeout::handle_timeout_error))
+                .layer(TimeoutLayer::new(request_timeout)),
+        )
         .layer(middleware::from_fn(request_tracing::tracing_middleware))
+        .layer(middleware::from_fn(crate::metrics::track_http_metrics))
         .layer(middleware::from_fn(
             validation::payload_size::payload_size_validation_middleware,
         ))
@@ -301,6 +371,7 @@ async fn main() -> Result<()> {
 
         tracing::info!("Closing database connections cleanly...");
         pool.close().await;
+        telemetry::shutdown();
 
         let shutdown_duration = start_time.elapsed();
         tracing::info!(
@@ -317,6 +388,7 @@ async fn main() -> Result<()> {
         let _ = server_task.await;
         tracing::info!("Closing database connections cleanly...");
         pool.close().await;
+        telemetry::shutdown();
         tracing::info!("Shutdown complete");
     }
 
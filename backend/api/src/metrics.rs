@@ -1,8 +1,14 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
 use once_cell::sync::Lazy;
 use prometheus::{
     opts, Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
     IntGaugeVec, Registry, TextEncoder,
 };
+use std::time::Instant;
 
 pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
 
@@ -48,6 +54,8 @@ const LATENCY_BUCKETS: [f64; 14] = [
 ];
 
 // ── HTTP ────────────────────────────────────────────────────────────────────
+// `path` is the matched route pattern (e.g. `/api/contracts/:id`), never the
+// raw path, so label cardinality is bounded by the router.
 pub static HTTP_REQUESTS_TOTAL: Lazy<IntCounterVec> = counter_vec!(
     "http_requests_total",
     "Total HTTP requests",
@@ -56,7 +64,7 @@ pub static HTTP_REQUESTS_TOTAL: Lazy<IntCounterVec> = counter_vec!(
 pub static HTTP_REQUEST_DURATION: Lazy<HistogramVec> = histogram_vec!(
     "http_request_duration_seconds",
     "HTTP request latency",
-    &["method", "path"]
+    &["method", "path", "status_class"]
 );
 pub static HTTP_IN_FLIGHT: Lazy<IntGauge> =
     gauge!("http_requests_in_flight", "In-flight HTTP requests");
@@ -165,6 +173,10 @@ pub static CACHE_ENTRIES: Lazy<IntGauge> = gauge!("cache_entries", "Number of ca
 pub static ABI_CACHE_HITS: Lazy<IntCounter> = counter!("abi_cache_hits_total", "ABI cache hits");
 pub static ABI_CACHE_MISSES: Lazy<IntCounter> =
     counter!("abi_cache_misses_total", "ABI cache misses");
+pub static ABI_CACHE_NEGATIVE_HITS: Lazy<IntCounter> = counter!(
+    "abi_cache_negative_hits_total",
+    "ABI lookups answered by a cached not-found marker"
+);
 pub static VERIFICATION_CACHE_HITS: Lazy<IntCounter> =
     counter!("verification_cache_hits_total", "Verification cache hits");
 pub static VERIFICATION_CACHE_MISSES: Lazy<IntCounter> = counter!(
@@ -294,6 +306,7 @@ pub fn register_all(r: &Registry) -> prometheus::Result<()> {
     r.register(Box::new(CACHE_ENTRIES.clone()))?;
     r.register(Box::new(ABI_CACHE_HITS.clone()))?;
     r.register(Box::new(ABI_CACHE_MISSES.clone()))?;
+    r.register(Box::new(ABI_CACHE_NEGATIVE_HITS.clone()))?;
     r.register(Box::new(VERIFICATION_CACHE_HITS.clone()))?;
     r.register(Box::new(VERIFICATION_CACHE_MISSES.clone()))?;
     r.register(Box::new(RESOURCE_RECORDINGS.clone()))?;
@@ -333,15 +346,53 @@ pub fn gather_metrics(r: &Registry) -> String {
     String::from_utf8(buf).unwrap_or_default()
 }
 
-pub fn observe_http(method: &str, path: &str, status: u16, duration_secs: f64) {
+/// Route label for requests that matched no route (404 fallback)
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+pub fn observe_http(method: &str, route: &str, status: u16, duration_secs: f64) {
     HTTP_REQUESTS_TOTAL
-        .with_label_values(&[method, path, &status.to_string()])
+        .with_label_values(&[method, route, &status.to_string()])
         .inc();
     HTTP_REQUEST_DURATION
-        .with_label_values(&[method, path])
+        .with_label_values(&[method, route, status_class(status)])
         .observe(duration_secs);
 }
 
+/// "2xx", "4xx", ... for a status code
+pub fn status_class(status: u16) -> &'static str {
+    match status {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
+}
+
+/// Axum middleware recording `http_requests_total` and
+/// `http_request_duration_seconds` for every request. Must be added with
+/// `Router::layer` so the matched route is known.
+pub async fn track_http_metrics(
+    matched_path: Option<MatchedPath>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    let start = Instant::now();
+    let response = next.run(req).await;
+
+    let route = matched_path
+        .as_ref()
+        .map_or(UNMATCHED_ROUTE, |p| p.as_str());
+    observe_http(
+        method.as_str(),
+        route,
+        response.status().as_u16(),
+        start.elapsed().as_secs_f64(),
+    );
+    response
+}
+
 pub fn observe_verification_latency(result: &str, duration_secs: f64) {
     VERIFICATION_LATENCY
         .with_label_values(&[result])
@@ -450,8 +501,43 @@ mod tests {
         let _r = fresh_registry();
         observe_http("POST", "/api/contracts", 201, 0.055);
         let sample_count = HTTP_REQUEST_DURATION
-            .with_label_values(&["POST", "/api/contracts"])
+            .with_label_values(&["POST", "/api/contracts", "2xx"])
             .get_sample_count();
         assert!(sample_count >= 1);
     }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(status_class(200), "2xx");
+        assert_eq!(status_class(304), "3xx");
+        assert_eq!(status_class(429), "4xx");
+        assert_eq!(status_class(503), "5xx");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_labels_requests_by_route_pattern() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::Service;
+
+        let mut app = Router::new()
+            .route("/metrics-test/:id/perf", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn(track_http_metrics));
+
+        for id in ["a", "b"] {
+            let request = Request::builder()
+                .uri(format!("/metrics-test/{}/perf", id))
+                .body(Body::empty())
+                .unwrap();
+            app.call(request).await.unwrap();
+        }
+
+        let samples = HTTP_REQUEST_DURATION
+            .with_label_values(&["GET", "/metrics-test/:id/perf", "2xx"])
+            .get_sample_count();
+        assert_eq!(samples, 2);
+        let requests = HTTP_REQUESTS_TOTAL
+            .with_label_values(&["GET", "/metrics-test/:id/perf", "200"])
+            .get();
+        assert_eq!(requests, 2);
+    }
 }
@@ -37,6 +37,11 @@ mod tests {
             registry,
             is_shutting_down: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             health_monitor_status: crate::health_monitor::HealthMonitorStatus::default(),
+            default_gas_network: shared::models::Network::Mainnet,
+            background_jobs: crate::background_jobs::JobScheduler::new(Default::default()),
+            canary_events: Default::default(),
+            activity_events: Default::default(),
+            deployment_events: Default::default(),
         }
     }
 
@@ -0,0 +1,257 @@
+// api/src/metrics_retention.rs
+// Periodic pruning of raw performance, canary and A/B test metrics older than
+// the retention window. Expired performance metrics can first be rolled up
+// into daily `performance_trends` rows so long-range history survives.
+
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::background_jobs::JobScheduler;
+
+const DEFAULT_RETENTION_DAYS: i64 = 90;
+const DEFAULT_BATCH_SIZE: i64 = 5_000;
+const RETENTION_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Raw metric tables pruned by this job; all record their time in `timestamp`
+const METRIC_TABLES: &[&str] = &["performance_metrics", "canary_metrics", "ab_test_metrics"];
+
+#[derive(Debug, Clone)]
+pub struct RetentionConfig {
+    pub retention: ChronoDuration,
+    pub batch_size: i64,
+    /// Roll expired performance metrics up into daily trends before deleting them
+    pub downsample: bool,
+}
+
+impl RetentionConfig {
+    pub fn from_env() -> Self {
+        let retention_days = std::env::var("METRICS_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+        let batch_size = std::env::var("METRICS_RETENTION_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+        let downsample = std::env::var("METRICS_RETENTION_DOWNSAMPLE")
+            .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no"))
+            .unwrap_or(true);
+
+        Self {
+            retention: ChronoDuration::days(retention_days),
+            batch_size,
+            downsample,
+        }
+    }
+}
+
+/// Outcome of the last retention run on this replica, reported through the
+/// health monitor status endpoint.
+#[derive(Clone, Default)]
+pub struct RetentionStatus {
+    last_run: Arc<RwLock<Option<DateTime<Utc>>>>,
+    last_rows_deleted: Arc<AtomicU64>,
+    total_rows_deleted: Arc<AtomicU64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetentionStatusResponse {
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_rows_deleted: u64,
+    pub total_rows_deleted: u64,
+}
+
+impl RetentionStatus {
+    pub async fn record(&self, at: DateTime<Utc>, deleted: u64) {
+        *self.last_run.write().await = Some(at);
+        self.last_rows_deleted.store(deleted, Ordering::Relaxed);
+        self.total_rows_deleted
+            .fetch_add(deleted, Ordering::Relaxed);
+    }
+
+    pub async fn snapshot(&self) -> RetentionStatusResponse {
+        RetentionStatusResponse {
+            last_run: *self.last_run.read().await,
+            last_rows_deleted: self.last_rows_deleted.load(Ordering::Relaxed),
+            total_rows_deleted: self.total_rows_deleted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Register the retention job with the background scheduler.
+pub fn spawn_metrics_retention_task(
+    scheduler: &JobScheduler,
+    pool: PgPool,
+    status: RetentionStatus,
+) {
+    let config = RetentionConfig::from_env();
+    scheduler.spawn("metrics_retention", RETENTION_INTERVAL, move || {
+        let pool = pool.clone();
+        let config = config.clone();
+        let status = status.clone();
+        async move {
+            let now = Utc::now();
+            let deleted = run_retention(&pool, &config, now).await?;
+            status.record(now, deleted).await;
+            if deleted > 0 {
+                tracing::info!(deleted, "metrics_retention: pruned expired metrics");
+            }
+            Ok(())
+        }
+    });
+}
+
+/// Prune every metric older than the retention cutoff, returning how many
+/// rows were deleted.
+pub async fn run_retention(
+    pool: &PgPool,
+    config: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    prune(&mut conn, config, retention_cutoff(now, config.retention)).await
+}
+
+async fn prune(
+    conn: &mut PgConnection,
+    config: &RetentionConfig,
+    cutoff: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    if config.downsample {
+        let trends = downsample_performance_metrics(conn, cutoff).await?;
+        if trends > 0 {
+            tracing::info!(
+                trends,
+                "metrics_retention: rolled up expired performance metrics"
+            );
+        }
+    }
+
+    let mut deleted = 0;
+    for table in METRIC_TABLES {
+        let query = format!(
+            "DELETE FROM {table} WHERE id IN \
+             (SELECT id FROM {table} WHERE timestamp < $1 LIMIT $2)"
+        );
+        loop {
+            let batch = sqlx::query(&query)
+                .bind(cutoff)
+                .bind(config.batch_size)
+                .execute(&mut *conn)
+                .await?
+                .rows_affected();
+            deleted += batch;
+            if batch < config.batch_size as u64 {
+                break;
+            }
+        }
+    }
+
+    // Idempotency keys for metric writes only need to outlive client retries
+    sqlx::query(
+        "DELETE FROM metric_idempotency_keys \
+         WHERE created_at < NOW() - make_interval(secs => $1)",
+    )
+    .bind(*crate::idempotency::IDEMPOTENCY_TTL_SECS as f64)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(deleted)
+}
+
+/// Write one daily trend row per contract, function and metric type for the
+/// expired days. Days that already have a trend row are skipped, so a run
+/// interrupted between rolling up and deleting never double-counts.
+async fn downsample_performance_metrics(
+    conn: &mut PgConnection,
+    cutoff: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        WITH daily AS (
+            SELECT contract_id, function_name, metric_type,
+                   date_trunc('day', timestamp, 'UTC') AS day_start,
+                   AVG(value) AS avg_value,
+                   MIN(value) AS min_value,
+                   MAX(value) AS max_value,
+                   percentile_cont(0.50) WITHIN GROUP (ORDER BY value) AS p50_value,
+                   percentile_cont(0.95) WITHIN GROUP (ORDER BY value) AS p95_value,
+                   percentile_cont(0.99) WITHIN GROUP (ORDER BY value) AS p99_value,
+                   COUNT(*) AS sample_count
+            FROM performance_metrics
+            WHERE timestamp < $1
+            GROUP BY contract_id, function_name, metric_type, day_start
+        )
+        INSERT INTO performance_trends
+            (contract_id, function_name, metric_type, timeframe_start, timeframe_end,
+             avg_value, min_value, max_value, p50_value, p95_value, p99_value, sample_count)
+        SELECT d.contract_id, d.function_name, d.metric_type,
+               d.day_start, d.day_start + INTERVAL '1 day',
+               d.avg_value, d.min_value, d.max_value,
+               d.p50_value, d.p95_value, d.p99_value, d.sample_count
+        FROM daily d
+        WHERE NOT EXISTS (
+            SELECT 1 FROM performance_trends t
+            WHERE t.contract_id = d.contract_id
+              AND t.function_name IS NOT DISTINCT FROM d.function_name
+              AND t.metric_type = d.metric_type
+              AND t.timeframe_start = d.day_start
+              AND t.timeframe_end = d.day_start + INTERVAL '1 day'
+        )
+        "#,
+    )
+    .bind(cutoff)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Start of the UTC day `retention` before `now`. Cutting on a day boundary
+/// means every day rolled up into a trend is complete when it is expired.
+pub fn retention_cutoff(now: DateTime<Utc>, retention: ChronoDuration) -> DateTime<Utc> {
+    let cutoff = now - retention;
+    cutoff
+        .duration_trunc(ChronoDuration::days(1))
+        .unwrap_or(cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn cutoff_lands_on_the_start_of_a_utc_day() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 13, 45, 10).unwrap();
+
+        let cutoff = retention_cutoff(now, ChronoDuration::days(90));
+
+        assert_eq!(cutoff, Utc.with_ymd_and_hms(2026, 7, 19, 0, 0, 0).unwrap());
+        // Re-running later the same day yields the same boundary
+        let later = now + ChronoDuration::hours(9);
+        assert_eq!(retention_cutoff(later, ChronoDuration::days(90)), cutoff);
+    }
+
+    #[tokio::test]
+    async fn status_keeps_the_last_run_and_a_running_total() {
+        let status = RetentionStatus::default();
+        assert!(status.snapshot().await.last_run.is_none());
+
+        let now = Utc::now();
+        status.record(now, 1_200).await;
+        status.record(now, 30).await;
+
+        let snapshot = status.snapshot().await;
+        assert_eq!(snapshot.last_run, Some(now));
+        assert_eq!(snapshot.last_rows_deleted, 30);
+        assert_eq!(snapshot.total_rows_deleted, 1_230);
+    }
+}
@@ -1,19 +1,24 @@
 // migration_handlers.rs
 // Database migration versioning, rollback, and validation handlers (Issue #252).
-// Provides schema version tracking, checksum validation, advisory locking,
-// and rollback capability for safe database deployments.
+// Provides schema version tracking, checksum validation, a migration lock that
+// expires after a TTL, and rollback capability for safe database deployments.
 
 use axum::{
-    extract::{Path, State},
-    Json,
+    extract::{rejection::JsonRejection, Path, Query, State},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::FromRow;
+use std::path::{Path as FsPath, PathBuf};
+use uuid::Uuid;
 
 use crate::{
+    auth::AuthClaims,
     error::{ApiError, ApiResult},
+    handlers::map_json_rejection,
     state::AppState,
 };
 
@@ -59,8 +64,13 @@ pub struct MigrationStatusResponse {
 #[derive(Debug, Serialize)]
 pub struct MigrationValidationResponse {
     pub valid: bool,
+    /// Applied migrations whose file changed since registration, and rollback
+    /// scripts altered in the database
     pub mismatches: Vec<ChecksumMismatch>,
     pub missing: Vec<i32>,
+    /// Applied migrations whose file couldn't be read, so drift wasn't checked
+    pub unverified_files: Vec<String>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -94,11 +104,64 @@ pub struct RollbackResponse {
     pub message: String,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct RollbackQuery {
+    /// Report what the rollback would do without running it
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// One statement of a rollback script, as it would be executed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedStatement {
+    /// Leading keywords, e.g. "DROP TABLE" or "ALTER TABLE"
+    pub action: String,
+    /// Object the statement acts on, when it names one
+    pub target: Option<String>,
+    /// Whether the statement drops objects or deletes data
+    pub destructive: bool,
+    pub sql: String,
+}
+
+/// Result of `POST /api/admin/migrations/:version/rollback?dry_run=true`
 #[derive(Debug, Serialize)]
+pub struct RollbackPlanResponse {
+    pub version: i32,
+    pub dry_run: bool,
+    pub description: String,
+    pub filename: String,
+    pub applied_at: DateTime<Utc>,
+    /// Whether another migration operation currently holds the lock
+    pub locked: bool,
+    pub statements: Vec<PlannedStatement>,
+    /// Applied migrations newer than this one, which should be rolled back first
+    pub later_applied_versions: Vec<i32>,
+    /// Problems that would make the rollback fail or be refused
+    pub blockers: Vec<String>,
+    pub warnings: Vec<String>,
+    /// No blockers; the rollback would be attempted
+    pub safe: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
 pub struct LockStatusResponse {
     pub locked: bool,
     pub locked_by: Option<String>,
     pub locked_at: Option<DateTime<Utc>>,
+    /// Seconds since the lock was taken
+    pub lock_age_secs: Option<i64>,
+    /// Held for longer than the TTL; the next migration will take it over
+    pub stale: bool,
+    pub ttl_secs: i64,
+}
+
+/// Result of `POST /api/admin/migrations/lock/release`
+#[derive(Debug, Serialize)]
+pub struct LockReleaseResponse {
+    /// Whether the lock was held when it was cleared
+    pub released: bool,
+    pub previous_holder: Option<String>,
+    pub previous_locked_at: Option<DateTime<Utc>>,
 }
 
 // ─────────────────────────────────────────────────────────
@@ -112,27 +175,338 @@ pub fn compute_checksum(content: &str) -> String {
     hex::encode(hasher.finalize())
 }
 
-/// Advisory lock key for migration operations (arbitrary fixed i64).
-const MIGRATION_ADVISORY_LOCK_KEY: i64 = 252_252_252;
+/// Directory the migration files registered in `schema_versions` are read
+/// from to detect drift: `MIGRATIONS_DIR`, or the repository's migrations.
+fn migrations_dir() -> PathBuf {
+    std::env::var("MIGRATIONS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            FsPath::new(env!("CARGO_MANIFEST_DIR")).join("../../database/migrations")
+        })
+}
 
-/// Try to acquire the PostgreSQL advisory lock. Returns true if acquired.
-async fn try_acquire_lock(pool: &sqlx::PgPool) -> Result<bool, sqlx::Error> {
-    let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
-        .bind(MIGRATION_ADVISORY_LOCK_KEY)
-        .fetch_one(pool)
-        .await?;
-    Ok(acquired)
+/// Recompute the checksum of each applied migration's file in `dir` and
+/// report those that no longer match the checksum stored at registration.
+/// Files that can't be read are returned separately.
+pub fn find_checksum_drift(
+    versions: &[SchemaVersion],
+    dir: &FsPath,
+) -> (Vec<ChecksumMismatch>, Vec<String>) {
+    let mut mismatches = Vec::new();
+    let mut unreadable = Vec::new();
+
+    for applied in versions {
+        // Only the file name is used, so a registered path can't leave `dir`
+        let contents = FsPath::new(&applied.filename)
+            .file_name()
+            .and_then(|name| std::fs::read_to_string(dir.join(name)).ok());
+        let Some(contents) = contents else {
+            unreadable.push(applied.filename.clone());
+            continue;
+        };
+
+        let actual = compute_checksum(&contents);
+        if actual != applied.checksum {
+            mismatches.push(ChecksumMismatch {
+                version: applied.version,
+                filename: applied.filename.clone(),
+                expected_checksum: applied.checksum.clone(),
+                actual_checksum: actual,
+            });
+        }
+    }
+
+    (mismatches, unreadable)
+}
+
+/// Default for `MIGRATION_LOCK_TTL_SECS`
+const DEFAULT_LOCK_TTL_SECS: i64 = 900;
+
+/// How long the migration lock may be held before it's treated as abandoned
+/// by a crashed migrator and may be taken over.
+fn lock_ttl() -> chrono::Duration {
+    let secs = std::env::var("MIGRATION_LOCK_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_LOCK_TTL_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+/// Whether a lock taken at `locked_at` has outlived `ttl`
+pub fn lock_is_stale(locked_at: DateTime<Utc>, now: DateTime<Utc>, ttl: chrono::Duration) -> bool {
+    now - locked_at >= ttl
 }
 
-/// Release the PostgreSQL advisory lock.
-async fn release_lock(pool: &sqlx::PgPool) -> Result<(), sqlx::Error> {
-    sqlx::query("SELECT pg_advisory_unlock($1)")
-        .bind(MIGRATION_ADVISORY_LOCK_KEY)
-        .execute(pool)
+/// Status of a lock row as of `now`
+pub fn lock_status(
+    locked_by: Option<String>,
+    locked_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    ttl: chrono::Duration,
+) -> LockStatusResponse {
+    LockStatusResponse {
+        locked: locked_at.is_some(),
+        locked_by,
+        locked_at,
+        lock_age_secs: locked_at.map(|at| (now - at).num_seconds()),
+        stale: locked_at.is_some_and(|at| lock_is_stale(at, now, ttl)),
+        ttl_secs: ttl.num_seconds(),
+    }
+}
+
+/// Identifies one operation's hold on the migration lock, so it only ever
+/// releases its own lock and not one taken over after it went stale.
+fn new_lock_holder() -> String {
+    format!("pid {} ({})", std::process::id(), Uuid::new_v4())
+}
+
+async fn read_lock(
+    pool: &sqlx::PgPool,
+) -> Result<(Option<String>, Option<DateTime<Utc>>), sqlx::Error> {
+    let row: Option<(Option<String>, Option<DateTime<Utc>>)> =
+        sqlx::query_as("SELECT locked_by, locked_at FROM schema_migration_locks WHERE id = 1")
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.unwrap_or((None, None)))
+}
+
+async fn record_lock_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: &str,
+    previous_holder: Option<&str>,
+    previous_locked_at: Option<DateTime<Utc>>,
+    actor: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO schema_migration_lock_events
+            (event, previous_holder, previous_locked_at, actor)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(event)
+    .bind(previous_holder)
+    .bind(previous_locked_at)
+    .bind(actor)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Try to take the migration lock for `holder`. Returns true if acquired.
+/// A lock held for longer than the TTL is taken over, with a warning and an
+/// audit entry.
+async fn try_acquire_lock(pool: &sqlx::PgPool, holder: &str) -> Result<bool, sqlx::Error> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    // Only returns a row when the lock was free or stale; `previous` is the
+    // row as it was before this update
+    let taken: Option<(Option<String>, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        WITH previous AS (
+            SELECT locked_by, locked_at FROM schema_migration_locks WHERE id = 1 FOR UPDATE
+        )
+        UPDATE schema_migration_locks l
+        SET locked_by = $1, locked_at = $2
+        FROM previous
+        WHERE l.id = 1 AND (previous.locked_at IS NULL OR previous.locked_at <= $3)
+        RETURNING previous.locked_by, previous.locked_at
+        "#,
+    )
+    .bind(holder)
+    .bind(now)
+    .bind(now - lock_ttl())
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((previous_holder, previous_locked_at)) = taken else {
+        return Ok(false);
+    };
+    if let Some(locked_at) = previous_locked_at {
+        tracing::warn!(
+            previous_holder = previous_holder.as_deref().unwrap_or("unknown"),
+            %locked_at,
+            holder,
+            "taking over stale migration lock"
+        );
+        record_lock_event(
+            &mut tx,
+            "force_acquired",
+            previous_holder.as_deref(),
+            previous_locked_at,
+            holder,
+        )
         .await?;
+    }
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Release the migration lock if `holder` still holds it.
+async fn release_lock(pool: &sqlx::PgPool, holder: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE schema_migration_locks SET locked_by = NULL, locked_at = NULL \
+         WHERE id = 1 AND locked_by = $1",
+    )
+    .bind(holder)
+    .execute(pool)
+    .await?;
     Ok(())
 }
 
+/// Keywords naming the kind of object a DDL statement acts on, e.g. TABLE in
+/// "DROP TABLE"
+const OBJECT_KINDS: &[&str] = &[
+    "TABLE",
+    "INDEX",
+    "TYPE",
+    "VIEW",
+    "FUNCTION",
+    "TRIGGER",
+    "SEQUENCE",
+    "SCHEMA",
+    "EXTENSION",
+    "COLUMN",
+    "CONSTRAINT",
+];
+
+/// Whether the migration lock is held and not yet stale, without trying to
+/// take it.
+async fn lock_is_held(pool: &sqlx::PgPool) -> Result<bool, sqlx::Error> {
+    let (_, locked_at) = read_lock(pool).await?;
+    Ok(locked_at.is_some_and(|at| !lock_is_stale(at, Utc::now(), lock_ttl())))
+}
+
+/// Split a SQL script into statements on top-level semicolons, skipping
+/// those inside quotes, dollar-quoted bodies and comments. Errors when a
+/// quote or comment is never closed.
+pub fn split_sql_statements(sql: &str) -> Result<Vec<String>, String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                current.push(c);
+                loop {
+                    let Some((_, next)) = chars.next() else {
+                        return Err(format!("unterminated {} quote", c));
+                    };
+                    current.push(next);
+                    if next == c {
+                        // A doubled quote is an escaped quote, not the end
+                        if chars.peek().map(|(_, n)| *n) == Some(c) {
+                            current.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '-' if chars.peek().map(|(_, n)| *n) == Some('-') => {
+                for (_, next) in chars.by_ref() {
+                    if next == '\n' {
+                        current.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().map(|(_, n)| *n) == Some('*') => {
+                chars.next();
+                let mut closed = false;
+                let mut prev = ' ';
+                for (_, next) in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        closed = true;
+                        break;
+                    }
+                    prev = next;
+                }
+                if !closed {
+                    return Err("unterminated block comment".to_string());
+                }
+                current.push(' ');
+            }
+            '$' => {
+                let rest = &sql[i + 1..];
+                let tag_len = rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                    .unwrap_or(rest.len());
+                if rest[tag_len..].starts_with('$') {
+                    let delimiter = format!("${}$", &rest[..tag_len]);
+                    let body_start = i + delimiter.len();
+                    let Some(body_len) = sql[body_start..].find(&delimiter) else {
+                        return Err(format!("unterminated {} string", delimiter));
+                    };
+                    let end = body_start + body_len + delimiter.len();
+                    current.push_str(&sql[i..end]);
+                    while chars.peek().is_some_and(|(j, _)| *j < end) {
+                        chars.next();
+                    }
+                } else {
+                    current.push(c);
+                }
+            }
+            ';' => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim().to_string());
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        statements.push(current.trim().to_string());
+    }
+
+    Ok(statements)
+}
+
+/// Describe what a single rollback statement does
+pub fn plan_statement(sql: &str) -> PlannedStatement {
+    let words: Vec<&str> = sql.split_whitespace().collect();
+    let upper: Vec<String> = words.iter().map(|w| w.to_ascii_uppercase()).collect();
+    let keyword = |i: usize| upper.get(i).map(String::as_str);
+
+    let (action_len, target_at) = match keyword(0) {
+        Some("DROP" | "CREATE" | "ALTER") => {
+            let kind_at = (1..upper.len().min(4))
+                .find(|&i| OBJECT_KINDS.contains(&upper[i].as_str()))
+                .unwrap_or(1);
+            (kind_at + 1, kind_at + 1)
+        }
+        Some("DELETE") => (2, 2),
+        Some("INSERT") => (2, 2),
+        Some("UPDATE" | "TRUNCATE") => (1, 1),
+        _ => (1, usize::MAX),
+    };
+
+    // Skip IF [NOT] EXISTS and TABLE (for TRUNCATE TABLE) before the name
+    let target = words
+        .iter()
+        .zip(&upper)
+        .skip(target_at)
+        .find(|(_, u)| !matches!(u.as_str(), "IF" | "NOT" | "EXISTS" | "TABLE" | "ONLY"))
+        .map(|(w, _)| w.trim_end_matches(['(', ',']).to_string());
+
+    let action = upper[..action_len.min(upper.len())].join(" ");
+    let destructive = matches!(keyword(0), Some("DROP" | "TRUNCATE" | "DELETE"))
+        || (keyword(0) == Some("ALTER") && upper.iter().any(|w| w == "DROP"));
+
+    PlannedStatement {
+        action,
+        target,
+        destructive,
+        sql: sql.to_string(),
+    }
+}
+
 // ─────────────────────────────────────────────────────────
 // Handlers
 // ─────────────────────────────────────────────────────────
@@ -173,13 +547,7 @@ pub async fn get_migration_status(
         .filter(|v| v.rolled_back_at.is_some())
         .count() as i64;
 
-    // Check advisory lock status
-    let has_lock: bool =
-        sqlx::query_scalar("SELECT NOT pg_try_advisory_lock($1) OR pg_advisory_unlock($1)")
-            .bind(MIGRATION_ADVISORY_LOCK_KEY)
-            .fetch_one(&state.db)
-            .await
-            .unwrap_or(false);
+    let has_lock = lock_is_held(&state.db).await.unwrap_or(false);
 
     let mut warnings = Vec::new();
 
@@ -216,13 +584,14 @@ pub async fn get_migration_status(
 ///
 /// Register a new migration with its SQL content and optional rollback script.
 /// Computes SHA-256 checksum and stores it for future validation.
-/// Uses advisory lock to prevent concurrent registration.
+/// Takes the migration lock to prevent concurrent registration.
 pub async fn register_migration(
     State(state): State<AppState>,
-    Json(body): Json<RegisterMigrationRequest>,
+    payload: Result<Json<RegisterMigrationRequest>, JsonRejection>,
 ) -> ApiResult<Json<RegisterMigrationResponse>> {
-    // Acquire advisory lock
-    let acquired = try_acquire_lock(&state.db)
+    let Json(body) = payload.map_err(map_json_rejection)?;
+    let holder = new_lock_holder();
+    let acquired = try_acquire_lock(&state.db, &holder)
         .await
         .map_err(|e| ApiError::internal(format!("Lock error: {e}")))?;
 
@@ -236,7 +605,7 @@ pub async fn register_migration(
     // Ensure we release the lock on all exit paths
     let result = register_migration_inner(&state, &body).await;
 
-    let _ = release_lock(&state.db).await;
+    let _ = release_lock(&state.db, &holder).await;
 
     result
 }
@@ -306,13 +675,23 @@ async fn register_migration_inner(
 /// POST /api/admin/migrations/:version/rollback
 ///
 /// Roll back a specific migration version by executing its DOWN script.
-/// Uses advisory lock to prevent concurrent operations.
+/// Takes the migration lock to prevent concurrent operations.
+///
+/// With `?dry_run=true` nothing is executed and the lock is not taken; the
+/// response is a `RollbackPlanResponse` describing what would happen.
 pub async fn rollback_migration(
     State(state): State<AppState>,
     Path(version): Path<i32>,
-) -> ApiResult<Json<RollbackResponse>> {
-    // Acquire advisory lock
-    let acquired = try_acquire_lock(&state.db)
+    Query(params): Query<RollbackQuery>,
+) -> ApiResult<Response> {
+    if params.dry_run {
+        return plan_rollback(&state, version)
+            .await
+            .map(|plan| Json(plan).into_response());
+    }
+
+    let holder = new_lock_holder();
+    let acquired = try_acquire_lock(&state.db, &holder)
         .await
         .map_err(|e| ApiError::internal(format!("Lock error: {e}")))?;
 
@@ -325,9 +704,140 @@ pub async fn rollback_migration(
 
     let result = rollback_migration_inner(&state, version).await;
 
-    let _ = release_lock(&state.db).await;
+    let _ = release_lock(&state.db, &holder).await;
 
-    result
+    result.map(IntoResponse::into_response)
+}
+
+/// Dry run of `rollback_migration_inner`: the same checks, plus the lock
+/// state and the statements that would run, without executing anything or
+/// taking the lock.
+async fn plan_rollback(state: &AppState, version: i32) -> ApiResult<RollbackPlanResponse> {
+    let migration: Option<SchemaVersion> = sqlx::query_as(
+        r#"
+        SELECT id, version, description, filename, checksum,
+               applied_at, applied_by, execution_time_ms,
+               rolled_back_at, rollback_by
+        FROM schema_versions
+        WHERE version = $1
+        "#,
+    )
+    .bind(version)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("DB error: {e}")))?;
+
+    let migration = migration.ok_or_else(|| {
+        ApiError::not_found(
+            "NotFound",
+            format!("Migration version {} not found", version),
+        )
+    })?;
+
+    let rollback: Option<SchemaRollbackScript> = sqlx::query_as(
+        r#"
+        SELECT id, version, down_sql, checksum, created_at
+        FROM schema_rollback_scripts
+        WHERE version = $1
+        "#,
+    )
+    .bind(version)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("DB error: {e}")))?;
+
+    let later_applied_versions: Vec<i32> = sqlx::query_scalar(
+        "SELECT version FROM schema_versions
+         WHERE version > $1 AND rolled_back_at IS NULL
+         ORDER BY version DESC",
+    )
+    .bind(version)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("DB error: {e}")))?;
+
+    let locked = lock_is_held(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Lock error: {e}")))?;
+
+    Ok(build_rollback_plan(
+        migration,
+        rollback.as_ref(),
+        later_applied_versions,
+        locked,
+    ))
+}
+
+fn build_rollback_plan(
+    migration: SchemaVersion,
+    rollback: Option<&SchemaRollbackScript>,
+    later_applied_versions: Vec<i32>,
+    locked: bool,
+) -> RollbackPlanResponse {
+    let version = migration.version;
+    let mut blockers = Vec::new();
+    let mut warnings = Vec::new();
+    let mut statements = Vec::new();
+
+    if migration.rolled_back_at.is_some() {
+        blockers.push(format!(
+            "Migration version {} has already been rolled back",
+            version
+        ));
+    }
+    if locked {
+        blockers.push("Another migration operation is in progress".to_string());
+    }
+
+    match rollback {
+        None => blockers.push(format!(
+            "No rollback script found for migration version {}",
+            version
+        )),
+        Some(script) => {
+            if compute_checksum(&script.down_sql) != script.checksum {
+                blockers.push(format!(
+                    "Rollback script checksum mismatch for version {}",
+                    version
+                ));
+            }
+            match split_sql_statements(&script.down_sql) {
+                Ok(parsed) if parsed.is_empty() => {
+                    blockers.push("Rollback script contains no statements".to_string())
+                }
+                Ok(parsed) => statements = parsed.iter().map(|s| plan_statement(s)).collect(),
+                Err(err) => blockers.push(format!("Rollback script is not valid SQL: {}", err)),
+            }
+        }
+    }
+
+    if !later_applied_versions.is_empty() {
+        warnings.push(format!(
+            "Versions {:?} were applied after {} and should be rolled back first",
+            later_applied_versions, version
+        ));
+    }
+    let destructive = statements.iter().filter(|s| s.destructive).count();
+    if destructive > 0 {
+        warnings.push(format!(
+            "{} statement(s) drop objects or delete data",
+            destructive
+        ));
+    }
+
+    RollbackPlanResponse {
+        version,
+        dry_run: true,
+        description: migration.description,
+        filename: migration.filename,
+        applied_at: migration.applied_at,
+        locked,
+        statements,
+        later_applied_versions,
+        safe: blockers.is_empty(),
+        blockers,
+        warnings,
+    }
 }
 
 async fn rollback_migration_inner(
@@ -455,10 +965,9 @@ pub async fn validate_migrations(
         }
     }
 
-    // Checksums are stored at registration time; we report the stored state.
-    // In a full implementation, we'd re-read migration files from disk and compare.
-    // Here we verify rollback script checksums are consistent.
-    let mut mismatches = Vec::new();
+    // Migration files edited after they were applied
+    let dir = migrations_dir();
+    let (mut mismatches, unverified_files) = find_checksum_drift(&versions, &dir);
 
     let rollback_scripts: Vec<SchemaRollbackScript> = sqlx::query_as(
         r#"
@@ -483,13 +992,49 @@ pub async fn validate_migrations(
         }
     }
 
-    let valid = mismatches.is_empty() && missing.is_empty();
+    Ok(Json(validation_response(
+        mismatches,
+        missing,
+        unverified_files,
+        &dir,
+    )))
+}
+
+/// Validation result for the given findings. Migrations that couldn't be
+/// checked against their files, including because `dir` doesn't exist, make
+/// it invalid just like drift does.
+fn validation_response(
+    mismatches: Vec<ChecksumMismatch>,
+    missing: Vec<i32>,
+    unverified_files: Vec<String>,
+    dir: &FsPath,
+) -> MigrationValidationResponse {
+    let mut warnings = Vec::new();
+    let dir_found = dir.is_dir();
+    if !dir_found {
+        warnings.push(format!(
+            "Migrations directory {} not found; set MIGRATIONS_DIR to the deployed migrations",
+            dir.display()
+        ));
+    }
+    if !unverified_files.is_empty() {
+        warnings.push(format!(
+            "{} applied migration file(s) could not be read from {}",
+            unverified_files.len(),
+            dir.display()
+        ));
+    }
 
-    Ok(Json(MigrationValidationResponse {
-        valid,
+    MigrationValidationResponse {
+        valid: dir_found
+            && mismatches.is_empty()
+            && missing.is_empty()
+            && unverified_files.is_empty(),
         mismatches,
         missing,
-    }))
+        unverified_files,
+        warnings,
+    }
 }
 
 /// GET /api/admin/migrations/:version
@@ -523,29 +1068,72 @@ pub async fn get_migration_version(
 
 /// GET /api/admin/migrations/lock
 ///
-/// Check the current advisory lock status.
+/// Check the current migration lock status, including how long it has been
+/// held and whether it has outlived `MIGRATION_LOCK_TTL_SECS`.
 pub async fn get_lock_status(State(state): State<AppState>) -> ApiResult<Json<LockStatusResponse>> {
-    // Try to acquire and immediately release to check if lock is free
-    let can_lock = try_acquire_lock(&state.db)
+    let (locked_by, locked_at) = read_lock(&state.db)
         .await
-        .map_err(|e| ApiError::internal(format!("Lock error: {e}")))?;
+        .map_err(|e| ApiError::internal(format!("DB error: {e}")))?;
 
-    if can_lock {
-        let _ = release_lock(&state.db).await;
-    }
+    Ok(Json(lock_status(
+        locked_by,
+        locked_at,
+        Utc::now(),
+        lock_ttl(),
+    )))
+}
 
-    let lock_row: Option<(Option<String>, Option<DateTime<Utc>>)> =
-        sqlx::query_as("SELECT locked_by, locked_at FROM schema_migration_locks WHERE id = 1")
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::internal(format!("DB error: {e}")))?;
+/// POST /api/admin/migrations/lock/release
+///
+/// Clear the migration lock whoever holds it, to recover from a migrator that
+/// died without releasing it. Recorded in `schema_migration_lock_events`.
+pub async fn release_migration_lock(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AuthClaims>,
+) -> ApiResult<Json<LockReleaseResponse>> {
+    let db_err = |e: sqlx::Error| ApiError::internal(format!("DB error: {e}"));
+    let mut tx = state.db.begin().await.map_err(db_err)?;
 
-    let (locked_by, locked_at) = lock_row.unwrap_or((None, None));
+    let previous: Option<(Option<String>, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        WITH previous AS (
+            SELECT locked_by, locked_at FROM schema_migration_locks WHERE id = 1 FOR UPDATE
+        )
+        UPDATE schema_migration_locks l
+        SET locked_by = NULL, locked_at = NULL
+        FROM previous
+        WHERE l.id = 1
+        RETURNING previous.locked_by, previous.locked_at
+        "#,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(db_err)?;
+    let (previous_holder, previous_locked_at) = previous.unwrap_or((None, None));
 
-    Ok(Json(LockStatusResponse {
-        locked: !can_lock,
-        locked_by,
-        locked_at,
+    let released = previous_locked_at.is_some();
+    if released {
+        tracing::warn!(
+            previous_holder = previous_holder.as_deref().unwrap_or("unknown"),
+            released_by = %claims.sub,
+            "migration lock released manually"
+        );
+        record_lock_event(
+            &mut tx,
+            "released",
+            previous_holder.as_deref(),
+            previous_locked_at,
+            &claims.sub,
+        )
+        .await
+        .map_err(db_err)?;
+    }
+    tx.commit().await.map_err(db_err)?;
+
+    Ok(Json(LockReleaseResponse {
+        released,
+        previous_holder,
+        previous_locked_at,
     }))
 }
 
@@ -626,3 +1214,191 @@ pub async fn check_migrations_on_startup(pool: &sqlx::PgPool) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(version: i32) -> SchemaVersion {
+        SchemaVersion {
+            id: 1,
+            version,
+            description: "add widgets".to_string(),
+            filename: format!("{:03}_widgets.sql", version),
+            checksum: String::new(),
+            applied_at: Utc::now(),
+            applied_by: "postgres".to_string(),
+            execution_time_ms: None,
+            rolled_back_at: None,
+            rollback_by: None,
+        }
+    }
+
+    #[test]
+    fn lock_older_than_ttl_is_stale() {
+        let now = Utc::now();
+        let ttl = chrono::Duration::minutes(15);
+
+        let free = lock_status(None, None, now, ttl);
+        assert!(!free.locked && !free.stale);
+        assert_eq!(free.lock_age_secs, None);
+
+        let held = lock_status(
+            Some("pid 1".to_string()),
+            Some(now - chrono::Duration::minutes(5)),
+            now,
+            ttl,
+        );
+        assert!(held.locked && !held.stale);
+        assert_eq!(held.lock_age_secs, Some(300));
+
+        let abandoned = lock_status(
+            Some("pid 1".to_string()),
+            Some(now - chrono::Duration::hours(2)),
+            now,
+            ttl,
+        );
+        assert!(abandoned.locked && abandoned.stale);
+        assert_eq!(abandoned.ttl_secs, 900);
+    }
+
+    #[test]
+    fn edited_migration_files_are_reported_as_drift() {
+        let dir = std::env::temp_dir().join(format!("migration-drift-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("001_widgets.sql"),
+            "CREATE TABLE widgets (id INT);",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("002_gadgets.sql"),
+            "CREATE TABLE gadgets (id BIGINT);",
+        )
+        .unwrap();
+
+        let mut untouched = migration(1);
+        untouched.filename = "001_widgets.sql".to_string();
+        untouched.checksum = compute_checksum("CREATE TABLE widgets (id INT);");
+        let mut edited = migration(2);
+        edited.filename = "../elsewhere/002_gadgets.sql".to_string();
+        edited.checksum = compute_checksum("CREATE TABLE gadgets (id INT);");
+        let mut deleted = migration(3);
+        deleted.filename = "003_gone.sql".to_string();
+
+        let (mismatches, unreadable) = find_checksum_drift(&[untouched, edited, deleted], &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].version, 2);
+        assert_eq!(
+            mismatches[0].expected_checksum,
+            compute_checksum("CREATE TABLE gadgets (id INT);")
+        );
+        assert_eq!(
+            mismatches[0].actual_checksum,
+            compute_checksum("CREATE TABLE gadgets (id BIGINT);")
+        );
+        assert_eq!(unreadable, vec!["003_gone.sql".to_string()]);
+    }
+
+    #[test]
+    fn unchecked_migrations_fail_validation() {
+        let dir = std::env::temp_dir();
+        assert!(validation_response(vec![], vec![], vec![], &dir).valid);
+
+        let unread = validation_response(vec![], vec![], vec!["003_gone.sql".into()], &dir);
+        assert!(!unread.valid);
+        assert_eq!(unread.warnings.len(), 1);
+
+        let absent = dir.join(format!("no-migrations-{}", uuid::Uuid::new_v4()));
+        let no_dir = validation_response(vec![], vec![], vec![], &absent);
+        assert!(!no_dir.valid);
+        assert!(no_dir.warnings[0].contains("MIGRATIONS_DIR"));
+    }
+
+    fn script(version: i32, down_sql: &str) -> SchemaRollbackScript {
+        SchemaRollbackScript {
+            id: 1,
+            version,
+            down_sql: down_sql.to_string(),
+            checksum: compute_checksum(down_sql),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn statements_split_on_top_level_semicolons_only() {
+        let sql = r#"
+            -- undo widgets; all of it
+            DROP INDEX IF EXISTS idx_widgets_name;
+            UPDATE settings SET note = 'a;b' WHERE key = 'it''s';
+            CREATE FUNCTION noop() RETURNS void AS $body$ BEGIN; END; $body$ LANGUAGE plpgsql;
+            /* trailing; comment */ DROP TABLE widgets
+        "#;
+
+        let statements = split_sql_statements(sql).unwrap();
+
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0], "DROP INDEX IF EXISTS idx_widgets_name");
+        assert!(statements[1].ends_with("'it''s'"));
+        assert!(statements[2].contains("$body$ BEGIN; END; $body$"));
+        assert_eq!(statements[3], "DROP TABLE widgets");
+    }
+
+    #[test]
+    fn unterminated_sql_is_rejected() {
+        assert!(split_sql_statements("DELETE FROM t WHERE a = 'x;").is_err());
+        assert!(split_sql_statements("DROP TABLE t; /* never closed").is_err());
+        assert!(split_sql_statements("SELECT $$ open").is_err());
+    }
+
+    #[test]
+    fn statements_are_described_with_target_and_destructiveness() {
+        let drop = plan_statement("DROP TABLE IF EXISTS widgets CASCADE");
+        assert_eq!(drop.action, "DROP TABLE");
+        assert_eq!(drop.target.as_deref(), Some("widgets"));
+        assert!(drop.destructive);
+
+        let alter = plan_statement("ALTER TABLE contracts DROP COLUMN widget_id");
+        assert_eq!(alter.action, "ALTER TABLE");
+        assert_eq!(alter.target.as_deref(), Some("contracts"));
+        assert!(alter.destructive);
+
+        let index = plan_statement("CREATE UNIQUE INDEX idx_a ON t (a)");
+        assert_eq!(index.action, "CREATE UNIQUE INDEX");
+        assert_eq!(index.target.as_deref(), Some("idx_a"));
+        assert!(!index.destructive);
+    }
+
+    #[test]
+    fn plan_reports_blockers_without_failing() {
+        let mut tampered = script(7, "DROP TABLE widgets;");
+        tampered.checksum = "0".repeat(64);
+
+        let plan = build_rollback_plan(migration(7), Some(&tampered), vec![9, 8], true);
+
+        assert!(plan.dry_run);
+        assert!(!plan.safe);
+        assert_eq!(plan.blockers.len(), 2);
+        assert_eq!(plan.statements.len(), 1);
+        assert_eq!(plan.later_applied_versions, vec![9, 8]);
+        assert_eq!(plan.warnings.len(), 2);
+    }
+
+    #[test]
+    fn clean_rollback_is_safe() {
+        let down = script(
+            7,
+            "DROP INDEX idx_widgets; ALTER TABLE widgets ADD COLUMN legacy TEXT;",
+        );
+        let plan = build_rollback_plan(migration(7), Some(&down), vec![], false);
+
+        assert!(plan.safe, "{:?}", plan.blockers);
+        assert_eq!(plan.statements.len(), 2);
+        assert!(!plan.statements[1].destructive);
+
+        let missing = build_rollback_plan(migration(7), None, vec![], false);
+        assert!(!missing.safe);
+    }
+}
@@ -1,7 +1,46 @@
 // Update Monitor - Checks for dependency updates
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
 use sqlx::PgPool;
-use semver::Version;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use uuid::Uuid;
 
+use crate::background_jobs::JobScheduler;
+use crate::dependency::{check_dependency_updates, DependencyUpdate, UpdateType};
+use crate::notifier::{format_notification_message, send_email, send_webhook};
+
+const MONITOR_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+type MonitorError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Register the dependency update check with the background scheduler.
+pub fn spawn_update_monitor_task(scheduler: &JobScheduler, pool: PgPool) {
+    let config = MonitorConfig::from_env();
+    scheduler.spawn("dependency_update_monitor", MONITOR_INTERVAL, move || {
+        let pool = pool.clone();
+        async move {
+            check_for_updates(&pool, &config)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+            Ok(())
+        }
+    });
+}
+
+/// A publisher's row in `notification_settings`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PublisherSettings {
+    pub publisher_address: String,
+    pub email: Option<String>,
+    pub webhook_url: Option<String>,
+    pub frequency: String,
+    pub filter_level: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct UpdateInfo {
     pub contract_name: String,
     pub current_version: String,
@@ -10,103 +49,178 @@ pub struct UpdateInfo {
     pub is_security: bool,
 }
 
-pub enum UpdateType {
-    Patch,
-    Minor,
-    Major,
+impl From<DependencyUpdate> for UpdateInfo {
+    fn from(update: DependencyUpdate) -> Self {
+        Self {
+            contract_name: update.name,
+            current_version: update.current_version,
+            latest_version: update.latest_version,
+            update_type: update.update_type,
+            is_security: update.is_security,
+        }
+    }
 }
 
-pub async fn check_for_updates(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+/// Bounds for a single `check_for_updates` run.
+#[derive(Debug, Clone, Copy)]
+pub struct MonitorConfig {
+    /// Publishers processed at the same time
+    pub max_concurrency: usize,
+    /// Time one publisher may take (queries plus notification delivery)
+    pub publisher_timeout: Duration,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 8,
+            publisher_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl MonitorConfig {
+    /// Reads `MONITOR_MAX_CONCURRENCY` and `MONITOR_PUBLISHER_TIMEOUT_SECS`,
+    /// keeping the defaults for unset or invalid values.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(limit) = std::env::var("MONITOR_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| *v > 0)
+        {
+            config.max_concurrency = limit;
+        }
+
+        if let Some(secs) = std::env::var("MONITOR_PUBLISHER_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+        {
+            config.publisher_timeout = Duration::from_secs(secs);
+        }
+
+        config
+    }
+}
+
+/// Per-run tally of how each publisher was handled
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MonitorSummary {
+    pub notified: usize,
+    pub up_to_date: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+}
+
+pub async fn check_for_updates(
+    pool: &PgPool,
+    config: &MonitorConfig,
+) -> Result<MonitorSummary, MonitorError> {
     // 1. Get all publishers with notifications enabled
-    let publishers = sqlx::query!(
-        "SELECT DISTINCT publisher_address, email, webhook_url, frequency, filter_level
+    let publishers: Vec<PublisherSettings> = sqlx::query_as(
+        "SELECT publisher_address, email, webhook_url, frequency, filter_level
          FROM notification_settings
-         WHERE enabled = true"
+         WHERE enabled = true",
     )
     .fetch_all(pool)
     .await?;
 
-    for publisher in publishers {
-        // 2. Get all contracts by this publisher
-        let contracts = sqlx::query!(
-            "SELECT name, version, dependencies
-             FROM contracts
-             WHERE publisher_address = $1
-             ORDER BY name, published_at DESC",
-            publisher.publisher_address
-        )
-        .fetch_all(pool)
-        .await?;
+    // 2. Process publishers concurrently; one failing or hanging publisher
+    //    does not hold up or abort the others
+    let summary = process_bounded(publishers, config, |publisher| async move {
+        let address = publisher.publisher_address.clone();
+        process_publisher(pool, publisher)
+            .await
+            .map_err(|e| format!("publisher {}: {}", address, e))
+    })
+    .await;
 
-        let mut updates = Vec::new();
+    tracing::info!(
+        notified = summary.notified,
+        up_to_date = summary.up_to_date,
+        failed = summary.failed,
+        timed_out = summary.timed_out,
+        "dependency update check finished"
+    );
 
-        for contract in contracts {
-            // 3. Parse dependencies
-            let deps: Vec<Dependency> = serde_json::from_value(contract.dependencies)?;
+    Ok(summary)
+}
 
-            for dep in deps {
-                // 4. Check if dependency has newer version
-                if let Some(update) = check_dependency_update(pool, &dep).await? {
-                    // 5. Filter by update level
-                    if should_notify(&update, &publisher.filter_level) {
-                        updates.push(update);
-                    }
-                }
-            }
-        }
+/// Runs `process` over `items` with at most `config.max_concurrency` in flight,
+/// each bounded by `config.publisher_timeout`. `process` returns the number of
+/// updates it notified about.
+async fn process_bounded<T, F, Fut>(
+    items: Vec<T>,
+    config: &MonitorConfig,
+    process: F,
+) -> MonitorSummary
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<usize, String>>,
+{
+    let timeout = config.publisher_timeout;
+    let outcomes: Vec<_> = stream::iter(items)
+        .map(|item| tokio::time::timeout(timeout, process(item)))
+        .buffer_unordered(config.max_concurrency.max(1))
+        .collect()
+        .await;
 
-        // 6. Send notification if updates found
-        if !updates.empty() {
-            send_notification(&publisher, updates).await?;
+    let mut summary = MonitorSummary::default();
+    for outcome in outcomes {
+        match outcome {
+            Ok(Ok(0)) => summary.up_to_date += 1,
+            Ok(Ok(_)) => summary.notified += 1,
+            Ok(Err(e)) => {
+                tracing::warn!(error = %e, "dependency update check failed");
+                summary.failed += 1;
+            }
+            Err(_) => {
+                tracing::warn!(
+                    timeout_secs = timeout.as_secs(),
+                    "dependency update check timed out"
+                );
+                summary.timed_out += 1;
+            }
         }
     }
-
-    Ok(())
+    summary
 }
 
-async fn check_dependency_update(
+async fn process_publisher(
     pool: &PgPool,
-    dep: &Dependency,
-) -> Result<Option<UpdateInfo>, Box<dyn std::error::Error>> {
-    // Get latest version of dependency
-    let latest = sqlx::query!(
-        "SELECT version, is_security_update
-         FROM contracts
-         WHERE name = $1
-         ORDER BY published_at DESC
-         LIMIT 1",
-        dep.name
+    publisher: PublisherSettings,
+) -> Result<usize, MonitorError> {
+    // Get all contracts by this publisher
+    let contract_ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT c.id
+         FROM contracts c
+         JOIN publishers p ON p.id = c.publisher_id
+         WHERE p.stellar_address = $1
+         ORDER BY c.name",
     )
-    .fetch_optional(pool)
+    .bind(&publisher.publisher_address)
+    .fetch_all(pool)
     .await?;
 
-    if let Some(latest_version) = latest {
-        let current = Version::parse(&dep.version_requirement)?;
-        let latest = Version::parse(&latest_version.version)?;
-
-        if latest > current {
-            let update_type = determine_update_type(&current, &latest);
-            return Ok(Some(UpdateInfo {
-                contract_name: dep.name.clone(),
-                current_version: current.to_string(),
-                latest_version: latest.to_string(),
-                update_type,
-                is_security: latest_version.is_security_update.unwrap_or(false),
-            }));
+    let mut updates = Vec::new();
+
+    for contract_id in contract_ids {
+        // Check each dependency for a newer version, filtered by update level
+        for update in check_dependency_updates(pool, contract_id).await? {
+            let update = UpdateInfo::from(update);
+            if should_notify(&update, &publisher.filter_level) {
+                updates.push(update);
+            }
         }
     }
 
-    Ok(None)
-}
-
-fn determine_update_type(current: &Version, latest: &Version) -> UpdateType {
-    if current.major != latest.major {
-        UpdateType::Major
-    } else if current.minor != latest.minor {
-        UpdateType::Minor
-    } else {
-        UpdateType::Patch
+    // Send notification if updates found
+    if updates.is_empty() {
+        return Ok(0);
     }
+    send_notification(pool, &publisher, updates).await
 }
 
 fn should_notify(update: &UpdateInfo, filter: &str) -> bool {
@@ -119,16 +233,114 @@ fn should_notify(update: &UpdateInfo, filter: &str) -> bool {
     }
 }
 
+/// What a publisher has already been told, loaded from `notified_updates`
+#[derive(Debug, Default, Clone)]
+pub struct NotificationLedger {
+    /// (dependency name, version) -> whether it was sent as a security update
+    sent: HashMap<(String, String), bool>,
+    last_notified_at: Option<DateTime<Utc>>,
+}
+
+impl NotificationLedger {
+    async fn load(pool: &PgPool, publisher_address: &str) -> Result<Self, sqlx::Error> {
+        let rows: Vec<(String, String, bool, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT dependency_name, version, is_security, notified_at
+             FROM notified_updates
+             WHERE publisher_address = $1",
+        )
+        .bind(publisher_address)
+        .fetch_all(pool)
+        .await?;
+
+        let mut ledger = Self::default();
+        for (name, version, is_security, notified_at) in rows {
+            ledger.sent.insert((name, version), is_security);
+            ledger.last_notified_at = ledger.last_notified_at.max(Some(notified_at));
+        }
+        Ok(ledger)
+    }
+
+    /// Updates worth sending now: ones never notified, or notified before they
+    /// became security updates. Nothing is due while the publisher's
+    /// `frequency` window since the last notification is still open.
+    pub fn pending(
+        &self,
+        updates: Vec<UpdateInfo>,
+        frequency: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<UpdateInfo> {
+        if let (Some(last), Some(interval)) = (self.last_notified_at, frequency_interval(frequency))
+        {
+            if now - last < interval {
+                return Vec::new();
+            }
+        }
+        updates
+            .into_iter()
+            .filter(|update| {
+                match self
+                    .sent
+                    .get(&(update.contract_name.clone(), update.latest_version.clone()))
+                {
+                    None => true,
+                    Some(&was_security) => update.is_security && !was_security,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Minimum time between notifications for a `frequency` setting; `None`
+/// notifies on every run
+fn frequency_interval(frequency: &str) -> Option<ChronoDuration> {
+    match frequency.to_ascii_lowercase().as_str() {
+        "daily" => Some(ChronoDuration::days(1)),
+        "weekly" => Some(ChronoDuration::weeks(1)),
+        _ => None,
+    }
+}
+
+async fn record_notified(
+    pool: &PgPool,
+    publisher_address: &str,
+    updates: &[UpdateInfo],
+) -> Result<(), sqlx::Error> {
+    for update in updates {
+        sqlx::query(
+            "INSERT INTO notified_updates (publisher_address, dependency_name, version, is_security)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (publisher_address, dependency_name, version) DO UPDATE
+             SET is_security = EXCLUDED.is_security, notified_at = NOW()",
+        )
+        .bind(publisher_address)
+        .bind(&update.contract_name)
+        .bind(&update.latest_version)
+        .bind(update.is_security)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Notify the publisher about the updates they haven't been told about yet,
+/// returning how many were sent.
 async fn send_notification(
+    pool: &PgPool,
     publisher: &PublisherSettings,
     updates: Vec<UpdateInfo>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<usize, MonitorError> {
+    let ledger = NotificationLedger::load(pool, &publisher.publisher_address).await?;
+    let updates = ledger.pending(updates, &publisher.frequency, Utc::now());
+    if updates.is_empty() {
+        return Ok(0);
+    }
+
     // Format notification message
     let message = format_notification_message(&updates);
 
     // Send email
-    if !publisher.email.is_empty() {
-        send_email(&publisher.email, &message).await?;
+    if let Some(email) = publisher.email.as_deref().filter(|e| !e.is_empty()) {
+        send_email(email, &message).await?;
     }
 
     // Send webhook
@@ -136,5 +348,186 @@ async fn send_notification(
         send_webhook(webhook_url, &updates).await?;
     }
 
-    Ok(())
-}
\ No newline at end of file
+    record_notified(pool, &publisher.publisher_address, &updates).await?;
+    Ok(updates.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn hanging_publisher_does_not_block_others() {
+        let config = MonitorConfig {
+            max_concurrency: 2,
+            publisher_timeout: Duration::from_millis(100),
+        };
+        let started = Instant::now();
+
+        let summary = process_bounded(
+            vec!["hangs", "a", "b", "c", "fails"],
+            &config,
+            |name| async move {
+                match name {
+                    "hangs" => std::future::pending().await,
+                    "fails" => Err("webhook returned 500".to_string()),
+                    "c" => Ok(0),
+                    _ => Ok(1),
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(
+            summary,
+            MonitorSummary {
+                notified: 2,
+                up_to_date: 1,
+                failed: 1,
+                timed_out: 1,
+            }
+        );
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    fn update(name: &str, version: &str, is_security: bool) -> UpdateInfo {
+        UpdateInfo {
+            contract_name: name.to_string(),
+            current_version: "1.0.0".to_string(),
+            latest_version: version.to_string(),
+            update_type: UpdateType::Minor,
+            is_security,
+        }
+    }
+
+    /// Settings for a new publisher with no email or webhook configured, so
+    /// notifications are only recorded
+    async fn seed_settings(pool: &PgPool, frequency: &str) -> PublisherSettings {
+        let settings = PublisherSettings {
+            publisher_address: format!("G{}", &Uuid::new_v4().simple().to_string()[..20]),
+            email: None,
+            webhook_url: None,
+            frequency: frequency.to_string(),
+            filter_level: "All".to_string(),
+        };
+        sqlx::query(
+            "INSERT INTO notification_settings (publisher_address, frequency, filter_level)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(&settings.publisher_address)
+        .bind(&settings.frequency)
+        .bind(&settings.filter_level)
+        .execute(pool)
+        .await
+        .expect("seed notification settings");
+        settings
+    }
+
+    async fn notified(pool: &PgPool, publisher: &str) -> Vec<(String, DateTime<Utc>)> {
+        sqlx::query_as(
+            "SELECT version, notified_at FROM notified_updates
+             WHERE publisher_address = $1 ORDER BY version",
+        )
+        .bind(publisher)
+        .fetch_all(pool)
+        .await
+        .expect("load notified updates")
+    }
+
+    #[tokio::test]
+    async fn consecutive_runs_notify_an_update_once() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let contract_id = crate::test_support::seed_contract(&pool, "aa").await;
+        let dependency_id = crate::test_support::seed_contract(&pool, "bb").await;
+        for version in ["1.0.0", "1.1.0"] {
+            sqlx::query(
+                "INSERT INTO contract_versions (contract_id, version, wasm_hash)
+                 VALUES ($1, $2, 'bb')",
+            )
+            .bind(dependency_id)
+            .bind(version)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO contract_dependencies
+                 (contract_id, dependency_name, dependency_contract_id, version_constraint)
+             VALUES ($1, 'token', $2, '=1.0.0')",
+        )
+        .bind(contract_id)
+        .bind(dependency_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let address: String = sqlx::query_scalar(
+            "SELECT p.stellar_address FROM contracts c
+             JOIN publishers p ON p.id = c.publisher_id WHERE c.id = $1",
+        )
+        .bind(contract_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO notification_settings (publisher_address) VALUES ($1)")
+            .bind(&address)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let config = MonitorConfig::default();
+        check_for_updates(&pool, &config).await.unwrap();
+        let first = notified(&pool, &address).await;
+        check_for_updates(&pool, &config).await.unwrap();
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].0, "1.1.0");
+        // Not re-sent: the second run left the record untouched
+        assert_eq!(notified(&pool, &address).await, first);
+    }
+
+    #[tokio::test]
+    async fn escalation_to_security_is_notified_again() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let publisher = seed_settings(&pool, "immediate").await;
+        let send = |updates| send_notification(&pool, &publisher, updates);
+
+        assert_eq!(
+            send(vec![update("token", "1.1.0", false)]).await.unwrap(),
+            1
+        );
+        let escalated = vec![update("token", "1.1.0", true)];
+        assert_eq!(send(escalated.clone()).await.unwrap(), 1);
+        assert_eq!(send(escalated).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn weekly_publishers_are_throttled() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let publisher = seed_settings(&pool, "weekly").await;
+        let send = |updates| send_notification(&pool, &publisher, updates);
+
+        assert_eq!(
+            send(vec![update("token", "1.1.0", false)]).await.unwrap(),
+            1
+        );
+        let newer = vec![update("token", "1.2.0", false)];
+        assert_eq!(send(newer.clone()).await.unwrap(), 0);
+
+        sqlx::query(
+            "UPDATE notified_updates SET notified_at = NOW() - INTERVAL '8 days'
+             WHERE publisher_address = $1",
+        )
+        .bind(&publisher.publisher_address)
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(send(newer).await.unwrap(), 1);
+    }
+}
@@ -1,39 +1,174 @@
 // Notification Service
+//
+// Emails go through the backend chosen by `EMAIL_BACKEND`:
+//
+//  EMAIL_BACKEND      Needs
+//  ─────────────────  ──────────────────────────────────────────────────────
+//  sendgrid (default) SENDGRID_API_KEY
+//  smtp               SMTP_HOST; SMTP_PORT (default 587), SMTP_USER/SMTP_PASS
+//                     when the relay requires authentication
+//
+// `EMAIL_FROM` overrides the sender address for either backend.
+use lettre::{
+    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
 use reqwest::Client;
 use serde_json::json;
 
+use crate::monitor::UpdateInfo;
+
+const DEFAULT_FROM: &str = "notifications@soroban-registry.com";
+const SUBJECT: &str = "Contract Dependency Updates Available";
+const DEFAULT_SMTP_PORT: u16 = 587;
+/// Port on which relays expect TLS from the first byte rather than STARTTLS
+const IMPLICIT_TLS_PORT: u16 = 465;
+
+/// The selected backend is missing or has invalid settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailConfigError(String);
+
+impl std::fmt::Display for EmailConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "email backend misconfigured: {}", self.0)
+    }
+}
+
+impl std::error::Error for EmailConfigError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    /// Username and password, when the relay requires authentication
+    pub credentials: Option<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailBackend {
+    SendGrid { api_key: String },
+    Smtp(SmtpConfig),
+}
+
+impl EmailBackend {
+    pub fn from_env() -> Result<Self, EmailConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self, EmailConfigError> {
+        let set = |name: &str| {
+            var(name)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let require = |name: &str, backend: &str| {
+            set(name).ok_or_else(|| {
+                EmailConfigError(format!(
+                    "{} must be set when EMAIL_BACKEND={}",
+                    name, backend
+                ))
+            })
+        };
+
+        match set("EMAIL_BACKEND")
+            .map(|b| b.to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("sendgrid") => Ok(EmailBackend::SendGrid {
+                api_key: require("SENDGRID_API_KEY", "sendgrid")?,
+            }),
+            Some("smtp") => {
+                let port = match set("SMTP_PORT") {
+                    Some(raw) => raw.parse::<u16>().map_err(|_| {
+                        EmailConfigError(format!("SMTP_PORT must be a port number, got '{}'", raw))
+                    })?,
+                    None => DEFAULT_SMTP_PORT,
+                };
+                let credentials = match (set("SMTP_USER"), set("SMTP_PASS")) {
+                    (Some(user), Some(pass)) => Some((user, pass)),
+                    (None, None) => None,
+                    _ => {
+                        return Err(EmailConfigError(
+                            "SMTP_USER and SMTP_PASS must be set together".to_string(),
+                        ))
+                    }
+                };
+                Ok(EmailBackend::Smtp(SmtpConfig {
+                    host: require("SMTP_HOST", "smtp")?,
+                    port,
+                    credentials,
+                }))
+            }
+            Some(other) => Err(EmailConfigError(format!(
+                "unknown EMAIL_BACKEND '{}' (expected sendgrid or smtp)",
+                other
+            ))),
+        }
+    }
+
+    async fn send(
+        &self,
+        from: &str,
+        to: &str,
+        html: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            EmailBackend::SendGrid { api_key } => {
+                Client::new()
+                    .post("https://api.sendgrid.com/v3/mail/send")
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .json(&json!({
+                        "personalizations": [{
+                            "to": [{"email": to}],
+                            "subject": SUBJECT
+                        }],
+                        "from": {"email": from},
+                        "content": [{
+                            "type": "text/html",
+                            "value": html
+                        }]
+                    }))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            EmailBackend::Smtp(config) => {
+                let email = Message::builder()
+                    .from(from.parse()?)
+                    .to(to.parse()?)
+                    .subject(SUBJECT)
+                    .header(ContentType::TEXT_HTML)
+                    .body(html.to_string())?;
+
+                let builder = if config.port == IMPLICIT_TLS_PORT {
+                    AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?
+                } else {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+                };
+                let mut builder = builder.port(config.port);
+                if let Some((user, pass)) = &config.credentials {
+                    builder = builder.credentials(Credentials::new(user.clone(), pass.clone()));
+                }
+                builder.build().send(email).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
 pub async fn send_email(
     to: &str,
     message: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Using SendGrid
-    let client = Client::new();
-    let sendgrid_key = std::env::var("SENDGRID_API_KEY")?;
-
-    client
-        .post("https://api.sendgrid.com/v3/mail/send")
-        .header("Authorization", format!("Bearer {}", sendgrid_key))
-        .json(&json!({
-            "personalizations": [{
-                "to": [{"email": to}],
-                "subject": "Contract Dependency Updates Available"
-            }],
-            "from": {"email": "notifications@soroban-registry.com"},
-            "content": [{
-                "type": "text/html",
-                "value": message
-            }]
-        }))
-        .send()
-        .await?;
-
-    Ok(())
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let backend = EmailBackend::from_env()?;
+    let from = std::env::var("EMAIL_FROM").unwrap_or_else(|_| DEFAULT_FROM.to_string());
+    backend.send(&from, to, message).await
 }
 
 pub async fn send_webhook(
     url: &str,
     updates: &[UpdateInfo],
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let client = Client::new();
 
     client
@@ -48,7 +183,7 @@ pub async fn send_webhook(
     Ok(())
 }
 
-fn format_notification_message(updates: &[UpdateInfo]) -> String {
+pub fn format_notification_message(updates: &[UpdateInfo]) -> String {
     let mut html = String::from("<h1>Contract Dependency Updates</h1>");
 
     for update in updates {
@@ -73,4 +208,58 @@ fn format_notification_message(updates: &[UpdateInfo]) -> String {
     }
 
     html
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn backend(vars: &[(&str, &str)]) -> Result<EmailBackend, EmailConfigError> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        EmailBackend::from_lookup(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn sendgrid_is_the_default_backend() {
+        assert_eq!(
+            backend(&[("SENDGRID_API_KEY", "key")]),
+            Ok(EmailBackend::SendGrid {
+                api_key: "key".to_string()
+            })
+        );
+        assert!(backend(&[])
+            .unwrap_err()
+            .to_string()
+            .contains("SENDGRID_API_KEY"));
+    }
+
+    #[test]
+    fn smtp_backend_reads_its_relay_settings() {
+        assert_eq!(
+            backend(&[
+                ("EMAIL_BACKEND", "smtp"),
+                ("SMTP_HOST", "mail.internal"),
+                ("SMTP_USER", "registry"),
+                ("SMTP_PASS", "secret"),
+            ]),
+            Ok(EmailBackend::Smtp(SmtpConfig {
+                host: "mail.internal".to_string(),
+                port: DEFAULT_SMTP_PORT,
+                credentials: Some(("registry".to_string(), "secret".to_string())),
+            }))
+        );
+
+        assert!(backend(&[("EMAIL_BACKEND", "smtp")])
+            .unwrap_err()
+            .to_string()
+            .contains("SMTP_HOST"));
+        assert!(backend(&[
+            ("EMAIL_BACKEND", "smtp"),
+            ("SMTP_HOST", "mail.internal"),
+            ("SMTP_PORT", "smtp"),
+        ])
+        .is_err());
+        assert!(backend(&[("EMAIL_BACKEND", "carrier-pigeon")]).is_err());
+    }
+}
@@ -1,18 +1,27 @@
 use axum::{
-    extract::{Json, Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{rejection::JsonRejection, Json, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde_json::{json, Value};
 use shared::models::{
-    CreateAlertConfigRequest, PerformanceAlert, PerformanceAlertConfig, PerformanceAnomaly,
-    PerformanceMetric, PerformanceTrend, RecordPerformanceMetricRequest,
+    CreateAlertConfigRequest, EvaluateAlertConfigsRequest, MetricType, PerformanceAlert,
+    PerformanceAlertConfig, PerformanceAnomaly, PerformanceMetric, PerformanceTrend,
+    RecordMetricSamplesRequest, RecordPerformanceMetricRequest, UpdateAlertConfigRequest,
 };
+use shared::pagination::{next_cursor, push_page, Cursor};
+use sqlx::{PgConnection, Postgres, QueryBuilder};
+use tracing::Instrument;
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
+    handlers::map_json_rejection,
+    idempotency::{self, Begun},
     state::AppState,
+    telemetry::db_span,
 };
 
 // ───────────────────── Query params ─────────────────────
@@ -23,6 +32,8 @@ pub struct ListMetricsQuery {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Opaque keyset cursor from a previous page's `next_cursor`; takes precedence over `offset`
+    pub cursor: Option<String>,
     pub metric_type: Option<String>,
     pub function_name: Option<String>,
 }
@@ -33,45 +44,181 @@ pub struct ListAlertsQuery {
     pub limit: i64,
     #[serde(default)]
     pub offset: i64,
+    /// Opaque keyset cursor from a previous page's `next_cursor`; takes precedence over `offset`
+    pub cursor: Option<String>,
     pub resolved: Option<bool>,
     pub severity: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct AggregateMetricsQuery {
+    /// Bucket width: `1m`, `5m`, `1h` or `1d`
+    pub interval: String,
+    pub metric_type: Option<String>,
+    /// Start of the range; defaults to 24 hours before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// End of the range; defaults to now
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct RegressionQuery {
+    pub metric_type: String,
+    pub baseline_version: String,
+    pub candidate_version: String,
+}
+
 fn default_limit() -> i64 {
     20
 }
 
+/// Most buckets an aggregate query may produce
+const MAX_AGGREGATE_BUCKETS: i64 = 2_000;
+
+/// Longest version label a metric may be tagged with
+const MAX_VERSION_LEN: usize = 50;
+
+/// Most recent samples per version compared by a regression check
+const MAX_REGRESSION_SAMPLES: i64 = 1_000;
+/// Fewest samples per version needed to estimate spread
+const MIN_REGRESSION_SAMPLES: usize = 2;
+/// Cohen's d at or above which a slower candidate counts as a regression (a
+/// "medium" effect)
+const REGRESSION_EFFECT_SIZE: f64 = 0.5;
+
+/// Most raw samples accepted by one `perf/metrics/samples` request
+const MAX_METRIC_SAMPLES: usize = 10_000;
+
+/// Threshold type whose `threshold_value` is a percentage change over `window_minutes`
+const RATE_OF_CHANGE: &str = "rate_of_change";
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SampleStats {
+    pub samples: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RegressionReport {
+    pub metric_type: String,
+    pub baseline_version: String,
+    pub candidate_version: String,
+    pub baseline: SampleStats,
+    pub candidate: SampleStats,
+    /// Change of the candidate mean relative to the baseline, in percent
+    pub mean_change_pct: Option<f64>,
+    /// Cohen's d of candidate over baseline; positive means the candidate is
+    /// higher (worse). `None` when neither version varies.
+    pub effect_size: Option<f64>,
+    pub regressed: bool,
+}
+
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+pub struct MetricBucket {
+    pub metric_type: MetricType,
+    pub bucket_start: DateTime<Utc>,
+    pub count: i64,
+    pub min: Decimal,
+    pub max: Decimal,
+    pub avg: Decimal,
+}
+
 // ───────────────────── Handlers ─────────────────────
 
-/// POST /api/contracts/:id/perf/metrics — record a performance metric
+/// POST /api/contracts/:id/perf/metrics — record a performance metric.
+/// Retries carrying the same `Idempotency-Key` replay the first response.
 pub async fn record_metric(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
-    Json(req): Json<RecordPerformanceMetricRequest>,
-) -> ApiResult<impl IntoResponse> {
+    headers: HeaderMap,
+    payload: Result<Json<RecordPerformanceMetricRequest>, JsonRejection>,
+) -> ApiResult<Response> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let key = idempotency::key_from_headers(&headers)?;
+    let scope = format!("performance_metric:{}", contract_uuid);
+    let mut write = match idempotency::begin(&state.db, &scope, key).await? {
+        Begun::Replay(response) => return Ok(response),
+        Begun::Write(write) => write,
+    };
+    let metric = insert_metric(write.conn(), contract_uuid, req).await?;
+    write.commit(StatusCode::CREATED, &metric).await
+}
+
+async fn insert_metric(
+    conn: &mut PgConnection,
+    contract_uuid: Uuid,
+    req: RecordPerformanceMetricRequest,
+) -> ApiResult<PerformanceMetric> {
+    let value = to_decimal(req.value, "value")?;
+    let p50 = req.p50.map(|v| to_decimal(v, "p50")).transpose()?;
+    let p95 = req.p95.map(|v| to_decimal(v, "p95")).transpose()?;
+    let p99 = req.p99.map(|v| to_decimal(v, "p99")).transpose()?;
+    let version = req.version.as_deref();
+    if version.is_some_and(|v| v.len() > MAX_VERSION_LEN) {
+        return Err(ApiError::bad_request(
+            "InvalidVersion",
+            format!("version must be at most {} characters", MAX_VERSION_LEN),
+        ));
+    }
 
     let metric: PerformanceMetric = sqlx::query_as(
         r#"
         INSERT INTO performance_metrics
-            (contract_id, metric_type, function_name, value, p50, p95, p99, metadata)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            (contract_id, metric_type, function_name, value, p50, p95, p99, metadata, version)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         RETURNING *
         "#,
     )
     .bind(contract_uuid)
     .bind(&req.metric_type)
     .bind(req.function_name.as_deref())
-    .bind(rust_decimal::Decimal::try_from(req.value).unwrap_or_default())
-    .bind(req.p50.map(|v| rust_decimal::Decimal::try_from(v).unwrap_or_default()))
-    .bind(req.p95.map(|v| rust_decimal::Decimal::try_from(v).unwrap_or_default()))
-    .bind(req.p99.map(|v| rust_decimal::Decimal::try_from(v).unwrap_or_default()))
+    .bind(value)
+    .bind(p50)
+    .bind(p95)
+    .bind(p99)
     .bind(&req.metadata)
-    .fetch_one(&state.db)
+    .bind(version)
+    .fetch_one(conn)
     .await
     .map_err(|e| db_err("record performance metric", e))?;
 
-    Ok((StatusCode::CREATED, Json(metric)))
+    Ok(metric)
+}
+
+/// POST /api/contracts/:id/perf/metrics/samples — record a batch of raw
+/// samples as one metric whose value is their mean and whose p50/p95/p99 are
+/// computed here. Retries carrying the same `Idempotency-Key` replay the first
+/// response.
+pub async fn record_metric_samples(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    headers: HeaderMap,
+    payload: Result<Json<RecordMetricSamplesRequest>, JsonRejection>,
+) -> ApiResult<Response> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let key = idempotency::key_from_headers(&headers)?;
+    let summary = summarize_samples(&req.samples)?;
+    let metric = RecordPerformanceMetricRequest {
+        contract_id: contract_uuid.to_string(),
+        metric_type: req.metric_type,
+        function_name: req.function_name,
+        value: summary.mean,
+        p50: Some(summary.p50),
+        p95: Some(summary.p95),
+        p99: Some(summary.p99),
+        metadata: req.metadata,
+        version: req.version,
+    };
+    let scope = format!("performance_metric_samples:{}", contract_uuid);
+    let mut write = match idempotency::begin(&state.db, &scope, key).await? {
+        Begun::Replay(response) => return Ok(response),
+        Begun::Write(write) => write,
+    };
+    let metric = insert_metric(write.conn(), contract_uuid, metric).await?;
+    write.commit(StatusCode::CREATED, &metric).await
 }
 
 /// GET /api/contracts/:id/perf/metrics — list performance metrics for a contract
@@ -82,45 +229,33 @@ pub async fn list_metrics(
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.max(0)
+    };
 
-    // Build dynamic query filters
-    let mut query = String::from(
-        "SELECT * FROM performance_metrics WHERE contract_id = $1",
-    );
-    let mut count_query = String::from(
-        "SELECT COUNT(*) FROM performance_metrics WHERE contract_id = $1",
-    );
-
-    if let Some(ref mt) = params.metric_type {
-        let clause = format!(" AND metric_type::text = '{}'", mt.replace('\'', "''"));
-        query.push_str(&clause);
-        count_query.push_str(&clause);
-    }
-    if let Some(ref func) = params.function_name {
-        let clause = format!(" AND function_name = '{}'", func.replace('\'', "''"));
-        query.push_str(&clause);
-        count_query.push_str(&clause);
-    }
-
-    query.push_str(&format!(
-        " ORDER BY timestamp DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
-
-    let metrics: Vec<PerformanceMetric> = sqlx::query_as(&query)
-        .bind(contract_uuid)
+    let mut query = metrics_query("SELECT * FROM performance_metrics", contract_uuid, &params);
+    push_page(&mut query, cursor.as_ref(), "timestamp", limit, offset);
+    let metrics: Vec<PerformanceMetric> = query
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_err("list performance metrics", e))?;
 
-    let total: i64 = sqlx::query_scalar(&count_query)
-        .bind(contract_uuid)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| db_err("count performance metrics", e))?;
+    let total: i64 = metrics_query(
+        "SELECT COUNT(*) FROM performance_metrics",
+        contract_uuid,
+        &params,
+    )
+    .build_query_scalar()
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("count performance metrics", e))?;
 
     Ok(Json(json!({
+        "next_cursor": next_cursor(&metrics, limit, |m| (m.timestamp, m.id)),
         "items": metrics,
         "total": total,
         "limit": limit,
@@ -128,6 +263,54 @@ pub async fn list_metrics(
     })))
 }
 
+/// GET /api/contracts/:id/perf/metrics/aggregate — per-bucket min/max/avg/count of
+/// metric values over a time range, one series per metric type
+pub async fn aggregate_metrics(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    Query(params): Query<AggregateMetricsQuery>,
+) -> ApiResult<Json<Value>> {
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let bucket_secs = parse_bucket_interval(&params.interval)?;
+    let to = params.to.unwrap_or_else(Utc::now);
+    let from = params.from.unwrap_or(to - chrono::Duration::hours(24));
+    check_bucket_count(from, to, bucket_secs)?;
+
+    let buckets: Vec<MetricBucket> = sqlx::query_as(
+        r#"
+        SELECT
+            metric_type,
+            to_timestamp(floor(extract(epoch FROM timestamp) / $2) * $2) AS bucket_start,
+            COUNT(*) AS count,
+            MIN(value) AS min,
+            MAX(value) AS max,
+            AVG(value) AS avg
+        FROM performance_metrics
+        WHERE contract_id = $1
+          AND timestamp >= $3 AND timestamp < $4
+          AND ($5::text IS NULL OR metric_type::text = $5)
+        GROUP BY metric_type, bucket_start
+        ORDER BY metric_type, bucket_start
+        "#,
+    )
+    .bind(contract_uuid)
+    .bind(bucket_secs as f64)
+    .bind(from)
+    .bind(to)
+    .bind(params.metric_type.as_deref())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("aggregate performance metrics", e))?;
+
+    Ok(Json(json!({
+        "interval": params.interval,
+        "metric_type": params.metric_type,
+        "from": from,
+        "to": to,
+        "buckets": buckets,
+    })))
+}
+
 /// GET /api/contracts/:id/perf/anomalies — list performance anomalies
 pub async fn list_anomalies(
     State(state): State<AppState>,
@@ -136,44 +319,37 @@ pub async fn list_anomalies(
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.max(0)
+    };
 
-    let mut query = String::from(
-        "SELECT * FROM performance_anomalies WHERE contract_id = $1",
-    );
-    let mut count_query = String::from(
-        "SELECT COUNT(*) FROM performance_anomalies WHERE contract_id = $1",
+    let mut query = alerts_query(
+        "SELECT * FROM performance_anomalies",
+        contract_uuid,
+        &params,
     );
-
-    if let Some(resolved) = params.resolved {
-        let clause = format!(" AND resolved = {}", resolved);
-        query.push_str(&clause);
-        count_query.push_str(&clause);
-    }
-    if let Some(ref severity) = params.severity {
-        let clause = format!(" AND severity::text = '{}'", severity.replace('\'', "''"));
-        query.push_str(&clause);
-        count_query.push_str(&clause);
-    }
-
-    query.push_str(&format!(
-        " ORDER BY detected_at DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
-
-    let anomalies: Vec<PerformanceAnomaly> = sqlx::query_as(&query)
-        .bind(contract_uuid)
+    push_page(&mut query, cursor.as_ref(), "detected_at", limit, offset);
+    let anomalies: Vec<PerformanceAnomaly> = query
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_err("list performance anomalies", e))?;
 
-    let total: i64 = sqlx::query_scalar(&count_query)
-        .bind(contract_uuid)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| db_err("count performance anomalies", e))?;
+    let total: i64 = alerts_query(
+        "SELECT COUNT(*) FROM performance_anomalies",
+        contract_uuid,
+        &params,
+    )
+    .build_query_scalar()
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("count performance anomalies", e))?;
 
     Ok(Json(json!({
+        "next_cursor": next_cursor(&anomalies, limit, |m| (m.detected_at, m.id)),
         "items": anomalies,
         "total": total,
         "limit": limit,
@@ -189,44 +365,33 @@ pub async fn list_alerts(
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.max(0)
+    };
 
-    let mut query = String::from(
-        "SELECT * FROM performance_alerts WHERE contract_id = $1",
-    );
-    let mut count_query = String::from(
-        "SELECT COUNT(*) FROM performance_alerts WHERE contract_id = $1",
-    );
-
-    if let Some(resolved) = params.resolved {
-        let clause = format!(" AND resolved = {}", resolved);
-        query.push_str(&clause);
-        count_query.push_str(&clause);
-    }
-    if let Some(ref severity) = params.severity {
-        let clause = format!(" AND severity::text = '{}'", severity.replace('\'', "''"));
-        query.push_str(&clause);
-        count_query.push_str(&clause);
-    }
-
-    query.push_str(&format!(
-        " ORDER BY triggered_at DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
-
-    let alerts: Vec<PerformanceAlert> = sqlx::query_as(&query)
-        .bind(contract_uuid)
+    let mut query = alerts_query("SELECT * FROM performance_alerts", contract_uuid, &params);
+    push_page(&mut query, cursor.as_ref(), "triggered_at", limit, offset);
+    let alerts: Vec<PerformanceAlert> = query
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_err("list performance alerts", e))?;
 
-    let total: i64 = sqlx::query_scalar(&count_query)
-        .bind(contract_uuid)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| db_err("count performance alerts", e))?;
+    let total: i64 = alerts_query(
+        "SELECT COUNT(*) FROM performance_alerts",
+        contract_uuid,
+        &params,
+    )
+    .build_query_scalar()
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("count performance alerts", e))?;
 
     Ok(Json(json!({
+        "next_cursor": next_cursor(&alerts, limit, |m| (m.triggered_at, m.id)),
         "items": alerts,
         "total": total,
         "limit": limit,
@@ -238,8 +403,9 @@ pub async fn list_alerts(
 pub async fn acknowledge_alert(
     State(state): State<AppState>,
     Path(alert_id): Path<String>,
-    Json(body): Json<Value>,
+    payload: Result<Json<Value>, JsonRejection>,
 ) -> ApiResult<Json<PerformanceAlert>> {
+    let Json(body) = payload.map_err(map_json_rejection)?;
     let alert_uuid = parse_uuid(&alert_id, "alert")?;
     let acknowledged_by = body
         .get("acknowledged_by")
@@ -301,22 +467,34 @@ pub async fn resolve_alert(
 }
 
 /// POST /api/contracts/:id/perf/alert-configs — configure an alert threshold
+///
+/// For `rate_of_change`, `threshold_value` is a percentage: the alert fires when a
+/// new metric differs by more than that percentage, up or down, from the latest
+/// metric of the same type recorded at least `window_minutes` earlier. It never
+/// fires when there is no such metric or its value is zero.
 pub async fn create_alert_config(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
-    Json(req): Json<CreateAlertConfigRequest>,
+    payload: Result<Json<CreateAlertConfigRequest>, JsonRejection>,
 ) -> ApiResult<impl IntoResponse> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let threshold_value = to_decimal(req.threshold_value, "threshold_value")?;
+    validate_alert_window(&req.threshold_type, req.window_minutes)?;
+    let webhook_url = validate_webhook_url(req.notify_webhook_url.as_deref())?;
 
     let config: PerformanceAlertConfig = sqlx::query_as(
         r#"
         INSERT INTO performance_alert_configs
-            (contract_id, metric_type, threshold_type, threshold_value, severity)
-        VALUES ($1, $2, $3, $4, COALESCE($5, 'warning'))
+            (contract_id, metric_type, threshold_type, threshold_value, window_minutes, severity,
+             notify_webhook_url)
+        VALUES ($1, $2, $3, $4, $5, COALESCE($6, 'warning'), $7)
         ON CONFLICT (contract_id, metric_type, threshold_type)
         DO UPDATE SET
             threshold_value = EXCLUDED.threshold_value,
+            window_minutes = EXCLUDED.window_minutes,
             severity = EXCLUDED.severity,
+            notify_webhook_url = EXCLUDED.notify_webhook_url,
             updated_at = NOW()
         RETURNING *
         "#,
@@ -324,8 +502,10 @@ pub async fn create_alert_config(
     .bind(contract_uuid)
     .bind(&req.metric_type)
     .bind(&req.threshold_type)
-    .bind(rust_decimal::Decimal::try_from(req.threshold_value).unwrap_or_default())
+    .bind(threshold_value)
+    .bind(req.window_minutes)
     .bind(&req.severity)
+    .bind(webhook_url)
     .fetch_one(&state.db)
     .await
     .map_err(|e| db_err("create alert config", e))?;
@@ -351,6 +531,108 @@ pub async fn list_alert_configs(
     Ok(Json(configs))
 }
 
+/// PATCH /api/contracts/:id/perf/alert-configs/:config_id — enable or disable an
+/// alert config, keeping its threshold
+pub async fn update_alert_config(
+    State(state): State<AppState>,
+    Path((contract_id, config_id)): Path<(String, String)>,
+    payload: Result<Json<UpdateAlertConfigRequest>, JsonRejection>,
+) -> ApiResult<Json<PerformanceAlertConfig>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let config_uuid = parse_uuid(&config_id, "alert config")?;
+
+    let config: PerformanceAlertConfig = sqlx::query_as(
+        r#"
+        UPDATE performance_alert_configs
+        SET enabled = $3, updated_at = NOW()
+        WHERE id = $1 AND contract_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(config_uuid)
+    .bind(contract_uuid)
+    .bind(req.enabled)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => alert_config_not_found(&config_id),
+        _ => db_err("update alert config", e),
+    })?;
+
+    Ok(Json(config))
+}
+
+/// DELETE /api/contracts/:id/perf/alert-configs/:config_id — remove an alert config
+pub async fn delete_alert_config(
+    State(state): State<AppState>,
+    Path((contract_id, config_id)): Path<(String, String)>,
+) -> ApiResult<StatusCode> {
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let config_uuid = parse_uuid(&config_id, "alert config")?;
+
+    let result =
+        sqlx::query("DELETE FROM performance_alert_configs WHERE id = $1 AND contract_id = $2")
+            .bind(config_uuid)
+            .bind(contract_uuid)
+            .execute(&state.db)
+            .await
+            .map_err(|e| db_err("delete alert config", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(alert_config_not_found(&config_id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/contracts/:id/perf/alert-configs/evaluate — list the enabled configs a
+/// metric would trigger, without recording the metric
+pub async fn evaluate_alert_configs(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    payload: Result<Json<EvaluateAlertConfigsRequest>, JsonRejection>,
+) -> ApiResult<Json<Value>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let value = to_decimal(req.value, "value")?;
+    let p95 = req.p95.map(|v| to_decimal(v, "p95")).transpose()?;
+    let p99 = req.p99.map(|v| to_decimal(v, "p99")).transpose()?;
+
+    let configs: Vec<PerformanceAlertConfig> = sqlx::query_as(
+        r#"
+        SELECT * FROM performance_alert_configs
+        WHERE contract_id = $1 AND metric_type = $2 AND enabled = TRUE
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(contract_uuid)
+    .bind(&req.metric_type)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("evaluate alert configs", e))?;
+
+    let evaluated = configs.len();
+    let mut matching = Vec::new();
+    for config in configs {
+        let previous = match config.window_minutes {
+            Some(window) if config.threshold_type == RATE_OF_CHANGE => {
+                fetch_value_before(&state, contract_uuid, &req.metric_type, window).await?
+            }
+            _ => None,
+        };
+        if alert_config_fires(&config, value, p95, p99, previous) {
+            matching.push(config);
+        }
+    }
+
+    Ok(Json(json!({
+        "metric_type": req.metric_type,
+        "evaluated": evaluated,
+        "would_fire": !matching.is_empty(),
+        "matching_configs": matching,
+    })))
+}
+
 /// GET /api/contracts/:id/perf/trends — list performance trends
 pub async fn list_trends(
     State(state): State<AppState>,
@@ -359,28 +641,28 @@ pub async fn list_trends(
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
     let limit = params.limit.clamp(1, 100);
-    let offset = params.offset.max(0);
+    let cursor = parse_cursor(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.max(0)
+    };
 
-    let mut query = String::from(
-        "SELECT * FROM performance_trends WHERE contract_id = $1",
-    );
-
-    if let Some(ref mt) = params.metric_type {
-        query.push_str(&format!(" AND metric_type::text = '{}'", mt.replace('\'', "''")));
+    let mut query = QueryBuilder::new("SELECT * FROM performance_trends WHERE contract_id = ");
+    query.push_bind(contract_uuid);
+    if let Some(metric_type) = &params.metric_type {
+        query.push(" AND metric_type::text = ").push_bind(metric_type);
     }
+    push_page(&mut query, cursor.as_ref(), "timeframe_end", limit, offset);
 
-    query.push_str(&format!(
-        " ORDER BY timeframe_end DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
-
-    let trends: Vec<PerformanceTrend> = sqlx::query_as(&query)
-        .bind(contract_uuid)
+    let trends: Vec<PerformanceTrend> = query
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| db_err("list performance trends", e))?;
 
     Ok(Json(json!({
+        "next_cursor": next_cursor(&trends, limit, |m| (m.timeframe_end, m.id)),
         "items": trends,
         "limit": limit,
         "offset": offset,
@@ -388,25 +670,18 @@ pub async fn list_trends(
 }
 
 /// GET /api/contracts/:id/perf/summary — get a comprehensive performance summary
+#[tracing::instrument(skip_all, fields(contract_id = %contract_id))]
 pub async fn get_performance_summary(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
 ) -> ApiResult<Json<Value>> {
     let contract_uuid = parse_uuid(&contract_id, "contract")?;
 
-    // Latest metrics per type
-    let latest_metrics: Vec<PerformanceMetric> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT ON (metric_type) *
-        FROM performance_metrics
-        WHERE contract_id = $1
-        ORDER BY metric_type, timestamp DESC
-        "#,
-    )
-    .bind(contract_uuid)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| db_err("get latest metrics", e))?;
+    let latest_metrics = fetch_latest_metrics(&state, contract_uuid).await?;
+    let latest_metric_timestamps: serde_json::Map<String, Value> = latest_metrics
+        .iter()
+        .map(|m| (format!("{:?}", m.metric_type), json!(m.timestamp)))
+        .collect();
 
     // Unresolved anomaly count
     let anomaly_count: i64 = sqlx::query_scalar(
@@ -414,6 +689,7 @@ pub async fn get_performance_summary(
     )
     .bind(contract_uuid)
     .fetch_one(&state.db)
+    .instrument(db_span("count unresolved anomalies"))
     .await
     .unwrap_or(0);
 
@@ -423,6 +699,7 @@ pub async fn get_performance_summary(
     )
     .bind(contract_uuid)
     .fetch_one(&state.db)
+    .instrument(db_span("count unresolved alerts"))
     .await
     .unwrap_or(0);
 
@@ -432,20 +709,181 @@ pub async fn get_performance_summary(
     )
     .bind(contract_uuid)
     .fetch_one(&state.db)
+    .instrument(db_span("count active alert configs"))
     .await
     .unwrap_or(0);
 
     Ok(Json(json!({
         "contract_id": contract_uuid,
         "latest_metrics": latest_metrics,
+        "latest_metric_timestamps": latest_metric_timestamps,
         "unresolved_anomalies": anomaly_count,
         "unresolved_alerts": alert_count,
         "active_alert_configs": config_count,
     })))
 }
 
+/// GET /api/contracts/:id/perf/regression — compare a metric's distribution
+/// between two versions and report whether the candidate regressed
+pub async fn get_version_regression(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    Query(params): Query<RegressionQuery>,
+) -> ApiResult<Json<RegressionReport>> {
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+
+    let baseline = fetch_version_values(
+        &state,
+        contract_uuid,
+        &params.metric_type,
+        &params.baseline_version,
+    )
+    .await?;
+    let candidate = fetch_version_values(
+        &state,
+        contract_uuid,
+        &params.metric_type,
+        &params.candidate_version,
+    )
+    .await?;
+
+    for (version, values) in [
+        (&params.baseline_version, &baseline),
+        (&params.candidate_version, &candidate),
+    ] {
+        if values.len() < MIN_REGRESSION_SAMPLES {
+            return Err(ApiError::unprocessable(
+                "InsufficientSamples",
+                format!(
+                    "Version {} has {} {} samples; at least {} are needed",
+                    version,
+                    values.len(),
+                    params.metric_type,
+                    MIN_REGRESSION_SAMPLES
+                ),
+            ));
+        }
+    }
+
+    Ok(Json(build_regression_report(params, &baseline, &candidate)))
+}
+
 // ───────────────────── Helpers ─────────────────────
 
+/// Most recent values of a metric recorded against `version`
+async fn fetch_version_values(
+    state: &AppState,
+    contract_uuid: Uuid,
+    metric_type: &str,
+    version: &str,
+) -> ApiResult<Vec<f64>> {
+    let values: Vec<Decimal> = sqlx::query_scalar(
+        r#"
+        SELECT value FROM performance_metrics
+        WHERE contract_id = $1 AND metric_type::text = $2 AND version = $3
+        ORDER BY timestamp DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(contract_uuid)
+    .bind(metric_type)
+    .bind(version)
+    .bind(MAX_REGRESSION_SAMPLES)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch metric values for version", e))?;
+
+    Ok(values.iter().filter_map(|v| v.to_f64()).collect())
+}
+
+/// Compare two versions' samples, each holding at least two values
+fn build_regression_report(
+    params: RegressionQuery,
+    baseline: &[f64],
+    candidate: &[f64],
+) -> RegressionReport {
+    let (baseline, candidate) = (sample_stats(baseline), sample_stats(candidate));
+    let effect_size = cohens_d(&baseline, &candidate);
+    let regressed = match effect_size {
+        Some(d) => d >= REGRESSION_EFFECT_SIZE,
+        None => candidate.mean > baseline.mean,
+    };
+
+    RegressionReport {
+        metric_type: params.metric_type,
+        baseline_version: params.baseline_version,
+        candidate_version: params.candidate_version,
+        mean_change_pct: (baseline.mean != 0.0)
+            .then(|| (candidate.mean - baseline.mean) / baseline.mean * 100.0),
+        baseline,
+        candidate,
+        effect_size,
+        regressed,
+    }
+}
+
+/// Mean and sample standard deviation; needs at least two values
+fn sample_stats(values: &[f64]) -> SampleStats {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    SampleStats {
+        samples: values.len(),
+        mean,
+        std_dev: variance.sqrt(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SampleSummary {
+    mean: f64,
+    p50: f64,
+    p95: f64,
+    p99: f64,
+}
+
+fn summarize_samples(samples: &[f64]) -> ApiResult<SampleSummary> {
+    if samples.is_empty() || samples.len() > MAX_METRIC_SAMPLES {
+        return Err(ApiError::bad_request(
+            "InvalidSamples",
+            format!("samples must hold 1 to {} values", MAX_METRIC_SAMPLES),
+        ));
+    }
+    if let Some(bad) = samples.iter().find(|v| !v.is_finite()) {
+        return Err(ApiError::bad_request(
+            "InvalidNumber",
+            format!("samples must be finite numbers, got {}", bad),
+        ));
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    Ok(SampleSummary {
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50: percentile(&sorted, 0.50),
+        p95: percentile(&sorted, 0.95),
+        p99: percentile(&sorted, 0.99),
+    })
+}
+
+/// Linearly interpolated percentile of sorted, non-empty values; matches
+/// Postgres' `percentile_cont` used by the daily trend rollups
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Difference in means over the pooled standard deviation
+fn cohens_d(baseline: &SampleStats, candidate: &SampleStats) -> Option<f64> {
+    let (n1, n2) = (baseline.samples as f64, candidate.samples as f64);
+    let pooled_variance = ((n1 - 1.0) * baseline.std_dev.powi(2)
+        + (n2 - 1.0) * candidate.std_dev.powi(2))
+        / (n1 + n2 - 2.0);
+    let pooled = pooled_variance.sqrt();
+    (pooled > 0.0).then(|| (candidate.mean - baseline.mean) / pooled)
+}
+
 fn parse_uuid(id: &str, label: &str) -> Result<Uuid, ApiError> {
     Uuid::parse_str(id).map_err(|_| {
         ApiError::bad_request(
@@ -455,7 +893,690 @@ fn parse_uuid(id: &str, label: &str) -> Result<Uuid, ApiError> {
     })
 }
 
+/// GET /api/contracts/:id/perf/metrics/prometheus — latest metric per type as
+/// Prometheus text-format gauges
+pub async fn export_metrics_prometheus(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    let contract_uuid = parse_uuid(&contract_id, "contract")?;
+    let latest_metrics = fetch_latest_metrics(&state, contract_uuid).await?;
+
+    Ok((
+        StatusCode::OK,
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        render_prometheus(contract_uuid, &latest_metrics),
+    ))
+}
+
+/// Latest metric of each type for a contract
+async fn fetch_latest_metrics(
+    state: &AppState,
+    contract_uuid: Uuid,
+) -> ApiResult<Vec<PerformanceMetric>> {
+    // Every row tied for the latest timestamp of its type; ties are broken below
+    let candidates: Vec<PerformanceMetric> = sqlx::query_as(
+        r#"
+        SELECT * FROM (
+            SELECT *, MAX(timestamp) OVER (PARTITION BY metric_type) AS latest_timestamp
+            FROM performance_metrics
+            WHERE contract_id = $1
+        ) ranked
+        WHERE timestamp = latest_timestamp
+        ORDER BY metric_type, id DESC
+        "#,
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .instrument(db_span("get latest metrics"))
+    .await
+    .map_err(|e| db_err("get latest metrics", e))?;
+
+    Ok(latest_per_type(candidates))
+}
+
+fn render_prometheus(contract_id: Uuid, metrics: &[PerformanceMetric]) -> String {
+    let mut out = String::from(
+        "# HELP soroban_contract_metric Latest recorded performance metric for a contract\n\
+         # TYPE soroban_contract_metric gauge\n",
+    );
+    for metric in metrics {
+        let labels = format!(
+            "contract_id=\"{}\",metric_type=\"{}\"",
+            contract_id,
+            metric_type_label(&metric.metric_type)
+        );
+        out.push_str(&format!(
+            "soroban_contract_metric{{{}}} {}\n",
+            labels, metric.value
+        ));
+        let quantiles = [
+            ("0.5", metric.p50),
+            ("0.95", metric.p95),
+            ("0.99", metric.p99),
+        ];
+        for (quantile, value) in quantiles {
+            if let Some(value) = value {
+                out.push_str(&format!(
+                    "soroban_contract_metric{{{},quantile=\"{}\"}} {}\n",
+                    labels, quantile, value
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// The metric type's name as stored in the database
+fn metric_type_label(metric_type: &MetricType) -> &'static str {
+    match metric_type {
+        MetricType::ExecutionTime => "execution_time",
+        MetricType::MemoryUsage => "memory_usage",
+        MetricType::StorageIo => "storage_io",
+        MetricType::GasConsumption => "gas_consumption",
+        MetricType::ErrorRate => "error_rate",
+    }
+}
+
+/// Width in seconds of an aggregate bucket interval
+fn parse_bucket_interval(interval: &str) -> Result<i64, ApiError> {
+    match interval {
+        "1m" => Ok(60),
+        "5m" => Ok(5 * 60),
+        "1h" => Ok(60 * 60),
+        "1d" => Ok(24 * 60 * 60),
+        other => Err(ApiError::bad_request(
+            "InvalidInterval",
+            format!("Unsupported interval '{}'; use 1m, 5m, 1h or 1d", other),
+        )),
+    }
+}
+
+fn check_bucket_count(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    bucket_secs: i64,
+) -> Result<(), ApiError> {
+    let span_secs = (to - from).num_seconds();
+    if span_secs <= 0 {
+        return Err(ApiError::bad_request(
+            "InvalidRange",
+            "`from` must be earlier than `to`",
+        ));
+    }
+    let buckets = (span_secs + bucket_secs - 1) / bucket_secs;
+    if buckets > MAX_AGGREGATE_BUCKETS {
+        return Err(ApiError::bad_request(
+            "TooManyBuckets",
+            format!(
+                "Range would produce {} buckets (max {}); narrow the range or use a wider interval",
+                buckets, MAX_AGGREGATE_BUCKETS
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Latest metric of each type, breaking timestamp ties by the highest id so
+/// the pick is stable across requests. Types keep their first-seen order.
+fn latest_per_type(metrics: Vec<PerformanceMetric>) -> Vec<PerformanceMetric> {
+    let mut latest: Vec<PerformanceMetric> = Vec::new();
+    for metric in metrics {
+        let existing = latest
+            .iter_mut()
+            .find(|m| m.metric_type == metric.metric_type);
+        match existing {
+            Some(current) if (metric.timestamp, metric.id) > (current.timestamp, current.id) => {
+                *current = metric;
+            }
+            Some(_) => {}
+            None => latest.push(metric),
+        }
+    }
+    latest
+}
+
+fn validate_alert_window(threshold_type: &str, window_minutes: Option<i32>) -> ApiResult<()> {
+    match window_minutes {
+        Some(window) if window <= 0 => Err(ApiError::bad_request(
+            "InvalidWindow",
+            "window_minutes must be positive",
+        )),
+        None if threshold_type == RATE_OF_CHANGE => Err(ApiError::bad_request(
+            "MissingWindow",
+            "rate_of_change alert configs require window_minutes",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// A blank URL clears the webhook
+fn validate_webhook_url(url: Option<&str>) -> ApiResult<Option<String>> {
+    let Some(url) = url.map(str::trim).filter(|url| !url.is_empty()) else {
+        return Ok(None);
+    };
+    crate::validation::validate_https_url_only(url)
+        .map_err(|e| ApiError::bad_request("InvalidWebhookUrl", e))?;
+    Ok(Some(url.to_string()))
+}
+
+/// Latest recorded value at least `window_minutes` old, as the trigger looks it up
+async fn fetch_value_before(
+    state: &AppState,
+    contract_id: Uuid,
+    metric_type: &MetricType,
+    window_minutes: i32,
+) -> ApiResult<Option<Decimal>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT value FROM performance_metrics
+        WHERE contract_id = $1 AND metric_type = $2
+          AND timestamp <= NOW() - make_interval(mins => $3)
+        ORDER BY timestamp DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(contract_id)
+    .bind(metric_type)
+    .bind(window_minutes)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("fetch previous metric", e))
+}
+
+/// Mirrors the threshold check in the `check_performance_thresholds` trigger.
+/// `previous` is the value `rate_of_change` compares against.
+fn alert_config_fires(
+    config: &PerformanceAlertConfig,
+    value: Decimal,
+    p95: Option<Decimal>,
+    p99: Option<Decimal>,
+    previous: Option<Decimal>,
+) -> bool {
+    match config.threshold_type.as_str() {
+        "p99_exceeds" => p99.is_some_and(|p99| p99 > config.threshold_value),
+        "p95_exceeds" => p95.is_some_and(|p95| p95 > config.threshold_value),
+        "value_exceeds" => value > config.threshold_value,
+        "value_below" => value < config.threshold_value,
+        RATE_OF_CHANGE => previous
+            .filter(|previous| !previous.is_zero())
+            .is_some_and(|previous| {
+                ((value - previous) / previous * Decimal::ONE_HUNDRED).abs()
+                    > config.threshold_value
+            }),
+        _ => false,
+    }
+}
+
+fn alert_config_not_found(config_id: &str) -> ApiError {
+    ApiError::not_found(
+        "AlertConfigNotFound",
+        format!("No alert config {} found for this contract", config_id),
+    )
+}
+
+/// `select` over a contract's metrics, filtered by the listing's query params
+fn metrics_query<'a>(
+    select: &str,
+    contract_uuid: Uuid,
+    params: &'a ListMetricsQuery,
+) -> QueryBuilder<'a, Postgres> {
+    let mut qb = QueryBuilder::new(select);
+    qb.push(" WHERE contract_id = ").push_bind(contract_uuid);
+    if let Some(metric_type) = &params.metric_type {
+        qb.push(" AND metric_type::text = ").push_bind(metric_type);
+    }
+    if let Some(function_name) = &params.function_name {
+        qb.push(" AND function_name = ").push_bind(function_name);
+    }
+    qb
+}
+
+/// `select` over a contract's anomalies or alerts, filtered by the listing's
+/// query params
+fn alerts_query<'a>(
+    select: &str,
+    contract_uuid: Uuid,
+    params: &'a ListAlertsQuery,
+) -> QueryBuilder<'a, Postgres> {
+    let mut qb = QueryBuilder::new(select);
+    qb.push(" WHERE contract_id = ").push_bind(contract_uuid);
+    if let Some(resolved) = params.resolved {
+        qb.push(" AND resolved = ").push_bind(resolved);
+    }
+    if let Some(severity) = &params.severity {
+        qb.push(" AND severity::text = ").push_bind(severity);
+    }
+    qb
+}
+
+fn parse_cursor(cursor: Option<&str>) -> Result<Option<Cursor>, ApiError> {
+    cursor
+        .map(|c| {
+            Cursor::decode(c).map_err(|e| ApiError::bad_request("InvalidCursor", e.to_string()))
+        })
+        .transpose()
+}
+
+fn to_decimal(value: f64, field: &str) -> Result<Decimal, ApiError> {
+    Decimal::try_from(value).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidNumber",
+            format!("{} must be a finite number in range, got {}", field, value),
+        )
+    })
+}
+
 fn db_err(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = operation, error = ?err, "database operation failed");
-    ApiError::internal("An unexpected database error occurred")
+    ApiError::database(operation, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::models::AlertSeverity;
+
+    #[tokio::test]
+    async fn malformed_bodies_are_coded_bad_requests() {
+        use axum::{body::Body, http::Request, routing::post, Router};
+        use tower::ServiceExt;
+
+        let db = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let app = Router::new()
+            .route("/:id", post(create_alert_config))
+            .with_state(crate::test_support::test_state(db));
+        let request = Request::post(format!("/{}", Uuid::new_v4()))
+            .header("content-type", "application/json")
+            .body(Body::from("{\"threshold_value\": "))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "InvalidRequest");
+    }
+
+    fn config(threshold_type: &str, threshold: i64) -> PerformanceAlertConfig {
+        PerformanceAlertConfig {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            metric_type: MetricType::ExecutionTime,
+            threshold_type: threshold_type.to_string(),
+            threshold_value: Decimal::from(threshold),
+            window_minutes: None,
+            severity: AlertSeverity::Critical,
+            enabled: true,
+            notify_webhook_url: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn metric(metric_type: MetricType, id: u128, timestamp: DateTime<Utc>) -> PerformanceMetric {
+        PerformanceMetric {
+            id: Uuid::from_u128(id),
+            contract_id: Uuid::nil(),
+            metric_type,
+            function_name: None,
+            value: Decimal::from(id as i64),
+            p50: None,
+            p95: None,
+            p99: None,
+            timestamp,
+            metadata: None,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn same_timestamp_latest_metrics_pick_highest_id() {
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::minutes(5);
+        let rows = vec![
+            metric(MetricType::ExecutionTime, 1, now),
+            metric(MetricType::ExecutionTime, 3, now),
+            metric(MetricType::ExecutionTime, 2, now),
+            metric(MetricType::ExecutionTime, 9, earlier),
+            metric(MetricType::GasConsumption, 4, earlier),
+        ];
+
+        let mut reversed = rows.clone();
+        reversed.reverse();
+
+        for input in [rows, reversed] {
+            let latest = latest_per_type(input);
+            assert_eq!(latest.len(), 2);
+            let exec = latest
+                .iter()
+                .find(|m| m.metric_type == MetricType::ExecutionTime)
+                .unwrap();
+            assert_eq!(exec.id, Uuid::from_u128(3));
+            assert_eq!(exec.timestamp, now);
+        }
+    }
+
+    #[test]
+    fn prometheus_export_emits_value_and_quantile_series() {
+        let mut gas = metric(MetricType::GasConsumption, 1, Utc::now());
+        gas.value = Decimal::from(1200);
+        gas.p50 = Some(Decimal::from(1000));
+        gas.p95 = Some(Decimal::from(1234));
+
+        let body = render_prometheus(Uuid::nil(), &[gas]);
+        let labels = format!(
+            "contract_id=\"{}\",metric_type=\"gas_consumption\"",
+            Uuid::nil()
+        );
+
+        assert!(body.starts_with("# HELP soroban_contract_metric "));
+        assert!(body.contains("# TYPE soroban_contract_metric gauge\n"));
+        assert!(body.contains(&format!("soroban_contract_metric{{{}}} 1200\n", labels)));
+        assert!(body.contains(&format!(
+            "soroban_contract_metric{{{},quantile=\"0.95\"}} 1234\n",
+            labels
+        )));
+        assert!(body.contains("quantile=\"0.5\"} 1000\n"));
+        assert!(!body.contains("quantile=\"0.99\""));
+    }
+
+    #[test]
+    fn samples_are_summarized_server_side() {
+        let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+
+        let summary = summarize_samples(&samples).unwrap();
+
+        assert_eq!(summary.mean, 50.5);
+        assert_eq!(summary.p50, 50.5);
+        assert!((summary.p95 - 95.05).abs() < 1e-9);
+        assert!((summary.p99 - 99.01).abs() < 1e-9);
+        assert_eq!(
+            summarize_samples(&[7.0]).unwrap(),
+            SampleSummary {
+                mean: 7.0,
+                p50: 7.0,
+                p95: 7.0,
+                p99: 7.0
+            }
+        );
+    }
+
+    #[test]
+    fn empty_oversized_or_non_finite_samples_are_rejected() {
+        assert!(summarize_samples(&[]).is_err());
+        assert!(summarize_samples(&vec![1.0; MAX_METRIC_SAMPLES + 1]).is_err());
+        assert!(summarize_samples(&[1.0, f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn bucket_intervals_are_parsed() {
+        assert_eq!(parse_bucket_interval("5m").unwrap(), 300);
+        assert_eq!(parse_bucket_interval("1d").unwrap(), 86_400);
+        assert!(parse_bucket_interval("2m").is_err());
+    }
+
+    #[test]
+    fn oversized_bucket_ranges_are_rejected() {
+        let to = Utc::now();
+        assert!(check_bucket_count(to - chrono::Duration::hours(24), to, 60).is_ok());
+        assert!(check_bucket_count(to - chrono::Duration::days(30), to, 60).is_err());
+        assert!(check_bucket_count(to - chrono::Duration::days(30), to, 3_600).is_ok());
+        assert!(check_bucket_count(to, to - chrono::Duration::hours(1), 60).is_err());
+    }
+
+    #[test]
+    fn breaching_value_matches_config() {
+        let exceeds = config("value_exceeds", 500);
+        assert!(alert_config_fires(
+            &exceeds,
+            Decimal::from(750),
+            None,
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn non_breaching_value_matches_nothing() {
+        let configs = [
+            config("value_exceeds", 500),
+            config("value_below", 10),
+            config("p99_exceeds", 900),
+        ];
+        let (value, p99) = (Decimal::from(200), Some(Decimal::from(800)));
+        assert!(!configs
+            .iter()
+            .any(|c| alert_config_fires(c, value, None, p99, None)));
+    }
+
+    #[test]
+    fn percentile_thresholds_need_the_percentile() {
+        let p95 = config("p95_exceeds", 100);
+        assert!(!alert_config_fires(
+            &p95,
+            Decimal::from(1_000),
+            None,
+            None,
+            None
+        ));
+        assert!(alert_config_fires(
+            &p95,
+            Decimal::ZERO,
+            Some(Decimal::from(101)),
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn rate_of_change_compares_percentage_against_previous_value() {
+        let mut rate = config(RATE_OF_CHANGE, 50);
+        rate.window_minutes = Some(15);
+        let fires = |value: i64, previous: Option<i64>| {
+            alert_config_fires(
+                &rate,
+                Decimal::from(value),
+                None,
+                None,
+                previous.map(Decimal::from),
+            )
+        };
+
+        assert!(fires(160, Some(100)));
+        assert!(fires(40, Some(100)));
+        assert!(!fires(150, Some(100)));
+        assert!(!fires(160, None));
+        assert!(!fires(160, Some(0)));
+    }
+
+    #[test]
+    fn rate_of_change_requires_a_positive_window() {
+        assert!(validate_alert_window(RATE_OF_CHANGE, None).is_err());
+        assert!(validate_alert_window(RATE_OF_CHANGE, Some(0)).is_err());
+        assert!(validate_alert_window(RATE_OF_CHANGE, Some(15)).is_ok());
+        assert!(validate_alert_window("value_exceeds", None).is_ok());
+    }
+
+    fn regression_query() -> RegressionQuery {
+        RegressionQuery {
+            metric_type: "execution_time".to_string(),
+            baseline_version: "1.0.0".to_string(),
+            candidate_version: "1.1.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn clearly_slower_candidate_is_a_regression() {
+        let baseline = [100.0, 104.0, 98.0, 101.0, 97.0, 100.0];
+        let candidate = [150.0, 148.0, 155.0, 152.0, 149.0, 151.0];
+
+        let report = build_regression_report(regression_query(), &baseline, &candidate);
+
+        assert!(report.regressed);
+        assert!(report.effect_size.unwrap() > 10.0);
+        assert!((report.mean_change_pct.unwrap() - 50.0).abs() < 1.0);
+        assert_eq!((report.baseline.samples, report.candidate.samples), (6, 6));
+    }
+
+    #[test]
+    fn noise_and_speedups_are_not_regressions() {
+        let baseline = [100.0, 110.0, 90.0, 105.0, 95.0];
+        let noisy = [101.0, 111.0, 91.0, 104.0, 96.0];
+        let faster = [60.0, 62.0, 58.0, 61.0, 59.0];
+
+        let report = build_regression_report(regression_query(), &baseline, &noisy);
+        assert!(!report.regressed, "{:?}", report.effect_size);
+
+        let report = build_regression_report(regression_query(), &baseline, &faster);
+        assert!(!report.regressed);
+        assert!(report.effect_size.unwrap() < 0.0);
+    }
+
+    #[test]
+    fn constant_samples_compare_means() {
+        let report = build_regression_report(regression_query(), &[5.0, 5.0], &[6.0, 6.0]);
+        assert_eq!(report.effect_size, None);
+        assert!(report.regressed);
+    }
+
+    #[test]
+    fn listing_filters_are_bound_not_interpolated() {
+        let injection = "'; DROP TABLE performance_metrics; --";
+        let params = ListMetricsQuery {
+            limit: 20,
+            offset: 0,
+            cursor: None,
+            metric_type: Some("execution_time".to_string()),
+            function_name: Some(injection.to_string()),
+        };
+        let cursor = Cursor::new(Utc::now(), Uuid::new_v4());
+
+        let mut qb = metrics_query("SELECT * FROM performance_metrics", Uuid::nil(), &params);
+        push_page(&mut qb, Some(&cursor), "timestamp", 20, 0);
+        let sql = qb.sql();
+        assert!(sql.contains("contract_id = $1"));
+        assert!(sql.contains("metric_type::text = $2"));
+        assert!(sql.contains("function_name = $3"));
+        assert!(sql.contains("(timestamp, id) < ($4, $5)"));
+        assert!(sql.ends_with("LIMIT $6 OFFSET $7"));
+        assert!(!sql.contains("DROP TABLE"));
+
+        let params = ListAlertsQuery {
+            limit: 20,
+            offset: 0,
+            cursor: None,
+            resolved: Some(false),
+            severity: Some(injection.to_string()),
+        };
+        let qb = alerts_query(
+            "SELECT COUNT(*) FROM performance_alerts",
+            Uuid::nil(),
+            &params,
+        );
+        let sql = qb.sql();
+        assert!(sql.ends_with("resolved = $2 AND severity::text = $3"));
+        assert!(!sql.contains("DROP TABLE"));
+    }
+
+    #[tokio::test]
+    async fn aggregates_are_bucketed_per_metric_type() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let contract_id = crate::test_support::seed_contract(&db, "perf-aggregate").await;
+        for (metric_type, value) in [
+            (MetricType::ExecutionTime, 120.0),
+            (MetricType::GasConsumption, 5000.0),
+        ] {
+            let req = RecordPerformanceMetricRequest {
+                contract_id: contract_id.to_string(),
+                metric_type,
+                function_name: None,
+                value,
+                p50: None,
+                p95: None,
+                p99: None,
+                metadata: None,
+                version: None,
+            };
+            let mut conn = db.acquire().await.unwrap();
+            insert_metric(&mut conn, contract_id, req).await.unwrap();
+        }
+
+        let now = Utc::now();
+        let Json(body) = aggregate_metrics(
+            State(state),
+            Path(contract_id.to_string()),
+            Query(AggregateMetricsQuery {
+                interval: "1h".to_string(),
+                metric_type: None,
+                from: Some(now - chrono::Duration::hours(1)),
+                to: Some(now + chrono::Duration::hours(1)),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let buckets = body["buckets"].as_array().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert!(buckets.iter().all(|b| b["count"] == 1));
+        let types: Vec<&str> = buckets
+            .iter()
+            .map(|b| b["metric_type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["ExecutionTime", "GasConsumption"]);
+    }
+
+    #[tokio::test]
+    async fn injected_function_name_matches_nothing() {
+        let Some(db) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let state = crate::test_support::test_state(db.clone());
+        let contract_id = crate::test_support::seed_contract(&db, "perf-injection").await;
+        let req = RecordPerformanceMetricRequest {
+            contract_id: contract_id.to_string(),
+            metric_type: MetricType::ExecutionTime,
+            function_name: Some("transfer".to_string()),
+            value: 120.0,
+            p50: None,
+            p95: None,
+            p99: None,
+            metadata: None,
+            version: None,
+        };
+        let mut conn = db.acquire().await.unwrap();
+        insert_metric(&mut conn, contract_id, req).await.unwrap();
+        drop(conn);
+
+        let Json(body) = list_metrics(
+            State(state),
+            Path(contract_id.to_string()),
+            Query(ListMetricsQuery {
+                limit: 20,
+                offset: 0,
+                cursor: None,
+                metric_type: None,
+                function_name: Some("'; DROP TABLE performance_metrics; --".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(body["items"].as_array().unwrap().is_empty());
+        assert_eq!(body["total"], 0);
+
+        let stored: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM performance_metrics WHERE contract_id = $1")
+                .bind(contract_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(stored, 1);
+    }
 }
@@ -42,18 +42,29 @@ use axum::{
     },
     middleware::Next,
     response::{IntoResponse, Response},
-    Json,
 };
-use serde_json::json;
 use tokio::sync::Mutex;
 
+use crate::error::ApiError;
+
 const DEFAULT_READ_LIMIT_PER_MINUTE: u32 = 100;
 const DEFAULT_WRITE_LIMIT_PER_MINUTE: u32 = 20;
 const DEFAULT_AUTH_LIMIT_PER_MINUTE: u32 = 1_000;
 const DEFAULT_HEALTH_LIMIT_PER_MINUTE: u32 = 10_000;
+const DEFAULT_EXPENSIVE_LIMIT_PER_MINUTE: u32 = 5;
 const DEFAULT_WINDOW_SECONDS: u64 = 60;
 const ENDPOINT_LIMIT_ENV_PREFIX: &str = "RATE_LIMIT_ENDPOINT_";
 
+/// CPU-heavy endpoints (WASM parsing, source builds) held to the expensive
+/// limit regardless of authentication
+const EXPENSIVE_ENDPOINTS: &[&str] = &[
+    "/api/contracts/batch-verify",
+    "/api/contracts/simulate-deploy",
+    "/api/contracts/:id/simulate-upgrade",
+    "/api/contracts/verify",
+    "/api/contracts/verify-on-chain",
+];
+
 /// How often the background task sweeps for expired buckets.
 const EVICTION_INTERVAL: Duration = Duration::from_secs(5 * 60); // every 5 minutes
 
@@ -170,6 +181,15 @@ impl RateLimitState {
         }
     }
 
+    /// Matched against the connecting peer rather than forwarded headers,
+    /// which any client can set.
+    fn is_allowlisted<B>(&self, request: &Request<B>) -> bool {
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .is_some_and(|peer| self.config.allowlist.contains(&peer.0.ip()))
+    }
+
     fn select_limit<B>(&self, request: &Request<B>) -> (u32, String) {
         let method = request.method();
         let matched_path = request
@@ -183,6 +203,10 @@ impl RateLimitState {
             return (*limit, endpoint_key);
         }
 
+        if method == Method::POST && EXPENSIVE_ENDPOINTS.contains(&matched_path) {
+            return (self.config.expensive_limit, endpoint_key);
+        }
+
         if matched_path == "/health" || method == Method::OPTIONS {
             return (self.config.health_limit, endpoint_key);
         }
@@ -204,13 +228,17 @@ struct RateLimitConfig {
     write_limit: u32,
     auth_limit: u32,
     health_limit: u32,
+    expensive_limit: u32,
     window: Duration,
     endpoint_limits: HashMap<String, u32>,
+    /// Peers that are never limited, e.g. internal services
+    allowlist: Vec<IpAddr>,
 }
 
 impl RateLimitConfig {
     fn from_env() -> Self {
-        let read_limit = env_u32("RATE_LIMIT_READ_PER_MINUTE", DEFAULT_READ_LIMIT_PER_MINUTE);
+        let default_read_limit = env_u32("RATE_LIMIT_RPM", DEFAULT_READ_LIMIT_PER_MINUTE);
+        let read_limit = env_u32("RATE_LIMIT_READ_PER_MINUTE", default_read_limit);
         let write_limit = env_u32(
             "RATE_LIMIT_WRITE_PER_MINUTE",
             DEFAULT_WRITE_LIMIT_PER_MINUTE,
@@ -220,6 +248,10 @@ impl RateLimitConfig {
             "RATE_LIMIT_HEALTH_PER_MINUTE",
             DEFAULT_HEALTH_LIMIT_PER_MINUTE,
         );
+        let expensive_limit = env_u32(
+            "RATE_LIMIT_EXPENSIVE_PER_MINUTE",
+            DEFAULT_EXPENSIVE_LIMIT_PER_MINUTE,
+        );
         let window_seconds = env_u64("RATE_LIMIT_WINDOW_SECONDS", DEFAULT_WINDOW_SECONDS).max(1);
 
         let mut endpoint_limits = HashMap::new();
@@ -240,13 +272,19 @@ impl RateLimitConfig {
             endpoint_limits.insert(endpoint_key.to_string(), limit);
         }
 
+        let allowlist = env::var("RATE_LIMIT_ALLOWLIST")
+            .map(|raw| parse_allowlist(&raw))
+            .unwrap_or_default();
+
         tracing::info!(
             read_limit,
             write_limit,
             auth_limit,
             health_limit,
+            expensive_limit,
             window_seconds,
             endpoint_overrides = endpoint_limits.len(),
+            allowlisted = allowlist.len(),
             "Rate limiter configured"
         );
 
@@ -255,8 +293,10 @@ impl RateLimitConfig {
             write_limit,
             auth_limit,
             health_limit,
+            expensive_limit,
             window: Duration::from_secs(window_seconds),
             endpoint_limits,
+            allowlist,
         }
     }
 
@@ -267,8 +307,10 @@ impl RateLimitConfig {
             write_limit,
             auth_limit: DEFAULT_AUTH_LIMIT_PER_MINUTE,
             health_limit,
+            expensive_limit: DEFAULT_EXPENSIVE_LIMIT_PER_MINUTE,
             window,
             endpoint_limits: HashMap::new(),
+            allowlist: Vec::new(),
         }
     }
 }
@@ -296,23 +338,22 @@ pub async fn rate_limit_middleware(
     request: Request<Body>,
     next: Next,
 ) -> Response {
+    if rate_limiter.is_allowlisted(&request) {
+        return next.run(request).await;
+    }
+
     // Extract request metadata before awaiting to avoid borrowing `request` across `.await`.
     let (limit, endpoint_key) = rate_limiter.select_limit(&request);
     let ip = extract_client_ip(&request);
     let decision = rate_limiter.check_request(ip, endpoint_key, limit).await;
 
     if !decision.allowed {
-        let mut response = (
+        let mut response = ApiError::new(
             StatusCode::TOO_MANY_REQUESTS,
-            Json(json!({
-                "error": "RateLimitExceeded",
-                "message": "Too many requests. Please retry after the indicated time.",
-                "code": 429,
-                "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
-                "correlation_id": uuid::Uuid::new_v4().to_string()
-            })),
+            "RateLimitExceeded",
+            "Too many requests. Please retry after the indicated time.",
         )
-            .into_response();
+        .into_response();
         attach_rate_limit_headers(&mut response, &decision);
         response.headers_mut().insert(
             RETRY_AFTER,
@@ -386,6 +427,21 @@ fn parse_ip_addr(raw: &str) -> Option<IpAddr> {
         .or_else(|| raw.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
 }
 
+/// Comma-separated IP addresses; invalid entries are skipped with a warning
+fn parse_allowlist(raw: &str) -> Vec<IpAddr> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let ip = parse_ip_addr(entry);
+            if ip.is_none() {
+                tracing::warn!("Ignoring invalid rate limit allowlist entry `{entry}`");
+            }
+            ip
+        })
+        .collect()
+}
+
 fn is_write_method(method: &Method) -> bool {
     matches!(
         *method,
@@ -470,19 +526,25 @@ mod tests {
         health_limit: u32,
         window: Duration,
     ) -> Router<()> {
-        let limiter = RateLimitState::new(RateLimitConfig::for_tests(
+        router(RateLimitConfig::for_tests(
             read_limit,
             write_limit,
             health_limit,
             window,
-        ));
+        ))
+    }
 
+    fn router(config: RateLimitConfig) -> Router<()> {
         Router::new()
             .route("/health", get(|| async { "ok" }))
             .route("/read", get(|| async { "read" }))
             .route("/write", post(|| async { "write" }))
+            .route(
+                "/api/contracts/simulate-deploy",
+                post(|| async { "simulated" }),
+            )
             .layer(middleware::from_fn_with_state(
-                limiter,
+                RateLimitState::new(config),
                 rate_limit_middleware,
             ))
     }
@@ -691,6 +753,60 @@ mod tests {
         assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
     }
 
+    #[tokio::test]
+    async fn expensive_endpoints_have_a_tighter_limit_even_when_authenticated() {
+        let app = test_app(100, 100, 10_000, Duration::from_secs(60));
+        let simulate = || {
+            Request::builder()
+                .uri("/api/contracts/simulate-deploy")
+                .method("POST")
+                .header("x-forwarded-for", "203.0.113.77")
+                .header("authorization", "Bearer token")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..DEFAULT_EXPENSIVE_LIMIT_PER_MINUTE {
+            assert_eq!(call(&app, simulate()).await.status(), StatusCode::OK);
+        }
+
+        let limited = call(&app, simulate()).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(limited.headers().contains_key(RETRY_AFTER));
+    }
+
+    #[tokio::test]
+    async fn allowlisted_peers_are_not_limited() {
+        let mut config = RateLimitConfig::for_tests(1, 1, 10_000, Duration::from_secs(60));
+        config.allowlist = parse_allowlist("10.1.2.3, not-an-ip");
+        assert_eq!(config.allowlist.len(), 1);
+        let app = router(config);
+
+        let from_peer = |peer: &str| {
+            Request::builder()
+                .uri("/read")
+                .method("GET")
+                .extension(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 4000)))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..3 {
+            let response = call(&app, from_peer("10.1.2.3")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(!response.headers().contains_key(HEADER_RATE_LIMIT_LIMIT));
+        }
+
+        assert_eq!(
+            call(&app, from_peer("10.9.9.9")).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(&app, from_peer("10.9.9.9")).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
     /// Verify that the eviction logic correctly removes expired buckets.
     #[tokio::test]
     async fn eviction_removes_expired_buckets() {
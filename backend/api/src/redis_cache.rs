@@ -0,0 +1,178 @@
+// redis_cache.rs
+// Redis-backed implementation of `CacheBackend`, shared across API replicas so
+// an invalidation on one node is visible to all of them.
+// Enabled with the `redis` feature and `CACHE_BACKEND=redis`.
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+use crate::cache::{CacheBackend, ABI_TTL, GENERIC_TTL, VERIFICATION_TTL};
+
+const KEY_PREFIX: &str = "soroban-registry";
+
+pub struct RedisBackend {
+    client: redis::Client,
+    connection: OnceCell<ConnectionManager>,
+}
+
+impl RedisBackend {
+    /// Validates `url`; the connection itself is established lazily on first use.
+    pub fn new(url: &str) -> redis::RedisResult<Self> {
+        Ok(Self {
+            client: redis::Client::open(url)?,
+            connection: OnceCell::new(),
+        })
+    }
+
+    async fn connection(&self) -> Option<ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await
+            .map_err(|err| tracing::warn!(error = %err, "redis cache connection failed"))
+            .ok()
+            .cloned()
+    }
+
+    async fn get_key(&self, key: &str) -> Option<String> {
+        let mut conn = self.connection().await?;
+        conn.get::<_, Option<String>>(key)
+            .await
+            .map_err(|err| tracing::warn!(error = %err, key, "redis cache GET failed"))
+            .ok()
+            .flatten()
+    }
+
+    async fn set_key(&self, key: &str, value: String, ttl: Duration) {
+        let Some(mut conn) = self.connection().await else {
+            return;
+        };
+        let ttl_secs = ttl.as_secs().max(1);
+        if let Err(err) = conn.set_ex::<_, _, ()>(key, value, ttl_secs).await {
+            tracing::warn!(error = %err, key, "redis cache SET failed");
+        }
+    }
+
+    async fn delete_keys(&self, keys: &[String]) {
+        if keys.is_empty() {
+            return;
+        }
+        let Some(mut conn) = self.connection().await else {
+            return;
+        };
+        if let Err(err) = conn.del::<_, ()>(keys).await {
+            tracing::warn!(error = %err, "redis cache DEL failed");
+        }
+    }
+}
+
+fn abi_key(contract_id: &str) -> String {
+    format!("{}:abi:{}", KEY_PREFIX, contract_id)
+}
+
+fn verification_key(bytecode_hash: &str) -> String {
+    format!("{}:verification:{}", KEY_PREFIX, bytecode_hash)
+}
+
+fn namespaced_key(ns: &str, key: &str) -> String {
+    format!("{}:ns:{}:{}", KEY_PREFIX, ns, key)
+}
+
+/// SCAN pattern matching every key in `ns`, with glob metacharacters escaped.
+fn namespace_pattern(ns: &str) -> String {
+    let mut escaped = String::with_capacity(ns.len());
+    for c in ns.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    format!("{}:ns:{}:*", KEY_PREFIX, escaped)
+}
+
+#[async_trait]
+impl CacheBackend for RedisBackend {
+    async fn get_abi(&self, contract_id: &str) -> Option<String> {
+        self.get_key(&abi_key(contract_id)).await
+    }
+
+    async fn put_abi(&self, contract_id: &str, abi: String) {
+        self.set_key(&abi_key(contract_id), abi, ABI_TTL).await;
+    }
+
+    async fn invalidate_abi(&self, contract_id: &str) {
+        self.delete_keys(&[abi_key(contract_id)]).await;
+    }
+
+    async fn get_verification(&self, bytecode_hash: &str) -> Option<String> {
+        self.get_key(&verification_key(bytecode_hash)).await
+    }
+
+    async fn put_verification(&self, bytecode_hash: &str, result: String) {
+        self.set_key(&verification_key(bytecode_hash), result, VERIFICATION_TTL)
+            .await;
+    }
+
+    async fn invalidate_verification(&self, bytecode_hash: &str) {
+        self.delete_keys(&[verification_key(bytecode_hash)]).await;
+    }
+
+    async fn get(&self, ns: &str, key: &str) -> Option<String> {
+        self.get_key(&namespaced_key(ns, key)).await
+    }
+
+    async fn put(&self, ns: &str, key: &str, value: String, ttl: Option<Duration>) {
+        self.set_key(&namespaced_key(ns, key), value, ttl.unwrap_or(GENERIC_TTL))
+            .await;
+    }
+
+    async fn invalidate(&self, ns: &str, key: &str) {
+        self.delete_keys(&[namespaced_key(ns, key)]).await;
+    }
+
+    async fn invalidate_namespace(&self, ns: &str) -> usize {
+        let Some(mut conn) = self.connection().await else {
+            return 0;
+        };
+
+        let keys: Vec<String> = match conn.scan_match::<_, String>(namespace_pattern(ns)).await {
+            Ok(mut iter) => {
+                let mut keys = Vec::new();
+                while let Some(key) = iter.next_item().await {
+                    keys.push(key);
+                }
+                keys
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, ns, "redis cache SCAN failed");
+                return 0;
+            }
+        };
+
+        for chunk in keys.chunks(500) {
+            self.delete_keys(chunk).await;
+        }
+
+        keys.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_prefixed_per_cache() {
+        assert_eq!(abi_key("C1"), "soroban-registry:abi:C1");
+        assert_eq!(verification_key("h1"), "soroban-registry:verification:h1");
+        assert_eq!(namespaced_key("graph", "all"), "soroban-registry:ns:graph:all");
+    }
+
+    #[test]
+    fn namespace_pattern_escapes_glob_characters() {
+        assert_eq!(namespace_pattern("graph"), "soroban-registry:ns:graph:*");
+        assert_eq!(namespace_pattern("a*b"), "soroban-registry:ns:a\\*b:*");
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{rejection::JsonRejection, Path, State},
     Json,
 };
 use chrono::Utc;
@@ -11,6 +11,7 @@ use shared::{
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::handlers::map_json_rejection;
 use crate::state::AppState;
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -73,8 +74,9 @@ pub async fn list_release_notes(
 pub async fn generate_release_notes(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(req): Json<GenerateReleaseNotesRequest>,
+    payload: Result<Json<GenerateReleaseNotesRequest>, JsonRejection>,
 ) -> ApiResult<Json<ReleaseNotesResponse>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let (contract_uuid, contract_id) = fetch_contract_identity(&state, &id).await?;
 
     // Validate the requested version is valid semver
@@ -206,8 +208,9 @@ pub async fn generate_release_notes(
 pub async fn update_release_notes(
     State(state): State<AppState>,
     Path((id, version)): Path<(String, String)>,
-    Json(req): Json<UpdateReleaseNotesRequest>,
+    payload: Result<Json<UpdateReleaseNotesRequest>, JsonRejection>,
 ) -> ApiResult<Json<ReleaseNotesResponse>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let (contract_uuid, _contract_id) = fetch_contract_identity(&state, &id).await?;
 
     // Ensure the record exists and is in draft status
@@ -264,8 +267,9 @@ pub async fn update_release_notes(
 pub async fn publish_release_notes(
     State(state): State<AppState>,
     Path((id, version)): Path<(String, String)>,
-    Json(req): Json<PublishReleaseNotesRequest>,
+    payload: Result<Json<PublishReleaseNotesRequest>, JsonRejection>,
 ) -> ApiResult<Json<ReleaseNotesResponse>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
     let (contract_uuid, _contract_id) = fetch_contract_identity(&state, &id).await?;
 
     let existing = sqlx::query_as::<_, ReleaseNotesGenerated>(
@@ -790,8 +794,7 @@ async fn fetch_contract_identity(state: &AppState, id: &str) -> ApiResult<(Uuid,
 }
 
 fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(operation = operation, error = ?err, "database operation failed");
-    ApiError::internal("Database operation failed")
+    ApiError::database(operation, err)
 }
 
 #[cfg(test)]
@@ -0,0 +1,102 @@
+// api/src/request_timeout.rs
+// Global limit on how long a request may take. A handler still running when
+// REQUEST_TIMEOUT_SECS elapses is dropped and the client gets a 503
+// `RequestTimeout`, so a stuck query or upstream call cannot hold a connection
+// open indefinitely or stall the drain on shutdown.
+
+use axum::http::StatusCode;
+use std::time::Duration;
+use tower::BoxError;
+
+use crate::error::ApiError;
+
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Read `REQUEST_TIMEOUT_SECS`, falling back to the default when unset or not
+/// a positive number.
+pub fn request_timeout_from_env() -> Duration {
+    request_timeout_from_lookup(|name| std::env::var(name).ok())
+}
+
+fn request_timeout_from_lookup(var: impl Fn(&str) -> Option<String>) -> Duration {
+    Duration::from_secs(
+        var("REQUEST_TIMEOUT_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS),
+    )
+}
+
+/// Turn errors from the `TimeoutLayer` stack into API responses.
+pub async fn handle_timeout_error(err: BoxError) -> ApiError {
+    if err.is::<tower::timeout::error::Elapsed>() {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "RequestTimeout",
+            "The request took too long to complete",
+        )
+    } else {
+        ApiError::internal(format!("Unhandled middleware error: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::error_handling::HandleErrorLayer;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::timeout::TimeoutLayer;
+    use tower::{ServiceBuilder, ServiceExt};
+
+    fn app(timeout: Duration) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(handle_timeout_error))
+                    .layer(TimeoutLayer::new(timeout)),
+            )
+    }
+
+    #[tokio::test]
+    async fn slow_requests_get_503() {
+        let response = app(Duration::from_millis(20))
+            .oneshot(Request::get("/slow").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "RequestTimeout");
+
+        let response = app(Duration::from_secs(1))
+            .oneshot(Request::get("/fast").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn timeout_falls_back_to_default() {
+        let timeout =
+            |value: Option<&str>| request_timeout_from_lookup(|_| value.map(str::to_string));
+
+        assert_eq!(timeout(None), Duration::from_secs(30));
+        assert_eq!(timeout(Some("0")), Duration::from_secs(30));
+        assert_eq!(timeout(Some("abc")), Duration::from_secs(30));
+        assert_eq!(timeout(Some(" 12 ")), Duration::from_secs(12));
+    }
+}
@@ -3,6 +3,10 @@
 //! Every incoming HTTP request gets a unique UUID (`X-Request-ID`), and a
 //! JSON-structured log line is emitted after the response is sent.
 //!
+//! The request runs inside an `http.request` span named after its route and
+//! carrying the contract ID, so handler and database spans nest under it when
+//! traces are exported (see `telemetry`).
+//!
 //! Health-check endpoints are intentionally skipped so they don't pollute
 //! the log stream.
 //!
@@ -11,15 +15,18 @@
 
 use axum::{
     body::Body,
-    extract::ConnectInfo,
+    extract::{ConnectInfo, MatchedPath, RawPathParams},
     http::{HeaderName, HeaderValue, Request},
     middleware::Next,
     response::Response,
 };
 use std::net::SocketAddr;
 use std::time::Instant;
+use tracing::{field, Instrument};
 use uuid::Uuid;
 
+use crate::telemetry;
+
 /// Paths that should never be logged (health checks, readiness probes, etc.)
 const SKIP_LOG_PATHS: &[&str] = &["/health", "/healthz", "/ready", "/ping", "/metrics"];
 
@@ -30,6 +37,8 @@ pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
 /// and add the `X-Request-ID` header to the response.
 pub async fn tracing_middleware(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    matched_path: Option<MatchedPath>,
+    params: Option<RawPathParams>,
     mut req: Request<Body>,
     next: Next,
 ) -> Response {
@@ -38,12 +47,29 @@ pub async fn tracing_middleware(
     let path = req.uri().path().to_owned();
     let user_ip = addr.ip().to_string();
 
+    let route = matched_path.as_ref().map_or("unmatched", |p| p.as_str());
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = %format!("{} {}", method, route),
+        otel.kind = "server",
+        request_id = %request_id,
+        http.method = %method,
+        http.route = %route,
+        http.status_code = field::Empty,
+        contract_id = field::Empty,
+    );
+    let params = params.iter().flat_map(|p| p.iter());
+    if let Some(contract_id) = telemetry::contract_id_param(route, params) {
+        span.record("contract_id", contract_id);
+    }
+
     // Inject the request ID into extensions so handlers / DB layers can read it
     req.extensions_mut().insert(RequestId(request_id.clone()));
 
     let start = Instant::now();
-    let mut response = next.run(req).await;
+    let mut response = next.run(req).instrument(span.clone()).await;
     let duration_ms = start.elapsed().as_millis() as u64;
+    span.record("http.status_code", response.status().as_u16());
 
     // Attach X-Request-ID to the response so clients can correlate logs
     if let Ok(val) = HeaderValue::from_str(&request_id) {
@@ -106,5 +132,6 @@ pub fn init_json_tracing() {
                 .unwrap_or_else(|_| "api=info,tower_http=info".into()),
         )
         .with(tracing_subscriber::fmt::layer().json()) // structured JSON output
+        .with(crate::telemetry::layer()) // OTLP export, when configured
         .init();
 }
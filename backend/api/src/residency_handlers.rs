@@ -17,8 +17,7 @@ use shared::models::{
 };
 
 fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
-    tracing::error!(context = ctx, error = %err, "database error");
-    ApiError::internal(format!("Database error during: {}", ctx))
+    ApiError::database(ctx, err)
 }
 
 fn not_found(id: Uuid) -> ApiError {
@@ -1,25 +1,36 @@
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{get, patch, post},
+    routing::{get, patch, post, MethodRouter},
     Router,
 };
 
 use crate::{
-    ab_test_handlers, activity_feed_handlers, batch_verify_handlers, breaking_changes,
-    canary_handlers, compatibility_testing_handlers, custom_metrics_handlers,
-    deprecation_handlers, handlers, auth, metrics_handler, migration_handlers,
+    ab_test_handlers, activity_feed_handlers, api_key_handlers, batch_verify_handlers,
+    breaking_changes, cache_handlers, canary_handlers, comparison_handlers, compatibility_testing_handlers,
+    custom_metrics_handlers,
+    deprecation_handlers, gas_history, handlers, auth, metrics_handler, migration_handlers,
     performance_handlers, simulation_handlers, state::AppState,
 };
 
+/// Requires a publisher API key for the handlers in `route`. Layered per
+/// handler so reads, including read-only POSTs, stay public.
+fn keyed(state: &AppState, route: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    route.route_layer(middleware::from_fn_with_state(
+        state.clone(),
+        auth::require_api_key,
+    ))
+}
+
 pub fn observability_routes() -> Router<AppState> {
     Router::new().route("/metrics", get(metrics_handler::metrics_endpoint))
 }
 
-pub fn contract_routes() -> Router<AppState> {
+pub fn contract_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         .route(
             "/api/contracts",
-            get(handlers::list_contracts).post(handlers::publish_contract),
+            get(handlers::list_contracts).merge(keyed(state, post(handlers::publish_contract))),
         )
         .route(
             "/api/contracts/trending",
@@ -29,21 +40,29 @@ pub fn contract_routes() -> Router<AppState> {
         .route("/api/contracts/:id", get(handlers::get_contract))
         .route(
             "/api/contracts/:id/metadata",
-            patch(handlers::update_contract_metadata),
+            keyed(state, patch(handlers::update_contract_metadata)),
         )
         .route(
             "/api/contracts/:id/publisher",
-            patch(handlers::change_contract_publisher),
+            keyed(state, patch(handlers::change_contract_publisher)),
         )
         .route(
             "/api/contracts/:id/status",
-            patch(handlers::update_contract_status),
+            keyed(state, patch(handlers::update_contract_status)),
         )
         .route(
             "/api/contracts/:id/audit-log",
             get(handlers::get_contract_audit_log),
         )
         .route("/api/contracts/:id/abi", get(handlers::get_contract_abi))
+        .route(
+            "/api/contracts/abi/batch",
+            post(handlers::get_contract_abis_batch),
+        )
+        .route(
+            "/api/contracts/:id/abi/diff",
+            get(breaking_changes::get_abi_diff),
+        )
         .route(
             "/api/contracts/:id/openapi.yaml",
             get(handlers::get_contract_openapi_yaml),
@@ -54,7 +73,8 @@ pub fn contract_routes() -> Router<AppState> {
         )
         .route(
             "/api/contracts/:id/versions",
-            get(handlers::get_contract_versions).post(handlers::create_contract_version),
+            get(handlers::get_contract_versions)
+                .merge(keyed(state, post(handlers::create_contract_version))),
         )
         .route(
             "/api/contracts/:id/changelog",
@@ -68,13 +88,18 @@ pub fn contract_routes() -> Router<AppState> {
             "/api/contracts/breaking-changes",
             get(breaking_changes::get_breaking_changes),
         )
+        .route(
+            "/api/contracts/compare",
+            get(comparison_handlers::compare_contracts),
+        )
         .route(
             "/api/contracts/:id/interactions",
-            get(handlers::get_contract_interactions).post(handlers::post_contract_interaction),
+            get(handlers::get_contract_interactions)
+                .merge(keyed(state, post(handlers::post_contract_interaction))),
         )
         .route(
             "/api/contracts/:id/interactions/batch",
-            post(handlers::post_contract_interactions_batch),
+            keyed(state, post(handlers::post_contract_interactions_batch)),
         )
         .route(
             "/api/contracts/:id/deprecation-info",
@@ -82,26 +107,41 @@ pub fn contract_routes() -> Router<AppState> {
         )
         .route(
             "/api/contracts/:id/deprecate",
-            post(deprecation_handlers::deprecate_contract),
+            keyed(state, post(deprecation_handlers::deprecate_contract)),
         )
         .route(
             "/api/contracts/:id/state/:key",
-            get(handlers::get_contract_state)
-                .put(handlers::update_contract_state)
-                .post(handlers::update_contract_state),
+            get(handlers::get_contract_state).merge(keyed(
+                state,
+                MethodRouter::new()
+                    .put(handlers::update_contract_state)
+                    .post(handlers::update_contract_state),
+            )),
         )
         .route(
             "/api/contracts/:id/analytics",
             get(handlers::get_contract_analytics),
         )
+        .route(
+            "/api/contracts/:id/analytics/recompute",
+            keyed(state, post(handlers::recompute_contract_analytics)),
+        )
         .route(
             "/api/contracts/:id/trust-score",
             get(handlers::get_trust_score),
         )
+        .route(
+            "/api/contracts/:id/trust-score/breakdown",
+            get(handlers::get_trust_score_breakdown),
+        )
         .route(
             "/api/contracts/:id/dependencies",
             get(handlers::get_contract_dependencies),
         )
+        .route(
+            "/api/contracts/:id/dependency-updates",
+            get(handlers::get_dependency_updates),
+        )
         .route(
             "/api/contracts/:id/dependents",
             get(handlers::get_contract_dependents),
@@ -110,10 +150,17 @@ pub fn contract_routes() -> Router<AppState> {
             "/api/contracts/:id/impact",
             get(handlers::get_impact_analysis),
         )
-        .route("/api/contracts/verify", post(handlers::verify_contract))
+        .route(
+            "/api/contracts/verify",
+            keyed(state, post(handlers::verify_contract)),
+        )
+        .route(
+            "/api/contracts/verify-on-chain",
+            keyed(state, post(handlers::verify_contract_on_chain)),
+        )
         .route(
             "/api/contracts/batch-verify",
-            post(batch_verify_handlers::batch_verify_contracts),
+            keyed(state, post(batch_verify_handlers::batch_verify_contracts)),
         )
         .route(
             "/api/contracts/:id/performance",
@@ -121,24 +168,41 @@ pub fn contract_routes() -> Router<AppState> {
         )
         .route(
             "/api/contracts/:id/metrics",
-            get(custom_metrics_handlers::get_contract_metrics)
-                .post(custom_metrics_handlers::record_contract_metric),
+            get(custom_metrics_handlers::get_contract_metrics).merge(keyed(
+                state,
+                post(custom_metrics_handlers::record_contract_metric),
+            )),
         )
         .route(
             "/api/contracts/:id/metrics/batch",
-            post(custom_metrics_handlers::record_metrics_batch),
+            keyed(state, post(custom_metrics_handlers::record_metrics_batch)),
         )
         .route(
             "/api/contracts/:id/metrics/catalog",
             get(custom_metrics_handlers::get_metric_catalog),
         )
+        .route(
+            "/api/contracts/:id/metrics/aggregate",
+            get(custom_metrics_handlers::get_metric_aggregate),
+        )
+        .route(
+            "/api/contracts/:id/metrics/histogram",
+            get(custom_metrics_handlers::get_metric_histogram),
+        )
         .route(
             "/api/contracts/:id/compatibility-matrix",
             get(compatibility_testing_handlers::get_compatibility_matrix),
         )
         .route(
             "/api/contracts/:id/compatibility-matrix/test",
-            post(compatibility_testing_handlers::run_compatibility_test),
+            keyed(
+                state,
+                post(compatibility_testing_handlers::run_compatibility_test),
+            ),
+        )
+        .route(
+            "/api/contracts/:id/compatibility-matrix/check",
+            get(compatibility_testing_handlers::check_version_compatibility),
         )
         .route(
             "/api/contracts/:id/compatibility-matrix/history",
@@ -150,38 +214,66 @@ pub fn contract_routes() -> Router<AppState> {
         )
         .route(
             "/api/contracts/:id/compatibility-matrix/notifications/read",
-            post(compatibility_testing_handlers::mark_notifications_read),
+            keyed(
+                state,
+                post(compatibility_testing_handlers::mark_notifications_read),
+            ),
         )
         .route(
             "/api/contracts/:id/deployments/status",
             get(handlers::get_deployment_status),
         )
+        .route(
+            "/api/contracts/:id/deployments/status/watch",
+            get(handlers::watch_deployment_status),
+        )
         .route(
             "/api/contracts/:id/deployment-status",
             get(handlers::get_deployment_status),
         )
-        .route("/api/deployments/green", post(handlers::deploy_green))
+        .route(
+            "/api/deployments/green",
+            keyed(state, post(handlers::deploy_green)),
+        )
         .route(
             "/api/contracts/:id/deploy-green",
-            post(handlers::deploy_green),
+            keyed(state, post(handlers::deploy_green)),
         )
         .route(
             "/api/contracts/simulate-deploy",
-            post(simulation_handlers::simulate_deploy),
+            post(simulation_handlers::simulate_deploy)
+                .layer(DefaultBodyLimit::max(crate::simulation::wasm_body_limit())),
+        )
+        .route(
+            "/api/contracts/:id/simulate-upgrade",
+            post(simulation_handlers::simulate_upgrade)
+                .layer(DefaultBodyLimit::max(crate::simulation::wasm_body_limit())),
+        )
+        .route(
+            "/api/contracts/extract-abi/stream",
+            post(simulation_handlers::extract_abi_stream)
+                .layer(DefaultBodyLimit::max(crate::simulation::wasm_body_limit())),
         )
     // TODO: backup_routes, notification_routes, and post_incident_routes
     // are available in the api library crate but need architectural refactoring
     // to be integrated with the main AppState
 }
 
-pub fn publisher_routes() -> Router<AppState> {
+pub fn publisher_routes(state: &AppState) -> Router<AppState> {
     Router::new()
-        .route("/api/publishers", post(handlers::create_publisher))
+        .route(
+            "/api/publishers",
+            keyed(state, post(handlers::create_publisher)),
+        )
         .route("/api/publishers/:id", get(handlers::get_publisher))
         .route(
             "/api/publishers/:id/contracts",
             get(handlers::get_publisher_contracts),
         )
+        .route(
+            "/api/publishers/:id/gas-summary",
+            get(gas_history::get_publisher_gas_summary),
+        )
 }
 
 pub fn health_routes() -> Router<AppState> {
@@ -219,6 +311,10 @@ pub fn migration_routes() -> Router<AppState> {
             "/api/admin/migrations/lock",
             get(migration_handlers::get_lock_status),
         )
+        .route(
+            "/api/admin/migrations/lock/release",
+            post(migration_handlers::release_migration_lock),
+        )
         .route(
             "/api/admin/migrations/:version",
             get(migration_handlers::get_migration_version),
@@ -229,6 +325,22 @@ pub fn migration_routes() -> Router<AppState> {
         )
 }
 
+pub fn cache_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/admin/cache/stats",
+            get(cache_handlers::get_cache_stats),
+        )
+        .route(
+            "/api/admin/cache/invalidate/:namespace",
+            post(cache_handlers::invalidate_cache_namespace),
+        )
+        .route(
+            "/api/admin/contracts/:id/cache/purge",
+            post(cache_handlers::purge_contract_cache),
+        )
+}
+
 pub fn compatibility_dashboard_routes() -> Router<AppState> {
     Router::new().route(
         "/api/compatibility-dashboard",
@@ -236,12 +348,13 @@ pub fn compatibility_dashboard_routes() -> Router<AppState> {
     )
 }
 
-pub fn canary_routes() -> Router<AppState> {
+pub fn canary_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         // Contract-scoped canary endpoints
         .route(
             "/api/contracts/:id/canary",
-            get(canary_handlers::list_canaries).post(canary_handlers::create_canary),
+            get(canary_handlers::list_canaries)
+                .merge(keyed(state, post(canary_handlers::create_canary))),
         )
         // Canary-specific endpoints
         .route(
@@ -250,29 +363,50 @@ pub fn canary_routes() -> Router<AppState> {
         )
         .route(
             "/api/canary/:canary_id/advance",
-            post(canary_handlers::advance_canary),
+            keyed(state, post(canary_handlers::advance_canary)),
         )
         .route(
             "/api/canary/:canary_id/rollback",
-            post(canary_handlers::rollback_canary),
+            keyed(state, post(canary_handlers::rollback_canary)),
         )
         .route(
             "/api/canary/:canary_id/complete",
-            post(canary_handlers::complete_canary),
+            keyed(state, post(canary_handlers::complete_canary)),
         )
         .route(
             "/api/canary/:canary_id/metrics",
             get(canary_handlers::list_canary_metrics)
-                .post(canary_handlers::record_canary_metric),
+                .merge(keyed(state, post(canary_handlers::record_canary_metric))),
+        )
+        .route(
+            "/api/canary/:canary_id/metrics/summary",
+            get(canary_handlers::get_canary_metrics_summary),
+        )
+        .route(
+            "/api/canary/:canary_id/analysis",
+            get(canary_handlers::get_canary_analysis),
+        )
+        .route(
+            "/api/canary/:canary_id/history",
+            get(canary_handlers::list_canary_history),
+        )
+        .route(
+            "/api/canary/:canary_id/comparison",
+            get(canary_handlers::get_canary_comparison),
+        )
+        .route(
+            "/api/canary/:canary_id/stream",
+            get(canary_handlers::stream_canary),
         )
 }
 
-pub fn ab_test_routes() -> Router<AppState> {
+pub fn ab_test_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         // Contract-scoped A/B test endpoints
         .route(
             "/api/contracts/:id/ab-tests",
-            get(ab_test_handlers::list_ab_tests).post(ab_test_handlers::create_ab_test),
+            get(ab_test_handlers::list_ab_tests)
+                .merge(keyed(state, post(ab_test_handlers::create_ab_test))),
         )
         // A/B test-specific endpoints
         .route(
@@ -281,33 +415,53 @@ pub fn ab_test_routes() -> Router<AppState> {
         )
         .route(
             "/api/ab-tests/:test_id/start",
-            post(ab_test_handlers::start_ab_test),
+            keyed(state, post(ab_test_handlers::start_ab_test)),
         )
         .route(
             "/api/ab-tests/:test_id/stop",
-            post(ab_test_handlers::stop_ab_test),
+            keyed(state, post(ab_test_handlers::stop_ab_test)),
         )
         .route(
             "/api/ab-tests/:test_id/cancel",
-            post(ab_test_handlers::cancel_ab_test),
+            keyed(state, post(ab_test_handlers::cancel_ab_test)),
         )
         .route(
             "/api/ab-tests/:test_id/metrics",
-            post(ab_test_handlers::record_ab_test_metric),
+            keyed(state, post(ab_test_handlers::record_ab_test_metric)),
+        )
+        .route(
+            "/api/ab-tests/:test_id/metrics/export",
+            get(ab_test_handlers::export_ab_test_metrics),
         )
         .route(
             "/api/ab-tests/:test_id/results",
             get(ab_test_handlers::get_ab_test_results),
         )
+        .route(
+            "/api/ab-tests/:test_id/assignments/:user_address",
+            get(ab_test_handlers::get_user_assignment),
+        )
 }
 
-pub fn performance_routes() -> Router<AppState> {
+pub fn performance_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         // Contract-scoped performance endpoints
         .route(
             "/api/contracts/:id/perf/metrics",
             get(performance_handlers::list_metrics)
-                .post(performance_handlers::record_metric),
+                .merge(keyed(state, post(performance_handlers::record_metric))),
+        )
+        .route(
+            "/api/contracts/:id/perf/metrics/samples",
+            keyed(state, post(performance_handlers::record_metric_samples)),
+        )
+        .route(
+            "/api/contracts/:id/perf/metrics/aggregate",
+            get(performance_handlers::aggregate_metrics),
+        )
+        .route(
+            "/api/contracts/:id/perf/metrics/prometheus",
+            get(performance_handlers::export_metrics_prometheus),
         )
         .route(
             "/api/contracts/:id/perf/anomalies",
@@ -319,8 +473,22 @@ pub fn performance_routes() -> Router<AppState> {
         )
         .route(
             "/api/contracts/:id/perf/alert-configs",
-            get(performance_handlers::list_alert_configs)
-                .post(performance_handlers::create_alert_config),
+            get(performance_handlers::list_alert_configs).merge(keyed(
+                state,
+                post(performance_handlers::create_alert_config),
+            )),
+        )
+        .route(
+            "/api/contracts/:id/perf/alert-configs/:config_id",
+            keyed(
+                state,
+                patch(performance_handlers::update_alert_config)
+                    .delete(performance_handlers::delete_alert_config),
+            ),
+        )
+        .route(
+            "/api/contracts/:id/perf/alert-configs/evaluate",
+            post(performance_handlers::evaluate_alert_configs),
         )
         .route(
             "/api/contracts/:id/perf/trends",
@@ -330,20 +498,104 @@ pub fn performance_routes() -> Router<AppState> {
             "/api/contracts/:id/perf/summary",
             get(performance_handlers::get_performance_summary),
         )
+        .route(
+            "/api/contracts/:id/perf/regression",
+            get(performance_handlers::get_version_regression),
+        )
         // Alert-specific action endpoints
         .route(
             "/api/perf/alerts/:alert_id/acknowledge",
-            post(performance_handlers::acknowledge_alert),
+            keyed(state, post(performance_handlers::acknowledge_alert)),
         )
         .route(
             "/api/perf/alerts/:alert_id/resolve",
-            post(performance_handlers::resolve_alert),
+            keyed(state, post(performance_handlers::resolve_alert)),
         )
 }
 
 pub fn admin_routes() -> Router<AppState> {
     Router::new()
         .route("/api/admin/audit-logs", get(handlers::get_all_audit_logs))
+        .route("/api/admin/api-keys", post(api_key_handlers::issue_api_key))
+        .route(
+            "/api/admin/background-jobs",
+            get(crate::background_jobs::get_background_job_status),
+        )
         .merge(migration_routes())
+        .merge(cache_admin_routes())
         .route_layer(middleware::from_fn(auth::require_admin))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request, http::StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn only_write_handlers_require_an_api_key() {
+        let db = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let state = crate::test_support::test_state(db);
+        let app = Router::new()
+            .merge(contract_routes(&state))
+            .merge(publisher_routes(&state))
+            .merge(canary_routes(&state))
+            .merge(ab_test_routes(&state))
+            .merge(performance_routes(&state))
+            .with_state(state);
+        let id = uuid::Uuid::nil();
+
+        let status = |method: &'static str, uri: String| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap();
+                app.oneshot(request).await.unwrap().status()
+            }
+        };
+
+        for (method, uri) in [
+            ("POST", "/api/publishers".to_string()),
+            ("POST", "/api/contracts".to_string()),
+            ("PATCH", format!("/api/contracts/{}/status", id)),
+            ("PUT", format!("/api/contracts/{}/state/k", id)),
+            ("POST", format!("/api/canary/{}/metrics", id)),
+            ("POST", format!("/api/ab-tests/{}/metrics", id)),
+            (
+                "DELETE",
+                format!("/api/contracts/{}/perf/alert-configs/{}", id, id),
+            ),
+        ] {
+            assert_eq!(
+                status(method, uri.clone()).await,
+                StatusCode::UNAUTHORIZED,
+                "{} {}",
+                method,
+                uri
+            );
+        }
+
+        for (method, uri) in [
+            ("POST", "/api/contracts/abi/batch".to_string()),
+            ("POST", "/api/contracts/simulate-deploy".to_string()),
+            ("POST", "/api/contracts/extract-abi/stream".to_string()),
+            (
+                "POST",
+                format!("/api/contracts/{}/perf/alert-configs/evaluate", id),
+            ),
+            ("GET", format!("/api/contracts/{}/state/k", id)),
+        ] {
+            assert_ne!(
+                status(method, uri.clone()).await,
+                StatusCode::UNAUTHORIZED,
+                "{} {}",
+                method,
+                uri
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use contract_abi::{types::SorobanType, RawContractSpec};
 use serde::{Deserialize, Serialize};
 
+use super::contract_spec::{decode_spec, find_spec_section, SpecFunction, CONTRACT_SPEC_SECTION};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AbiExtractionResult {
     pub success: bool,
@@ -17,87 +18,241 @@ pub struct FunctionInfo {
     pub is_view: bool,
 }
 
-pub fn extract_abi(wasm_bytes: &[u8]) -> AbiExtractionResult {
-    let mut errors = Vec::new();
-    let mut functions = Vec::new();
-    let mut types = Vec::new();
-
-    // Try to parse as contract spec JSON first
-    // In a real implementation, we would use soroban-sdk to extract WASM metadata
-    // For now, we'll use a basic approach based on WASM structure analysis
-
-    // Basic WASM analysis to infer function signatures
-    // This is a simplified implementation - full ABI extraction would require
-    // access to the compiled contract's metadata
-
-    // Check if we can find any embedded contract spec
-    if let Ok(spec) = extract_embedded_spec(wasm_bytes) {
-        for func in spec.functions {
-            types.push(func.name.clone());
-            functions.push(FunctionInfo {
-                name: func.name,
-                param_count: func.param_count,
-                return_type: func.return_type,
-                is_view: func.is_view,
-            });
+impl From<SpecFunction> for FunctionInfo {
+    fn from(func: SpecFunction) -> Self {
+        Self {
+            is_view: is_view_function(&func.name),
+            param_count: func.inputs.len() as u32,
+            return_type: Some(
+                func.output
+                    .map(|output| output.display_name())
+                    .unwrap_or_else(|| "void".to_string()),
+            ),
+            name: func.name,
         }
+    }
+}
+
+/// Extract the contract's interface from its `contractspecv0` section. Only
+/// modules without one fall back to guessing functions from the strings in
+/// the binary; a spec that doesn't decode is reported as an error.
+pub fn extract_abi(wasm_bytes: &[u8]) -> AbiExtractionResult {
+    let Some(spec) = find_spec_section(wasm_bytes) else {
+        let text = String::from_utf8_lossy(wasm_bytes);
+        let functions = guess_functions(|func_name| text.contains(func_name));
         return AbiExtractionResult {
             success: true,
-            errors,
+            errors: Vec::new(),
+            types: functions.iter().map(|f| f.name.clone()).collect(),
             functions,
-            types,
         };
+    };
+
+    match decode_spec(&spec) {
+        Ok(spec) => AbiExtractionResult {
+            success: true,
+            errors: Vec::new(),
+            functions: spec.functions.into_iter().map(FunctionInfo::from).collect(),
+            types: spec.types,
+        },
+        Err(message) => AbiExtractionResult {
+            success: false,
+            errors: vec![message],
+            functions: Vec::new(),
+            types: Vec::new(),
+        },
     }
+}
 
-    // If no embedded spec found, return basic info
-    // In production, this would connect to a full Soroban toolchain
-    AbiExtractionResult {
-        success: true,
-        errors,
-        functions,
-        types,
+/// Known common Soroban contract function patterns
+const COMMON_FUNCS: [&str; 9] = [
+    "init",
+    "set_admin",
+    "get_admin",
+    "transfer",
+    "balance",
+    "mint",
+    "burn",
+    "vote",
+    "proposal",
+];
+
+/// Heuristic fallback for modules without a contract spec: the common
+/// function names `present` says appear in the binary, with guessed
+/// signatures.
+fn guess_functions(present: impl Fn(&str) -> bool) -> Vec<FunctionInfo> {
+    COMMON_FUNCS
+        .iter()
+        .filter(|func_name| present(func_name))
+        .map(|func_name| FunctionInfo {
+            name: func_name.to_string(),
+            param_count: guess_param_count(func_name),
+            return_type: guess_return_type(func_name),
+            is_view: is_view_function(func_name),
+        })
+        .collect()
+}
+
+/// Event emitted by [`extract_abi_chunked`] while it walks a module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AbiExtractionEvent {
+    Progress {
+        sections_scanned: usize,
+        functions_found: usize,
+    },
+    Error {
+        section_index: usize,
+        message: String,
+    },
+    Complete {
+        abi: AbiExtractionResult,
+    },
+}
+
+impl AbiExtractionEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Progress { .. } => "progress",
+            Self::Error { .. } => "error",
+            Self::Complete { .. } => "complete",
+        }
     }
 }
 
-fn extract_embedded_spec(wasm_bytes: &[u8]) -> Result<ExtractedSpec, String> {
-    // Look for contract spec in WASM custom sections
-    // This is a placeholder - real implementation would use full WASM introspection
-
-    // Try to find any JSON-like data in the WASM
-    let wasm_str = String::from_utf8_lossy(wasm_bytes);
-
-    // Basic heuristics for contract functions
-    let mut functions = Vec::new();
-
-    // Known common Soroban contract function patterns
-    let common_funcs = [
-        "init",
-        "set_admin",
-        "get_admin",
-        "transfer",
-        "balance",
-        "mint",
-        "burn",
-        "vote",
-        "proposal",
-    ];
-
-    for func_name in common_funcs {
-        if wasm_str.contains(func_name) {
-            functions.push(ExtractedFunction {
-                name: func_name.to_string(),
-                param_count: guess_param_count(func_name),
-                return_type: guess_return_type(func_name),
-                is_view: is_view_function(func_name),
+/// Section-by-section variant of [`extract_abi`] for large contracts.
+///
+/// Runs the same function detection over each section in turn, reporting a
+/// progress event after every section. A malformed custom section or contract
+/// spec produces an error event and is skipped; a framing error that makes the
+/// rest of the module unreadable ends the scan. The last event is always
+/// `Complete`.
+pub fn extract_abi_chunked(wasm_bytes: &[u8], mut on_event: impl FnMut(AbiExtractionEvent)) {
+    let mut errors = Vec::new();
+    let mut found = vec![false; COMMON_FUNCS.len()];
+    let mut spec: Option<(Vec<FunctionInfo>, Vec<String>)> = None;
+    let mut spec_failed = false;
+    let mut sections_scanned = 0;
+
+    let mut report_error = |section_index: usize, message: String| {
+        errors.push(format!("section {}: {}", section_index, message));
+        AbiExtractionEvent::Error {
+            section_index,
+            message,
+        }
+    };
+
+    if wasm_bytes.len() < 8 || wasm_bytes[0..4] != [0x00, 0x61, 0x73, 0x6d] {
+        on_event(report_error(0, "Missing WASM module header".to_string()));
+    } else {
+        let mut offset = 8;
+        while offset < wasm_bytes.len() {
+            let section_index = sections_scanned;
+            let section_id = wasm_bytes[offset];
+            let (size, size_len) = match read_leb128_u32(&wasm_bytes[offset + 1..]) {
+                Some((size, len)) => (size as usize, len),
+                None => {
+                    on_event(report_error(
+                        section_index,
+                        "Invalid section size".to_string(),
+                    ));
+                    break;
+                }
+            };
+            let start = offset + 1 + size_len;
+            let Some(payload) = wasm_bytes.get(start..start + size) else {
+                on_event(report_error(
+                    section_index,
+                    format!("Section length {} exceeds module size", size),
+                ));
+                break;
+            };
+            offset = start + size;
+            sections_scanned += 1;
+
+            if section_id == 0 {
+                let spec_data = match custom_section_name(payload) {
+                    Ok((name, data)) if name == CONTRACT_SPEC_SECTION => Some(data),
+                    Ok(_) => None,
+                    Err(message) => {
+                        on_event(report_error(section_index, message));
+                        continue;
+                    }
+                };
+                if let Some(data) = spec_data {
+                    let (functions, types) = spec.get_or_insert_with(Default::default);
+                    match decode_spec(data) {
+                        Ok(decoded) => {
+                            functions.extend(decoded.functions.into_iter().map(FunctionInfo::from));
+                            types.extend(decoded.types);
+                        }
+                        Err(message) => {
+                            spec_failed = true;
+                            on_event(report_error(section_index, message));
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            let text = String::from_utf8_lossy(payload);
+            for (seen, func_name) in found.iter_mut().zip(COMMON_FUNCS) {
+                *seen |= text.contains(func_name);
+            }
+
+            on_event(AbiExtractionEvent::Progress {
+                sections_scanned,
+                functions_found: match &spec {
+                    Some((functions, _)) => functions.len(),
+                    None => found.iter().filter(|seen| **seen).count(),
+                },
             });
         }
     }
 
-    if functions.is_empty() {
-        return Err("No contract functions detected".to_string());
-    }
+    let (functions, types) = spec.unwrap_or_else(|| {
+        let functions = guess_functions(|func_name| {
+            COMMON_FUNCS
+                .iter()
+                .position(|name| *name == func_name)
+                .is_some_and(|i| found[i])
+        });
+        let types = functions.iter().map(|f| f.name.clone()).collect();
+        (functions, types)
+    });
+
+    on_event(AbiExtractionEvent::Complete {
+        abi: AbiExtractionResult {
+            success: !spec_failed,
+            errors,
+            functions,
+            types,
+        },
+    });
+}
 
-    Ok(ExtractedSpec { functions })
+/// Split a custom section's payload into its name and data
+fn custom_section_name(payload: &[u8]) -> Result<(&str, &[u8]), String> {
+    let (len, len_size) =
+        read_leb128_u32(payload).ok_or_else(|| "Invalid custom section name".to_string())?;
+    let name_end = len_size + len as usize;
+    let name = payload
+        .get(len_size..name_end)
+        .ok_or_else(|| "Custom section name exceeds section size".to_string())?;
+    let name = std::str::from_utf8(name)
+        .map_err(|_| "Custom section name is not valid UTF-8".to_string())?;
+    Ok((name, &payload[name_end..]))
+}
+
+fn read_leb128_u32(bytes: &[u8]) -> Option<(u32, usize)> {
+    let mut result: u32 = 0;
+    for (i, byte) in bytes.iter().take(5).enumerate() {
+        result |= u32::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((result, i + 1));
+        }
+    }
+    None
 }
 
 fn guess_param_count(func_name: &str) -> u32 {
@@ -121,15 +276,164 @@ fn is_view_function(func_name: &str) -> bool {
     matches!(func_name, "get_admin" | "balance")
 }
 
-#[derive(Debug, Clone)]
-struct ExtractedSpec {
-    functions: Vec<ExtractedFunction>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![id, payload.len() as u8];
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut payload = vec![name.len() as u8];
+        payload.extend_from_slice(name.as_bytes());
+        payload.extend_from_slice(data);
+        section(0, &payload)
+    }
 
-#[derive(Debug, Clone)]
-struct ExtractedFunction {
-    name: String,
-    param_count: u32,
-    return_type: Option<String>,
-    is_view: bool,
+    /// A token-style contract exporting `transfer` and `balance`, without a
+    /// contract spec but mentioning `mint` in a custom section.
+    fn token_contract() -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: one `() -> ()` signature
+        wasm.extend(section(0x01, &[0x01, 0x60, 0x00, 0x00]));
+        // Function section: two functions of type 0
+        wasm.extend(section(0x03, &[0x02, 0x00, 0x00]));
+        // Export section: `transfer` -> func 0, `balance` -> func 1
+        let mut exports = vec![0x02, 0x08];
+        exports.extend_from_slice(b"transfer");
+        exports.extend_from_slice(&[0x00, 0x00, 0x07]);
+        exports.extend_from_slice(b"balance");
+        exports.extend_from_slice(&[0x00, 0x01]);
+        wasm.extend(section(0x07, &exports));
+        // Code section: two empty bodies
+        wasm.extend(section(0x0a, &[0x02, 0x02, 0x00, 0x0b, 0x02, 0x00, 0x0b]));
+        wasm.extend(custom_section("name", b"fn mint"));
+        wasm
+    }
+
+    /// Counter contract with a constructor, an `Error` enum and functions
+    /// covering options, results, vectors and tuples in its spec. The spec
+    /// is soroban-sdk's own output; see `tests/fixtures/counter`.
+    const COUNTER_WASM: &[u8] = include_bytes!("../../tests/fixtures/counter.wasm");
+
+    fn collect(wasm: &[u8]) -> Vec<AbiExtractionEvent> {
+        let mut events = Vec::new();
+        extract_abi_chunked(wasm, |event| events.push(event));
+        events
+    }
+
+    #[test]
+    fn chunked_extraction_reports_progress_before_complete() {
+        let wasm = token_contract();
+        let events = collect(&wasm);
+
+        let (last, progress) = events.split_last().unwrap();
+        assert_eq!(progress.len(), 5);
+        assert!(progress
+            .iter()
+            .all(|event| matches!(event, AbiExtractionEvent::Progress { .. })));
+        assert!(matches!(
+            progress.last(),
+            Some(AbiExtractionEvent::Progress {
+                sections_scanned: 5,
+                functions_found: 3,
+            })
+        ));
+
+        let AbiExtractionEvent::Complete { abi } = last else {
+            panic!("expected complete event, got {:?}", last);
+        };
+        let names: Vec<_> = abi.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["transfer", "balance", "mint"]);
+        let whole: Vec<_> = extract_abi(&wasm)
+            .functions
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(names, whole);
+    }
+
+    #[test]
+    fn functions_are_read_from_the_contract_spec() {
+        let abi = extract_abi(COUNTER_WASM);
+        assert!(abi.success, "errors: {:?}", abi.errors);
+        assert_eq!(abi.types, vec!["Error"]);
+
+        let signatures: Vec<_> = abi
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.param_count, f.return_type.as_deref()))
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                ("__constructor", 2, Some("void")),
+                ("increment", 1, Some("Result<u32, Error>")),
+                ("get", 0, Some("u32")),
+                ("reset", 1, Some("void")),
+                ("history", 1, Some("Vec<(u64, u32)>")),
+            ]
+        );
+
+        let events = collect(COUNTER_WASM);
+        let Some(AbiExtractionEvent::Complete { abi: chunked }) = events.last() else {
+            panic!("expected complete event");
+        };
+        let names: Vec<_> = chunked.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, signatures.iter().map(|s| s.0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn undecodable_spec_is_an_error_not_a_guess() {
+        let mut wasm = token_contract();
+        wasm.extend(custom_section("contractspecv0", b"fn mint"));
+
+        let abi = extract_abi(&wasm);
+        assert!(!abi.success);
+        assert_eq!(abi.errors.len(), 1);
+        assert!(abi.functions.is_empty());
+    }
+
+    #[test]
+    fn malformed_custom_section_emits_error_and_continues() {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Custom section whose name length runs past the section end
+        wasm.extend(section(0x00, &[0x20, b'x']));
+        wasm.extend(custom_section("name", b"balance"));
+
+        let events = collect(&wasm);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[0],
+            AbiExtractionEvent::Error {
+                section_index: 0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[1],
+            AbiExtractionEvent::Progress {
+                sections_scanned: 2,
+                functions_found: 1,
+            }
+        ));
+        let AbiExtractionEvent::Complete { abi } = &events[2] else {
+            panic!("expected complete event");
+        };
+        assert_eq!(abi.errors.len(), 1);
+        assert_eq!(abi.functions[0].name, "balance");
+    }
+
+    #[test]
+    fn truncated_module_still_completes() {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        wasm.extend_from_slice(&[0x01, 0x40, 0x01]);
+
+        let events = collect(&wasm);
+        assert!(matches!(events[0], AbiExtractionEvent::Error { .. }));
+        assert!(matches!(events[1], AbiExtractionEvent::Complete { .. }));
+    }
 }
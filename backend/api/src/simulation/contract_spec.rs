@@ -0,0 +1,342 @@
+use contract_abi::types::SorobanType;
+use wasmparser::{Parser, Payload};
+
+/// Custom section the Soroban SDK writes the contract's interface to, as a
+/// stream of XDR `ScSpecEntry` values.
+pub const CONTRACT_SPEC_SECTION: &str = "contractspecv0";
+
+/// `ScSpecEntryKind` discriminants
+const ENTRY_FUNCTION: u32 = 0;
+const ENTRY_UDT_STRUCT: u32 = 1;
+const ENTRY_UDT_UNION: u32 = 2;
+const ENTRY_UDT_ENUM: u32 = 3;
+const ENTRY_UDT_ERROR_ENUM: u32 = 4;
+const ENTRY_EVENT: u32 = 5;
+
+/// Nesting limit for type definitions, so a crafted spec can't recurse deeply
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// A function declared in the contract spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecFunction {
+    pub name: String,
+    pub inputs: Vec<(String, SorobanType)>,
+    pub output: Option<SorobanType>,
+}
+
+/// The functions and user-defined type names declared in a contract spec
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractSpec {
+    pub functions: Vec<SpecFunction>,
+    pub types: Vec<String>,
+}
+
+/// The contents of the module's `contractspecv0` sections, or `None` when it
+/// has none. The linker may leave one section per crate, so all of them are
+/// concatenated.
+pub fn find_spec_section(wasm_bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut spec: Option<Vec<u8>> = None;
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        match payload {
+            Ok(Payload::CustomSection(c)) if c.name() == CONTRACT_SPEC_SECTION => {
+                spec.get_or_insert_with(Vec::new)
+                    .extend_from_slice(c.data());
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    spec
+}
+
+/// Decode the `ScSpecEntry` stream of a `contractspecv0` section.
+pub fn decode_spec(data: &[u8]) -> Result<ContractSpec, String> {
+    let mut reader = XdrReader { data, offset: 0 };
+    let mut spec = ContractSpec::default();
+    while !reader.is_empty() {
+        let entry_offset = reader.offset;
+        let kind = reader.u32()?;
+        match kind {
+            ENTRY_FUNCTION => spec.functions.push(reader.function()?),
+            ENTRY_UDT_STRUCT => spec.types.push(reader.udt_struct()?),
+            ENTRY_UDT_UNION => spec.types.push(reader.udt_union()?),
+            ENTRY_UDT_ENUM | ENTRY_UDT_ERROR_ENUM => spec.types.push(reader.udt_enum()?),
+            ENTRY_EVENT => reader.event()?,
+            // Entries carry no length prefix, so nothing after one can be read
+            _ => {
+                return Err(format!(
+                    "Unknown spec entry kind {} at byte {}",
+                    kind, entry_offset
+                ))
+            }
+        }
+    }
+    Ok(spec)
+}
+
+struct XdrReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl XdrReader<'_> {
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.offset..end))
+            .ok_or_else(|| format!("Contract spec truncated at byte {}", self.offset))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Variable-length opaque data, padded to a multiple of 4 bytes
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        let padded = len.checked_add(3).map(|n| n & !3).unwrap_or(usize::MAX);
+        let bytes = self.take(padded)?;
+        Ok(String::from_utf8_lossy(&bytes[..len]).into_owned())
+    }
+
+    fn count(&mut self) -> Result<usize, String> {
+        let count = self.u32()? as usize;
+        // Every element takes at least 4 bytes, which bounds bogus counts
+        if count > (self.data.len() - self.offset) / 4 {
+            return Err(format!(
+                "Contract spec array of {} elements at byte {} exceeds section size",
+                count, self.offset
+            ));
+        }
+        Ok(count)
+    }
+
+    /// `ScSpecFunctionV0`
+    fn function(&mut self) -> Result<SpecFunction, String> {
+        self.string()?; // doc
+        let name = self.string()?;
+        let mut inputs = Vec::new();
+        for _ in 0..self.count()? {
+            self.string()?; // doc
+            let input_name = self.string()?;
+            inputs.push((input_name, self.type_def(0)?));
+        }
+        let mut output = None;
+        for _ in 0..self.count()? {
+            output = Some(self.type_def(0)?);
+        }
+        Ok(SpecFunction {
+            name,
+            inputs,
+            output,
+        })
+    }
+
+    /// `ScSpecUDTStructV0`, returning its name
+    fn udt_struct(&mut self) -> Result<String, String> {
+        self.string()?; // doc
+        self.string()?; // lib
+        let name = self.string()?;
+        for _ in 0..self.count()? {
+            self.string()?; // doc
+            self.string()?; // name
+            self.type_def(0)?;
+        }
+        Ok(name)
+    }
+
+    /// `ScSpecUDTUnionV0`, returning its name
+    fn udt_union(&mut self) -> Result<String, String> {
+        self.string()?; // doc
+        self.string()?; // lib
+        let name = self.string()?;
+        for _ in 0..self.count()? {
+            let case_kind = self.u32()?;
+            self.string()?; // doc
+            self.string()?; // name
+            match case_kind {
+                0 => {}
+                1 => {
+                    for _ in 0..self.count()? {
+                        self.type_def(0)?;
+                    }
+                }
+                other => return Err(format!("Unknown union case kind {} in {}", other, name)),
+            }
+        }
+        Ok(name)
+    }
+
+    /// `ScSpecUDTEnumV0` or `ScSpecUDTErrorEnumV0`, returning its name
+    fn udt_enum(&mut self) -> Result<String, String> {
+        self.string()?; // doc
+        self.string()?; // lib
+        let name = self.string()?;
+        for _ in 0..self.count()? {
+            self.string()?; // doc
+            self.string()?; // name
+            self.u32()?; // value
+        }
+        Ok(name)
+    }
+
+    /// `ScSpecEventV0`, which only needs skipping
+    fn event(&mut self) -> Result<(), String> {
+        self.string()?; // doc
+        self.string()?; // lib
+        self.string()?; // name
+        for _ in 0..self.count()? {
+            self.string()?; // prefix topic
+        }
+        for _ in 0..self.count()? {
+            self.string()?; // doc
+            self.string()?; // name
+            self.type_def(0)?;
+            self.u32()?; // location
+        }
+        self.u32()?; // data format
+        Ok(())
+    }
+
+    /// `ScSpecTypeDef`
+    fn type_def(&mut self, depth: usize) -> Result<SorobanType, String> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(format!(
+                "Contract spec type nested deeper than {} levels",
+                MAX_TYPE_DEPTH
+            ));
+        }
+        let boxed = |reader: &mut Self| reader.type_def(depth + 1).map(Box::new);
+        let custom = |name: &str| SorobanType::Custom {
+            name: name.to_string(),
+        };
+        let type_code = self.u32()?;
+        Ok(match type_code {
+            0 => custom("Val"),
+            1 => SorobanType::Bool,
+            2 => SorobanType::Void,
+            3 => custom("Error"),
+            4 => SorobanType::U32,
+            5 => SorobanType::I32,
+            6 => SorobanType::U64,
+            7 => SorobanType::I64,
+            8 => SorobanType::Timepoint,
+            9 => SorobanType::Duration,
+            10 => SorobanType::U128,
+            11 => SorobanType::I128,
+            12 => SorobanType::U256,
+            13 => SorobanType::I256,
+            14 => SorobanType::Bytes,
+            16 => SorobanType::String,
+            17 => SorobanType::Symbol,
+            19 => SorobanType::Address,
+            20 => custom("MuxedAddress"),
+            1000 => SorobanType::Option {
+                value_type: boxed(self)?,
+            },
+            1001 => SorobanType::Result {
+                ok_type: boxed(self)?,
+                err_type: boxed(self)?,
+            },
+            1002 => SorobanType::Vec {
+                element_type: boxed(self)?,
+            },
+            1004 => SorobanType::Map {
+                key_type: boxed(self)?,
+                value_type: boxed(self)?,
+            },
+            1005 => {
+                let mut elements = Vec::new();
+                for _ in 0..self.count()? {
+                    elements.push(self.type_def(depth + 1)?);
+                }
+                SorobanType::Tuple { elements }
+            }
+            1006 => SorobanType::BytesN { n: self.u32()? },
+            2000 => SorobanType::Custom {
+                name: self.string()?,
+            },
+            other => return Err(format!("Unknown spec type {}", other)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(out: &mut Vec<u8>, value: &str) {
+        out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        out.extend_from_slice(value.as_bytes());
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    fn word(out: &mut Vec<u8>, value: u32) {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    #[test]
+    fn struct_entries_are_skipped_and_named() {
+        let mut data = Vec::new();
+        word(&mut data, ENTRY_UDT_STRUCT);
+        string(&mut data, "");
+        string(&mut data, "");
+        string(&mut data, "Config");
+        word(&mut data, 1);
+        string(&mut data, "");
+        string(&mut data, "owners");
+        word(&mut data, 1004);
+        word(&mut data, 19);
+        word(&mut data, 1006);
+        word(&mut data, 32);
+        word(&mut data, ENTRY_FUNCTION);
+        string(&mut data, "Returns the config");
+        string(&mut data, "config");
+        word(&mut data, 0);
+        word(&mut data, 1);
+        word(&mut data, 2000);
+        string(&mut data, "Config");
+
+        let spec = decode_spec(&data).unwrap();
+        assert_eq!(spec.types, vec!["Config"]);
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.functions[0].name, "config");
+        assert_eq!(
+            spec.functions[0]
+                .output
+                .as_ref()
+                .map(SorobanType::display_name),
+            Some("Config".to_string())
+        );
+    }
+
+    #[test]
+    fn truncated_and_unknown_entries_are_rejected() {
+        let mut data = Vec::new();
+        word(&mut data, ENTRY_FUNCTION);
+        string(&mut data, "");
+        assert!(decode_spec(&data).unwrap_err().contains("truncated"));
+
+        let mut data = Vec::new();
+        word(&mut data, 99);
+        assert!(decode_spec(&data)
+            .unwrap_err()
+            .contains("Unknown spec entry"));
+
+        // An array count far larger than the section
+        let mut data = Vec::new();
+        word(&mut data, ENTRY_FUNCTION);
+        string(&mut data, "");
+        string(&mut data, "f");
+        word(&mut data, u32::MAX);
+        assert!(decode_spec(&data).unwrap_err().contains("exceeds"));
+    }
+}
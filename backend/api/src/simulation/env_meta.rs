@@ -0,0 +1,125 @@
+use shared::models::InterfaceVersion;
+use wasmparser::{Parser, Payload};
+
+/// Custom section the Soroban SDK writes the contract's environment metadata to.
+pub const ENV_META_SECTION: &str = "contractenvmetav0";
+
+/// Protocol simulations are checked against when `SOROBAN_PROTOCOL_VERSION` is unset.
+pub const DEFAULT_TARGET_PROTOCOL: u32 = 23;
+
+/// `SCEnvMetaKind::SC_ENV_META_KIND_INTERFACE_VERSION`
+const INTERFACE_VERSION_KIND: u32 = 0;
+
+/// Reads the target network protocol from `SOROBAN_PROTOCOL_VERSION`, falling
+/// back to [`DEFAULT_TARGET_PROTOCOL`].
+pub fn target_protocol_version() -> u32 {
+    std::env::var("SOROBAN_PROTOCOL_VERSION")
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_TARGET_PROTOCOL)
+}
+
+/// The interface version recorded in the module's `contractenvmetav0`
+/// section, or `None` when the section is absent or unreadable.
+pub fn extract_interface_version(wasm_bytes: &[u8]) -> Option<InterfaceVersion> {
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        match payload {
+            Ok(Payload::CustomSection(c)) if c.name() == ENV_META_SECTION => {
+                return parse_env_meta(c.data());
+            }
+            Ok(_) => {}
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+/// Decodes the XDR stream of `SCEnvMetaEntry` values in the section. The
+/// interface version entry is a 4-byte kind followed by the protocol and
+/// pre-release numbers as big-endian u32s (a u64 with the protocol in its
+/// high half in older SDKs, which has the same encoding).
+fn parse_env_meta(mut data: &[u8]) -> Option<InterfaceVersion> {
+    while data.len() >= 12 {
+        let word = |at: usize| u32::from_be_bytes(data[at..at + 4].try_into().unwrap());
+        if word(0) != INTERFACE_VERSION_KIND {
+            // Entries of other kinds have no length prefix to skip them by
+            return None;
+        }
+        let version = InterfaceVersion {
+            protocol: word(4),
+            pre_release: word(8),
+        };
+        if version.protocol > 0 {
+            return Some(version);
+        }
+        data = &data[12..];
+    }
+    None
+}
+
+pub fn newer_protocol_message(version: &InterfaceVersion, target: u32) -> String {
+    format!(
+        "Contract was built for protocol {}, newer than the target network's protocol {}; deployment will fail",
+        version.protocol, target
+    )
+}
+
+pub fn older_protocol_message(version: &InterfaceVersion, target: u32) -> String {
+    format!(
+        "Contract was built for protocol {}, older than the target network's protocol {}; rebuild it with a current SDK if deployment fails",
+        version.protocol, target
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_with_env_meta(data: &[u8]) -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        wasm.extend_from_slice(&[0x00, (1 + ENV_META_SECTION.len() + data.len()) as u8]);
+        wasm.push(ENV_META_SECTION.len() as u8);
+        wasm.extend_from_slice(ENV_META_SECTION.as_bytes());
+        wasm.extend_from_slice(data);
+        wasm
+    }
+
+    fn interface_version_entry(protocol: u32, pre_release: u32) -> Vec<u8> {
+        let mut entry = INTERFACE_VERSION_KIND.to_be_bytes().to_vec();
+        entry.extend_from_slice(&protocol.to_be_bytes());
+        entry.extend_from_slice(&pre_release.to_be_bytes());
+        entry
+    }
+
+    #[test]
+    fn interface_version_is_read_from_the_section() {
+        let wasm = module_with_env_meta(&interface_version_entry(22, 0));
+
+        assert_eq!(
+            extract_interface_version(&wasm),
+            Some(InterfaceVersion {
+                protocol: 22,
+                pre_release: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn missing_or_malformed_sections_yield_nothing() {
+        let without_section = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        assert_eq!(extract_interface_version(&without_section), None);
+
+        // Truncated entry
+        let wasm = module_with_env_meta(&interface_version_entry(22, 0)[..10]);
+        assert_eq!(extract_interface_version(&wasm), None);
+
+        // Unknown entry kind
+        let mut entry = interface_version_entry(22, 0);
+        entry[3] = 7;
+        assert_eq!(
+            extract_interface_version(&module_with_env_meta(&entry)),
+            None
+        );
+    }
+}
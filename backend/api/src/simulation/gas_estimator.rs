@@ -1,5 +1,7 @@
 use crate::simulation::wasm_validator::WasmValidationResult;
 use serde::{Deserialize, Serialize};
+use shared::models::{FunctionGasEstimate, Network, RentEstimate};
+use wasmparser::{ExternalKind, Parser, Payload, TypeRef};
 
 const STROOPS_PER_XLM: i64 = 10_000_000;
 const BASE_DEPLOYMENT_COST: i64 = 50_000;
@@ -7,6 +9,57 @@ const COST_PER_KB: i64 = 5_000;
 const COST_PER_FUNCTION: i64 = 1_000;
 const COST_PER_TABLE: i64 = 2_000;
 const COST_PER_MEMORY_PAGE: i64 = 10_000;
+/// About 30 days of 5-second ledgers
+const DEFAULT_RENT_LEDGERS: u32 = 518_400;
+const RENT_PER_KB_PER_1K_LEDGERS: i64 = 100;
+/// Contract instance entry with no instance storage
+const INSTANCE_ENTRY_BYTES: u64 = 256;
+
+/// Fee schedule used to price a deployment. Stellar's test networks charge
+/// mainnet's resource fees, so one schedule serves every network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasModel {
+    pub base_deployment_cost: i64,
+    pub cost_per_kb: i64,
+    pub cost_per_function: i64,
+    pub cost_per_table: i64,
+    pub cost_per_memory_page: i64,
+    /// Ledgers the rent estimate keeps the contract alive for
+    pub rent_ledgers: u32,
+    /// Rent in stroops for one KB of ledger entry over 1,000 ledgers
+    pub rent_per_kb_per_1k_ledgers: i64,
+    pub instance_entry_bytes: u64,
+}
+
+impl Default for GasModel {
+    fn default() -> Self {
+        Self {
+            base_deployment_cost: BASE_DEPLOYMENT_COST,
+            cost_per_kb: COST_PER_KB,
+            cost_per_function: COST_PER_FUNCTION,
+            cost_per_table: COST_PER_TABLE,
+            cost_per_memory_page: COST_PER_MEMORY_PAGE,
+            rent_ledgers: DEFAULT_RENT_LEDGERS,
+            rent_per_kb_per_1k_ledgers: RENT_PER_KB_PER_1K_LEDGERS,
+            instance_entry_bytes: INSTANCE_ENTRY_BYTES,
+        }
+    }
+}
+
+/// Reads `DEFAULT_GAS_NETWORK`, the network simulations record and compare
+/// gas estimates under when a request omits one; defaults to mainnet when unset.
+pub fn default_gas_network_from_env() -> Result<Network, String> {
+    parse_default_gas_network(std::env::var("DEFAULT_GAS_NETWORK").ok())
+}
+
+fn parse_default_gas_network(value: Option<String>) -> Result<Network, String> {
+    match value {
+        Some(raw) if !raw.trim().is_empty() => raw
+            .parse::<Network>()
+            .map_err(|e| format!("invalid DEFAULT_GAS_NETWORK: {}", e)),
+        _ => Ok(Network::Mainnet),
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasEstimationResult {
@@ -16,36 +69,33 @@ pub struct GasEstimationResult {
     pub storage_cost_stroops: i64,
     pub wasm_size_kb: f64,
     pub complexity_factor: f64,
+    pub per_function: Vec<FunctionGasEstimate>,
+    pub rent_estimate: RentEstimate,
+    pub warnings: Vec<String>,
 }
 
 pub fn estimate_gas(
     wasm_bytes: &[u8],
     validation_result: &WasmValidationResult,
+    model: &GasModel,
 ) -> GasEstimationResult {
     let wasm_size_bytes = wasm_bytes.len() as i64;
     let wasm_size_kb = wasm_size_bytes as f64 / 1024.0;
 
-    // Calculate deployment cost based on WASM size
-    let size_cost = (wasm_size_kb as i64) * COST_PER_KB;
-
-    // Calculate function complexity cost
-    let function_cost = validation_result.function_count as i64 * COST_PER_FUNCTION;
-
-    // Calculate table cost
-    let table_cost = validation_result.table_count as i64 * COST_PER_TABLE;
-
-    // Calculate memory cost
-    let memory_cost = validation_result.memory_pages as i64 * COST_PER_MEMORY_PAGE;
-
-    // Total deployment cost
-    let deployment_cost =
-        BASE_DEPLOYMENT_COST + size_cost + function_cost + table_cost + memory_cost;
-
-    // Storage cost estimate (based on data section)
-    let storage_cost = validation_result.data_section_size as i64 * COST_PER_KB / 10;
-
-    // Total cost
-    let total_cost_stroops = deployment_cost + storage_cost;
+    let CostBreakdown {
+        deployment: deployment_cost,
+        storage: storage_cost,
+        total: total_cost_stroops,
+        mut warnings,
+    } = compute_costs(wasm_size_kb as i64, validation_result, model);
+    let (rent_estimate, rent_saturated) =
+        estimate_rent(wasm_size_bytes as u64, total_cost_stroops, model);
+    if rent_saturated {
+        warnings.push(format!(
+            "Estimated cost including rent exceeds {} stroops and was capped at that value",
+            i64::MAX
+        ));
+    }
 
     // Calculate complexity factor (0.0 - 1.0)
     let complexity_factor = calculate_complexity_factor(
@@ -57,6 +107,8 @@ pub fn estimate_gas(
 
     let total_cost_xlm = total_cost_stroops as f64 / STROOPS_PER_XLM as f64;
 
+    let per_function = attribute_function_costs(&exported_body_sizes(wasm_bytes), deployment_cost);
+
     GasEstimationResult {
         total_cost_stroops,
         total_cost_xlm,
@@ -64,9 +116,179 @@ pub fn estimate_gas(
         storage_cost_stroops: storage_cost,
         wasm_size_kb,
         complexity_factor,
+        per_function,
+        rent_estimate,
+        warnings,
+    }
+}
+
+/// Rent to keep the contract's code and instance entries alive for
+/// `model.rent_ledgers`, and whether it saturated at `i64::MAX`.
+///
+/// Assumes both are persistent entries billed at the model's flat per-KB
+/// rate, that the instance holds no instance storage, and that TTLs are
+/// extended exactly as needed; the write fee of each extension is ignored.
+fn estimate_rent(
+    wasm_size_bytes: u64,
+    one_time_cost: i64,
+    model: &GasModel,
+) -> (RentEstimate, bool) {
+    let entry_bytes = i128::from(wasm_size_bytes) + i128::from(model.instance_entry_bytes);
+    // Rounded up, so any entry kept alive costs at least a stroop
+    let rent_cost = entry_bytes
+        .checked_mul(i128::from(model.rent_per_kb_per_1k_ledgers))
+        .and_then(|rent| rent.checked_mul(i128::from(model.rent_ledgers)))
+        .map(|rent| (rent + 1_024_000 - 1) / 1_024_000)
+        .and_then(|rent| i64::try_from(rent).ok());
+    let total = rent_cost.and_then(|rent| one_time_cost.checked_add(rent));
+
+    let estimate = RentEstimate {
+        ledgers: model.rent_ledgers,
+        code_entry_bytes: wasm_size_bytes,
+        instance_entry_bytes: model.instance_entry_bytes,
+        rent_cost_stroops: rent_cost.unwrap_or(i64::MAX),
+        rent_cost_xlm: rent_cost.unwrap_or(i64::MAX) as f64 / STROOPS_PER_XLM as f64,
+        total_cost_of_ownership_stroops: total.unwrap_or(i64::MAX),
+    };
+    (estimate, total.is_none())
+}
+
+struct CostBreakdown {
+    deployment: i64,
+    storage: i64,
+    total: i64,
+    /// Set when any step overflowed and was pinned at `i64::MAX`
+    warnings: Vec<String>,
+}
+
+/// Prices a deployment with checked arithmetic so huge inputs saturate at
+/// `i64::MAX` instead of wrapping to a negative cost.
+fn compute_costs(
+    wasm_size_kb: i64,
+    validation_result: &WasmValidationResult,
+    model: &GasModel,
+) -> CostBreakdown {
+    let mut saturated = false;
+    let mut saturate = |cost: Option<i64>| {
+        cost.unwrap_or_else(|| {
+            saturated = true;
+            i64::MAX
+        })
+    };
+
+    // Size, function, table and memory costs on top of the base cost
+    let deployment = saturate(
+        [
+            Some(model.base_deployment_cost),
+            wasm_size_kb.checked_mul(model.cost_per_kb),
+            i64::from(validation_result.function_count).checked_mul(model.cost_per_function),
+            i64::from(validation_result.table_count).checked_mul(model.cost_per_table),
+            i64::try_from(validation_result.memory_pages)
+                .ok()
+                .and_then(|pages| pages.checked_mul(model.cost_per_memory_page)),
+        ]
+        .into_iter()
+        .try_fold(0i64, |sum, cost| sum.checked_add(cost?)),
+    );
+
+    // Storage cost estimate (based on data section)
+    let storage = saturate(
+        i64::from(validation_result.data_section_size)
+            .checked_mul(model.cost_per_kb)
+            .map(|cost| cost / 10),
+    );
+
+    let total = saturate(deployment.checked_add(storage));
+
+    let mut warnings = Vec::new();
+    if saturated {
+        tracing::warn!(
+            wasm_size_kb,
+            "gas cost overflowed i64; saturating at i64::MAX"
+        );
+        warnings.push(format!(
+            "Estimated cost exceeds {} stroops and was capped at that value",
+            i64::MAX
+        ));
+    }
+
+    CostBreakdown {
+        deployment,
+        storage,
+        total,
+        warnings,
+    }
+}
+
+/// Body sizes of exported functions, plus the total size of all local bodies.
+struct BodySizes {
+    exported: Vec<(String, u64)>,
+    total: u64,
+}
+
+fn exported_body_sizes(wasm_bytes: &[u8]) -> BodySizes {
+    let mut imported_functions = 0u32;
+    let mut exports: Vec<(String, u32)> = Vec::new();
+    let mut body_sizes: Vec<u64> = Vec::new();
+
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        match payload {
+            Ok(Payload::ImportSection(imports)) => {
+                imported_functions += imports
+                    .into_iter()
+                    .flatten()
+                    .filter(|imp| matches!(imp.ty, TypeRef::Func(_)))
+                    .count() as u32;
+            }
+            Ok(Payload::ExportSection(section)) => {
+                exports.extend(
+                    section
+                        .into_iter()
+                        .flatten()
+                        .filter(|exp| exp.kind == ExternalKind::Func)
+                        .map(|exp| (exp.name.to_string(), exp.index)),
+                );
+            }
+            Ok(Payload::CodeSectionEntry(body)) => {
+                body_sizes.push(body.range().len() as u64);
+            }
+            Err(_) => break,
+            _ => {}
+        }
+    }
+
+    let exported = exports
+        .into_iter()
+        .filter_map(|(name, index)| {
+            let local = index.checked_sub(imported_functions)? as usize;
+            body_sizes.get(local).map(|size| (name, *size))
+        })
+        .collect();
+
+    BodySizes {
+        exported,
+        total: body_sizes.iter().sum(),
     }
 }
 
+/// Splits `deployment_cost` across exported functions in proportion to their
+/// share of the contract's total code size.
+fn attribute_function_costs(sizes: &BodySizes, deployment_cost: i64) -> Vec<FunctionGasEstimate> {
+    if sizes.total == 0 {
+        return Vec::new();
+    }
+
+    sizes
+        .exported
+        .iter()
+        .map(|(name, size)| FunctionGasEstimate {
+            name: name.clone(),
+            estimated_cost_stroops: (deployment_cost as i128 * *size as i128 / sizes.total as i128)
+                as i64,
+        })
+        .collect()
+}
+
 fn calculate_complexity_factor(
     function_count: u32,
     table_count: u32,
@@ -81,3 +303,148 @@ fn calculate_complexity_factor(
 
     func_factor + table_factor + memory_factor + size_factor
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body_sizes(exported: &[(&str, u64)], total: u64) -> BodySizes {
+        BodySizes {
+            exported: exported
+                .iter()
+                .map(|(name, size)| (name.to_string(), *size))
+                .collect(),
+            total,
+        }
+    }
+
+    #[test]
+    fn larger_bodies_get_proportionally_more_cost() {
+        let sizes = body_sizes(&[("transfer", 300), ("balance", 100)], 500);
+        let per_function = attribute_function_costs(&sizes, 10_000);
+        assert_eq!(
+            per_function,
+            vec![
+                FunctionGasEstimate {
+                    name: "transfer".to_string(),
+                    estimated_cost_stroops: 6_000,
+                },
+                FunctionGasEstimate {
+                    name: "balance".to_string(),
+                    estimated_cost_stroops: 2_000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn exported_bodies_are_mapped_past_imported_functions() {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: one `() -> ()` signature
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // Import section: `env::log` as function 0
+        wasm.extend_from_slice(&[
+            0x02, 0x0b, 0x01, 0x03, b'e', b'n', b'v', 0x03, b'l', b'o', b'g', 0x00, 0x00,
+        ]);
+        // Function section: functions 1 and 2
+        wasm.extend_from_slice(&[0x03, 0x03, 0x02, 0x00, 0x00]);
+        // Export section: `big` -> func 1, `small` -> func 2
+        wasm.extend_from_slice(&[
+            0x07, 0x0f, 0x02, 0x03, b'b', b'i', b'g', 0x00, 0x01, 0x05, b's', b'm', b'a', b'l',
+            b'l', 0x00, 0x02,
+        ]);
+        // Code section: a 5-byte body (three nops) and a 2-byte empty body
+        wasm.extend_from_slice(&[
+            0x0a, 0x0a, 0x02, 0x05, 0x00, 0x01, 0x01, 0x01, 0x0b, 0x02, 0x00, 0x0b,
+        ]);
+
+        let sizes = exported_body_sizes(&wasm);
+        assert_eq!(
+            sizes.exported,
+            vec![("big".to_string(), 5), ("small".to_string(), 2)]
+        );
+        assert_eq!(sizes.total, 7);
+    }
+
+    fn validation(function_count: u32, data_section_size: u32) -> WasmValidationResult {
+        WasmValidationResult {
+            valid: true,
+            errors: vec![],
+            warnings: vec![],
+            function_count,
+            table_count: 1,
+            data_section_size,
+            memory_pages: 1,
+            memory_maximum_pages: None,
+            export_functions: vec![],
+            import_functions: vec![],
+            disallowed_imports: vec![],
+            duplicate_exports: vec![],
+            reserved_exports: vec![],
+            features_used: vec![],
+            custom_sections: vec![],
+        }
+    }
+
+    #[test]
+    fn ordinary_sizes_are_priced_exactly() {
+        let model = GasModel::default();
+        let costs = compute_costs(10, &validation(3, 100), &model);
+        assert_eq!(costs.deployment, 50_000 + 50_000 + 3_000 + 2_000 + 10_000);
+        assert_eq!(costs.storage, 50_000);
+        assert_eq!(costs.total, costs.deployment + costs.storage);
+        assert!(costs.warnings.is_empty());
+    }
+
+    #[test]
+    fn huge_size_saturates_instead_of_going_negative() {
+        let model = GasModel::default();
+        let costs = compute_costs(i64::MAX / 1_000, &validation(3, 100), &model);
+        assert_eq!(costs.deployment, i64::MAX);
+        assert_eq!(costs.total, i64::MAX);
+        assert_eq!(costs.warnings.len(), 1);
+    }
+
+    #[test]
+    fn rent_scales_with_size_and_horizon() {
+        let mut model = GasModel {
+            rent_ledgers: 1_000,
+            ..Default::default()
+        };
+        // 768 bytes of code plus the 256-byte instance is one KB
+        let (rent, saturated) = estimate_rent(768, 5_000, &model);
+        assert_eq!(rent.rent_cost_stroops, 100);
+        assert_eq!(rent.total_cost_of_ownership_stroops, 5_100);
+        assert!(!saturated);
+
+        model.rent_ledgers = 10_000;
+        assert_eq!(estimate_rent(768, 5_000, &model).0.rent_cost_stroops, 1_000);
+
+        model.rent_per_kb_per_1k_ledgers = i64::MAX;
+        model.rent_ledgers = u32::MAX;
+        let (rent, saturated) = estimate_rent(1 << 20, 5_000, &model);
+        assert_eq!(rent.total_cost_of_ownership_stroops, i64::MAX);
+        assert!(saturated);
+    }
+
+    #[test]
+    fn unset_default_gas_network_is_mainnet() {
+        assert!(matches!(
+            parse_default_gas_network(None),
+            Ok(Network::Mainnet)
+        ));
+        assert!(matches!(
+            parse_default_gas_network(Some("  ".to_string())),
+            Ok(Network::Mainnet)
+        ));
+    }
+
+    #[test]
+    fn configured_default_gas_network_is_parsed() {
+        assert!(matches!(
+            parse_default_gas_network(Some("Testnet".to_string())),
+            Ok(Network::Testnet)
+        ));
+        assert!(parse_default_gas_network(Some("devnet".to_string())).is_err());
+    }
+}
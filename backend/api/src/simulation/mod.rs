@@ -1,9 +1,19 @@
 pub mod abi_extractor;
+pub mod contract_spec;
+pub mod env_meta;
 pub mod gas_estimator;
 pub mod performance_analyzer;
+pub mod wasm_decoder;
 pub mod wasm_validator;
 
-pub use abi_extractor::{extract_abi, AbiExtractionResult};
-pub use gas_estimator::{estimate_gas, GasEstimationResult};
+pub use abi_extractor::{
+    extract_abi, extract_abi_chunked, AbiExtractionEvent, AbiExtractionResult,
+};
+pub use env_meta::{extract_interface_version, target_protocol_version};
+pub use gas_estimator::{estimate_gas, GasEstimationResult, GasModel};
 pub use performance_analyzer::{analyze_performance, PerformanceAnalysisResult};
+pub use wasm_decoder::{
+    check_base64_len, check_wasm_size, decode_wasm, max_wasm_size_bytes, max_wasm_upload_bytes,
+    wasm_body_limit, WasmDecodeError,
+};
 pub use wasm_validator::{validate_wasm, WasmValidationResult};
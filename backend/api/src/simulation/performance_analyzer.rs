@@ -1,6 +1,11 @@
 use crate::simulation::abi_extractor::AbiExtractionResult;
 use crate::simulation::wasm_validator::WasmValidationResult;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasmparser::{ExternalKind, FunctionBody, Operator, Parser, Payload, TypeRef};
+
+/// Loop nesting at which a function is reported as high complexity
+const DEEP_LOOP_NESTING: u32 = 3;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceAnalysisResult {
@@ -76,8 +81,9 @@ pub fn analyze_performance(
     }
 
     // Analyze each exported function
+    let loop_profiles = exported_loop_profiles(wasm_bytes);
     for func_name in &validation_result.export_functions {
-        let analysis = analyze_function(func_name, abi_result);
+        let analysis = analyze_function(func_name, abi_result, loop_profiles.get(func_name));
         function_analysis.push(analysis);
     }
 
@@ -113,7 +119,119 @@ pub fn analyze_performance(
     }
 }
 
-fn analyze_function(func_name: &str, abi_result: &AbiExtractionResult) -> FunctionAnalysis {
+/// Loop structure of one function body
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct LoopProfile {
+    /// Deepest nesting of `loop` blocks
+    max_depth: u32,
+    /// Branches that jump back to the start of an enclosing loop
+    backward_branches: u32,
+}
+
+/// Loop profiles of exported functions, keyed by export name. Bodies that fail
+/// to parse are left out.
+fn exported_loop_profiles(wasm_bytes: &[u8]) -> HashMap<String, LoopProfile> {
+    let mut imported_functions = 0u32;
+    let mut exports: Vec<(String, u32)> = Vec::new();
+    let mut profiles: Vec<Option<LoopProfile>> = Vec::new();
+
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        match payload {
+            Ok(Payload::ImportSection(imports)) => {
+                imported_functions += imports
+                    .into_iter()
+                    .flatten()
+                    .filter(|imp| matches!(imp.ty, TypeRef::Func(_)))
+                    .count() as u32;
+            }
+            Ok(Payload::ExportSection(section)) => {
+                exports.extend(
+                    section
+                        .into_iter()
+                        .flatten()
+                        .filter(|exp| exp.kind == ExternalKind::Func)
+                        .map(|exp| (exp.name.to_string(), exp.index)),
+                );
+            }
+            Ok(Payload::CodeSectionEntry(body)) => profiles.push(loop_profile(&body)),
+            Err(_) => break,
+            _ => {}
+        }
+    }
+
+    exports
+        .into_iter()
+        .filter_map(|(name, index)| {
+            let local = index.checked_sub(imported_functions)? as usize;
+            profiles.get(local).copied().flatten().map(|p| (name, p))
+        })
+        .collect()
+}
+
+fn loop_profile(body: &FunctionBody) -> Option<LoopProfile> {
+    let mut reader = body.get_operators_reader().ok()?;
+    // One entry per open block, `true` for loops; the function body is the outermost block
+    let mut blocks = vec![false];
+    let mut profile = LoopProfile::default();
+
+    while !reader.eof() {
+        let targets: Vec<u32> = match reader.read().ok()? {
+            Operator::Loop { .. } => {
+                blocks.push(true);
+                let depth = blocks.iter().filter(|is_loop| **is_loop).count() as u32;
+                profile.max_depth = profile.max_depth.max(depth);
+                continue;
+            }
+            Operator::Block { .. }
+            | Operator::If { .. }
+            | Operator::Try { .. }
+            | Operator::TryTable { .. } => {
+                blocks.push(false);
+                continue;
+            }
+            Operator::End => {
+                blocks.pop();
+                continue;
+            }
+            Operator::Br { relative_depth } | Operator::BrIf { relative_depth } => {
+                vec![relative_depth]
+            }
+            Operator::BrTable { targets } => {
+                let mut all = targets.targets().collect::<Result<Vec<_>, _>>().ok()?;
+                all.push(targets.default());
+                all
+            }
+            _ => continue,
+        };
+
+        for depth in targets {
+            let target = blocks.len().checked_sub(depth as usize + 1);
+            if target.is_some_and(|i| blocks[i]) {
+                profile.backward_branches += 1;
+            }
+        }
+    }
+
+    Some(profile)
+}
+
+fn analyze_function(
+    func_name: &str,
+    abi_result: &AbiExtractionResult,
+    loops: Option<&LoopProfile>,
+) -> FunctionAnalysis {
+    // Deeply nested loops outweigh anything the name suggests
+    if let Some(loops) = loops.filter(|l| l.max_depth >= DEEP_LOOP_NESTING) {
+        return FunctionAnalysis {
+            name: func_name.to_string(),
+            complexity: "high".to_string(),
+            recommendation: Some(format!(
+                "Loops nested {} deep ({} backward branches) - bound iteration counts or paginate",
+                loops.max_depth, loops.backward_branches
+            )),
+        };
+    }
+
     // Check if function is in ABI result
     let has_abi = abi_result.functions.iter().any(|f| &f.name == func_name);
 
@@ -149,3 +267,64 @@ fn analyze_function(func_name: &str, abi_result: &AbiExtractionResult) -> Functi
         recommendation,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `get_all` holds three nested loops with a branch back to the innermost;
+    /// `noop` is empty.
+    fn nested_loop_wasm() -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: one `() -> ()` signature
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // Function section: functions 0 and 1
+        wasm.extend_from_slice(&[0x03, 0x03, 0x02, 0x00, 0x00]);
+        // Export section: `get_all` -> func 0, `noop` -> func 1
+        wasm.extend_from_slice(&[
+            0x07, 0x12, 0x02, 0x07, b'g', b'e', b't', b'_', b'a', b'l', b'l', 0x00, 0x00, 0x04,
+            b'n', b'o', b'o', b'p', 0x00, 0x01,
+        ]);
+        // Code section: loop { loop { loop { br 0 } } }, then an empty body
+        wasm.extend_from_slice(&[
+            0x0a, 0x12, 0x02, 0x0d, 0x00, 0x03, 0x40, 0x03, 0x40, 0x03, 0x40, 0x0c, 0x00, 0x0b,
+            0x0b, 0x0b, 0x0b, 0x02, 0x00, 0x0b,
+        ]);
+        wasm
+    }
+
+    fn empty_abi() -> AbiExtractionResult {
+        AbiExtractionResult {
+            success: true,
+            errors: vec![],
+            functions: vec![],
+            types: vec![],
+        }
+    }
+
+    #[test]
+    fn loop_nesting_and_backward_branches_are_counted() {
+        let profiles = exported_loop_profiles(&nested_loop_wasm());
+        assert_eq!(
+            profiles.get("get_all"),
+            Some(&LoopProfile {
+                max_depth: 3,
+                backward_branches: 1,
+            })
+        );
+        assert_eq!(profiles.get("noop"), Some(&LoopProfile::default()));
+    }
+
+    #[test]
+    fn deeply_nested_loops_are_high_complexity_regardless_of_name() {
+        let profiles = exported_loop_profiles(&nested_loop_wasm());
+        let abi = empty_abi();
+
+        let nested = analyze_function("get_all", &abi, profiles.get("get_all"));
+        assert_eq!(nested.complexity, "high");
+        assert!(nested.recommendation.is_some());
+
+        let flat = analyze_function("noop", &abi, profiles.get("noop"));
+        assert_eq!(flat.complexity, "unknown");
+    }
+}
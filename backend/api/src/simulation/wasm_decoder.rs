@@ -0,0 +1,219 @@
+use flate2::read::GzDecoder;
+use shared::models::WasmEncoding;
+use std::io::Read;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Default for `MAX_WASM_SIZE_BYTES`, comfortably above Soroban's on-chain contract size limit
+const DEFAULT_MAX_WASM_SIZE_BYTES: usize = 256 * 1024;
+
+/// Room in a request body for the JSON fields sent alongside the base64 WASM
+const JSON_OVERHEAD_BYTES: usize = 64 * 1024;
+
+/// Largest WASM module, after decoding and inflating, that any endpoint accepts
+static MAX_WASM_SIZE_BYTES: once_cell::sync::Lazy<usize> = once_cell::sync::Lazy::new(|| {
+    std::env::var("MAX_WASM_SIZE_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_WASM_SIZE_BYTES)
+});
+
+/// Largest upload (after base64 decoding, before inflating) the simulator accepts.
+/// Defaults to `MAX_WASM_SIZE_BYTES`, since compression only makes uploads smaller.
+static MAX_WASM_UPLOAD_BYTES: once_cell::sync::Lazy<usize> = once_cell::sync::Lazy::new(|| {
+    std::env::var("SIMULATION_MAX_WASM_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or_else(max_wasm_size_bytes)
+});
+
+pub fn max_wasm_size_bytes() -> usize {
+    *MAX_WASM_SIZE_BYTES
+}
+
+pub fn max_wasm_upload_bytes() -> usize {
+    *MAX_WASM_UPLOAD_BYTES
+}
+
+/// Request body limit for routes that take a base64 WASM upload, so an
+/// oversized body is refused while it is read rather than after buffering it
+pub fn wasm_body_limit() -> usize {
+    max_wasm_upload_bytes().div_ceil(3) * 4 + JSON_OVERHEAD_BYTES
+}
+
+/// Rejects a decoded module larger than `limit`, naming both sizes
+pub fn check_wasm_size(wasm: &[u8], limit: usize) -> Result<(), String> {
+    if wasm.len() > limit {
+        return Err(format!(
+            "WASM binary is {} bytes, above the {} byte limit",
+            wasm.len(),
+            limit
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects a base64 payload whose decoded size would exceed `max_decoded`,
+/// using only its length so oversized bodies are never decoded into memory.
+pub fn check_base64_len(encoded: &str, max_decoded: usize) -> Result<(), String> {
+    let padding = encoded
+        .bytes()
+        .rev()
+        .take_while(|b| *b == b'=')
+        .count()
+        .min(2);
+    let decoded_len = (encoded.len().div_ceil(4) * 3).saturating_sub(padding);
+    if decoded_len > max_decoded {
+        return Err(format!(
+            "WASM binary of about {} bytes exceeds the {} byte upload limit",
+            decoded_len, max_decoded
+        ));
+    }
+    Ok(())
+}
+
+/// Upper bound on an inflated upload, well above Soroban's on-chain WASM limit,
+/// so a small gzip bomb cannot exhaust memory.
+pub const MAX_DECOMPRESSED_WASM_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasmDecodeError {
+    DecompressionFailed(String),
+    TooLarge { limit: u64 },
+}
+
+impl WasmDecodeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DecompressionFailed(_) => "DecompressionFailed",
+            Self::TooLarge { .. } => "DecompressedWasmTooLarge",
+        }
+    }
+}
+
+impl std::fmt::Display for WasmDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DecompressionFailed(e) => write!(f, "Failed to inflate gzip WASM: {}", e),
+            Self::TooLarge { limit } => {
+                write!(f, "Decompressed WASM exceeds the {} byte limit", limit)
+            }
+        }
+    }
+}
+
+/// Returns the raw WASM for an upload, inflating it when `encoding` is gzip or,
+/// if no encoding was given, when the bytes start with the gzip magic number.
+pub fn decode_wasm(
+    bytes: Vec<u8>,
+    encoding: Option<WasmEncoding>,
+) -> Result<Vec<u8>, WasmDecodeError> {
+    let gzip = match encoding {
+        Some(WasmEncoding::Gzip) => true,
+        Some(WasmEncoding::Raw) => false,
+        None => bytes.starts_with(&GZIP_MAGIC),
+    };
+    if !gzip {
+        return Ok(bytes);
+    }
+    inflate(&bytes, MAX_DECOMPRESSED_WASM_BYTES)
+}
+
+fn inflate(bytes: &[u8], limit: u64) -> Result<Vec<u8>, WasmDecodeError> {
+    let mut inflated = Vec::new();
+    GzDecoder::new(bytes)
+        .take(limit + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|e| WasmDecodeError::DecompressionFailed(e.to_string()))?;
+    if inflated.len() as u64 > limit {
+        return Err(WasmDecodeError::TooLarge { limit });
+    }
+    Ok(inflated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const WASM_HEADER: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn base64_length_bounds_decoded_size() {
+        use base64::Engine;
+        let encoded = base64::engine::general_purpose::STANDARD.encode([0u8; 10]);
+        assert!(check_base64_len(&encoded, 10).is_ok());
+        assert!(check_base64_len(&encoded, 9).is_err());
+    }
+
+    #[test]
+    fn oversized_base64_is_rejected_before_decoding() {
+        // Not valid base64, so an error here can only come from the length check
+        let encoded = "!".repeat(4 * 1024);
+        let err = check_base64_len(&encoded, 1024).unwrap_err();
+        assert!(err.contains("exceeds the 1024 byte upload limit"));
+    }
+
+    #[test]
+    fn decoded_size_check_reports_limit_and_actual_size() {
+        assert!(check_wasm_size(&[0u8; 1024], 1024).is_ok());
+        let err = check_wasm_size(&[0u8; 1025], 1024).unwrap_err();
+        assert_eq!(err, "WASM binary is 1025 bytes, above the 1024 byte limit");
+    }
+
+    #[test]
+    fn body_limit_fits_a_maximal_base64_upload() {
+        use base64::Engine;
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(vec![0u8; max_wasm_upload_bytes()]);
+        assert!(encoded.len() < wasm_body_limit());
+    }
+
+    #[test]
+    fn raw_wasm_passes_through() {
+        let decoded = decode_wasm(WASM_HEADER.to_vec(), None).unwrap();
+        assert_eq!(decoded, WASM_HEADER);
+    }
+
+    #[test]
+    fn gzip_is_detected_from_magic_bytes() {
+        let decoded = decode_wasm(gzip(&WASM_HEADER), None).unwrap();
+        assert_eq!(decoded, WASM_HEADER);
+    }
+
+    #[test]
+    fn explicit_gzip_encoding_is_honored() {
+        let decoded = decode_wasm(gzip(&WASM_HEADER), Some(WasmEncoding::Gzip)).unwrap();
+        assert_eq!(decoded, WASM_HEADER);
+    }
+
+    #[test]
+    fn malformed_gzip_stream_fails() {
+        let mut compressed = gzip(&WASM_HEADER);
+        compressed.truncate(12);
+        let err = decode_wasm(compressed, None).unwrap_err();
+        assert_eq!(err.code(), "DecompressionFailed");
+
+        let err = decode_wasm(WASM_HEADER.to_vec(), Some(WasmEncoding::Gzip)).unwrap_err();
+        assert_eq!(err.code(), "DecompressionFailed");
+    }
+
+    #[test]
+    fn inflated_size_is_capped() {
+        let bomb = gzip(&vec![0u8; 4096]);
+        assert_eq!(
+            inflate(&bomb, 1024).unwrap_err(),
+            WasmDecodeError::TooLarge { limit: 1024 }
+        );
+        assert_eq!(inflate(&bomb, 4096).unwrap().len(), 4096);
+    }
+}
@@ -1,5 +1,30 @@
 use serde::{Deserialize, Serialize};
-use wasmparser::Parser;
+use shared::models::CustomSectionInfo;
+use std::collections::BTreeSet;
+use wasmparser::{ExternalKind, Operator, Parser, TypeRef, Validator, WasmFeatures};
+
+/// Host modules a Soroban contract may import from when no override is configured.
+pub const DEFAULT_ALLOWED_IMPORT_MODULES: &[&str] = &["env"];
+
+/// Proposals a Soroban host accepts on top of WebAssembly 1.0. SIMD, threads
+/// and reference types are rejected by the network.
+pub const SOROBAN_WASM_FEATURES: WasmFeatures = WasmFeatures::WASM1
+    .union(WasmFeatures::SIGN_EXTENSION)
+    .union(WasmFeatures::SATURATING_FLOAT_TO_INT)
+    .union(WasmFeatures::MULTI_VALUE)
+    .union(WasmFeatures::BULK_MEMORY);
+
+/// Declared memory maximums above this many 64 KiB pages (64 MiB) are flagged.
+pub const LARGE_MEMORY_MAXIMUM_PAGES: u64 = 1024;
+
+/// Name the Soroban host looks up a contract's linear memory by.
+pub const MEMORY_EXPORT_NAME: &str = "memory";
+
+/// Soroban reserves exports starting with this prefix for host-invoked entry
+/// points; only those in [`HOST_ENTRY_POINTS`] are meaningful.
+const RESERVED_EXPORT_PREFIX: &str = "__";
+
+const HOST_ENTRY_POINTS: &[&str] = &["__constructor", "__check_auth"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WasmValidationResult {
@@ -10,19 +35,143 @@ pub struct WasmValidationResult {
     pub table_count: u32,
     pub data_section_size: u32,
     pub memory_pages: u64,
+    pub memory_maximum_pages: Option<u64>,
     pub export_functions: Vec<String>,
     pub import_functions: Vec<String>,
+    pub disallowed_imports: Vec<String>,
+    /// Export names that appear more than once
+    pub duplicate_exports: Vec<String>,
+    /// Exports shadowing a name reserved by the Soroban host
+    pub reserved_exports: Vec<String>,
+    /// Post-MVP proposals whose instructions appear in function bodies, e.g.
+    /// `simd`, `threads`, `bulk-memory` or `reference-types`
+    pub features_used: Vec<String>,
+    /// Custom sections in the order they appear
+    pub custom_sections: Vec<CustomSectionInfo>,
+}
+
+/// Reads the permitted host modules from `WASM_ALLOWED_IMPORT_MODULES`
+/// (comma-separated), falling back to [`DEFAULT_ALLOWED_IMPORT_MODULES`].
+pub fn allowed_import_modules() -> Vec<String> {
+    std::env::var("WASM_ALLOWED_IMPORT_MODULES")
+        .ok()
+        .map(|raw| {
+            raw.split(',')
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|modules| !modules.is_empty())
+        .unwrap_or_else(|| {
+            DEFAULT_ALLOWED_IMPORT_MODULES
+                .iter()
+                .map(|m| m.to_string())
+                .collect()
+        })
+}
+
+pub fn disallowed_import_message(import: &str) -> String {
+    format!("Import '{}' references a disallowed host module", import)
+}
+
+fn dangling_export_message(name: &str, index: u32, total_functions: u32) -> String {
+    format!(
+        "Export '{}' references function {} but the module only has {} functions",
+        name, index, total_functions
+    )
+}
+
+pub fn duplicate_export_message(name: &str) -> String {
+    format!("Export name '{}' is declared more than once", name)
+}
+
+pub fn reserved_export_message(name: &str) -> String {
+    format!(
+        "Export '{}' collides with a name reserved by the Soroban host",
+        name
+    )
+}
+
+pub fn missing_memory_export_message() -> String {
+    format!(
+        "Contract declares a memory but does not export it as '{}'",
+        MEMORY_EXPORT_NAME
+    )
+}
+
+pub fn unsupported_feature_message(feature: &str) -> String {
+    format!(
+        "Uses the WebAssembly '{}' feature, which the Soroban host does not support",
+        feature
+    )
+}
+
+/// The post-MVP proposal an instruction belongs to, or `None` for MVP ones.
+fn operator_proposal(op: &Operator) -> Option<&'static str> {
+    macro_rules! define_match_operator {
+        ($( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident ($($ann:tt)*))*) => {
+            match op {
+                $( Operator::$op { .. } => define_match_operator!(impl_one @$proposal), )*
+                _ => None,
+            }
+        };
+        (impl_one @mvp) => { None };
+        (impl_one @$proposal:ident) => { Some(stringify!($proposal)) };
+    }
+    wasmparser::for_each_operator!(define_match_operator)
+}
+
+/// Whether the Soroban host accepts instructions from `proposal`.
+fn proposal_supported(proposal: &str) -> bool {
+    let feature = match proposal {
+        "sign_extension" => WasmFeatures::SIGN_EXTENSION,
+        "saturating_float_to_int" => WasmFeatures::SATURATING_FLOAT_TO_INT,
+        "bulk_memory" => WasmFeatures::BULK_MEMORY,
+        "reference_types" => WasmFeatures::REFERENCE_TYPES,
+        "simd" => WasmFeatures::SIMD,
+        "relaxed_simd" => WasmFeatures::RELAXED_SIMD,
+        "threads" => WasmFeatures::THREADS,
+        "tail_call" => WasmFeatures::TAIL_CALL,
+        "exceptions" | "legacy_exceptions" => WasmFeatures::EXCEPTIONS,
+        "gc" => WasmFeatures::GC,
+        "function_references" => WasmFeatures::FUNCTION_REFERENCES,
+        _ => return false,
+    };
+    SOROBAN_WASM_FEATURES.contains(feature)
+}
+
+/// Whether a function export shadows a host-reserved name.
+fn is_reserved_export(name: &str) -> bool {
+    name == MEMORY_EXPORT_NAME
+        || (name.starts_with(RESERVED_EXPORT_PREFIX) && !HOST_ENTRY_POINTS.contains(&name))
 }
 
 pub fn validate_wasm(wasm_bytes: &[u8]) -> WasmValidationResult {
+    validate_wasm_with_allowlist(wasm_bytes, &allowed_import_modules())
+}
+
+pub fn validate_wasm_with_allowlist(
+    wasm_bytes: &[u8],
+    allowed_modules: &[String],
+) -> WasmValidationResult {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
     let mut function_count = 0u32;
     let mut table_count = 0u32;
     let mut data_section_size = 0u32;
     let mut memory_pages = 0u64;
+    let mut memory_maximum_pages = None;
     let mut export_functions = Vec::new();
     let mut import_functions = Vec::new();
+    let mut imported_function_count = 0u32;
+    let mut disallowed_imports = Vec::new();
+    let mut duplicate_exports: Vec<String> = Vec::new();
+    let mut reserved_exports = Vec::new();
+    let mut export_names = std::collections::HashSet::new();
+    let mut declares_memory = false;
+    let mut exports_memory = false;
+    let mut proposals_used = BTreeSet::new();
+    let mut custom_sections = Vec::new();
 
     let parser = Parser::new(0);
 
@@ -42,7 +191,23 @@ pub fn validate_wasm(wasm_bytes: &[u8]) -> WasmValidationResult {
             Ok(wasmparser::Payload::MemorySection(m)) => {
                 for memory in m {
                     if let Ok(mem) = memory {
+                        declares_memory = true;
                         memory_pages = mem.initial;
+                        memory_maximum_pages = mem.maximum;
+                        if let Some(maximum) = mem.maximum {
+                            if maximum < mem.initial {
+                                errors.push(format!(
+                                    "Memory maximum ({} pages) is less than initial ({} pages)",
+                                    maximum, mem.initial
+                                ));
+                            } else if maximum > LARGE_MEMORY_MAXIMUM_PAGES {
+                                warnings.push(format!(
+                                    "Memory maximum of {} pages ({} MB) is unusually large",
+                                    maximum,
+                                    maximum * 64 / 1024
+                                ));
+                            }
+                        }
                     }
                 }
             }
@@ -50,8 +215,34 @@ pub fn validate_wasm(wasm_bytes: &[u8]) -> WasmValidationResult {
                 data_section_size = d.count();
             }
             Ok(wasmparser::Payload::ExportSection(e)) => {
+                // Imports and local functions share one index space, and both
+                // sections precede exports, so the count is final here
+                let total_functions = imported_function_count + function_count;
                 for export in e {
                     if let Ok(exp) = export {
+                        if exp.kind == ExternalKind::Func && exp.index >= total_functions {
+                            errors.push(dangling_export_message(
+                                exp.name,
+                                exp.index,
+                                total_functions,
+                            ));
+                        }
+                        if !export_names.insert(exp.name)
+                            && !duplicate_exports.iter().any(|d| d == exp.name)
+                        {
+                            errors.push(duplicate_export_message(exp.name));
+                            duplicate_exports.push(exp.name.to_string());
+                        }
+                        match exp.kind {
+                            ExternalKind::Memory if exp.name == MEMORY_EXPORT_NAME => {
+                                exports_memory = true;
+                            }
+                            ExternalKind::Func if is_reserved_export(exp.name) => {
+                                warnings.push(reserved_export_message(exp.name));
+                                reserved_exports.push(exp.name.to_string());
+                            }
+                            _ => {}
+                        }
                         export_functions.push(exp.name.to_string());
                     }
                 }
@@ -59,7 +250,14 @@ pub fn validate_wasm(wasm_bytes: &[u8]) -> WasmValidationResult {
             Ok(wasmparser::Payload::ImportSection(i)) => {
                 for import in i {
                     if let Ok(imp) = import {
+                        if matches!(imp.ty, TypeRef::Func(_)) {
+                            imported_function_count += 1;
+                        }
                         let name = format!("{}::{}", imp.module, imp.name);
+                        if !allowed_modules.iter().any(|m| m == imp.module) {
+                            errors.push(disallowed_import_message(&name));
+                            disallowed_imports.push(name.clone());
+                        }
                         import_functions.push(name);
                     }
                 }
@@ -69,13 +267,49 @@ pub fn validate_wasm(wasm_bytes: &[u8]) -> WasmValidationResult {
                     warnings.push("No code section found - contract may be empty".to_string());
                 }
             }
-            Err(e) => {
-                errors.push(format!("WASM parsing error: {}", e));
+            Ok(wasmparser::Payload::CodeSectionEntry(body)) => {
+                if let Ok(reader) = body.get_operators_reader() {
+                    // Undecodable bodies are reported by the validator below
+                    for op in reader.into_iter().map_while(Result::ok) {
+                        if let Some(proposal) = operator_proposal(&op) {
+                            proposals_used.insert(proposal);
+                        }
+                    }
+                }
+            }
+            Ok(wasmparser::Payload::CustomSection(c)) => {
+                custom_sections.push(CustomSectionInfo {
+                    name: c.name().to_string(),
+                    size_bytes: c.data().len(),
+                });
             }
+            // Reported with its offset by the validator below
+            Err(_) => break,
             _ => {}
         }
     }
 
+    let mut features_used = Vec::with_capacity(proposals_used.len());
+    for proposal in proposals_used {
+        let feature = proposal.replace('_', "-");
+        if !proposal_supported(proposal) {
+            warnings.push(unsupported_feature_message(&feature));
+        }
+        features_used.push(feature);
+    }
+
+    if declares_memory && !exports_memory {
+        errors.push(missing_memory_export_message());
+    }
+
+    if let Err(e) = Validator::new_with_features(SOROBAN_WASM_FEATURES).validate_all(wasm_bytes) {
+        errors.push(format!(
+            "WASM validation error at offset {}: {}",
+            e.offset(),
+            e.message()
+        ));
+    }
+
     let valid = errors.is_empty();
 
     if function_count == 0 {
@@ -94,7 +328,371 @@ pub fn validate_wasm(wasm_bytes: &[u8]) -> WasmValidationResult {
         table_count,
         data_section_size,
         memory_pages,
+        memory_maximum_pages,
         export_functions,
         import_functions,
+        disallowed_imports,
+        duplicate_exports,
+        reserved_exports,
+        features_used,
+        custom_sections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a minimal module with one imported function from `module`
+    /// and one exported local function.
+    fn module_importing(module: &str) -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: one `() -> ()` signature
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // Import section: `{module}::fd_write`
+        let name = b"fd_write";
+        let size = 1 + 1 + module.len() + 1 + name.len() + 2;
+        wasm.extend_from_slice(&[0x02, size as u8, 0x01, module.len() as u8]);
+        wasm.extend_from_slice(module.as_bytes());
+        wasm.push(name.len() as u8);
+        wasm.extend_from_slice(name);
+        wasm.extend_from_slice(&[0x00, 0x00]);
+        // Function section
+        wasm.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]);
+        // Export section: `run` -> func 1
+        wasm.extend_from_slice(&[0x07, 0x07, 0x01, 0x03, b'r', b'u', b'n', 0x00, 0x01]);
+        // Code section: empty body
+        wasm.extend_from_slice(&[0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b]);
+        wasm
+    }
+
+    fn leb128(mut value: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    /// Encodes an export section; each export is `(name, kind, index)`.
+    fn export_section(exports: &[(&str, u8, u8)]) -> Vec<u8> {
+        let mut body = vec![exports.len() as u8];
+        for (name, kind, index) in exports {
+            body.push(name.len() as u8);
+            body.extend_from_slice(name.as_bytes());
+            body.extend_from_slice(&[*kind, *index]);
+        }
+        let mut section = vec![0x07, body.len() as u8];
+        section.extend(body);
+        section
+    }
+
+    const FUNC: u8 = 0x00;
+    const MEMORY: u8 = 0x02;
+
+    /// Builds a minimal module with one local function, no memory, and the
+    /// given exports.
+    fn module_exporting(exports: &[(&str, u8, u8)]) -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: one `() -> ()` signature
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // Function section
+        wasm.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]);
+        wasm.extend(export_section(exports));
+        // Code section: empty body
+        wasm.extend_from_slice(&[0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b]);
+        wasm
+    }
+
+    /// Builds a minimal module with one local function and a memory
+    /// declaring `initial` and `maximum` pages, with the given exports.
+    fn module_with_memory_exporting(
+        initial: u32,
+        maximum: u32,
+        exports: &[(&str, u8, u8)],
+    ) -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        // Type section: one `() -> ()` signature
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // Function section
+        wasm.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]);
+        // Memory section: one memory with both limits
+        let mut limits = vec![0x01, 0x01];
+        limits.extend(leb128(initial));
+        limits.extend(leb128(maximum));
+        wasm.extend_from_slice(&[0x05, limits.len() as u8]);
+        wasm.extend(limits);
+        wasm.extend(export_section(exports));
+        // Code section: empty body
+        wasm.extend_from_slice(&[0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b]);
+        wasm
+    }
+
+    /// Builds a minimal module exporting `run` and its memory, which declares
+    /// `initial` and `maximum` pages.
+    fn module_with_memory(initial: u32, maximum: u32) -> Vec<u8> {
+        module_with_memory_exporting(
+            initial,
+            maximum,
+            &[("run", FUNC, 0), (MEMORY_EXPORT_NAME, MEMORY, 0)],
+        )
+    }
+
+    fn env_only() -> Vec<String> {
+        vec!["env".to_string()]
+    }
+
+    #[test]
+    fn env_imports_are_accepted() {
+        let result = validate_wasm_with_allowlist(&module_importing("env"), &env_only());
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert!(result.disallowed_imports.is_empty());
+        assert_eq!(result.import_functions, vec!["env::fd_write"]);
+    }
+
+    #[test]
+    fn non_env_imports_are_rejected() {
+        let result = validate_wasm_with_allowlist(
+            &module_importing("wasi_snapshot_preview1"),
+            &env_only(),
+        );
+        assert!(!result.valid);
+        assert_eq!(
+            result.disallowed_imports,
+            vec!["wasi_snapshot_preview1::fd_write"]
+        );
+        assert!(result.errors.contains(&disallowed_import_message(
+            "wasi_snapshot_preview1::fd_write"
+        )));
+    }
+
+    #[test]
+    fn allowlist_can_permit_additional_modules() {
+        let allowed = vec!["env".to_string(), "wasi_snapshot_preview1".to_string()];
+        let result =
+            validate_wasm_with_allowlist(&module_importing("wasi_snapshot_preview1"), &allowed);
+        assert!(result.valid, "errors: {:?}", result.errors);
+    }
+
+    #[test]
+    fn semantically_invalid_body_is_rejected() {
+        let mut wasm = module_importing("env");
+        // Replace the empty body with one calling a function index that does not exist
+        let len = wasm.len();
+        wasm.truncate(len - 6);
+        wasm.extend_from_slice(&[0x0a, 0x06, 0x01, 0x04, 0x00, 0x10, 0x09, 0x0b]);
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(!result.valid);
+        assert_eq!(result.function_count, 1);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.starts_with("WASM validation error at offset")));
+    }
+
+    #[test]
+    fn export_past_the_function_count_is_rejected() {
+        let mut wasm = module_importing("env");
+        // Point `run` at function 2; only the import (0) and one local (1) exist
+        let index = wasm.len() - 7;
+        assert_eq!(wasm[index], 0x01);
+        wasm[index] = 0x02;
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(!result.valid);
+        assert!(result
+            .errors
+            .contains(&dangling_export_message("run", 2, 2)));
+    }
+
+    #[test]
+    fn simd_is_not_a_soroban_feature() {
+        let mut wasm = module_importing("env");
+        // Body: v128.const 0; drop
+        let len = wasm.len();
+        wasm.truncate(len - 6);
+        let mut body = vec![0x00, 0xfd, 0x0c];
+        body.extend_from_slice(&[0u8; 16]);
+        body.extend_from_slice(&[0x1a, 0x0b]);
+        wasm.extend_from_slice(&[0x0a, (body.len() + 2) as u8, 0x01, body.len() as u8]);
+        wasm.extend_from_slice(&body);
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(!result.valid);
+    }
+
+    #[test]
+    fn consistent_memory_limits_are_reported() {
+        let result = validate_wasm_with_allowlist(&module_with_memory(2, 16), &env_only());
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert_eq!(result.memory_pages, 2);
+        assert_eq!(result.memory_maximum_pages, Some(16));
+        assert!(result
+            .warnings
+            .iter()
+            .all(|w| !w.contains("Memory maximum")));
+    }
+
+    #[test]
+    fn memory_maximum_below_initial_is_rejected() {
+        let result = validate_wasm_with_allowlist(&module_with_memory(4, 2), &env_only());
+        assert!(!result.valid);
+        assert_eq!(result.memory_pages, 4);
+        assert_eq!(result.memory_maximum_pages, Some(2));
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.contains("less than initial")));
+    }
+
+    #[test]
+    fn huge_memory_maximum_is_warned() {
+        let result = validate_wasm_with_allowlist(&module_with_memory(1, 65536), &env_only());
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert_eq!(result.memory_maximum_pages, Some(65536));
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("unusually large")));
+    }
+
+    #[test]
+    fn duplicate_export_names_are_rejected() {
+        let wasm = module_exporting(&[("run", FUNC, 0), ("run", FUNC, 0), ("run", FUNC, 0)]);
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(!result.valid);
+        assert_eq!(result.duplicate_exports, vec!["run"]);
+        assert_eq!(
+            result
+                .errors
+                .iter()
+                .filter(|e| **e == duplicate_export_message("run"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn reserved_export_names_are_warned() {
+        let wasm = module_exporting(&[
+            ("__constructor", FUNC, 0),
+            ("__internal", FUNC, 0),
+            (MEMORY_EXPORT_NAME, FUNC, 0),
+        ]);
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert_eq!(result.reserved_exports, vec!["__internal", "memory"]);
+        assert!(result
+            .warnings
+            .contains(&reserved_export_message("__internal")));
+    }
+
+    #[test]
+    fn declared_memory_must_be_exported() {
+        let wasm = module_with_memory_exporting(1, 16, &[("run", FUNC, 0)]);
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(!result.valid);
+        assert!(result.errors.contains(&missing_memory_export_message()));
+
+        let exported = validate_wasm_with_allowlist(&module_with_memory(1, 16), &env_only());
+        assert!(!exported.errors.contains(&missing_memory_export_message()));
+    }
+
+    /// Builds a module with one memory whose single exported function runs
+    /// `body` (instructions only, without locals or the final `end`).
+    fn module_running(body: &[u8]) -> Vec<u8> {
+        let mut wasm = module_with_memory(1, 16);
+        // Replace the empty code section
+        wasm.truncate(wasm.len() - 6);
+        let mut func = vec![0x00];
+        func.extend_from_slice(body);
+        func.push(0x0b);
+        wasm.extend_from_slice(&[0x0a, (func.len() + 2) as u8, 0x01, func.len() as u8]);
+        wasm.extend(func);
+        wasm
+    }
+
+    #[test]
+    fn mvp_bodies_use_no_features() {
+        let result = validate_wasm_with_allowlist(&module_with_memory(1, 16), &env_only());
+        assert!(result.features_used.is_empty());
+    }
+
+    #[test]
+    fn bulk_memory_is_reported_without_a_warning() {
+        // memory.fill(0, 0, 0)
+        let body = [0x41, 0x00, 0x41, 0x00, 0x41, 0x00, 0xfc, 0x0b, 0x00];
+
+        let result = validate_wasm_with_allowlist(&module_running(&body), &env_only());
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert_eq!(result.features_used, vec!["bulk-memory"]);
+        assert!(!result
+            .warnings
+            .contains(&unsupported_feature_message("bulk-memory")));
+    }
+
+    #[test]
+    fn simd_and_atomics_are_reported_and_warned() {
+        // v128.const 0; drop; i32.const 0; i32.atomic.load; drop
+        let mut body = vec![0xfd, 0x0c];
+        body.extend_from_slice(&[0u8; 16]);
+        body.extend_from_slice(&[0x1a, 0x41, 0x00, 0xfe, 0x10, 0x02, 0x00, 0x1a]);
+
+        let result = validate_wasm_with_allowlist(&module_running(&body), &env_only());
+        assert!(!result.valid);
+        assert_eq!(result.features_used, vec!["simd", "threads"]);
+        assert!(result
+            .warnings
+            .contains(&unsupported_feature_message("simd")));
+        assert!(result
+            .warnings
+            .contains(&unsupported_feature_message("threads")));
+    }
+
+    #[test]
+    fn reference_type_instructions_are_reported() {
+        // ref.null func; ref.is_null; drop
+        let body = [0xd0, 0x70, 0xd1, 0x1a];
+
+        let result = validate_wasm_with_allowlist(&module_running(&body), &env_only());
+        assert_eq!(result.features_used, vec!["reference-types"]);
+    }
+
+    #[test]
+    fn custom_sections_are_listed_by_name_and_size() {
+        let mut wasm = module_with_memory(1, 16);
+        for (name, data) in [
+            ("contractspecv0", &[0u8; 12][..]),
+            ("producers", &[1, 2][..]),
+        ] {
+            wasm.extend_from_slice(&[0x00, (1 + name.len() + data.len()) as u8, name.len() as u8]);
+            wasm.extend_from_slice(name.as_bytes());
+            wasm.extend_from_slice(data);
+        }
+
+        let result = validate_wasm_with_allowlist(&wasm, &env_only());
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert_eq!(
+            result.custom_sections,
+            vec![
+                CustomSectionInfo {
+                    name: "contractspecv0".to_string(),
+                    size_bytes: 12,
+                },
+                CustomSectionInfo {
+                    name: "producers".to_string(),
+                    size_bytes: 2,
+                },
+            ]
+        );
     }
 }
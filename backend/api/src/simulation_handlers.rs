@@ -1,213 +1,144 @@
 use axum::{
-    extract::{Json, State},
-    response::IntoResponse,
+    extract::{rejection::JsonRejection, Json, Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
 };
 use base64::Engine;
+use serde::{Deserialize, Serialize};
 use shared::models::{
-    ContractFunctionInfo, GasEstimate, PerformanceMetrics, SimulateDeployRequest, SimulationError,
-    SimulationResult, SimulationWarning,
+    ContractFunctionInfo, GasDelta, GasEstimate, InterfaceVersion, Network, PerformanceMetrics,
+    SimulateDeployRequest, SimulationError, SimulationResult, SimulationWarning, WasmEncoding,
 };
-use std::time::Instant;
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+use uuid::Uuid;
 
 use crate::{
+    breaking_changes::{
+        build_abi_diff, diff_extracted_abi, fetch_latest_abi_for_contract, AbiDiff,
+    },
     error::{ApiError, ApiResult},
-    simulation,
+    gas_history,
+    handlers::db_internal_error,
+    simulation::{
+        self,
+        env_meta::{newer_protocol_message, older_protocol_message},
+        wasm_validator::{
+            disallowed_import_message, duplicate_export_message, missing_memory_export_message,
+            reserved_export_message, unsupported_feature_message,
+        },
+    },
     state::AppState,
+    type_safety::parser::parse_json_spec,
     validation::validate_contract_id,
 };
 
+/// Hard limit on the validation, ABI, gas and performance steps
+const SIMULATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tracing::instrument(skip_all, fields(contract_id = tracing::field::Empty))]
 pub async fn simulate_deploy(
     State(state): State<AppState>,
-    Json(req): Json<SimulateDeployRequest>,
+    payload: Result<Json<SimulateDeployRequest>, JsonRejection>,
 ) -> ApiResult<impl IntoResponse> {
     let start_time = Instant::now();
+    let Json(req) = payload.map_err(map_wasm_body_rejection)?;
+    tracing::Span::current().record("contract_id", req.contract_id.as_str());
 
-    let wasm_binary = match base64::engine::general_purpose::STANDARD.decode(&req.wasm_binary) {
+    let wasm_binary = match decode_upload(&req.wasm_binary, req.encoding) {
         Ok(bytes) => bytes,
-        Err(e) => {
-            return Ok(Json(SimulationResult {
-                valid: false,
-                errors: vec![SimulationError {
-                    code: "InvalidBase64".to_string(),
-                    message: format!("Failed to decode base64 WASM binary: {}", e),
-                    field: Some("wasm_binary".to_string()),
-                }],
-                warnings: vec![],
-                gas_estimate: GasEstimate {
-                    total_cost_stroops: 0,
-                    total_cost_xlm: 0.0,
-                    wasm_size_kb: 0.0,
-                    complexity_factor: 0.0,
-                    deployment_cost_stroops: 0,
-                    storage_cost_stroops: 0,
-                },
-                performance_metrics: PerformanceMetrics {
-                    estimated_execution_time_ms: 0,
-                    memory_estimate_kb: 0,
-                    function_count: 0,
-                    table_size_bytes: 0,
-                    data_section_bytes: 0,
-                    warnings: vec![],
-                },
-                abi_preview: None,
-                contract_functions: None,
-            }));
-        }
+        Err(error) => return Ok(Json(rejected(vec![error]))),
     };
 
-    let wasm_bytes = wasm_binary.as_slice();
-    let wasm_size_kb = wasm_bytes.len() as f64 / 1024.0;
-
-    if wasm_bytes.is_empty() {
-        return Ok(Json(SimulationResult {
-            valid: false,
-            errors: vec![SimulationError {
-                code: "EmptyWasm".to_string(),
-                message: "WASM binary is empty".to_string(),
-                field: Some("wasm_binary".to_string()),
-            }],
-            warnings: vec![],
-            gas_estimate: GasEstimate {
-                total_cost_stroops: 0,
-                total_cost_xlm: 0.0,
-                wasm_size_kb: 0.0,
-                complexity_factor: 0.0,
-                deployment_cost_stroops: 0,
-                storage_cost_stroops: 0,
-            },
-            performance_metrics: PerformanceMetrics {
-                estimated_execution_time_ms: 0,
-                memory_estimate_kb: 0,
-                function_count: 0,
-                table_size_bytes: 0,
-                data_section_bytes: 0,
-                warnings: vec![],
-            },
-            abi_preview: None,
-            contract_functions: None,
-        }));
-    }
-
     // Validate contract_id
     if let Err(e) = validate_contract_id(&req.contract_id) {
-        return Ok(Json(SimulationResult {
-            valid: false,
-            errors: vec![SimulationError {
-                code: "InvalidContractId".to_string(),
-                message: e,
-                field: Some("contract_id".to_string()),
-            }],
-            warnings: vec![],
-            gas_estimate: GasEstimate {
-                total_cost_stroops: 0,
-                total_cost_xlm: 0.0,
-                wasm_size_kb: 0.0,
-                complexity_factor: 0.0,
-                deployment_cost_stroops: 0,
-                storage_cost_stroops: 0,
-            },
-            performance_metrics: PerformanceMetrics {
-                estimated_execution_time_ms: 0,
-                memory_estimate_kb: 0,
-                function_count: 0,
-                table_size_bytes: 0,
-                data_section_bytes: 0,
-                warnings: vec![],
-            },
-            abi_preview: None,
-            contract_functions: None,
-        }));
+        return Ok(reject("InvalidContractId", e, "contract_id"));
     }
 
     // Validate name
     if req.name.is_empty() {
-        return Ok(Json(SimulationResult {
-            valid: false,
-            errors: vec![SimulationError {
-                code: "InvalidName".to_string(),
-                message: "Contract name cannot be empty".to_string(),
-                field: Some("name".to_string()),
-            }],
-            warnings: vec![],
-            gas_estimate: GasEstimate {
-                total_cost_stroops: 0,
-                total_cost_xlm: 0.0,
-                wasm_size_kb: 0.0,
-                complexity_factor: 0.0,
-                deployment_cost_stroops: 0,
-                storage_cost_stroops: 0,
-            },
-            performance_metrics: PerformanceMetrics {
-                estimated_execution_time_ms: 0,
-                memory_estimate_kb: 0,
-                function_count: 0,
-                table_size_bytes: 0,
-                data_section_bytes: 0,
-                warnings: vec![],
-            },
-            abi_preview: None,
-            contract_functions: None,
-        }));
+        return Ok(reject(
+            "InvalidName",
+            "Contract name cannot be empty",
+            "name",
+        ));
     }
 
-    // Run WASM validation
-    let validation_result = simulation::validate_wasm(wasm_bytes);
+    if req.rent_ledgers == Some(0) {
+        return Ok(reject(
+            "InvalidRentLedgers",
+            "rent_ledgers must be greater than 0",
+            "rent_ledgers",
+        ));
+    }
 
-    if !validation_result.valid {
-        let errors: Vec<SimulationError> = validation_result
-            .errors
-            .iter()
-            .map(|e| SimulationError {
-                code: "WasmValidationError".to_string(),
-                message: e.clone(),
-                field: Some("wasm_binary".to_string()),
-            })
-            .collect();
+    let mut gas_model = simulation::GasModel::default();
+    if let Some(ledgers) = req.rent_ledgers {
+        gas_model.rent_ledgers = ledgers;
+    }
+    let wasm_hash = gas_history::module_hash(&wasm_binary);
+    let pipeline = match simulate(wasm_binary, gas_model).await? {
+        Ok(pipeline) => pipeline,
+        Err(rejection) => return Ok(rejection),
+    };
 
-        return Ok(Json(SimulationResult {
-            valid: false,
-            errors,
-            warnings: vec![],
-            gas_estimate: GasEstimate {
-                total_cost_stroops: 0,
-                total_cost_xlm: 0.0,
-                wasm_size_kb: 0.0,
-                complexity_factor: 0.0,
-                deployment_cost_stroops: 0,
-                storage_cost_stroops: 0,
-            },
-            performance_metrics: PerformanceMetrics {
-                estimated_execution_time_ms: 0,
-                memory_estimate_kb: 0,
-                function_count: 0,
-                table_size_bytes: 0,
-                data_section_bytes: 0,
-                warnings: vec![],
-            },
-            abi_preview: None,
-            contract_functions: None,
-        }));
+    let gas_network = gas_network(req.network.as_ref(), &state.default_gas_network);
+    let gas_delta = previous_gas_delta(
+        &state,
+        &req.contract_id,
+        gas_network,
+        &wasm_hash,
+        &pipeline.gas,
+    )
+    .await;
+    if let Err(err) = gas_history::record_estimate(
+        &state.db,
+        &req.contract_id,
+        gas_network,
+        &wasm_hash,
+        &pipeline.gas,
+    )
+    .await
+    {
+        tracing::warn!(error = ?err, contract_id = %req.contract_id, "failed to record gas estimate");
     }
 
-    // Extract ABI
-    let abi_result = simulation::extract_abi(wasm_bytes);
+    Ok(Json(simulation_result(
+        pipeline,
+        gas_delta,
+        req.include_sections,
+        start_time,
+    )))
+}
 
-    // Estimate gas
-    let gas_result = simulation::estimate_gas(wasm_bytes, &validation_result);
+/// Assemble the result of a successful simulation run.
+fn simulation_result(
+    pipeline: Pipeline,
+    gas_delta: Option<GasDelta>,
+    include_sections: bool,
+    start_time: Instant,
+) -> SimulationResult {
+    let Pipeline {
+        validation: validation_result,
+        abi: abi_result,
+        gas: gas_result,
+        performance: performance_result,
+        interface_version,
+    } = pipeline;
 
-    // Analyze performance
-    let performance_result =
-        simulation::analyze_performance(wasm_bytes, &validation_result, &abi_result);
+    let version_warning = interface_version
+        .as_ref()
+        .and_then(|version| protocol_warning(version, simulation::target_protocol_version()));
 
     // Convert warnings
-    let warnings: Vec<SimulationWarning> = validation_result
-        .warnings
-        .iter()
-        .map(|w| SimulationWarning {
-            code: "WasmWarning".to_string(),
-            message: w.clone(),
-            severity: Some("low".to_string()),
-        })
+    let warnings: Vec<SimulationWarning> = validation_warnings(&validation_result)
+        .into_iter()
         .chain(
             performance_result
                 .warnings
@@ -218,6 +149,12 @@ pub async fn simulate_deploy(
                     severity: Some(w.severity.clone()),
                 }),
         )
+        .chain(gas_result.warnings.iter().map(|w| SimulationWarning {
+            code: "GasCostSaturated".to_string(),
+            message: w.clone(),
+            severity: Some("medium".to_string()),
+        }))
+        .chain(version_warning)
         .collect();
 
     // Build contract functions info
@@ -245,7 +182,7 @@ pub async fn simulate_deploy(
         });
     }
 
-    Ok(Json(SimulationResult {
+    SimulationResult {
         valid: true,
         errors: vec![],
         warnings: final_warnings,
@@ -256,6 +193,8 @@ pub async fn simulate_deploy(
             complexity_factor: gas_result.complexity_factor,
             deployment_cost_stroops: gas_result.deployment_cost_stroops,
             storage_cost_stroops: gas_result.storage_cost_stroops,
+            per_function: gas_result.per_function,
+            rent_estimate: Some(gas_result.rent_estimate),
         },
         performance_metrics: PerformanceMetrics {
             estimated_execution_time_ms: performance_result.estimated_execution_time_ms,
@@ -265,7 +204,7 @@ pub async fn simulate_deploy(
             data_section_bytes: validation_result.data_section_size,
             warnings: vec![],
         },
-        abi_preview: if !abi_result.types.is_empty() {
+        abi_preview: if !abi_result.functions.is_empty() || !abi_result.types.is_empty() {
             Some(serde_json::json!({
                 "function_count": abi_result.functions.len(),
                 "type_count": abi_result.types.len(),
@@ -278,5 +217,544 @@ pub async fn simulate_deploy(
         } else {
             Some(contract_functions)
         },
+        gas_delta,
+        custom_sections: include_sections.then_some(validation_result.custom_sections),
+        interface_version,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimulateUpgradeRequest {
+    /// Base64-encoded replacement WASM binary
+    pub wasm_binary: String,
+    /// Encoding of `wasm_binary` after base64 decoding; gzip is auto-detected when omitted
+    #[serde(default)]
+    pub encoding: Option<WasmEncoding>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpgradeSimulation {
+    pub contract_id: String,
+    /// The module simulates cleanly and keeps the deployed interface intact
+    pub safe_to_upgrade: bool,
+    pub simulation: SimulationResult,
+    /// Changes against the registered ABI; `None` when the module was
+    /// rejected or no functions could be extracted from it
+    pub abi_diff: Option<AbiDiff>,
+    /// Gas compared with the deployed version's latest recorded estimate
+    pub gas_delta: Option<GasDelta>,
+}
+
+/// POST /api/contracts/:id/simulate-upgrade — simulate a replacement WASM for a
+/// registered contract and check it against the currently registered ABI.
+///
+/// The estimate isn't recorded in the gas history, since nothing is deployed.
+#[tracing::instrument(skip_all, fields(contract_id = %id))]
+pub async fn simulate_upgrade(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Result<Json<SimulateUpgradeRequest>, JsonRejection>,
+) -> ApiResult<Json<UpgradeSimulation>> {
+    let start_time = Instant::now();
+    let Json(req) = payload.map_err(map_wasm_body_rejection)?;
+    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            "InvalidContractId",
+            format!("Invalid contract ID format: {}", id),
+        )
+    })?;
+
+    let (contract_id, network): (String, Network) =
+        sqlx::query_as("SELECT contract_id, network FROM contracts WHERE id = $1")
+            .bind(contract_uuid)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract for upgrade simulation", err))?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "ContractNotFound",
+                    format!("No contract found with ID: {}", id),
+                )
+            })?;
+    let deployed_abi = fetch_latest_abi_for_contract(&state, &id).await?;
+    let deployed_abi = parse_json_spec(&deployed_abi, &contract_id).map_err(|e| {
+        ApiError::bad_request("InvalidABI", format!("Failed to parse deployed ABI: {}", e))
+    })?;
+
+    let unsafe_upgrade = |simulation| UpgradeSimulation {
+        contract_id: contract_id.clone(),
+        safe_to_upgrade: false,
+        simulation,
+        abi_diff: None,
+        gas_delta: None,
+    };
+    let wasm_binary = match decode_upload(&req.wasm_binary, req.encoding) {
+        Ok(bytes) => bytes,
+        Err(error) => return Ok(Json(unsafe_upgrade(rejected(vec![error])))),
+    };
+    let wasm_hash = gas_history::module_hash(&wasm_binary);
+    let pipeline = match simulate(wasm_binary, simulation::GasModel::default()).await? {
+        Ok(pipeline) => pipeline,
+        Err(Json(rejection)) => return Ok(Json(unsafe_upgrade(rejection))),
+    };
+
+    let gas_delta =
+        previous_gas_delta(&state, &contract_id, &network, &wasm_hash, &pipeline.gas).await;
+    let functions = pipeline.abi.functions.clone();
+    let mut simulation = simulation_result(pipeline, None, false, start_time);
+
+    let abi_diff = if functions.is_empty() {
+        simulation.warnings.push(SimulationWarning {
+            code: "AbiNotExtracted".to_string(),
+            message: "No functions could be extracted from the module, so its interface \
+                      could not be compared with the deployed ABI"
+                .to_string(),
+            severity: Some("high".to_string()),
+        });
+        None
+    } else {
+        Some(build_abi_diff(
+            contract_id.clone(),
+            "deployed".to_string(),
+            "upload".to_string(),
+            diff_extracted_abi(&deployed_abi, &functions),
+        ))
+    };
+
+    Ok(Json(UpgradeSimulation {
+        safe_to_upgrade: abi_diff.as_ref().is_some_and(|diff| !diff.breaking),
+        contract_id,
+        simulation,
+        abi_diff,
+        gas_delta,
     }))
 }
+
+/// Base64-decode, decompress and size-check an uploaded module, or the error
+/// to reject it with.
+fn decode_upload(
+    wasm_base64: &str,
+    encoding: Option<WasmEncoding>,
+) -> Result<Vec<u8>, SimulationError> {
+    let error = |code: &str, message: String| SimulationError {
+        code: code.to_string(),
+        message,
+        field: Some("wasm_binary".to_string()),
+    };
+
+    simulation::check_base64_len(wasm_base64, simulation::max_wasm_upload_bytes())
+        .map_err(|e| error("WasmTooLarge", e))?;
+
+    let wasm_binary = base64::engine::general_purpose::STANDARD
+        .decode(wasm_base64)
+        .map_err(|e| {
+            error(
+                "InvalidBase64",
+                format!("Failed to decode base64 WASM binary: {}", e),
+            )
+        })?;
+
+    let wasm_binary = simulation::decode_wasm(wasm_binary, encoding)
+        .map_err(|e| error(e.code(), e.to_string()))?;
+
+    simulation::check_wasm_size(&wasm_binary, simulation::max_wasm_size_bytes())
+        .map_err(|e| error("WasmTooLarge", e))?;
+
+    if wasm_binary.is_empty() {
+        return Err(error("EmptyWasm", "WASM binary is empty".to_string()));
+    }
+    Ok(wasm_binary)
+}
+
+/// Run the pipeline under [`SIMULATION_TIMEOUT`], or the rejection to respond
+/// with when it times out or the module is invalid.
+async fn simulate(
+    wasm_binary: Vec<u8>,
+    gas_model: simulation::GasModel,
+) -> ApiResult<Result<Pipeline, Json<SimulationResult>>> {
+    let pipeline = run_with_deadline(SIMULATION_TIMEOUT, move || {
+        run_pipeline(&wasm_binary, &gas_model)
+    })
+    .await?;
+
+    Ok(match pipeline {
+        Some(Ok(pipeline)) => Ok(pipeline),
+        Some(Err((errors, warnings))) => Err(Json(SimulationResult {
+            warnings,
+            ..rejected(errors)
+        })),
+        None => Err(Json(rejected(vec![SimulationError {
+            code: "SimulationTimeout".to_string(),
+            message: format!(
+                "Simulation did not finish within {}s",
+                SIMULATION_TIMEOUT.as_secs()
+            ),
+            field: None,
+        }]))),
+    })
+}
+
+/// `gas` of the module `wasm_hash` compared with the estimate of the deployed
+/// version, or of another build of the contract, if any was recorded
+async fn previous_gas_delta(
+    state: &AppState,
+    contract_id: &str,
+    network: &Network,
+    wasm_hash: &str,
+    gas: &simulation::GasEstimationResult,
+) -> Option<GasDelta> {
+    match gas_history::previous_estimate(&state.db, contract_id, network, wasm_hash).await {
+        Ok(previous) => previous.map(|(cost, estimated_at)| {
+            gas_history::gas_delta(gas.total_cost_stroops, cost, estimated_at)
+        }),
+        Err(err) => {
+            tracing::warn!(error = ?err, contract_id = %contract_id, "failed to fetch previous gas estimate");
+            None
+        }
+    }
+}
+
+/// Outputs of the CPU-bound simulation steps for a valid module
+struct Pipeline {
+    validation: simulation::WasmValidationResult,
+    abi: simulation::AbiExtractionResult,
+    gas: simulation::GasEstimationResult,
+    performance: simulation::PerformanceAnalysisResult,
+    interface_version: Option<InterfaceVersion>,
+}
+
+/// Validator warnings, coded so clients can tell reserved export names and
+/// unsupported WASM features apart from general notes.
+fn validation_warnings(validation: &simulation::WasmValidationResult) -> Vec<SimulationWarning> {
+    validation
+        .warnings
+        .iter()
+        .map(|w| {
+            let is_reserved_export = validation
+                .reserved_exports
+                .iter()
+                .any(|name| *w == reserved_export_message(name));
+            let is_unsupported_feature = validation
+                .features_used
+                .iter()
+                .any(|feature| *w == unsupported_feature_message(feature));
+            let (code, severity) = if is_reserved_export {
+                ("ReservedExportName", "medium")
+            } else if is_unsupported_feature {
+                ("UnsupportedWasmFeature", "high")
+            } else {
+                ("WasmWarning", "low")
+            };
+            SimulationWarning {
+                code: code.to_string(),
+                message: w.clone(),
+                severity: Some(severity.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Validate, extract the ABI, estimate gas and analyze performance, stopping
+/// with the validation errors, and its warnings, if the module is invalid.
+fn run_pipeline(
+    wasm_bytes: &[u8],
+    gas_model: &simulation::GasModel,
+) -> Result<Pipeline, (Vec<SimulationError>, Vec<SimulationWarning>)> {
+    let validation = tracing::info_span!("simulation.validate")
+        .in_scope(|| simulation::validate_wasm(wasm_bytes));
+
+    if !validation.valid {
+        let errors = validation
+            .errors
+            .iter()
+            .map(|e| {
+                let is_disallowed_import = validation
+                    .disallowed_imports
+                    .iter()
+                    .any(|import| *e == disallowed_import_message(import));
+                let is_duplicate_export = validation
+                    .duplicate_exports
+                    .iter()
+                    .any(|name| *e == duplicate_export_message(name));
+                let code = if is_disallowed_import {
+                    "DisallowedImport"
+                } else if is_duplicate_export {
+                    "DuplicateExport"
+                } else if *e == missing_memory_export_message() {
+                    "MissingMemoryExport"
+                } else {
+                    "WasmValidationError"
+                };
+                SimulationError {
+                    code: code.to_string(),
+                    message: e.clone(),
+                    field: Some("wasm_binary".to_string()),
+                }
+            })
+            .collect();
+        return Err((errors, validation_warnings(&validation)));
+    }
+
+    let abi = tracing::info_span!("simulation.extract_abi")
+        .in_scope(|| simulation::extract_abi(wasm_bytes));
+    let gas = tracing::info_span!("simulation.estimate_gas")
+        .in_scope(|| simulation::estimate_gas(wasm_bytes, &validation, gas_model));
+    let performance = tracing::info_span!("simulation.analyze_performance")
+        .in_scope(|| simulation::analyze_performance(wasm_bytes, &validation, &abi));
+    let interface_version = simulation::extract_interface_version(wasm_bytes);
+
+    Ok(Pipeline {
+        validation,
+        abi,
+        gas,
+        performance,
+        interface_version,
+    })
+}
+
+/// Flags a contract built for a different protocol than the target network
+/// runs. Newer ones are rejected on deploy; older ones usually still work.
+fn protocol_warning(version: &InterfaceVersion, target: u32) -> Option<SimulationWarning> {
+    let (code, message, severity) = match version.protocol.cmp(&target) {
+        std::cmp::Ordering::Greater => (
+            "ProtocolVersionTooNew",
+            newer_protocol_message(version, target),
+            "high",
+        ),
+        std::cmp::Ordering::Less => (
+            "ProtocolVersionOutdated",
+            older_protocol_message(version, target),
+            "low",
+        ),
+        std::cmp::Ordering::Equal => return None,
+    };
+    Some(SimulationWarning {
+        code: code.to_string(),
+        message,
+        severity: Some(severity.to_string()),
+    })
+}
+
+/// Run CPU-bound `work` on the blocking pool, giving up after `limit`.
+/// Returns `None` on timeout; the blocking thread can't be interrupted and
+/// finishes in the background.
+async fn run_with_deadline<T: Send + 'static>(
+    limit: Duration,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> ApiResult<Option<T>> {
+    // Blocking-pool threads don't inherit the caller's span
+    let span = tracing::Span::current();
+    let work = move || span.in_scope(work);
+    match tokio::time::timeout(limit, tokio::task::spawn_blocking(work)).await {
+        Ok(Ok(output)) => Ok(Some(output)),
+        Ok(Err(err)) => {
+            tracing::error!(error = ?err, "simulation task failed");
+            Err(ApiError::internal("Simulation failed unexpectedly"))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Result for a request rejected before simulation ran.
+fn rejected(errors: Vec<SimulationError>) -> SimulationResult {
+    SimulationResult {
+        valid: false,
+        errors,
+        warnings: vec![],
+        gas_estimate: GasEstimate {
+            total_cost_stroops: 0,
+            total_cost_xlm: 0.0,
+            wasm_size_kb: 0.0,
+            complexity_factor: 0.0,
+            deployment_cost_stroops: 0,
+            storage_cost_stroops: 0,
+            per_function: vec![],
+            rent_estimate: None,
+        },
+        performance_metrics: PerformanceMetrics {
+            estimated_execution_time_ms: 0,
+            memory_estimate_kb: 0,
+            function_count: 0,
+            table_size_bytes: 0,
+            data_section_bytes: 0,
+            warnings: vec![],
+        },
+        abi_preview: None,
+        contract_functions: None,
+        gas_delta: None,
+        custom_sections: None,
+        interface_version: None,
+    }
+}
+
+/// A body cut off by the route's `DefaultBodyLimit` is reported as `WasmTooLarge`
+fn map_wasm_body_rejection(err: JsonRejection) -> ApiError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "WasmTooLarge",
+            format!(
+                "Request body exceeds the {} byte limit for WASM uploads (WASM limit: {} bytes)",
+                simulation::wasm_body_limit(),
+                simulation::max_wasm_size_bytes()
+            ),
+        );
+    }
+    ApiError::bad_request(
+        "InvalidRequest",
+        format!("Invalid JSON payload: {}", err.body_text()),
+    )
+}
+
+fn reject(code: &str, message: impl Into<String>, field: &str) -> Json<SimulationResult> {
+    Json(rejected(vec![SimulationError {
+        code: code.to_string(),
+        message: message.into(),
+        field: Some(field.to_string()),
+    }]))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExtractAbiStreamRequest {
+    /// Base64-encoded WASM binary
+    pub wasm_binary: String,
+}
+
+/// Streams ABI extraction progress for large contracts as server-sent events.
+///
+/// Emits `progress` events as sections are scanned, `error` events for
+/// malformed sections, and a final `complete` event carrying the full ABI.
+pub async fn extract_abi_stream(
+    payload: Result<Json<ExtractAbiStreamRequest>, JsonRejection>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let Json(req) = payload.map_err(map_wasm_body_rejection)?;
+    simulation::check_base64_len(&req.wasm_binary, simulation::max_wasm_upload_bytes())
+        .map_err(|e| ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "WasmTooLarge", e))?;
+
+    let wasm_binary = base64::engine::general_purpose::STANDARD
+        .decode(&req.wasm_binary)
+        .map_err(|e| {
+            ApiError::bad_request(
+                "InvalidBase64",
+                format!("Failed to decode base64 WASM binary: {}", e),
+            )
+        })?;
+    if wasm_binary.is_empty() {
+        return Err(ApiError::bad_request("EmptyWasm", "WASM binary is empty"));
+    }
+    simulation::check_wasm_size(&wasm_binary, simulation::max_wasm_size_bytes())
+        .map_err(|e| ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "WasmTooLarge", e))?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    tokio::task::spawn_blocking(move || {
+        simulation::extract_abi_chunked(&wasm_binary, |event| {
+            // A send error only means the client disconnected.
+            let _ = tx.blocking_send(event);
+        });
+    });
+
+    let stream = ReceiverStream::new(rx).map(|event| Ok(abi_event_to_sse(&event)));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn abi_event_to_sse(event: &simulation::AbiExtractionEvent) -> Event {
+    Event::default()
+        .event(event.name())
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event("error"))
+}
+
+/// Network a deploy estimate is recorded and compared under: the requested
+/// one, or the configured default when omitted.
+fn gas_network<'a>(requested: Option<&'a Network>, default_network: &'a Network) -> &'a Network {
+    requested.unwrap_or(default_network)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abi_events_are_named_by_kind() {
+        let event = simulation::AbiExtractionEvent::Progress {
+            sections_scanned: 1,
+            functions_found: 0,
+        };
+        let sse = format!("{:?}", abi_event_to_sse(&event));
+        assert!(sse.contains("progress"));
+        assert!(sse.contains("sections_scanned"));
+    }
+
+    #[test]
+    fn configured_default_network_applies_when_network_omitted() {
+        let network = gas_network(None, &Network::Futurenet);
+        assert!(matches!(network, Network::Futurenet));
+    }
+
+    #[test]
+    fn requested_network_overrides_default_network() {
+        let network = gas_network(Some(&Network::Testnet), &Network::Futurenet);
+        assert!(matches!(network, Network::Testnet));
+    }
+
+    #[tokio::test]
+    async fn work_past_the_deadline_is_abandoned() {
+        let slow = run_with_deadline(Duration::from_millis(20), || {
+            std::thread::sleep(Duration::from_millis(500));
+        })
+        .await
+        .unwrap();
+        assert!(slow.is_none());
+
+        let fast = run_with_deadline(Duration::from_secs(5), || 42)
+            .await
+            .unwrap();
+        assert_eq!(fast, Some(42));
+    }
+
+    #[tokio::test]
+    async fn body_over_the_route_limit_is_wasm_too_large() {
+        use axum::{body::Body, extract::DefaultBodyLimit, http::Request, routing::post, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new().route(
+            "/extract",
+            post(extract_abi_stream).layer(DefaultBodyLimit::max(64)),
+        );
+        let body = serde_json::json!({ "wasm_binary": "A".repeat(256) }).to_string();
+        let request = Request::post("/extract")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"]["code"], "WasmTooLarge");
+    }
+
+    #[test]
+    fn protocol_mismatches_are_warned() {
+        let built_for = |protocol| InterfaceVersion {
+            protocol,
+            pre_release: 0,
+        };
+
+        assert!(protocol_warning(&built_for(22), 22).is_none());
+        let newer = protocol_warning(&built_for(23), 22).unwrap();
+        assert_eq!(newer.code, "ProtocolVersionTooNew");
+        assert_eq!(newer.severity.as_deref(), Some("high"));
+        let older = protocol_warning(&built_for(20), 22).unwrap();
+        assert_eq!(older.code, "ProtocolVersionOutdated");
+    }
+
+    #[test]
+    fn invalid_module_stops_the_pipeline() {
+        let model = simulation::GasModel::default();
+        let (errors, _) = run_pipeline(b"not wasm", &model).err().unwrap();
+        assert!(errors.iter().all(|e| e.code == "WasmValidationError"));
+    }
+}
@@ -1,6 +1,11 @@
+use crate::activity_events::ActivityEventHub;
+use crate::background_jobs::JobScheduler;
 use crate::cache::{CacheConfig, CacheLayer};
+use crate::canary_events::CanaryEventHub;
+use crate::deployment_events::DeploymentEventHub;
 use crate::health_monitor::HealthMonitorStatus;
 use prometheus::Registry;
+use shared::models::Network;
 use sqlx::PgPool;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -15,11 +20,22 @@ pub struct AppState {
     pub registry: Registry,
     pub is_shutting_down: Arc<AtomicBool>,
     pub health_monitor_status: HealthMonitorStatus,
+    /// Network gas estimates are recorded under when a simulation request omits one
+    pub default_gas_network: Network,
+    /// Bounded scheduler that periodic DB-heavy jobs run through
+    pub background_jobs: JobScheduler,
+    /// Newly recorded canary metrics, fanned out to live streams
+    pub canary_events: Arc<CanaryEventHub>,
+    /// Notable registry actions, fanned out to activity feed sockets
+    pub activity_events: Arc<ActivityEventHub>,
+    /// Wakes long-polling deployment status watchers
+    pub deployment_events: Arc<DeploymentEventHub>,
 }
 
 impl AppState {
     pub fn new(db: PgPool, registry: Registry, is_shutting_down: Arc<AtomicBool>) -> Self {
         let config = CacheConfig::from_env();
+        let background_jobs = JobScheduler::from_env().with_leader_election(db.clone());
         Self {
             db,
             started_at: Instant::now(),
@@ -27,6 +43,11 @@ impl AppState {
             registry,
             is_shutting_down,
             health_monitor_status: HealthMonitorStatus::default(),
+            default_gas_network: Network::Mainnet,
+            background_jobs,
+            canary_events: Arc::new(CanaryEventHub::default()),
+            activity_events: Arc::new(ActivityEventHub::default()),
+            deployment_events: Arc::new(DeploymentEventHub::default()),
         }
     }
 }
@@ -0,0 +1,534 @@
+// api/src/stellar.rs
+// Soroban RPC client used to check what is actually installed on-chain, as
+// opposed to what the registry believes is deployed.
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::Network;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::cache::CacheLayer;
+
+/// Generic cache namespace for on-chain status, keyed by contract UUID
+pub const ON_CHAIN_STATUS_NS: &str = "on_chain_status";
+/// How long a cached on-chain check is trusted before RPC is asked again
+pub const ON_CHAIN_STATUS_TTL: Duration = Duration::from_secs(30);
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+/// `LedgerEntryType::CONTRACT_DATA` in the Stellar XDR
+const CONTRACT_DATA_ENTRY_TYPE: u32 = 6;
+/// `LedgerEntryType::CONTRACT_CODE` in the Stellar XDR
+const CONTRACT_CODE_ENTRY_TYPE: u32 = 7;
+/// `ScAddressType::SC_ADDRESS_TYPE_CONTRACT`
+const SC_ADDRESS_CONTRACT: u32 = 1;
+/// `ScValType::SCV_CONTRACT_INSTANCE`
+const SCV_CONTRACT_INSTANCE: u32 = 19;
+/// `ScValType::SCV_LEDGER_KEY_CONTRACT_INSTANCE`
+const SCV_LEDGER_KEY_CONTRACT_INSTANCE: u32 = 20;
+/// `ContractDataDurability::PERSISTENT`
+const PERSISTENT_DURABILITY: u32 = 1;
+/// `ContractExecutableType::CONTRACT_EXECUTABLE_WASM`
+const EXECUTABLE_WASM: u32 = 0;
+/// Strkey version byte of a contract (`C...`) address
+const STRKEY_CONTRACT_VERSION: u8 = 2 << 3;
+
+static HTTP: once_cell::sync::Lazy<reqwest::Client> = once_cell::sync::Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(RPC_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StellarRpcError {
+    InvalidWasmHash(String),
+    InvalidContractAddress(String),
+    Request(String),
+    Rpc(String),
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for StellarRpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidWasmHash(hash) => write!(f, "invalid WASM hash: {}", hash),
+            Self::InvalidContractAddress(address) => {
+                write!(f, "invalid contract address: {}", address)
+            }
+            Self::Request(msg) => write!(f, "RPC request failed: {}", msg),
+            Self::Rpc(msg) => write!(f, "RPC error: {}", msg),
+            Self::InvalidResponse(msg) => write!(f, "unexpected RPC response: {}", msg),
+        }
+    }
+}
+
+/// Whether a WASM blob is installed on a network, as of `checked_at`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnChainStatus {
+    pub wasm_hash: String,
+    pub on_chain: bool,
+    /// Ledger the code entry was last modified in, when installed
+    pub ledger_sequence: Option<u32>,
+    /// Latest ledger the RPC node had seen when asked
+    pub latest_ledger: u32,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl OnChainStatus {
+    pub fn is_fresh(&self, wasm_hash: &str, now: DateTime<Utc>) -> bool {
+        self.wasm_hash == wasm_hash
+            && now - self.checked_at
+                < chrono::Duration::from_std(ON_CHAIN_STATUS_TTL).unwrap_or_default()
+    }
+}
+
+pub struct StellarRpcClient {
+    url: String,
+}
+
+impl StellarRpcClient {
+    /// Client for `network`'s RPC endpoint: `STELLAR_RPC_URL_<NETWORK>` (e.g.
+    /// `STELLAR_RPC_URL_TESTNET`) or else `STELLAR_RPC_URL`. `None` when
+    /// neither is set.
+    pub fn for_network(network: &Network) -> Option<Self> {
+        let specific = format!("STELLAR_RPC_URL_{}", network.to_string().to_uppercase());
+        std::env::var(specific)
+            .or_else(|_| std::env::var("STELLAR_RPC_URL"))
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| Self { url })
+    }
+
+    /// Look up the contract code entry for `wasm_hash` via `getLedgerEntries`
+    pub async fn wasm_status(&self, wasm_hash: &str) -> Result<OnChainStatus, StellarRpcError> {
+        let response = self.contract_code_entry(wasm_hash).await?;
+        status_from_response(wasm_hash, response, Utc::now())
+    }
+
+    /// The WASM installed under `wasm_hash`, or `None` when it isn't on-chain
+    pub async fn fetch_wasm(&self, wasm_hash: &str) -> Result<Option<Vec<u8>>, StellarRpcError> {
+        let result = into_result(self.contract_code_entry(wasm_hash).await?)?;
+        result
+            .entries
+            .unwrap_or_default()
+            .into_iter()
+            .next()
+            .map(|entry| contract_code_from_xdr(&entry.xdr))
+            .transpose()
+    }
+
+    /// Hash of the WASM the contract at `contract_address` currently runs, read
+    /// from its instance entry. `None` when the address has no instance or runs
+    /// a built-in executable such as a Stellar asset contract.
+    pub async fn installed_wasm_hash(
+        &self,
+        contract_address: &str,
+    ) -> Result<Option<String>, StellarRpcError> {
+        let key = contract_instance_key(contract_address)?;
+        let result = into_result(self.ledger_entries(key).await?)?;
+        let wasm_hash = result
+            .entries
+            .unwrap_or_default()
+            .into_iter()
+            .next()
+            .map(|entry| wasm_hash_from_instance_xdr(&entry.xdr))
+            .transpose()?;
+        Ok(wasm_hash.flatten())
+    }
+
+    async fn contract_code_entry(&self, wasm_hash: &str) -> Result<RpcResponse, StellarRpcError> {
+        self.ledger_entries(contract_code_key(wasm_hash)?).await
+    }
+
+    async fn ledger_entries(&self, key: String) -> Result<RpcResponse, StellarRpcError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getLedgerEntries",
+            "params": { "keys": [key] },
+        });
+
+        HTTP.post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| StellarRpcError::Request(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| StellarRpcError::InvalidResponse(e.to_string()))
+    }
+}
+
+/// On-chain status of a contract's WASM, served from the generic cache while
+/// fresh. `Ok(None)` when no RPC endpoint is configured for `network`.
+pub async fn cached_wasm_status(
+    cache: &CacheLayer,
+    contract_id: Uuid,
+    network: &Network,
+    wasm_hash: &str,
+) -> Result<Option<OnChainStatus>, StellarRpcError> {
+    let Some(client) = StellarRpcClient::for_network(network) else {
+        return Ok(None);
+    };
+
+    let key = contract_id.to_string();
+    if let (Some(cached), true) = cache.get(ON_CHAIN_STATUS_NS, &key).await {
+        if let Ok(status) = serde_json::from_str::<OnChainStatus>(&cached) {
+            if status.is_fresh(wasm_hash, Utc::now()) {
+                return Ok(Some(status));
+            }
+        }
+    }
+
+    let status = client.wasm_status(wasm_hash).await?;
+    if let Ok(serialized) = serde_json::to_string(&status) {
+        cache
+            .put(
+                ON_CHAIN_STATUS_NS,
+                &key,
+                serialized,
+                Some(ON_CHAIN_STATUS_TTL),
+            )
+            .await;
+    }
+    Ok(Some(status))
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<LedgerEntriesResult>,
+    error: Option<RpcErrorBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcErrorBody {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerEntriesResult {
+    #[serde(default)]
+    entries: Option<Vec<LedgerEntry>>,
+    latest_ledger: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerEntry {
+    /// Base64 XDR `LedgerEntryData`
+    xdr: String,
+    last_modified_ledger_seq: u32,
+}
+
+/// Base64 XDR `LedgerKey::ContractCode` for a hex WASM hash
+fn contract_code_key(wasm_hash: &str) -> Result<String, StellarRpcError> {
+    let hash = hex::decode(wasm_hash)
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or_else(|| StellarRpcError::InvalidWasmHash(wasm_hash.to_string()))?;
+
+    let mut xdr = CONTRACT_CODE_ENTRY_TYPE.to_be_bytes().to_vec();
+    xdr.extend(hash);
+    Ok(base64::engine::general_purpose::STANDARD.encode(xdr))
+}
+
+/// Base64 XDR `LedgerKey::ContractData` for the instance entry of a contract
+/// address
+fn contract_instance_key(contract_address: &str) -> Result<String, StellarRpcError> {
+    let contract = decode_contract_address(contract_address)?;
+
+    let mut xdr = CONTRACT_DATA_ENTRY_TYPE.to_be_bytes().to_vec();
+    xdr.extend(SC_ADDRESS_CONTRACT.to_be_bytes());
+    xdr.extend(contract);
+    xdr.extend(SCV_LEDGER_KEY_CONTRACT_INSTANCE.to_be_bytes());
+    xdr.extend(PERSISTENT_DURABILITY.to_be_bytes());
+    Ok(base64::engine::general_purpose::STANDARD.encode(xdr))
+}
+
+/// The 32-byte contract ID inside a `C...` strkey
+fn decode_contract_address(address: &str) -> Result<[u8; 32], StellarRpcError> {
+    let invalid = || StellarRpcError::InvalidContractAddress(address.to_string());
+    let decoded = base32_decode(address).ok_or_else(invalid)?;
+    if decoded.len() != 35 || decoded[0] != STRKEY_CONTRACT_VERSION {
+        return Err(invalid());
+    }
+    let (payload, checksum) = decoded.split_at(33);
+    if crc16_xmodem(payload).to_le_bytes() != checksum {
+        return Err(invalid());
+    }
+    payload[1..].try_into().map_err(|_| invalid())
+}
+
+/// RFC 4648 base32 without padding, as used by strkeys
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in input.bytes() {
+        let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn crc16_xmodem(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn into_result(response: RpcResponse) -> Result<LedgerEntriesResult, StellarRpcError> {
+    if let Some(error) = response.error {
+        return Err(StellarRpcError::Rpc(error.message));
+    }
+    response
+        .result
+        .ok_or_else(|| StellarRpcError::InvalidResponse("missing result".to_string()))
+}
+
+fn status_from_response(
+    wasm_hash: &str,
+    response: RpcResponse,
+    checked_at: DateTime<Utc>,
+) -> Result<OnChainStatus, StellarRpcError> {
+    let result = into_result(response)?;
+    let entry = result.entries.unwrap_or_default().into_iter().next();
+
+    Ok(OnChainStatus {
+        wasm_hash: wasm_hash.to_string(),
+        on_chain: entry.is_some(),
+        ledger_sequence: entry.map(|e| e.last_modified_ledger_seq),
+        latest_ledger: result.latest_ledger,
+        checked_at,
+    })
+}
+
+/// WASM bytes of a base64 XDR `LedgerEntryData::ContractCode`
+fn contract_code_from_xdr(xdr: &str) -> Result<Vec<u8>, StellarRpcError> {
+    let malformed =
+        |what: &str| StellarRpcError::InvalidResponse(format!("malformed contract code: {}", what));
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(xdr.trim())
+        .map_err(|_| malformed("not base64"))?;
+    let mut rest = bytes.as_slice();
+
+    if read_u32(&mut rest) != Some(CONTRACT_CODE_ENTRY_TYPE) {
+        return Err(malformed("not a contract code entry"));
+    }
+    // ContractCodeEntryV1 is an ExtensionPoint followed by
+    // ContractCodeCostInputs, itself an ExtensionPoint and ten u32 counts
+    let ext_len = match read_u32(&mut rest) {
+        Some(0) => 0,
+        Some(1) => 4 + 4 + 10 * 4,
+        _ => return Err(malformed("unknown extension")),
+    };
+    take(&mut rest, ext_len + 32).ok_or_else(|| malformed("truncated header"))?;
+
+    let code_len = read_u32(&mut rest).ok_or_else(|| malformed("truncated length"))?;
+    take(&mut rest, code_len as usize)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| malformed("truncated code"))
+}
+
+/// WASM hash a base64 XDR `LedgerEntryData::ContractData` instance entry
+/// points at, or `None` for a non-WASM executable
+fn wasm_hash_from_instance_xdr(xdr: &str) -> Result<Option<String>, StellarRpcError> {
+    let malformed = |what: &str| {
+        StellarRpcError::InvalidResponse(format!("malformed contract instance: {}", what))
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(xdr.trim())
+        .map_err(|_| malformed("not base64"))?;
+    let mut rest = bytes.as_slice();
+
+    if read_u32(&mut rest) != Some(CONTRACT_DATA_ENTRY_TYPE) {
+        return Err(malformed("not a contract data entry"));
+    }
+    // Extension point, then the contract address
+    if read_u32(&mut rest) != Some(0) || read_u32(&mut rest) != Some(SC_ADDRESS_CONTRACT) {
+        return Err(malformed("unexpected header"));
+    }
+    take(&mut rest, 32).ok_or_else(|| malformed("truncated address"))?;
+    if read_u32(&mut rest) != Some(SCV_LEDGER_KEY_CONTRACT_INSTANCE) {
+        return Err(malformed("not an instance key"));
+    }
+    read_u32(&mut rest).ok_or_else(|| malformed("truncated durability"))?;
+    if read_u32(&mut rest) != Some(SCV_CONTRACT_INSTANCE) {
+        return Err(malformed("not a contract instance"));
+    }
+
+    match read_u32(&mut rest) {
+        Some(EXECUTABLE_WASM) => take(&mut rest, 32)
+            .map(|hash| Some(hex::encode(hash)))
+            .ok_or_else(|| malformed("truncated WASM hash")),
+        Some(_) => Ok(None),
+        None => Err(malformed("truncated executable")),
+    }
+}
+
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if bytes.len() < n {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Some(head)
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Option<u32> {
+    take(bytes, 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+    #[test]
+    fn contract_code_key_is_type_tag_then_hash() {
+        let key = contract_code_key(HASH).unwrap();
+        let xdr = base64::engine::general_purpose::STANDARD
+            .decode(key)
+            .unwrap();
+        assert_eq!(&xdr[..4], &[0, 0, 0, 7]);
+        assert_eq!(hex::encode(&xdr[4..]), HASH);
+
+        assert!(contract_code_key("abc").is_err());
+    }
+
+    #[test]
+    fn installed_and_missing_code_are_distinguished() {
+        let installed: RpcResponse = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "entries": [{
+                    "key": "AAAABw==",
+                    "xdr": "AAAABw==",
+                    "lastModifiedLedgerSeq": 51234,
+                    "liveUntilLedgerSeq": 2125234
+                }],
+                "latestLedger": 51300
+            }
+        }))
+        .unwrap();
+        let status = status_from_response(HASH, installed, Utc::now()).unwrap();
+        assert!(status.on_chain);
+        assert_eq!(status.ledger_sequence, Some(51234));
+        assert_eq!(status.latest_ledger, 51300);
+
+        let missing: RpcResponse = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "entries": null, "latestLedger": 51300 }
+        }))
+        .unwrap();
+        let status = status_from_response(HASH, missing, Utc::now()).unwrap();
+        assert!(!status.on_chain);
+        assert_eq!(status.ledger_sequence, None);
+    }
+
+    #[test]
+    fn cached_status_expires_and_tracks_hash() {
+        let now = Utc::now();
+        let status = OnChainStatus {
+            wasm_hash: HASH.to_string(),
+            on_chain: true,
+            ledger_sequence: Some(1),
+            latest_ledger: 1,
+            checked_at: now - chrono::Duration::seconds(5),
+        };
+        assert!(status.is_fresh(HASH, now));
+        assert!(!status.is_fresh("other", now));
+        assert!(!status.is_fresh(HASH, now + chrono::Duration::minutes(1)));
+    }
+
+    /// `ContractCodeEntry` with a v1 extension whose cost inputs are 1..=10,
+    /// encoded with stellar-xdr 25
+    const CONTRACT_CODE_V1_XDR: &str = "AAAABwAAAAEAAAAAAAAAAAAAAAEAAAACAAAAAwAAAAQAAAAFAAAABgAAAAcAAAAIAAAACQAAAAqhssPU5fYHGCk6S1xtfo+QobLD1OX2BxgpOktcbX6PkAAAAAkAYXNtAQAAAAcAAAA=";
+    /// Contract whose ID is 32 bytes of 0x11
+    const CONTRACT_ADDRESS: &str = "CAIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRDB3V";
+
+    fn contract_code_xdr(code: &[u8]) -> String {
+        let mut xdr = CONTRACT_CODE_ENTRY_TYPE.to_be_bytes().to_vec();
+        xdr.extend(0u32.to_be_bytes());
+        xdr.extend(hex::decode(HASH).unwrap());
+        xdr.extend((code.len() as u32).to_be_bytes());
+        xdr.extend(code);
+        xdr.resize(xdr.len().next_multiple_of(4), 0);
+        base64::engine::general_purpose::STANDARD.encode(xdr)
+    }
+
+    #[test]
+    fn contract_code_is_read_from_ledger_entry_xdr() {
+        let code = b"\0asm\x01\0\0\0\x07";
+        assert_eq!(
+            contract_code_from_xdr(&contract_code_xdr(code)).unwrap(),
+            code
+        );
+        assert_eq!(contract_code_from_xdr(CONTRACT_CODE_V1_XDR).unwrap(), code);
+
+        let mut truncated = base64::engine::general_purpose::STANDARD
+            .decode(contract_code_xdr(code))
+            .unwrap();
+        truncated.truncate(truncated.len() - 8);
+        let truncated = base64::engine::general_purpose::STANDARD.encode(truncated);
+        assert!(contract_code_from_xdr(&truncated).is_err());
+        assert!(contract_code_from_xdr("AAAABg==").is_err());
+    }
+
+    #[test]
+    fn instance_key_matches_stellar_xdr() {
+        assert_eq!(
+            contract_instance_key(CONTRACT_ADDRESS).unwrap(),
+            "AAAABgAAAAEREREREREREREREREREREREREREREREREREREREREREQAAABQAAAAB"
+        );
+
+        // Bad checksum, account address, not base32
+        let mut corrupted = CONTRACT_ADDRESS.to_string();
+        corrupted.replace_range(55.., "W");
+        assert!(contract_instance_key(&corrupted).is_err());
+        assert!(
+            contract_instance_key("GAIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRCEIRDB3V")
+                .is_err()
+        );
+        assert!(contract_instance_key("C1").is_err());
+    }
+
+    #[test]
+    fn installed_wasm_hash_is_read_from_instance_entry() {
+        // Both encoded with stellar-xdr 25: a WASM instance with one storage
+        // entry, and a Stellar asset contract
+        let wasm_instance = "AAAABgAAAAAAAAABEREREREREREREREREREREREREREREREREREREREREREAAAAUAAAAAQAAABMAAAAAobLD1OX2BxgpOktcbX6PkKGyw9Tl9gcYKTpLXG1+j5AAAAABAAAAAQAAAAMAAAABAAAAAwAAAAI=";
+        let asset_instance = "AAAABgAAAAAAAAABEREREREREREREREREREREREREREREREREREREREREREAAAAUAAAAAQAAABMAAAABAAAAAA==";
+
+        assert_eq!(
+            wasm_hash_from_instance_xdr(wasm_instance)
+                .unwrap()
+                .as_deref(),
+            Some(HASH)
+        );
+        assert_eq!(wasm_hash_from_instance_xdr(asset_instance).unwrap(), None);
+        assert!(wasm_hash_from_instance_xdr(CONTRACT_CODE_V1_XDR).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+// api/src/telemetry.rs
+// Distributed tracing: optional OTLP export of `tracing` spans, and the span
+// helpers used by the request and database layers.
+
+use tracing::Span;
+
+/// Span for one database round trip, nested under the current request span.
+/// Attach it with `.instrument(db_span(..))`; its duration is the query's.
+pub fn db_span(operation: &'static str) -> Span {
+    tracing::info_span!(
+        "db.query",
+        otel.name = operation,
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.operation = operation,
+    )
+}
+
+/// Contract a request is about: a `contract_id` path param, or `id` on
+/// routes under `/api/contracts/`.
+pub fn contract_id_param<'a>(
+    route: &str,
+    params: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Option<&'a str> {
+    let under_contracts = route.starts_with("/api/contracts/");
+    params
+        .into_iter()
+        .find(|(name, _)| *name == "contract_id" || (under_contracts && *name == "id"))
+        .map(|(_, value)| value)
+}
+
+#[cfg(feature = "otel")]
+pub use otel::{layer, shutdown};
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace as sdktrace, Resource};
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    const SERVICE_NAME: &str = "soroban-registry-api";
+
+    /// OTLP export layer, when `OTEL_EXPORTER_OTLP_ENDPOINT` is set. Must be
+    /// called inside the Tokio runtime.
+    pub fn layer<S>() -> Option<OpenTelemetryLayer<S, sdktrace::Tracer>>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .ok()
+            .filter(|v| !v.trim().is_empty())?;
+
+        let exporter = opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(endpoint);
+        // Also registers the global tracer provider that `shutdown` flushes
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(
+                sdktrace::Config::default().with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    SERVICE_NAME,
+                )])),
+            )
+            .install_batch(runtime::Tokio);
+        match tracer {
+            Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+            Err(err) => {
+                // The subscriber isn't installed yet, so this can't go through `tracing`
+                eprintln!("OTLP exporter disabled: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Flush spans still waiting in the batch exporter
+    pub fn shutdown() {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn layer() -> Option<tracing_subscriber::layer::Identity> {
+    None
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn shutdown() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contract_id_comes_from_contract_routes_only() {
+        assert_eq!(
+            contract_id_param("/api/contracts/:id/perf/summary", [("id", "abc")]),
+            Some("abc")
+        );
+        assert_eq!(
+            contract_id_param(
+                "/api/publishers/:id/contracts/:contract_id",
+                [("id", "pub"), ("contract_id", "abc")]
+            ),
+            Some("abc")
+        );
+        assert_eq!(
+            contract_id_param("/api/ab-tests/:id/results", [("id", "test")]),
+            None
+        );
+    }
+}
@@ -0,0 +1,72 @@
+// api/src/test_support.rs
+// Helpers for tests that need a real database. They connect to
+// TEST_DATABASE_URL, which must point at a database with the migrations
+// applied; when it is unset the tests return early so `cargo test` still
+// passes without Postgres. Tests seed rows under fresh UUIDs, so they can run
+// concurrently against a shared database.
+
+use prometheus::Registry;
+use sqlx::PgPool;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::cache::{CacheConfig, CacheLayer};
+use crate::state::AppState;
+
+/// Pool for `TEST_DATABASE_URL`, or `None` (after saying so) when unset
+pub async fn test_pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set; skipping database test");
+        return None;
+    };
+    // Surface the logged cause of database errors in failing tests
+    let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+    Some(
+        sqlx::postgres::PgPoolOptions::new()
+            .max_connections(4)
+            .connect(&url)
+            .await
+            .expect("connect to TEST_DATABASE_URL"),
+    )
+}
+
+/// App state over `db` with default caches and no background leader election
+pub fn test_state(db: PgPool) -> AppState {
+    AppState {
+        db,
+        started_at: Instant::now(),
+        cache: Arc::new(CacheLayer::new(CacheConfig::default())),
+        registry: Registry::new(),
+        is_shutting_down: Arc::new(AtomicBool::new(false)),
+        health_monitor_status: crate::health_monitor::HealthMonitorStatus::default(),
+        default_gas_network: shared::models::Network::Mainnet,
+        background_jobs: crate::background_jobs::JobScheduler::new(Default::default()),
+        canary_events: Default::default(),
+        activity_events: Default::default(),
+        deployment_events: Default::default(),
+    }
+}
+
+/// Insert a publisher and a testnet contract owned by it, returning the
+/// contract's row id
+pub async fn seed_contract(db: &PgPool, wasm_hash: &str) -> Uuid {
+    let publisher_id: Uuid =
+        sqlx::query_scalar("INSERT INTO publishers (stellar_address) VALUES ($1) RETURNING id")
+            .bind(format!("G{}", &Uuid::new_v4().simple().to_string()[..20]))
+            .fetch_one(db)
+            .await
+            .expect("seed publisher");
+    sqlx::query_scalar(
+        "INSERT INTO contracts (contract_id, wasm_hash, name, publisher_id, network)
+         VALUES ($1, $2, 'test-contract', $3, 'testnet')
+         RETURNING id",
+    )
+    .bind(format!("C{}", &Uuid::new_v4().simple().to_string()[..20]))
+    .bind(wasm_hash)
+    .bind(publisher_id)
+    .fetch_one(db)
+    .await
+    .expect("seed contract")
+}
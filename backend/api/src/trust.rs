@@ -2,15 +2,24 @@
 //
 // Contract Trust Scoring Engine
 //
+// The one trust score behind `/trust-score`, its `/breakdown`, contract
+// comparison and the `trust_score` listing order.
+//
 // ── Score breakdown (max 100 points) ────────────────────────────────────────
 //
 //  Factor                  Weight   Description
 //  ──────────────────────  ──────   ────────────────────────────────────────
-//  Verification status       25 pt  +25 if is_verified = true
-//  Audit quality             35 pt  latest audit overall_score × 0.35
-//  Usage / adoption          20 pt  deployments + interactions, capped at 20
+//  Verification status       20 pt  +20 if is_verified = true
+//  Audit quality             25 pt  latest audit overall_score × 0.25
+//  Usage / adoption          15 pt  deployments + interactions, capped at 15
 //  Contract age              10 pt  days since created_at, capped at 10
-//  No critical vulns         10 pt  −10 per unresolved critical audit failure
+//  No critical vulns         10 pt  −5 per critical check the latest audit
+//                                   failed; nothing without an audit
+//  Dependency health         10 pt  share of registry dependencies that are
+//                                   verified and not deprecated (all of none),
+//                                   once the contract itself is verified
+//  Publisher reputation      10 pt  share of the publisher's other contracts
+//                                   that are verified and not deprecated
 //
 // ── Trust tiers ─────────────────────────────────────────────────────────────
 //
@@ -25,17 +34,19 @@
 
 use chrono::Utc;
 use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
 
 // ── Weight constants ──────────────────────────────────────────────────────────
 
 /// Maximum points awarded for on-chain verification
-pub const WEIGHT_VERIFIED: f64 = 25.0;
+pub const WEIGHT_VERIFIED: f64 = 20.0;
 
 /// Maximum points from audit quality (latest audit score × this fraction)
-pub const WEIGHT_AUDIT: f64 = 35.0;
+pub const WEIGHT_AUDIT: f64 = 25.0;
 
 /// Maximum points from usage/adoption signals
-pub const WEIGHT_USAGE: f64 = 20.0;
+pub const WEIGHT_USAGE: f64 = 15.0;
 
 /// Maximum points from contract age
 pub const WEIGHT_AGE: f64 = 10.0;
@@ -43,6 +54,15 @@ pub const WEIGHT_AGE: f64 = 10.0;
 /// Maximum points from having no critical vulnerabilities
 pub const WEIGHT_NO_VULNS: f64 = 10.0;
 
+/// Points deducted from the vulnerability factor per critical failure
+const CRITICAL_VULN_PENALTY: f64 = 5.0;
+
+/// Maximum points from healthy registry dependencies
+pub const WEIGHT_DEPENDENCIES: f64 = 10.0;
+
+/// Maximum points from the publisher's other contracts
+pub const WEIGHT_PUBLISHER: f64 = 10.0;
+
 /// Number of deployments needed to earn full usage points
 const USAGE_DEPLOYMENT_CAP: f64 = 50.0;
 
@@ -73,6 +93,18 @@ pub struct TrustInput {
 
     /// Number of unresolved critical-severity audit check failures
     pub unresolved_critical_vulns: i64,
+
+    /// Dependencies resolved to a contract in the registry
+    pub resolved_dependencies: i64,
+
+    /// Resolved dependencies that are verified and not deprecated
+    pub healthy_dependencies: i64,
+
+    /// The publisher's contracts other than this one
+    pub publisher_other_contracts: i64,
+
+    /// Of those, how many are verified and not deprecated
+    pub publisher_healthy_contracts: i64,
 }
 
 // ── Output types ──────────────────────────────────────────────────────────────
@@ -129,7 +161,7 @@ pub fn trust_badge(score: f64) -> (&'static str, &'static str) {
 ///
 /// Returns a fully-populated [`TrustScore`] with per-factor breakdown.
 pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
-    let mut factors: Vec<TrustFactor> = Vec::with_capacity(5);
+    let mut factors: Vec<TrustFactor> = Vec::with_capacity(7);
     let mut total = 0.0f64;
 
     // ── Factor 1: Verification status ────────────────────────────────────────
@@ -161,7 +193,10 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
                 "Latest security audit scored {:.1}/100. Audit score contributes up to {:.0} trust points.",
                 s, WEIGHT_AUDIT
             ),
-            None => "No security audit found. Complete an audit to earn up to 35 points.".into(),
+            None => format!(
+                "No security audit found. Complete an audit to earn up to {:.0} points.",
+                WEIGHT_AUDIT
+            ),
         },
     });
 
@@ -199,15 +234,22 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
     });
 
     // ── Factor 5: No critical vulnerabilities ─────────────────────────────────
-    // Each unresolved critical vuln deducts from this factor (floored at 0)
-    let vuln_penalty = (input.unresolved_critical_vulns as f64 * 5.0).min(WEIGHT_NO_VULNS);
-    let vuln_points  = (WEIGHT_NO_VULNS - vuln_penalty).max(0.0);
+    // Only an audit can rule vulnerabilities out. Each unresolved critical
+    // vuln deducts from this factor (floored at 0).
+    let vuln_penalty = (input.unresolved_critical_vulns as f64 * CRITICAL_VULN_PENALTY)
+        .min(WEIGHT_NO_VULNS);
+    let vuln_points  = match input.latest_audit_score {
+        Some(_) => (WEIGHT_NO_VULNS - vuln_penalty).max(0.0),
+        None    => 0.0,
+    };
     total += vuln_points;
     factors.push(TrustFactor {
         name: "Vulnerability Status",
         points_earned: vuln_points,
         points_max: WEIGHT_NO_VULNS,
-        explanation: if input.unresolved_critical_vulns == 0 {
+        explanation: if input.latest_audit_score.is_none() {
+            "No security audit to rule out critical vulnerabilities.".into()
+        } else if input.unresolved_critical_vulns == 0 {
             "No unresolved critical vulnerabilities detected.".into()
         } else {
             format!(
@@ -217,6 +259,63 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
         },
     });
 
+    // ── Factor 6: Dependency health ───────────────────────────────────────────
+    // Declared dependencies are only trusted once the source declaring them is
+    // verified. A contract without registry dependencies has nothing
+    // unhealthy to inherit.
+    let dependency_ratio = if !input.is_verified {
+        0.0
+    } else if input.resolved_dependencies > 0 {
+        input.healthy_dependencies as f64 / input.resolved_dependencies as f64
+    } else {
+        1.0
+    };
+    let dependency_points = dependency_ratio.clamp(0.0, 1.0) * WEIGHT_DEPENDENCIES;
+    total += dependency_points;
+    let unhealthy = input.resolved_dependencies - input.healthy_dependencies;
+    factors.push(TrustFactor {
+        name: "Dependency Health",
+        points_earned: dependency_points,
+        points_max: WEIGHT_DEPENDENCIES,
+        explanation: if !input.is_verified {
+            "Declared dependencies count once the contract is verified.".into()
+        } else if input.resolved_dependencies <= 0 {
+            "No dependencies on other registry contracts.".into()
+        } else if unhealthy > 0 {
+            format!(
+                "{} of {} dependencies are unverified or deprecated.",
+                unhealthy, input.resolved_dependencies
+            )
+        } else {
+            format!(
+                "All {} dependencies are verified and not deprecated.",
+                input.resolved_dependencies
+            )
+        },
+    });
+
+    // ── Factor 7: Publisher reputation ────────────────────────────────────────
+    let publisher_ratio = if input.publisher_other_contracts > 0 {
+        input.publisher_healthy_contracts as f64 / input.publisher_other_contracts as f64
+    } else {
+        0.0
+    };
+    let publisher_points = publisher_ratio.clamp(0.0, 1.0) * WEIGHT_PUBLISHER;
+    total += publisher_points;
+    factors.push(TrustFactor {
+        name: "Publisher Reputation",
+        points_earned: publisher_points,
+        points_max: WEIGHT_PUBLISHER,
+        explanation: if input.publisher_other_contracts <= 0 {
+            "The publisher has no other contracts to build a reputation on yet.".into()
+        } else {
+            format!(
+                "{} of the publisher's {} other contracts are verified and not deprecated.",
+                input.publisher_healthy_contracts, input.publisher_other_contracts
+            )
+        },
+    });
+
     // ── Assemble result ───────────────────────────────────────────────────────
     let score = total.clamp(0.0, 100.0);
     let (badge, badge_icon) = trust_badge(score);
@@ -237,6 +336,141 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
     TrustScore { score, badge, badge_icon, factors, summary }
 }
 
+// ── Input collection ─────────────────────────────────────────────────────────
+
+/// Gather the scoring inputs for the contract with row id `contract_id`, or
+/// `None` when there is no such contract. Vulnerabilities are the critical
+/// checks failed in the contract's latest security audit.
+pub async fn load_trust_input(
+    db: &PgPool,
+    contract_id: Uuid,
+) -> Result<Option<TrustInput>, sqlx::Error> {
+    type TrustRow = (
+        bool,
+        chrono::DateTime<Utc>,
+        Option<f64>,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+    );
+
+    // A contract counts as healthy when it is verified and not deprecated
+    let row: Option<TrustRow> = sqlx::query_as(
+        r#"
+        SELECT c.is_verified, c.created_at, a.overall_score,
+               (SELECT COUNT(*) FROM audit_checks ac
+                 WHERE ac.audit_id = a.id AND ac.status = 'failed'
+                   AND ac.severity = 'Critical'),
+               (SELECT COUNT(*) FROM contract_deployments x WHERE x.contract_id = c.id),
+               (SELECT COUNT(*) FROM contract_interactions i WHERE i.contract_id = c.id),
+               (SELECT COUNT(*) FROM contract_dependencies d
+                 WHERE d.contract_id = c.id AND d.dependency_contract_id IS NOT NULL),
+               (SELECT COUNT(*) FROM contract_dependencies d
+                 JOIN contracts dc ON dc.id = d.dependency_contract_id
+                 WHERE d.contract_id = c.id AND dc.is_verified
+                   AND NOT EXISTS (SELECT 1 FROM contract_deprecations x
+                                    WHERE x.contract_id = dc.id)),
+               (SELECT COUNT(*) FROM contracts p
+                 WHERE p.publisher_id = c.publisher_id AND p.id <> c.id),
+               (SELECT COUNT(*) FROM contracts p
+                 WHERE p.publisher_id = c.publisher_id AND p.id <> c.id AND p.is_verified
+                   AND NOT EXISTS (SELECT 1 FROM contract_deprecations x
+                                    WHERE x.contract_id = p.id))
+        FROM contracts c
+        LEFT JOIN LATERAL (
+            SELECT id, overall_score FROM security_audits
+            WHERE contract_id = c.id
+            ORDER BY audit_date DESC
+            LIMIT 1
+        ) a ON TRUE
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(
+        |(
+            is_verified,
+            created_at,
+            latest_audit_score,
+            unresolved_critical_vulns,
+            total_deployments,
+            total_interactions,
+            resolved_dependencies,
+            healthy_dependencies,
+            publisher_other_contracts,
+            publisher_healthy_contracts,
+        )| TrustInput {
+            is_verified,
+            latest_audit_score,
+            total_deployments,
+            total_interactions,
+            created_at,
+            unresolved_critical_vulns,
+            resolved_dependencies,
+            healthy_dependencies,
+            publisher_other_contracts,
+            publisher_healthy_contracts,
+        },
+    ))
+}
+
+/// SQL expression computing [`compute_trust_score`] for the contract aliased
+/// `c` from the same inputs as [`load_trust_input`], so listings can sort by
+/// it without scoring every row in Rust.
+pub fn trust_score_sql() -> String {
+    // A contract counts as healthy when it is verified and not deprecated
+    let healthy = |alias: &str| {
+        format!(
+            "{alias}.is_verified AND NOT EXISTS \
+             (SELECT 1 FROM contract_deprecations x WHERE x.contract_id = {alias}.id)"
+        )
+    };
+    format!(
+        "(CASE WHEN c.is_verified THEN {verification} ELSE 0 END \
+         + (LEAST((SELECT COUNT(*) FROM contract_deployments x WHERE x.contract_id = c.id)::float8 \
+                  / {deployment_cap}, 1) * 0.6 \
+            + LEAST((SELECT COUNT(*) FROM contract_interactions i WHERE i.contract_id = c.id)::float8 \
+                    / {interaction_cap}, 1) * 0.4) * {usage} \
+         + LEAST(GREATEST(FLOOR(EXTRACT(EPOCH FROM NOW() - c.created_at) / 86400), 0) \
+                 / {age_days}, 1) * {age} \
+         + COALESCE((SELECT a.overall_score / 100 * {audit} \
+                            + GREATEST({no_vulns} - {vuln_penalty} * \
+                                (SELECT COUNT(*) FROM audit_checks ac \
+                                  WHERE ac.audit_id = a.id AND ac.status = 'failed' \
+                                    AND ac.severity = 'Critical'), 0) \
+                     FROM security_audits a WHERE a.contract_id = c.id \
+                     ORDER BY a.audit_date DESC LIMIT 1), 0) \
+         + CASE WHEN c.is_verified THEN \
+             COALESCE((SELECT COUNT(*) FILTER (WHERE {dep_healthy})::float8 / NULLIF(COUNT(*), 0) \
+                       FROM contract_dependencies d JOIN contracts dc ON dc.id = d.dependency_contract_id \
+                       WHERE d.contract_id = c.id), 1) * {dependencies} \
+           ELSE 0 END \
+         + COALESCE((SELECT COUNT(*) FILTER (WHERE {pub_healthy})::float8 / NULLIF(COUNT(*), 0) \
+                     FROM contracts p WHERE p.publisher_id = c.publisher_id AND p.id <> c.id), \
+                    0) * {publisher})",
+        verification = WEIGHT_VERIFIED,
+        deployment_cap = USAGE_DEPLOYMENT_CAP,
+        interaction_cap = USAGE_INTERACTION_CAP,
+        usage = WEIGHT_USAGE,
+        age_days = AGE_DAYS_CAP,
+        age = WEIGHT_AGE,
+        audit = WEIGHT_AUDIT,
+        no_vulns = WEIGHT_NO_VULNS,
+        vuln_penalty = CRITICAL_VULN_PENALTY,
+        dep_healthy = healthy("dc"),
+        dependencies = WEIGHT_DEPENDENCIES,
+        pub_healthy = healthy("p"),
+        publisher = WEIGHT_PUBLISHER,
+    )
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -251,6 +485,10 @@ mod tests {
             total_interactions: 0,
             created_at: Utc::now(),
             unresolved_critical_vulns: 0,
+            resolved_dependencies: 0,
+            healthy_dependencies: 0,
+            publisher_other_contracts: 0,
+            publisher_healthy_contracts: 0,
         }
     }
 
@@ -262,19 +500,19 @@ mod tests {
     }
 
     #[test]
-    fn verified_adds_25_points() {
+    fn verified_adds_20_points() {
         let input = TrustInput { is_verified: true, ..base_input() };
         let score = compute_trust_score(&input);
         let v = score.factors.iter().find(|f| f.name == "Verification Status").unwrap();
-        assert_eq!(v.points_earned, 25.0);
+        assert_eq!(v.points_earned, 20.0);
     }
 
     #[test]
-    fn perfect_audit_adds_35_points() {
+    fn perfect_audit_adds_25_points() {
         let input = TrustInput { latest_audit_score: Some(100.0), ..base_input() };
         let score = compute_trust_score(&input);
         let a = score.factors.iter().find(|f| f.name == "Audit Quality").unwrap();
-        assert!((a.points_earned - 35.0).abs() < 0.01);
+        assert!((a.points_earned - 25.0).abs() < 0.01);
     }
 
     #[test]
@@ -294,6 +532,10 @@ mod tests {
             total_interactions: 10000,
             created_at: Utc::now() - chrono::Duration::days(365),
             unresolved_critical_vulns: 0,
+            resolved_dependencies: 2,
+            healthy_dependencies: 2,
+            publisher_other_contracts: 3,
+            publisher_healthy_contracts: 3,
         };
         let score = compute_trust_score(&input);
         assert!(score.score <= 100.0);
@@ -312,8 +554,142 @@ mod tests {
     }
 
     #[test]
-    fn factors_count_is_five() {
+    fn factors_count_is_seven() {
         let score = compute_trust_score(&base_input());
-        assert_eq!(score.factors.len(), 5);
+        assert_eq!(score.factors.len(), 7);
+    }
+
+    #[test]
+    fn factor_points_sum_to_the_score() {
+        let input = TrustInput {
+            is_verified: true,
+            latest_audit_score: Some(80.0),
+            total_deployments: 10,
+            total_interactions: 250,
+            created_at: Utc::now() - chrono::Duration::days(90),
+            unresolved_critical_vulns: 1,
+            resolved_dependencies: 4,
+            healthy_dependencies: 3,
+            publisher_other_contracts: 2,
+            publisher_healthy_contracts: 1,
+        };
+        let score = compute_trust_score(&input);
+        let sum: f64 = score.factors.iter().map(|f| f.points_earned).sum();
+        let max: f64 = score.factors.iter().map(|f| f.points_max).sum();
+        assert!((score.score - sum).abs() < 1e-9);
+        assert_eq!(max, 100.0);
+        // 20 + 20 + (0.12 + 0.2) × 15 + 5 + 5 + 7.5 + 5
+        assert!((score.score - 67.3).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn sql_score_matches_the_engine() {
+        let Some(pool) = crate::test_support::test_pool().await else {
+            return;
+        };
+        let contract_id = crate::test_support::seed_contract(&pool, "cc").await;
+        let dependency_id = crate::test_support::seed_contract(&pool, "dd").await;
+        sqlx::query("UPDATE contracts SET is_verified = true WHERE id = $1")
+            .bind(contract_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO contract_dependencies
+                 (contract_id, dependency_name, dependency_contract_id, version_constraint)
+             VALUES ($1, 'dep', $2, '*')",
+        )
+        .bind(contract_id)
+        .bind(dependency_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // An older clean audit, superseded by one failing two critical checks
+        for (days_ago, score, failed) in [(30, 95.0, 0), (1, 70.0, 2)] {
+            let audit_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO security_audits (contract_id, auditor, audit_date, overall_score)
+                 VALUES ($1, 'auditor', NOW() - make_interval(days => $2), $3)
+                 RETURNING id",
+            )
+            .bind(contract_id)
+            .bind(days_ago)
+            .bind(score)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            let checks = [("IV-001", "Critical"), ("AC-001", "Critical"), ("IV-003", "High")];
+            for (i, (check_id, severity)) in checks.into_iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO audit_checks (audit_id, check_id, severity, status)
+                     VALUES ($1, $2, $3, $4)",
+                )
+                .bind(audit_id)
+                .bind(check_id)
+                .bind(severity)
+                .bind(if i < failed || severity == "High" { "failed" } else { "passed" })
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+        }
+
+        let input = load_trust_input(&pool, contract_id).await.unwrap().unwrap();
+        let engine = compute_trust_score(&input).score;
+        let sql: f64 = sqlx::query_scalar(&format!(
+            "SELECT {}::float8 FROM contracts c WHERE c.id = $1",
+            trust_score_sql()
+        ))
+        .bind(contract_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!((input.resolved_dependencies, input.healthy_dependencies), (1, 0));
+        assert_eq!(input.latest_audit_score, Some(70.0));
+        assert_eq!(input.unresolved_critical_vulns, 2);
+        assert!((engine - sql).abs() < 1e-6, "engine {} vs sql {}", engine, sql);
+    }
+
+    #[test]
+    fn only_an_audit_rules_out_vulnerabilities() {
+        let vuln_points = |input: &TrustInput| {
+            compute_trust_score(input)
+                .factors
+                .into_iter()
+                .find(|f| f.name == "Vulnerability Status")
+                .unwrap()
+                .points_earned
+        };
+        assert_eq!(vuln_points(&base_input()), 0.0);
+        let audited = TrustInput {
+            latest_audit_score: Some(60.0),
+            ..base_input()
+        };
+        assert_eq!(vuln_points(&audited), WEIGHT_NO_VULNS);
+        let one_critical = TrustInput {
+            unresolved_critical_vulns: 1,
+            ..audited
+        };
+        assert_eq!(vuln_points(&one_critical), WEIGHT_NO_VULNS - 5.0);
+    }
+
+    #[test]
+    fn dependencies_count_once_verified() {
+        let dependency_points = |input: &TrustInput| {
+            compute_trust_score(input)
+                .factors
+                .into_iter()
+                .find(|f| f.name == "Dependency Health")
+                .unwrap()
+                .points_earned
+        };
+        let unhealthy = TrustInput {
+            resolved_dependencies: 2,
+            healthy_dependencies: 1,
+            ..base_input()
+        };
+        assert_eq!(dependency_points(&unhealthy), 0.0);
+        assert_eq!(dependency_points(&TrustInput { is_verified: true, ..unhealthy }), 5.0);
+        assert_eq!(dependency_points(&TrustInput { is_verified: true, ..base_input() }), 10.0);
     }
 }
\ No newline at end of file
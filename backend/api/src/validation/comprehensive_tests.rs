@@ -485,8 +485,8 @@ mod tests {
         ];
 
         let response = crate::validation::extractors::ValidationErrorResponse::new(errors);
-        assert_eq!(response.error, "ValidationError");
-        assert_eq!(response.code, 400);
+        assert_eq!(response.error.code, "ValidationError");
+        assert_eq!(response.status, 400);
         assert!(response.errors.len() == 2);
         assert!(!response.correlation_id.is_empty());
     }
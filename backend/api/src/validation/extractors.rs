@@ -13,6 +13,8 @@ use chrono::{SecondsFormat, Utc};
 use serde::{de::DeserializeOwned, Serialize};
 use uuid::Uuid;
 
+use crate::error::ErrorBody;
+
 /// A field-level validation error
 #[derive(Debug, Clone, Serialize)]
 pub struct FieldError {
@@ -32,10 +34,9 @@ impl FieldError {
 /// Validation error response body
 #[derive(Debug, Serialize)]
 pub struct ValidationErrorResponse {
-    pub error: String,
-    pub message: String,
+    pub error: ErrorBody,
     pub errors: Vec<FieldError>,
-    pub code: u16,
+    pub status: u16,
     pub timestamp: String,
     pub correlation_id: String,
 }
@@ -49,10 +50,12 @@ impl ValidationErrorResponse {
         };
 
         Self {
-            error: "ValidationError".to_string(),
-            message: error_summary,
+            error: ErrorBody {
+                code: "ValidationError".to_string(),
+                message: error_summary,
+            },
             errors,
-            code: 400,
+            status: 400,
             timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
             correlation_id: Uuid::new_v4().to_string(),
         }
@@ -323,10 +326,10 @@ mod tests {
 
         let response = ValidationErrorResponse::new(errors);
 
-        assert_eq!(response.error, "ValidationError");
-        assert_eq!(response.code, 400);
+        assert_eq!(response.error.code, "ValidationError");
+        assert_eq!(response.status, 400);
         assert_eq!(response.errors.len(), 2);
-        assert!(response.message.contains("2 fields"));
+        assert!(response.error.message.contains("2 fields"));
     }
 
     #[test]
@@ -334,6 +337,6 @@ mod tests {
         let errors = vec![FieldError::new("name", "is required")];
         let response = ValidationErrorResponse::new(errors);
 
-        assert!(response.message.contains("field 'name'"));
+        assert!(response.error.message.contains("field 'name'"));
     }
 }
@@ -45,13 +45,15 @@
 //!
 //! ```json
 //! {
-//!   "error": "ValidationError",
-//!   "message": "Validation failed for 2 fields",
+//!   "error": {
+//!     "code": "ValidationError",
+//!     "message": "Validation failed for 2 fields"
+//!   },
 //!   "errors": [
 //!     {"field": "contract_id", "message": "must be a valid Stellar contract ID"},
 //!     {"field": "name", "message": "must be at least 1 character"}
 //!   ],
-//!   "code": 400,
+//!   "status": 400,
 //!   "timestamp": "2026-02-20T10:30:00Z",
 //!   "correlation_id": "uuid-here"
 //! }
@@ -12,28 +12,14 @@ use axum::{
     http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
-    Json,
 };
-use chrono::{SecondsFormat, Utc};
-use serde::Serialize;
-use serde_json::json;
 use std::net::SocketAddr;
-use uuid::Uuid;
+
+use crate::error::ApiError;
 
 const DEFAULT_MAX_PAYLOAD_MB: u64 = 5;
 const HEADER_CONTENT_LENGTH: &str = "content-length";
 
-#[derive(Debug, Serialize)]
-struct PayloadTooLargeResponse {
-    error: String,
-    message: String,
-    code: u16,
-    max_size_mb: u64,
-    max_size_bytes: u64,
-    timestamp: String,
-    correlation_id: String,
-}
-
 /// Get configured max payload size in bytes
 pub fn get_max_payload_bytes() -> u64 {
     let env_mb = std::env::var("MAX_PAYLOAD_SIZE_MB")
@@ -44,6 +30,19 @@ pub fn get_max_payload_bytes() -> u64 {
     env_mb * 1024 * 1024
 }
 
+/// 413 error for a body over `max_bytes`, in the standard error shape
+fn payload_too_large(max_bytes: u64) -> ApiError {
+    ApiError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        "PayloadTooLarge",
+        format!(
+            "Request payload exceeds maximum size of {} MB ({} bytes)",
+            max_bytes / (1024 * 1024),
+            max_bytes
+        ),
+    )
+}
+
 /// Middleware that validates request payload size
 ///
 /// Returns 413 Payload Too Large if the request body exceeds the configured limit.
@@ -61,8 +60,12 @@ pub async fn payload_size_validation_middleware(
         if let Ok(content_length_str) = content_length_str.to_str() {
             if let Ok(size) = content_length_str.parse::<u64>() {
                 if size > max_bytes {
-                    let correlation_id = Uuid::new_v4().to_string();
-                    let max_mb = max_bytes / (1024 * 1024);
+                    let response = payload_too_large(max_bytes).into_response();
+                    let correlation_id = response
+                        .headers()
+                        .get("x-correlation-id")
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default();
 
                     // Log the violation
                     let client_ip = addr.ip();
@@ -76,23 +79,10 @@ pub async fn payload_size_validation_middleware(
                         size as usize,
                         max_bytes as usize,
                         path,
-                        &correlation_id,
-                    );
-
-                    let response = PayloadTooLargeResponse {
-                        error: "PayloadTooLarge".to_string(),
-                        message: format!(
-                            "Request payload exceeds maximum size of {} MB ({} bytes)",
-                            max_mb, max_bytes
-                        ),
-                        code: 413,
-                        max_size_mb: max_mb,
-                        max_size_bytes: max_bytes,
-                        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
                         correlation_id,
-                    };
+                    );
 
-                    return Err((StatusCode::PAYLOAD_TOO_LARGE, Json(response)).into_response());
+                    return Err(response);
                 }
             }
         }
@@ -111,4 +101,20 @@ mod tests {
         let max = get_max_payload_bytes();
         assert_eq!(max, 5 * 1024 * 1024);
     }
+
+    #[tokio::test]
+    async fn oversized_payloads_get_the_standard_error_body() {
+        let response = payload_too_large(5 * 1024 * 1024).into_response();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "PayloadTooLarge");
+        assert_eq!(
+            body["error"]["message"],
+            "Request payload exceeds maximum size of 5 MB (5242880 bytes)"
+        );
+        assert_eq!(body["status"], 413);
+    }
 }
@@ -7,13 +7,13 @@ use shared::models::{
     ChangePublisherRequest, CreateContractVersionRequest, CreateInteractionBatchRequest,
     CreateInteractionRequest, CreateMigrationRequest, DependencyDeclaration, PublishRequest,
     Publisher, UpdateContractMetadataRequest, UpdateContractStatusRequest,
-    UpdateMigrationStatusRequest, VerifyRequest,
+    UpdateMigrationStatusRequest, VerifyOnChainRequest, VerifyRequest,
 };
 
 use super::extractors::{FieldError, Validatable, ValidationBuilder};
 use super::sanitizers::{
     normalize_contract_id, normalize_stellar_address, sanitize_description_optional, sanitize_name,
-    sanitize_tags, sanitize_url_optional, trim,
+    sanitize_tags, sanitize_url_optional, trim, trim_optional,
 };
 use super::validators::{
     validate_category_whitelist, validate_contract_id, validate_json_depth, validate_length,
@@ -45,8 +45,17 @@ const ALLOWED_CATEGORIES: &[&str] = &["DEX", "Lending", "Bridge", "Oracle", "Tok
 const MAX_DEPENDENCY_NAME_LENGTH: usize = 255;
 /// Maximum length for version constraint
 const MAX_VERSION_CONSTRAINT_LENGTH: usize = 100;
-/// Maximum number of dependencies
-const MAX_DEPENDENCIES_COUNT: usize = 50;
+/// Default maximum number of dependencies, overridable via `MAX_CONTRACT_DEPENDENCIES`
+const DEFAULT_MAX_DEPENDENCIES_COUNT: usize = 256;
+
+/// Maximum number of dependencies a single contract may declare
+static MAX_DEPENDENCIES_COUNT: once_cell::sync::Lazy<usize> = once_cell::sync::Lazy::new(|| {
+    std::env::var("MAX_CONTRACT_DEPENDENCIES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_MAX_DEPENDENCIES_COUNT)
+});
 
 // ─────────────────────────────────────────────────────────────────────────────
 // PublishRequest validation
@@ -113,10 +122,12 @@ impl Validatable for PublishRequest {
         });
 
         builder.check("dependencies", || {
-            if self.dependencies.len() > MAX_DEPENDENCIES_COUNT {
+            let max = *MAX_DEPENDENCIES_COUNT;
+            if self.dependencies.len() > max {
                 return Err(format!(
-                    "at most {} dependencies are allowed",
-                    MAX_DEPENDENCIES_COUNT
+                    "at most {} dependencies are allowed, got {}",
+                    max,
+                    self.dependencies.len()
                 ));
             }
             Ok(())
@@ -128,6 +139,12 @@ impl Validatable for PublishRequest {
                     builder.add_error(format!("dependencies[{}].{}", i, err.field), err.message);
                 }
             }
+            if dep.name == self.contract_id || dep.name.eq_ignore_ascii_case(&self.name) {
+                builder.add_error(
+                    format!("dependencies[{}].name", i),
+                    "a contract cannot depend on itself",
+                );
+            }
         }
 
         builder.build()
@@ -491,6 +508,45 @@ impl Validatable for DependencyDeclaration {
             }
             validate_length(&self.version_constraint, 1, MAX_VERSION_CONSTRAINT_LENGTH)
         });
+        builder.check("version_constraint", || {
+            semver::VersionReq::parse(&self.version_constraint)
+                .map(|_| ())
+                .map_err(|e| format!("invalid version requirement: {}", e))
+        });
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// VerifyOnChainRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for VerifyOnChainRequest {
+    fn sanitize(&mut self) {
+        trim_optional(&mut self.contract_id);
+        self.contract_id = self.contract_id.as_deref().map(normalize_contract_id);
+        trim_optional(&mut self.wasm_hash);
+        self.wasm_hash = self.wasm_hash.as_deref().map(str::to_ascii_lowercase);
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        match (&self.contract_id, &self.wasm_hash) {
+            (Some(contract_id), None) => {
+                builder.check("contract_id", || validate_contract_id(contract_id));
+            }
+            (None, Some(wasm_hash)) => {
+                builder.check("wasm_hash", || validate_wasm_hash(wasm_hash));
+            }
+            _ => {
+                builder.add_error(
+                    "contract_id",
+                    "provide exactly one of contract_id or wasm_hash",
+                );
+            }
+        }
 
         builder.build()
     }
@@ -578,4 +634,108 @@ mod tests {
             Some("https://github.com/user/repo".to_string())
         );
     }
+
+    fn publish_request_with(dependencies: Vec<DependencyDeclaration>) -> PublishRequest {
+        PublishRequest {
+            contract_id: valid_contract_id(),
+            wasm_hash: "a".repeat(64),
+            name: "My Contract".to_string(),
+            description: None,
+            network: Network::Testnet,
+            category: None,
+            tags: vec![],
+            source_url: None,
+            publisher_address: valid_stellar_address(),
+            dependencies,
+        }
+    }
+
+    fn dependency(name: &str, version_constraint: &str) -> DependencyDeclaration {
+        DependencyDeclaration {
+            name: name.to_string(),
+            version_constraint: version_constraint.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_publish_request_valid_dependencies() {
+        let req = publish_request_with(vec![
+            dependency("token", "^1.2.0"),
+            dependency("oracle", "~0.3"),
+            dependency("router", "*"),
+        ]);
+
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn test_publish_request_too_many_dependencies() {
+        let deps = (0..=*MAX_DEPENDENCIES_COUNT)
+            .map(|i| dependency(&format!("dep-{}", i), "^1.0.0"))
+            .collect();
+        let req = publish_request_with(deps);
+
+        let errors = req.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "dependencies"));
+    }
+
+    #[test]
+    fn test_publish_request_malformed_version_requirement() {
+        let req = publish_request_with(vec![
+            dependency("token", "^1.2.0"),
+            dependency("oracle", ">>=1.0"),
+        ]);
+
+        let errors = req.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "dependencies[1].version_constraint");
+    }
+
+    #[test]
+    fn test_publish_request_self_dependency() {
+        let req = publish_request_with(vec![
+            dependency("token", "^1.2.0"),
+            dependency("my contract", "*"),
+            dependency(&valid_contract_id(), "*"),
+        ]);
+
+        let errors = req.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["dependencies[1].name", "dependencies[2].name"]);
+    }
+
+    #[test]
+    fn test_verify_on_chain_request_takes_exactly_one_identifier() {
+        let mut by_hash = VerifyOnChainRequest {
+            contract_id: Some("  ".to_string()),
+            wasm_hash: Some(format!(" {} ", "A".repeat(64))),
+            network: None,
+        };
+        by_hash.sanitize();
+        assert_eq!(by_hash.contract_id, None);
+        assert_eq!(by_hash.wasm_hash, Some("a".repeat(64)));
+        assert!(by_hash.validate().is_ok());
+
+        let by_address = VerifyOnChainRequest {
+            contract_id: Some(valid_contract_id()),
+            wasm_hash: None,
+            network: Some(Network::Testnet),
+        };
+        assert!(by_address.validate().is_ok());
+
+        let both = VerifyOnChainRequest {
+            contract_id: Some(valid_contract_id()),
+            wasm_hash: Some("a".repeat(64)),
+            network: None,
+        };
+        let errors = both.validate().unwrap_err();
+        assert_eq!(errors[0].field, "contract_id");
+
+        let neither = VerifyOnChainRequest {
+            contract_id: None,
+            wasm_hash: None,
+            network: None,
+        };
+        assert!(neither.validate().is_err());
+    }
 }
@@ -0,0 +1,114 @@
+// Writes ../counter.wasm: the interface version and contract spec sections
+// soroban-sdk generates for this contract, plus contract meta naming the SDK
+// version, in a module whose exported functions are stubs. Both SDK sections
+// are byte-for-byte what it links into a wasm32 build; the stub code keeps the
+// fixture small and means regenerating it needs no wasm32 target.
+//
+//     cargo run --example write_fixture
+//
+// `cargo build --release --target wasm32v1-none` builds the deployable
+// contract from the same source.
+
+use counter_fixture::{Counter, Error};
+use soroban_sdk::xdr::{
+    Limited, Limits, ReadXdr, ScMetaEntry, ScMetaV0, ScSpecEntry, StringM, WriteXdr,
+};
+
+fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![id];
+    leb128(&mut out, payload.len());
+    out.extend_from_slice(payload);
+    out
+}
+
+fn custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    leb128(&mut payload, name.len());
+    payload.extend_from_slice(name.as_bytes());
+    payload.extend_from_slice(data);
+    section(0, &payload)
+}
+
+fn leb128(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn meta(key: &str, val: &str) -> Vec<u8> {
+    ScMetaEntry::ScMetaV0(ScMetaV0 {
+        key: StringM::try_from(key).unwrap(),
+        val: StringM::try_from(val).unwrap(),
+    })
+    .to_xdr(Limits::none())
+    .unwrap()
+}
+
+fn main() {
+    let functions = ["__constructor", "increment", "get", "reset", "history"];
+    let spec: Vec<u8> = [
+        &Error::spec_xdr()[..],
+        &Counter::spec_xdr___constructor()[..],
+        &Counter::spec_xdr_increment()[..],
+        &Counter::spec_xdr_get()[..],
+        &Counter::spec_xdr_reset()[..],
+        &Counter::spec_xdr_history()[..],
+    ]
+    .concat();
+    // Fails if the SDK's output isn't a stream of well-formed entries
+    let entries = ScSpecEntry::read_xdr_iter(&mut Limited::new(spec.as_slice(), Limits::none()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(entries.len(), functions.len() + 1);
+
+    let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+    // Type section: one `() -> i64` signature, Soroban's `Val`
+    wasm.extend(section(0x01, &[0x01, 0x60, 0x00, 0x01, 0x7e]));
+    // Function section: every export has type 0
+    let mut types = Vec::new();
+    leb128(&mut types, functions.len());
+    types.extend(std::iter::repeat(0x00).take(functions.len()));
+    wasm.extend(section(0x03, &types));
+    // Memory section: one memory of 16 pages
+    wasm.extend(section(0x05, &[0x01, 0x00, 0x10]));
+    // Export section: the memory and each function
+    let mut exports = Vec::new();
+    leb128(&mut exports, functions.len() + 1);
+    leb128(&mut exports, "memory".len());
+    exports.extend_from_slice(b"memory");
+    exports.extend_from_slice(&[0x02, 0x00]);
+    for (index, name) in functions.iter().enumerate() {
+        leb128(&mut exports, name.len());
+        exports.extend_from_slice(name.as_bytes());
+        exports.push(0x00);
+        leb128(&mut exports, index);
+    }
+    wasm.extend(section(0x07, &exports));
+    // Code section: each body returns `i64.const 0`
+    let mut code = Vec::new();
+    leb128(&mut code, functions.len());
+    for _ in functions {
+        code.extend_from_slice(&[0x04, 0x00, 0x42, 0x00, 0x0b]);
+    }
+    wasm.extend(section(0x0a, &code));
+
+    wasm.extend(custom_section(
+        "contractenvmetav0",
+        &soroban_env_common::meta::XDR,
+    ));
+    wasm.extend(custom_section(
+        "contractmetav0",
+        &meta("rssdkver", "25.3.2"),
+    ));
+    wasm.extend(custom_section("contractspecv0", &spec));
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../counter.wasm");
+    std::fs::write(path, &wasm).unwrap();
+    println!("wrote {} bytes to {}", wasm.len(), path);
+}
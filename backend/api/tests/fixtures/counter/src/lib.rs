@@ -0,0 +1,53 @@
+// Counter contract behind the `counter.wasm` ABI extraction fixture: a
+// constructor, an error enum, and functions taking and returning options,
+// results, vectors and tuples.
+
+#![no_std]
+use soroban_sdk::{contract, contracterror, contractimpl, symbol_short, Address, Env, Symbol, Vec};
+
+const COUNT: Symbol = symbol_short!("COUNT");
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    Overflow = 1,
+    Unauthorized = 2,
+}
+
+#[contract]
+pub struct Counter;
+
+#[contractimpl]
+impl Counter {
+    pub fn __constructor(env: Env, admin: Address, start: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &admin);
+        env.storage().instance().set(&COUNT, &start);
+    }
+
+    /// Add `by` to the counter
+    pub fn increment(env: Env, by: u32) -> Result<u32, Error> {
+        let count: u32 = env.storage().instance().get(&COUNT).unwrap_or(0);
+        let count = count.checked_add(by).ok_or(Error::Overflow)?;
+        env.storage().instance().set(&COUNT, &count);
+        Ok(count)
+    }
+
+    pub fn get(env: Env) -> u32 {
+        env.storage().instance().get(&COUNT).unwrap_or(0)
+    }
+
+    pub fn reset(env: Env, caller: Option<Address>) {
+        if let Some(caller) = caller {
+            caller.require_auth();
+        }
+        env.storage().instance().set(&COUNT, &0u32);
+    }
+
+    pub fn history(env: Env, limit: u32) -> Vec<(u64, u32)> {
+        let _ = limit;
+        Vec::new(&env)
+    }
+}
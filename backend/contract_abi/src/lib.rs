@@ -4,6 +4,8 @@ pub mod openapi;
 pub mod parser;
 pub mod types;
 
-pub use openapi::{generate_openapi, to_json, to_yaml, OpenApiDoc};
+pub use openapi::{
+    generate_openapi, generate_openapi_with_options, to_json, to_yaml, OpenApiDoc, OpenApiOptions,
+};
 pub use parser::{parse_contract_abi, parse_json_spec, ParseError, RawContractSpec};
 pub use types::*;
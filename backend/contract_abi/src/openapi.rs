@@ -3,7 +3,7 @@
 //! Produces OpenAPI YAML/JSON from ContractABI for documentation and Swagger UI.
 
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::types::*;
 
@@ -112,15 +112,31 @@ pub struct OpenApiComponents {
     pub schemas: Option<BTreeMap<String, Schema>>,
 }
 
+/// Options for `generate_openapi_with_options`
+#[derive(Debug, Clone, Default)]
+pub struct OpenApiOptions {
+    /// Add a synthesized `examples` entry to every request and response body
+    pub include_examples: bool,
+}
+
 /// Generate OpenAPI 3.0 document from ContractABI
 pub fn generate_openapi(abi: &ContractABI, base_path: Option<&str>) -> OpenApiDoc {
+    generate_openapi_with_options(abi, base_path, &OpenApiOptions::default())
+}
+
+/// Generate OpenAPI 3.0 document from ContractABI with the given options
+pub fn generate_openapi_with_options(
+    abi: &ContractABI,
+    base_path: Option<&str>,
+    options: &OpenApiOptions,
+) -> OpenApiDoc {
     let base = base_path.unwrap_or("/invoke");
     let mut paths = BTreeMap::new();
     let mut schema_gen = SchemaGenerator::new();
 
     for func in abi.public_functions() {
         let path = format!("{}/{}", base.trim_end_matches('/'), func.name);
-        let op = operation_from_function(func, abi, &mut schema_gen);
+        let op = operation_from_function(func, abi, &mut schema_gen, options);
         paths.insert(
             path,
             PathItem {
@@ -156,6 +172,7 @@ fn operation_from_function(
     func: &ContractFunction,
     abi: &ContractABI,
     schema_gen: &mut SchemaGenerator,
+    options: &OpenApiOptions,
 ) -> Operation {
     let operation_id = func.name.clone();
     let summary = func.doc.as_deref().unwrap_or(&func.name).to_string();
@@ -170,7 +187,9 @@ fn operation_from_function(
             MediaType {
                 schema: schema_ref,
                 example: example.clone(),
-                examples: None,
+                examples: options
+                    .include_examples
+                    .then(|| named_example(params_example(&func.params, &abi.types))),
             },
         )]);
 
@@ -195,7 +214,9 @@ fn operation_from_function(
                 MediaType {
                     schema: response_schema,
                     example: response_example,
-                    examples: None,
+                    examples: options
+                        .include_examples
+                        .then(|| named_example(example_value(&func.return_type, &abi.types))),
                 },
             )])),
         },
@@ -235,6 +256,104 @@ fn operation_from_function(
     }
 }
 
+/// Sample Stellar account (the all-zero ed25519 key) used for `Address` examples
+const EXAMPLE_ADDRESS: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+/// Nesting beyond which examples stop expanding, so self-referencing custom
+/// types cannot recurse forever
+const MAX_EXAMPLE_DEPTH: usize = 16;
+
+fn named_example(value: serde_json::Value) -> BTreeMap<String, Example> {
+    BTreeMap::from([("default".to_string(), Example { value })])
+}
+
+fn params_example(
+    params: &[FunctionParam],
+    types: &HashMap<String, SorobanType>,
+) -> serde_json::Value {
+    serde_json::Value::Object(
+        params
+            .iter()
+            .map(|p| (p.name.clone(), example_value(&p.param_type, types)))
+            .collect(),
+    )
+}
+
+/// A placeholder value of type `t`: zero for numbers, a sample address, empty
+/// collections, the first variant of an enum. Custom type names are resolved
+/// through `types`, the ABI's user-defined types.
+pub fn example_value(t: &SorobanType, types: &HashMap<String, SorobanType>) -> serde_json::Value {
+    example_value_at(t, types, 0)
+}
+
+fn example_value_at(
+    t: &SorobanType,
+    types: &HashMap<String, SorobanType>,
+    depth: usize,
+) -> serde_json::Value {
+    use serde_json::Value;
+
+    if depth > MAX_EXAMPLE_DEPTH {
+        return Value::Null;
+    }
+    let nested = |t: &SorobanType| example_value_at(t, types, depth + 1);
+
+    match t {
+        SorobanType::Bool => Value::Bool(false),
+        SorobanType::I32
+        | SorobanType::I64
+        | SorobanType::I128
+        | SorobanType::I256
+        | SorobanType::U32
+        | SorobanType::U64
+        | SorobanType::U128
+        | SorobanType::U256
+        | SorobanType::Timepoint
+        | SorobanType::Duration => Value::from(0),
+        SorobanType::Symbol => Value::String("symbol".to_string()),
+        SorobanType::String => Value::String("string".to_string()),
+        SorobanType::Bytes => Value::String(String::new()),
+        SorobanType::BytesN { n } => Value::String(zero_bytes_base64(*n as usize)),
+        SorobanType::Address => Value::String(EXAMPLE_ADDRESS.to_string()),
+        SorobanType::Void => Value::Null,
+        SorobanType::Option { value_type } => nested(value_type),
+        SorobanType::Result { ok_type, .. } => nested(ok_type),
+        SorobanType::Vec { .. } => Value::Array(Vec::new()),
+        SorobanType::Map { .. } => Value::Object(serde_json::Map::new()),
+        SorobanType::Tuple { elements } => Value::Array(elements.iter().map(nested).collect()),
+        SorobanType::Struct { fields, .. } => Value::Object(
+            fields
+                .iter()
+                .map(|f| (f.name.clone(), nested(&f.field_type)))
+                .collect(),
+        ),
+        SorobanType::Enum { variants, .. } => variants
+            .first()
+            .map(|v| Value::String(v.name.clone()))
+            .unwrap_or(Value::Null),
+        SorobanType::Custom { name } => {
+            if let Some(resolved) = types.get(name) {
+                return nested(resolved);
+            }
+            match SorobanType::from_type_string(name) {
+                SorobanType::Custom { .. } => Value::Object(serde_json::Map::new()),
+                builtin => nested(&builtin),
+            }
+        }
+    }
+}
+
+/// Base64 encoding of `n` zero bytes
+fn zero_bytes_base64(n: usize) -> String {
+    let mut encoded = "AAAA".repeat(n / 3);
+    match n % 3 {
+        1 => encoded.push_str("AA=="),
+        2 => encoded.push_str("AAA="),
+        _ => {}
+    }
+    encoded
+}
+
 #[allow(dead_code)]
 struct SchemaGenerator {
     schemas: BTreeMap<String, Schema>,
@@ -663,3 +782,72 @@ pub fn to_yaml(doc: &OpenApiDoc) -> Result<String, serde_yaml::Error> {
 pub fn to_json(doc: &OpenApiDoc) -> Result<String, serde_json::Error> {
     serde_json::to_string_pretty(doc)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(name: &str, param_type: SorobanType) -> FunctionParam {
+        FunctionParam {
+            name: name.to_string(),
+            param_type,
+            doc: None,
+        }
+    }
+
+    #[test]
+    fn examples_follow_abi_types() {
+        let types = HashMap::new();
+        let params = [
+            param("to", SorobanType::Address),
+            param("amount", SorobanType::I128),
+            param(
+                "memo",
+                SorobanType::Vec {
+                    element_type: Box::new(SorobanType::Symbol),
+                },
+            ),
+            param("salt", SorobanType::BytesN { n: 32 }),
+        ];
+
+        let example = params_example(&params, &types);
+
+        assert_eq!(example["to"], EXAMPLE_ADDRESS);
+        assert_eq!(example["amount"], 0);
+        assert_eq!(example["memo"], serde_json::json!([]));
+        assert_eq!(example["salt"].as_str().unwrap().len(), 44);
+    }
+
+    #[test]
+    fn custom_types_resolve_through_abi_types() {
+        let types = HashMap::from([(
+            "Config".to_string(),
+            SorobanType::Struct {
+                name: "Config".to_string(),
+                fields: vec![StructField {
+                    name: "admin".to_string(),
+                    field_type: SorobanType::Address,
+                    doc: None,
+                }],
+            },
+        )]);
+        let custom = SorobanType::Custom {
+            name: "Config".to_string(),
+        };
+
+        assert_eq!(
+            example_value(&custom, &types),
+            serde_json::json!({ "admin": EXAMPLE_ADDRESS })
+        );
+    }
+
+    #[test]
+    fn self_referencing_custom_type_terminates() {
+        let node = SorobanType::Custom {
+            name: "Node".to_string(),
+        };
+        let types = HashMap::from([("Node".to_string(), node.clone())]);
+
+        assert_eq!(example_value(&node, &types), serde_json::Value::Null);
+    }
+}
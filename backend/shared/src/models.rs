@@ -35,6 +35,19 @@ pub struct Contract {
     pub network_configs: Option<serde_json::Value>,
 }
 
+/// A contract in a listing, with its relevance when the listing was a
+/// full-text `search`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RankedContract {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub contract: Contract,
+    /// `ts_rank` of the contract's name, tags and description against `search`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub search_rank: Option<f32>,
+}
+
 /// Response for GET /contracts/:id with optional network-specific slice (Issue #43)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractGetResponse {
@@ -79,6 +92,19 @@ impl std::fmt::Display for Network {
     }
 }
 
+impl std::str::FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "mainnet" => Ok(Self::Mainnet),
+            "testnet" => Ok(Self::Testnet),
+            "futurenet" => Ok(Self::Futurenet),
+            other => Err(format!("unknown network: {}", other)),
+        }
+    }
+}
+
 /// Upgrade strategy for contract upgrades
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "upgrade_strategy_type", rename_all = "lowercase")]
@@ -193,6 +219,9 @@ pub struct GraphEdge {
 pub struct GraphResponse {
     pub nodes: Vec<GraphNode>,
     pub edges: Vec<GraphEdge>,
+    /// Circular dependencies, each listed as the contracts along the loop
+    #[serde(default)]
+    pub cycles: Vec<Vec<Uuid>>,
 }
 
 /// Request to publish a new contract
@@ -265,25 +294,57 @@ pub enum DeprecationStatus {
     Retired,
 }
 
+/// Why a contract was deprecated
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "deprecation_reason", rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
+pub enum DeprecationReason {
+    Superseded,
+    Vulnerable,
+    Abandoned,
+    Merged,
+}
+
+impl std::fmt::Display for DeprecationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeprecationReason::Superseded => write!(f, "superseded"),
+            DeprecationReason::Vulnerable => write!(f, "vulnerable"),
+            DeprecationReason::Abandoned => write!(f, "abandoned"),
+            DeprecationReason::Merged => write!(f, "merged"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeprecationInfo {
     pub contract_id: String,
     pub status: DeprecationStatus,
+    pub reason: Option<DeprecationReason>,
     pub deprecated_at: Option<DateTime<Utc>>,
     pub retirement_at: Option<DateTime<Utc>>,
+    /// Successor contract consumers should migrate to
     pub replacement_contract_id: Option<String>,
     pub migration_guide_url: Option<String>,
     pub notes: Option<String>,
     pub days_remaining: Option<i64>,
+    /// End-of-life date after which the contract is retired automatically
+    pub sunset_at: Option<DateTime<Utc>>,
+    /// Whole days left before `sunset_at`; 0 once it has passed
+    pub days_until_sunset: Option<i64>,
     pub dependents_notified: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeprecateContractRequest {
     pub retirement_at: DateTime<Utc>,
+    #[serde(default)]
+    pub reason: Option<DeprecationReason>,
     pub replacement_contract_id: Option<String>,
     pub migration_guide_url: Option<String>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub sunset_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -301,9 +362,23 @@ pub struct DeprecationNotification {
 pub struct ImpactAnalysisResponse {
     pub contract_id: Uuid,
     pub change_type: Option<String>,
+    /// Levels of dependents walked
+    pub depth: u32,
     pub affected_count: usize,
     pub affected_contracts: Vec<Contract>,
+    /// `affected_contracts` grouped by how many dependency hops away they are
+    pub impacted_by_distance: Vec<ImpactLevel>,
     pub has_cycles: bool,
+    /// The traversal was cut short on a densely connected graph
+    pub truncated: bool,
+}
+
+/// Contracts impacted at one distance from the changed contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactLevel {
+    /// 1 for direct dependents, 2 for their dependents, and so on
+    pub distance: i32,
+    pub contracts: Vec<Contract>,
 }
 /// Dependency declaration in publish request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -353,6 +428,20 @@ pub struct VerifyRequest {
     pub compiler_version: String,
 }
 
+/// Request to verify an already-deployed contract against its registered
+/// source, using the WASM installed on-chain. Exactly one of `contract_id`
+/// and `wasm_hash` is given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyOnChainRequest {
+    #[serde(default)]
+    pub contract_id: Option<String>,
+    #[serde(default)]
+    pub wasm_hash: Option<String>,
+    /// Narrows the registry lookup when the same contract is on several networks
+    #[serde(default)]
+    pub network: Option<Network>,
+}
+
 /// Sorting options for contracts
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -373,10 +462,21 @@ pub enum SortOrder {
     Desc,
 }
 
+/// Sort keys for the contract listing's `sort` parameter
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractSort {
+    PublishedAt,
+    Name,
+    TrustScore,
+}
+
 /// Search/filter parameters for contracts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractSearchParams {
     pub query: Option<String>,
+    /// Full-text search over name, tags and description, ranked by relevance
+    pub search: Option<String>,
     pub network: Option<Network>,
     /// Multiple networks filter (e.g. ?network=mainnet&network=testnet)
     pub networks: Option<Vec<Network>>,
@@ -384,11 +484,24 @@ pub struct ContractSearchParams {
     pub category: Option<String>,
     pub tags: Option<Vec<String>>,
     pub maturity: Option<MaturityLevel>,
+    /// Latest verification status: `pending`, `verified` or `failed`
+    pub status: Option<String>,
+    /// Stellar address of the publishing account
+    pub publisher_address: Option<String>,
+    /// Only verified (`true`) or only unverified (`false`) contracts
+    pub verified: Option<bool>,
+    pub tag: Option<String>,
     pub page: Option<i64>,
     #[serde(alias = "page_size")]
     pub limit: Option<i64>,
+    /// Takes precedence over `page` when both are given
+    pub offset: Option<i64>,
     pub sort_by: Option<SortBy>,
     pub sort_order: Option<SortOrder>,
+    /// Overrides `sort_by` when given
+    pub sort: Option<ContractSort>,
+    /// Direction for `sort`; defaults to ascending for `name`, else descending
+    pub order: Option<SortOrder>,
     pub cursor: Option<String>,
 }
 
@@ -654,9 +767,14 @@ pub enum CanaryStatus {
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "rollout_stage", rename_all = "snake_case")]
 pub enum RolloutStage {
+    // snake_case alone would map these to `stage1` etc.
+    #[sqlx(rename = "stage_1")]
     Stage1,
+    #[sqlx(rename = "stage_2")]
     Stage2,
+    #[sqlx(rename = "stage_3")]
     Stage3,
+    #[sqlx(rename = "stage_4")]
     Stage4,
     Complete,
 }
@@ -678,6 +796,10 @@ pub struct CanaryRelease {
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_by: Option<String>,
+    pub p95_latency_threshold_ms: Option<Decimal>,
+    pub p99_latency_threshold_ms: Option<Decimal>,
+    /// Roll back rather than pause when a latency threshold is breached
+    pub auto_rollback_on_latency: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -691,6 +813,35 @@ pub struct CanaryMetric {
     pub avg_response_time_ms: Option<Decimal>,
     pub p95_response_time_ms: Option<Decimal>,
     pub p99_response_time_ms: Option<Decimal>,
+    /// Named business metrics recorded with this sample, as a JSON object of
+    /// `CanaryBusinessMetric`
+    pub business_metrics: Option<serde_json::Value>,
+    /// Sampled from the traffic still served by the from-deployment rather
+    /// than from the canary
+    pub baseline: bool,
+}
+
+/// One recorded move of a canary between rollout stages, manual or automatic
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CanaryStageTransition {
+    pub id: Uuid,
+    pub canary_id: Uuid,
+    pub from_stage: RolloutStage,
+    pub to_stage: RolloutStage,
+    pub from_percentage: i32,
+    pub to_percentage: i32,
+    pub transitioned_at: DateTime<Utc>,
+    /// Operator, or `auto-rollback` / `latency-gate` for automatic transitions
+    pub transitioned_by: Option<String>,
+    pub metrics_at_transition: Option<serde_json::Value>,
+}
+
+/// A business metric (e.g. conversion rate) observed on canary traffic, with the
+/// same metric for control traffic when available
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanaryBusinessMetric {
+    pub value: f64,
+    pub baseline: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -708,6 +859,10 @@ pub struct CreateCanaryRequest {
     pub contract_id: String,
     pub to_deployment_id: String,
     pub error_rate_threshold: Option<f64>,
+    pub p95_latency_threshold_ms: Option<f64>,
+    pub p99_latency_threshold_ms: Option<f64>,
+    #[serde(default)]
+    pub auto_rollback_on_latency: bool,
     pub created_by: Option<String>,
 }
 
@@ -726,6 +881,11 @@ pub struct RecordCanaryMetricRequest {
     pub avg_response_time_ms: Option<f64>,
     pub p95_response_time_ms: Option<f64>,
     pub p99_response_time_ms: Option<f64>,
+    #[serde(default)]
+    pub business_metrics: std::collections::BTreeMap<String, CanaryBusinessMetric>,
+    /// Record a sample of the from-deployment's traffic during the canary
+    #[serde(default)]
+    pub baseline: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
@@ -738,7 +898,7 @@ pub enum AbTestStatus {
     Cancelled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "variant_type", rename_all = "snake_case")]
 pub enum VariantType {
     Control,
@@ -763,6 +923,16 @@ pub struct AbTest {
     pub ended_at: Option<DateTime<Utc>>,
     pub created_by: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Stop the test automatically once it reaches significance and sample size
+    pub auto_stop: bool,
+    /// Variant the test was decided for, once it has been
+    pub winner: Option<VariantType>,
+    /// Variant that met the stopping condition on the latest evaluations
+    pub auto_stop_candidate: Option<VariantType>,
+    /// Consecutive evaluations `auto_stop_candidate` has met the stopping condition
+    pub auto_stop_streak: i32,
+    /// Metrics the treatment must not significantly increase to be declared the winner
+    pub guardrail_metrics: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -809,6 +979,8 @@ pub struct AbTestResult {
     pub statistical_significance: Option<Decimal>,
     pub is_winner: bool,
     pub calculated_at: DateTime<Utc>,
+    /// Guardrail comparisons made when the result was calculated
+    pub guardrail_outcomes: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -823,6 +995,11 @@ pub struct CreateAbTestRequest {
     pub hypothesis: Option<String>,
     pub significance_threshold: Option<f64>,
     pub min_sample_size: Option<i32>,
+    #[serde(default)]
+    pub auto_stop: bool,
+    /// Secondary metrics where higher is worse, e.g. `error_rate`
+    #[serde(default)]
+    pub guardrail_metrics: Vec<String>,
     pub created_by: Option<String>,
 }
 
@@ -841,7 +1018,7 @@ pub struct GetVariantRequest {
     pub user_address: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "metric_type", rename_all = "snake_case")]
 pub enum MetricType {
     ExecutionTime,
@@ -871,6 +1048,9 @@ pub struct PerformanceMetric {
     pub p99: Option<Decimal>,
     pub timestamp: DateTime<Utc>,
     pub metadata: Option<serde_json::Value>,
+    /// Contract version the metric was recorded against
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -905,6 +1085,13 @@ pub struct PerformanceAlert {
     pub resolved: bool,
     pub resolved_at: Option<DateTime<Utc>>,
     pub message: Option<String>,
+    /// Webhook delivery state: `pending`, `delivered` or `failed`; `None` when
+    /// the alert's config has no webhook
+    pub notification_status: Option<String>,
+    pub notification_attempts: i32,
+    /// Error from the last failed delivery attempt
+    pub notification_error: Option<String>,
+    pub notified_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -934,8 +1121,12 @@ pub struct PerformanceAlertConfig {
     pub metric_type: MetricType,
     pub threshold_type: String,
     pub threshold_value: Decimal,
+    /// Look-back window for `rate_of_change` thresholds
+    pub window_minutes: Option<i32>,
     pub severity: AlertSeverity,
     pub enabled: bool,
+    /// POSTed the alert details whenever this config triggers an alert
+    pub notify_webhook_url: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -950,6 +1141,20 @@ pub struct RecordPerformanceMetricRequest {
     pub p95: Option<f64>,
     pub p99: Option<f64>,
     pub metadata: Option<serde_json::Value>,
+    /// Contract version the metric was recorded against, for regression tracking
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// Raw samples for one metric; the server computes the value and percentiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordMetricSamplesRequest {
+    pub metric_type: MetricType,
+    pub function_name: Option<String>,
+    pub samples: Vec<f64>,
+    pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -958,7 +1163,27 @@ pub struct CreateAlertConfigRequest {
     pub metric_type: MetricType,
     pub threshold_type: String,
     pub threshold_value: f64,
+    /// Required for `rate_of_change`: minutes back to the metric compared against
+    #[serde(default)]
+    pub window_minutes: Option<i32>,
     pub severity: Option<AlertSeverity>,
+    /// HTTPS endpoint notified when this config triggers an alert
+    #[serde(default)]
+    pub notify_webhook_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAlertConfigRequest {
+    pub enabled: bool,
+}
+
+/// A hypothetical metric to test alert configs against; nothing is recorded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluateAlertConfigsRequest {
+    pub metric_type: MetricType,
+    pub value: f64,
+    pub p95: Option<f64>,
+    pub p99: Option<f64>,
 }
 
 // ────────────────────────────────────────────────────────────────────────────
@@ -987,6 +1212,18 @@ pub struct CustomMetric {
     pub timestamp: DateTime<Utc>,
     pub network: Network,
     pub created_at: DateTime<Utc>,
+    /// String dimensions such as `function` or `network`
+    pub labels: serde_json::Value,
+    /// Bucketed observations, for histogram samples
+    pub buckets: Option<serde_json::Value>,
+}
+
+/// One histogram bucket: how many observations fell at or below `le` and
+/// above the previous bucket's bound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    pub le: f64,
+    pub count: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -994,6 +1231,7 @@ pub struct RecordCustomMetricRequest {
     pub contract_id: String,
     pub metric_name: String,
     pub metric_type: CustomMetricType,
+    /// For histograms, the sum of all observations
     pub value: f64,
     pub unit: Option<String>,
     pub metadata: Option<serde_json::Value>,
@@ -1001,6 +1239,11 @@ pub struct RecordCustomMetricRequest {
     pub transaction_hash: Option<String>,
     pub timestamp: Option<DateTime<Utc>>,
     pub network: Option<Network>,
+    #[serde(default)]
+    pub labels: std::collections::HashMap<String, String>,
+    /// Required for histograms, in ascending `le` order
+    #[serde(default)]
+    pub buckets: Option<Vec<HistogramBucket>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -1106,6 +1349,8 @@ pub struct ContractAnalyticsResponse {
     pub deployments: DeploymentStats,
     pub interactors: InteractorStats,
     pub timeline: Vec<TimelineEntry>,
+    /// When these figures were last recomputed
+    pub computed_at: DateTime<Utc>,
 }
 
 /// Deployment statistics
@@ -1204,6 +1449,7 @@ pub enum AuditActionType {
     PublisherChanged,
     VersionCreated,
     Rollback,
+    ContractRetired,
 }
 
 impl std::fmt::Display for AuditActionType {
@@ -1215,6 +1461,7 @@ impl std::fmt::Display for AuditActionType {
             Self::PublisherChanged => "publisher_changed",
             Self::VersionCreated => "version_created",
             Self::Rollback => "rollback",
+            Self::ContractRetired => "contract_retired",
         };
         write!(f, "{}", s)
     }
@@ -1933,12 +2180,32 @@ pub struct SimulateDeployRequest {
     pub contract_id: String,
     pub name: String,
     pub description: Option<String>,
-    pub network: Network,
+    /// Network the estimate is recorded and compared under; falls back to
+    /// `DEFAULT_GAS_NETWORK` when omitted
+    #[serde(default)]
+    pub network: Option<Network>,
+    /// Encoding of `wasm_binary` after base64 decoding; gzip is auto-detected when omitted
+    #[serde(default)]
+    pub encoding: Option<WasmEncoding>,
     pub category: Option<String>,
     pub tags: Vec<String>,
     pub publisher_address: String,
     #[serde(default)]
     pub dependencies: Vec<DependencyDeclaration>,
+    /// List the module's custom sections in the result
+    #[serde(default)]
+    pub include_sections: bool,
+    /// Ledgers the rent estimate keeps the contract alive for; the gas
+    /// model's default horizon when omitted
+    #[serde(default)]
+    pub rent_ledgers: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WasmEncoding {
+    Raw,
+    Gzip,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1952,6 +2219,33 @@ pub struct SimulationResult {
     pub abi_preview: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contract_functions: Option<Vec<ContractFunctionInfo>>,
+    /// Change against the contract's most recent recorded estimate; absent
+    /// when it has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_delta: Option<GasDelta>,
+    /// Custom sections in the module, when `include_sections` was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_sections: Option<Vec<CustomSectionInfo>>,
+    /// Protocol the contract was built against, from its `contractenvmetav0`
+    /// section; absent when the module has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_version: Option<InterfaceVersion>,
+}
+
+/// Soroban environment interface version a contract was built against
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InterfaceVersion {
+    pub protocol: u32,
+    /// Non-zero only for contracts built against a pre-release environment
+    pub pre_release: u32,
+}
+
+/// Name and size of a WASM custom section, e.g. `contractspecv0`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomSectionInfo {
+    pub name: String,
+    /// Length of the section's contents, excluding its name
+    pub size_bytes: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1978,6 +2272,42 @@ pub struct GasEstimate {
     pub complexity_factor: f64,
     pub deployment_cost_stroops: i64,
     pub storage_cost_stroops: i64,
+    /// Deployment cost attributed to each exported function by code size
+    #[serde(default)]
+    pub per_function: Vec<FunctionGasEstimate>,
+    /// Ongoing state rent; not included in `total_cost_stroops`
+    #[serde(default)]
+    pub rent_estimate: Option<RentEstimate>,
+}
+
+/// Rent to keep a contract's code and instance ledger entries alive
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RentEstimate {
+    pub ledgers: u32,
+    /// Size of the contract code entry, i.e. the WASM module
+    pub code_entry_bytes: u64,
+    pub instance_entry_bytes: u64,
+    pub rent_cost_stroops: i64,
+    pub rent_cost_xlm: f64,
+    /// One-time deployment and storage cost plus rent over `ledgers`
+    pub total_cost_of_ownership_stroops: i64,
+}
+
+/// A gas estimate compared with the previous one recorded for the contract
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GasDelta {
+    pub previous_total_cost_stroops: i64,
+    pub previous_estimated_at: DateTime<Utc>,
+    /// Current minus previous total cost; positive means more expensive
+    pub delta_stroops: i64,
+    /// `delta_stroops` relative to the previous cost; `None` when that was zero
+    pub delta_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FunctionGasEstimate {
+    pub name: String,
+    pub estimated_cost_stroops: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2,6 +2,7 @@ use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 use uuid::Uuid;
 
 /// Represents a pagination cursor
@@ -37,6 +38,45 @@ impl Cursor {
     }
 }
 
+/// Append a keyset (after `cursor`) or offset page ordered by
+/// `ts_column DESC, id DESC` to a query whose WHERE clause is already open.
+/// The cursor, limit and offset are bound, never interpolated.
+pub fn push_page(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    cursor: Option<&Cursor>,
+    ts_column: &str,
+    limit: i64,
+    offset: i64,
+) {
+    if let Some(cursor) = cursor {
+        qb.push(format_args!(" AND ({}, id) < (", ts_column))
+            .push_bind(cursor.timestamp)
+            .push(", ")
+            .push_bind(cursor.id)
+            .push(")");
+    }
+    qb.push(format_args!(" ORDER BY {} DESC, id DESC LIMIT ", ts_column))
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+}
+
+/// Cursor for the page after `items`, or `None` when the page wasn't full and
+/// there is nothing left to fetch
+pub fn next_cursor<T>(
+    items: &[T],
+    limit: i64,
+    key: impl Fn(&T) -> (DateTime<Utc>, Uuid),
+) -> Option<String> {
+    if (items.len() as i64) < limit {
+        return None;
+    }
+    items.last().map(|last| {
+        let (timestamp, id) = key(last);
+        Cursor::new(timestamp, id).encode()
+    })
+}
+
 /// Helper to extract cursor from a list of items
 pub trait CursorProvider {
     fn get_cursor(&self) -> Cursor;
@@ -63,6 +103,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_push_page_binds_the_cursor() {
+        let cursor = Cursor::new(Utc::now(), Uuid::new_v4());
+        let mut qb = QueryBuilder::new("SELECT * FROM t WHERE a = ");
+        qb.push_bind(1);
+        push_page(&mut qb, Some(&cursor), "created_at", 20, 0);
+        assert_eq!(
+            qb.sql(),
+            "SELECT * FROM t WHERE a = $1 AND (created_at, id) < ($2, $3) \
+             ORDER BY created_at DESC, id DESC LIMIT $4 OFFSET $5"
+        );
+
+        let mut qb = QueryBuilder::new("SELECT * FROM t WHERE a = ");
+        qb.push_bind(1);
+        push_page(&mut qb, None, "created_at", 20, 40);
+        assert_eq!(
+            qb.sql(),
+            "SELECT * FROM t WHERE a = $1 ORDER BY created_at DESC, id DESC LIMIT $2 OFFSET $3"
+        );
+    }
+
+    #[test]
+    fn test_next_cursor_only_for_full_pages() {
+        let rows: Vec<(DateTime<Utc>, Uuid)> =
+            (0..3).map(|_| (Utc::now(), Uuid::new_v4())).collect();
+
+        assert!(next_cursor(&rows, 4, |r| *r).is_none());
+
+        let encoded = next_cursor(&rows, 3, |r| *r).unwrap();
+        assert_eq!(Cursor::decode(&encoded).unwrap().id, rows[2].1);
+    }
+
     #[test]
     fn test_invalid_cursor() {
         assert!(Cursor::decode("notbase64").is_err());
@@ -199,7 +199,7 @@ pub async fn sign_proposal(
     let body: serde_json::Value = response.json().await?;
 
     if !status.is_success() {
-        let err = body["message"].as_str().unwrap_or("unknown error");
+        let err = body["error"]["message"].as_str().unwrap_or("unknown error");
         anyhow::bail!("API error ({}): {}", status, err);
     }
 
@@ -251,7 +251,7 @@ pub async fn execute_proposal(api_url: &str, proposal_id: &str) -> Result<()> {
     let body: serde_json::Value = response.json().await?;
 
     if !status.is_success() {
-        let err = body["message"].as_str().unwrap_or("unknown error");
+        let err = body["error"]["message"].as_str().unwrap_or("unknown error");
         anyhow::bail!("API error ({}): {}", status, err);
     }
 
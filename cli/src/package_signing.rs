@@ -160,7 +160,9 @@ async fn verify_with_signature(
     let result: serde_json::Value = response.json().await?;
 
     if !status.is_success() {
-        let msg = result["message"].as_str().unwrap_or("Unknown error");
+        let msg = result["error"]["message"]
+            .as_str()
+            .unwrap_or("Unknown error");
         bail!("Verification failed: {}", msg);
     }
 
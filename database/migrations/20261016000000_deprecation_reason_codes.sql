@@ -0,0 +1,9 @@
+-- Structured deprecation reasons so consumers can tell why a contract was retired
+
+CREATE TYPE deprecation_reason AS ENUM ('superseded', 'vulnerable', 'abandoned', 'merged');
+
+ALTER TABLE contract_deprecations ADD COLUMN reason deprecation_reason;
+
+CREATE INDEX IF NOT EXISTS idx_contract_deprecations_replacement
+    ON contract_deprecations(replacement_contract_id)
+    WHERE replacement_contract_id IS NOT NULL;
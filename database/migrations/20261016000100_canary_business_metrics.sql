@@ -0,0 +1,8 @@
+-- Named business metrics (e.g. conversion rate) recorded alongside canary
+-- technical metrics, optionally with the control value for comparison.
+-- Baseline rows are samples of the from-deployment's traffic recorded
+-- alongside a canary, so comparisons can use the same window as the canary.
+
+ALTER TABLE canary_metrics
+    ADD COLUMN business_metrics JSONB,
+    ADD COLUMN baseline BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,72 @@
+-- `rate_of_change` alert thresholds: threshold_value is a percentage, and the
+-- alert fires when a new metric differs from the latest one recorded at least
+-- window_minutes earlier by more than that percentage, in either direction.
+-- Also replaces the `%.2f` specifiers format() doesn't support.
+
+ALTER TABLE performance_alert_configs ADD COLUMN window_minutes INTEGER
+    CHECK (window_minutes > 0);
+
+ALTER TABLE performance_alert_configs ADD CONSTRAINT performance_alert_configs_rate_window
+    CHECK (threshold_type <> 'rate_of_change' OR window_minutes IS NOT NULL);
+
+CREATE OR REPLACE FUNCTION check_performance_thresholds()
+RETURNS TRIGGER AS $$
+DECLARE
+    alert_config RECORD;
+    threshold_met BOOLEAN;
+    alert_msg TEXT;
+    previous_value DECIMAL(15,4);
+BEGIN
+    FOR alert_config IN
+        SELECT * FROM performance_alert_configs
+        WHERE contract_id = NEW.contract_id
+          AND metric_type = NEW.metric_type
+          AND enabled = TRUE
+    LOOP
+        threshold_met := FALSE;
+
+        CASE alert_config.threshold_type
+            WHEN 'p99_exceeds' THEN
+                threshold_met := NEW.p99 IS NOT NULL AND NEW.p99 > alert_config.threshold_value;
+            WHEN 'p95_exceeds' THEN
+                threshold_met := NEW.p95 IS NOT NULL AND NEW.p95 > alert_config.threshold_value;
+            WHEN 'value_exceeds' THEN
+                threshold_met := NEW.value > alert_config.threshold_value;
+            WHEN 'value_below' THEN
+                threshold_met := NEW.value < alert_config.threshold_value;
+            WHEN 'rate_of_change' THEN
+                SELECT value INTO previous_value
+                FROM performance_metrics
+                WHERE contract_id = NEW.contract_id
+                  AND metric_type = NEW.metric_type
+                  AND timestamp <= NEW.timestamp - make_interval(mins => alert_config.window_minutes)
+                ORDER BY timestamp DESC
+                LIMIT 1;
+
+                threshold_met := previous_value IS NOT NULL
+                    AND previous_value <> 0
+                    AND ABS((NEW.value - previous_value) / previous_value * 100)
+                        > alert_config.threshold_value;
+            ELSE
+                threshold_met := FALSE;
+        END CASE;
+
+        IF threshold_met THEN
+            alert_msg := format('%s metric %s threshold: %s (current: %s)',
+                              NEW.metric_type, alert_config.threshold_type,
+                              round(alert_config.threshold_value, 2), round(NEW.value, 2));
+
+            INSERT INTO performance_alerts (
+                contract_id, metric_type, threshold_type, threshold_value,
+                current_value, severity, message
+            ) VALUES (
+                NEW.contract_id, NEW.metric_type, alert_config.threshold_type,
+                alert_config.threshold_value, NEW.value, alert_config.severity, alert_msg
+            )
+            ON CONFLICT DO NOTHING;
+        END IF;
+    END LOOP;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,8 @@
+-- Stored contract analytics, recomputed on demand so reads don't aggregate
+-- contract_interactions every time
+
+CREATE TABLE contract_analytics_snapshots (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    analytics JSONB NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
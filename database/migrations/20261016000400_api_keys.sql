@@ -0,0 +1,17 @@
+-- Publisher API keys for write endpoints. Only a SHA-256 hash of each key is
+-- stored; key_prefix (the key's first characters) narrows the lookup so the
+-- hash itself is compared in constant time by the API.
+
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    publisher_id UUID NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    name VARCHAR(255),
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash CHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_api_keys_prefix ON api_keys(key_prefix) WHERE revoked_at IS NULL;
+CREATE INDEX idx_api_keys_publisher_id ON api_keys(publisher_id);
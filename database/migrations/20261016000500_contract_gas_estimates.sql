@@ -0,0 +1,19 @@
+-- History of deployment gas estimates for registered contracts, recorded on
+-- every successful deploy simulation, with the module each was simulated for
+-- so it can be compared with the deployed version or an earlier build
+
+CREATE TABLE contract_gas_estimates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    network network_type NOT NULL,
+    total_cost_stroops BIGINT NOT NULL,
+    deployment_cost_stroops BIGINT NOT NULL,
+    storage_cost_stroops BIGINT NOT NULL,
+    wasm_size_kb DOUBLE PRECISION NOT NULL,
+    complexity_factor DOUBLE PRECISION NOT NULL,
+    wasm_hash TEXT,
+    estimated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_contract_gas_estimates_latest
+    ON contract_gas_estimates(contract_id, estimated_at DESC);
@@ -0,0 +1,8 @@
+-- Contract version a performance metric was recorded against, so regressions
+-- can be attributed to a release
+
+ALTER TABLE performance_metrics ADD COLUMN version VARCHAR(50);
+
+CREATE INDEX idx_performance_metrics_version
+    ON performance_metrics(contract_id, metric_type, version)
+    WHERE version IS NOT NULL;
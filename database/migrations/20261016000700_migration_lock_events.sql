@@ -0,0 +1,14 @@
+-- Audit trail for the migration lock being force-acquired after its TTL or
+-- released manually by an admin
+
+CREATE TABLE schema_migration_lock_events (
+    id BIGSERIAL PRIMARY KEY,
+    event VARCHAR(32) NOT NULL,
+    previous_holder VARCHAR(255),
+    previous_locked_at TIMESTAMPTZ,
+    actor VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_schema_migration_lock_events_created_at
+    ON schema_migration_lock_events(created_at DESC);
@@ -0,0 +1,23 @@
+-- Single weighted search document over a contract's name, tags and
+-- description for `GET /api/contracts?search=`, ranked with ts_rank.
+-- Queries are parsed with contracts_build_tsquery (026_full_text_search).
+
+-- array_to_string is only STABLE, which a generated column can't use
+CREATE OR REPLACE FUNCTION contracts_tags_text(tags TEXT[])
+RETURNS TEXT
+LANGUAGE sql
+IMMUTABLE
+AS $$ SELECT COALESCE(array_to_string(tags, ' '), '') $$;
+
+ALTER TABLE contracts
+  ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (
+      setweight(to_tsvector('english', name), 'A') ||
+      setweight(to_tsvector('english', contracts_tags_text(tags)), 'B') ||
+      setweight(to_tsvector('english', COALESCE(description, '')), 'C')
+    ) STORED;
+
+CREATE INDEX idx_contracts_search_vector
+  ON contracts USING GIN (search_vector);
+
+ANALYZE contracts;
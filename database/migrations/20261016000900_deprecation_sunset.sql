@@ -0,0 +1,12 @@
+-- Optional end-of-life date for deprecated contracts. A background job marks
+-- contracts retired once their sunset passes and records it in the audit log.
+
+ALTER TABLE contract_deprecations
+    ADD COLUMN sunset_at TIMESTAMPTZ,
+    ADD COLUMN retired_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_contract_deprecations_pending_sunset
+    ON contract_deprecations(sunset_at)
+    WHERE sunset_at IS NOT NULL AND retired_at IS NULL;
+
+ALTER TYPE audit_action_type ADD VALUE IF NOT EXISTS 'contract_retired';
@@ -0,0 +1,12 @@
+-- Dimensioned custom metrics: free-form string labels on every sample, and
+-- bucketed observations for histogram samples
+
+ALTER TABLE contract_custom_metrics
+    ADD COLUMN labels JSONB NOT NULL DEFAULT '{}'::jsonb,
+    ADD COLUMN buckets JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_custom_metrics_labels
+    ON contract_custom_metrics USING GIN (labels);
+
+COMMENT ON COLUMN contract_custom_metrics.labels IS 'String key/value dimensions, e.g. {"function": "swap", "network": "mainnet"}';
+COMMENT ON COLUMN contract_custom_metrics.buckets IS 'Histogram samples only: [{"le": upper_bound, "count": n}, ...] in ascending bound order';
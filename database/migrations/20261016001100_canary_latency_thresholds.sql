@@ -0,0 +1,9 @@
+-- Latency gates for canary releases: a recorded p95/p99 above its threshold
+-- either rolls the canary back or pauses it for operator review
+
+ALTER TABLE canary_releases
+    ADD COLUMN p95_latency_threshold_ms DECIMAL(10,2),
+    ADD COLUMN p99_latency_threshold_ms DECIMAL(10,2),
+    ADD COLUMN auto_rollback_on_latency BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN canary_releases.auto_rollback_on_latency IS 'Roll back instead of pausing when a latency threshold is breached';
@@ -0,0 +1,12 @@
+-- Opt-in automatic stopping of A/B tests once the result is significant and
+-- both variants are sampled enough. The streak columns let the evaluator
+-- require the same winner on consecutive runs before it stops a test.
+
+ALTER TABLE ab_tests
+    ADD COLUMN auto_stop BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN winner variant_type,
+    ADD COLUMN auto_stop_candidate variant_type,
+    ADD COLUMN auto_stop_streak INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_ab_tests_auto_stop
+    ON ab_tests(id) WHERE auto_stop AND status = 'running';
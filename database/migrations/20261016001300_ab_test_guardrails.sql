@@ -0,0 +1,10 @@
+-- Guardrail metrics: secondary metrics (errors, latency, ...) the treatment
+-- must not significantly worsen for it to be declared the winner
+
+ALTER TABLE ab_tests
+    ADD COLUMN guardrail_metrics TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE ab_test_results
+    ADD COLUMN guardrail_outcomes JSONB;
+
+COMMENT ON COLUMN ab_test_results.guardrail_outcomes IS 'Per-guardrail comparison of treatment against control at calculation time';
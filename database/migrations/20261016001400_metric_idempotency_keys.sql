@@ -0,0 +1,16 @@
+-- Idempotency keys for the metric-recording endpoints. A retried request
+-- carrying an already-processed `Idempotency-Key` gets the stored response
+-- instead of inserting (and incrementing aggregates) a second time.
+
+CREATE TABLE IF NOT EXISTS metric_idempotency_keys (
+    scope TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    -- NULL while the first request is still being processed
+    status_code SMALLINT,
+    response JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (scope, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_idempotency_keys_created_at
+    ON metric_idempotency_keys (created_at);
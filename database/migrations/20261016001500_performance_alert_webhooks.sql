@@ -0,0 +1,86 @@
+-- Webhook notifications for performance alerts. An alert config may name a
+-- `notify_webhook_url`; alerts it triggers are queued as `pending` and POSTed
+-- by the API's alert webhook job, which retries with backoff and records the
+-- outcome on the alert.
+
+ALTER TABLE performance_alert_configs ADD COLUMN notify_webhook_url TEXT;
+
+ALTER TABLE performance_alerts
+    ADD COLUMN notify_webhook_url TEXT,
+    -- NULL when the config had no webhook
+    ADD COLUMN notification_status TEXT
+        CHECK (notification_status IN ('pending', 'delivered', 'failed')),
+    ADD COLUMN notification_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN notification_error TEXT,
+    ADD COLUMN next_notification_at TIMESTAMPTZ,
+    ADD COLUMN notified_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_performance_alerts_pending_notifications
+    ON performance_alerts (next_notification_at)
+    WHERE notification_status = 'pending';
+
+CREATE OR REPLACE FUNCTION check_performance_thresholds()
+RETURNS TRIGGER AS $$
+DECLARE
+    alert_config RECORD;
+    threshold_met BOOLEAN;
+    alert_msg TEXT;
+    previous_value DECIMAL(15,4);
+BEGIN
+    FOR alert_config IN
+        SELECT * FROM performance_alert_configs
+        WHERE contract_id = NEW.contract_id
+          AND metric_type = NEW.metric_type
+          AND enabled = TRUE
+    LOOP
+        threshold_met := FALSE;
+
+        CASE alert_config.threshold_type
+            WHEN 'p99_exceeds' THEN
+                threshold_met := NEW.p99 IS NOT NULL AND NEW.p99 > alert_config.threshold_value;
+            WHEN 'p95_exceeds' THEN
+                threshold_met := NEW.p95 IS NOT NULL AND NEW.p95 > alert_config.threshold_value;
+            WHEN 'value_exceeds' THEN
+                threshold_met := NEW.value > alert_config.threshold_value;
+            WHEN 'value_below' THEN
+                threshold_met := NEW.value < alert_config.threshold_value;
+            WHEN 'rate_of_change' THEN
+                SELECT value INTO previous_value
+                FROM performance_metrics
+                WHERE contract_id = NEW.contract_id
+                  AND metric_type = NEW.metric_type
+                  AND timestamp <= NEW.timestamp - make_interval(mins => alert_config.window_minutes)
+                ORDER BY timestamp DESC
+                LIMIT 1;
+
+                threshold_met := previous_value IS NOT NULL
+                    AND previous_value <> 0
+                    AND ABS((NEW.value - previous_value) / previous_value * 100)
+                        > alert_config.threshold_value;
+            ELSE
+                threshold_met := FALSE;
+        END CASE;
+
+        IF threshold_met THEN
+            alert_msg := format('%s metric %s threshold: %s (current: %s)',
+                              NEW.metric_type, alert_config.threshold_type,
+                              round(alert_config.threshold_value, 2), round(NEW.value, 2));
+
+            INSERT INTO performance_alerts (
+                contract_id, metric_type, threshold_type, threshold_value,
+                current_value, severity, message,
+                notify_webhook_url, notification_status, next_notification_at
+            ) VALUES (
+                NEW.contract_id, NEW.metric_type, alert_config.threshold_type,
+                alert_config.threshold_value, NEW.value, alert_config.severity, alert_msg,
+                alert_config.notify_webhook_url,
+                CASE WHEN alert_config.notify_webhook_url IS NOT NULL THEN 'pending' END,
+                CASE WHEN alert_config.notify_webhook_url IS NOT NULL THEN NOW() END
+            )
+            ON CONFLICT DO NOTHING;
+        END IF;
+    END LOOP;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
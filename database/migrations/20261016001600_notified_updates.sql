@@ -0,0 +1,16 @@
+-- Dependency updates each publisher has already been notified about, so the
+-- scheduled update monitor doesn't email the same update every cycle. An
+-- update is only re-sent when it becomes a security update; `notified_at`
+-- also throttles publishers whose `frequency` is daily or weekly.
+
+CREATE TABLE IF NOT EXISTS notified_updates (
+    publisher_address VARCHAR(56) NOT NULL,
+    dependency_name VARCHAR(255) NOT NULL,
+    version VARCHAR(50) NOT NULL,
+    is_security BOOLEAN NOT NULL DEFAULT FALSE,
+    notified_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (publisher_address, dependency_name, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notified_updates_publisher_notified_at
+    ON notified_updates (publisher_address, notified_at DESC);
@@ -0,0 +1,7 @@
+-- The error-rate rollback now happens in the API when a canary metric is
+-- recorded, after a minimum number of requests. The trigger it replaces read
+-- `NEW.canary_id`, which `canary_releases` doesn't have, so every update of a
+-- canary's totals failed.
+
+DROP TRIGGER IF EXISTS canary_auto_rollback_trigger ON canary_releases;
+DROP FUNCTION IF EXISTS check_canary_error_rate();
@@ -0,0 +1,15 @@
+-- Per-publisher settings for the dependency update monitor: where to send
+-- notifications (`email` and/or `webhook_url`), how often (`frequency`:
+-- immediate, daily or weekly) and which updates (`filter_level`: All, Minor,
+-- Major or Security).
+
+CREATE TABLE IF NOT EXISTS notification_settings (
+    publisher_address VARCHAR(56) PRIMARY KEY,
+    email VARCHAR(255),
+    webhook_url TEXT,
+    frequency VARCHAR(20) NOT NULL DEFAULT 'immediate',
+    filter_level VARCHAR(20) NOT NULL DEFAULT 'All',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,9 @@
+-- When each background job last ran on any replica. The scheduler claims a
+-- run by moving `last_run_at` forward only when the previous run is older
+-- than (most of) the job's period, so with several replicas a job still runs
+-- about once per period.
+
+CREATE TABLE IF NOT EXISTS background_job_runs (
+    job_name VARCHAR(100) PRIMARY KEY,
+    last_run_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,33 @@
+-- Security audits and their checklist results, as written by the audit
+-- handlers. The trust score reads the latest audit's `overall_score` and
+-- counts its failed checks of `Critical` severity; `severity` is copied from
+-- the static checklist when an audit's checks are seeded so that count
+-- doesn't depend on the checklist's code.
+
+CREATE TABLE IF NOT EXISTS security_audits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    contract_source TEXT,
+    auditor VARCHAR(255) NOT NULL,
+    audit_date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    overall_score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    summary TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_security_audits_contract_date
+    ON security_audits (contract_id, audit_date DESC);
+
+CREATE TABLE IF NOT EXISTS audit_checks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    audit_id UUID NOT NULL REFERENCES security_audits(id) ON DELETE CASCADE,
+    check_id VARCHAR(20) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    notes TEXT,
+    auto_detected BOOLEAN NOT NULL DEFAULT FALSE,
+    evidence TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (audit_id, check_id)
+);
@@ -43,6 +43,8 @@ export interface Contract {
   logical_id?: string;
   /** Per-network configs: { mainnet: {...}, testnet: {...} } */
   network_configs?: Record<Network, NetworkConfig>;
+  /** Full-text relevance, present when listed with `search` */
+  search_rank?: number;
 }
 
 /** GET /contracts/:id response when ?network= is used (Issue #43) */
@@ -115,6 +117,7 @@ export interface ContractAnalyticsResponse {
   deployments: DeploymentStats;
   interactors: InteractorStats;
   timeline: TimelineEntry[];
+  computed_at: string;
 }
 
 export interface ContractVersion {
@@ -165,6 +168,8 @@ export type MaturityLevel = 'alpha' | 'beta' | 'stable' | 'mature' | 'legacy';
 
 export interface ContractSearchParams {
   query?: string;
+  /** Full-text search over name, tags and description, ranked by relevance */
+  search?: string;
   network?: "mainnet" | "testnet" | "futurenet";
   networks?: Array<"mainnet" | "testnet" | "futurenet">;
   verified_only?: boolean;
@@ -228,6 +233,9 @@ export interface MetricSeriesResponse {
   resolution: 'hour' | 'day' | 'raw';
   points?: MetricSeriesPoint[];
   samples?: MetricSample[];
+  total: number;
+  limit: number;
+  offset: number;
 }
 
 export type DeprecationStatus = 'active' | 'deprecated' | 'retired';
@@ -480,6 +488,7 @@ export const api = {
 
     const queryParams = new URLSearchParams();
     if (params?.query) queryParams.append("query", params.query);
+    if (params?.search) queryParams.append("search", params.search);
     if (params?.network) queryParams.append("network", params.network);
     params?.networks?.forEach((network) => queryParams.append("network", network));
     if (params?.verified_only !== undefined)
@@ -1104,6 +1113,8 @@ export interface GraphEdge {
 export interface GraphResponse {
   nodes: GraphNode[];
   edges: GraphEdge[];
+  /** Circular dependencies, each as the contract ids along the loop */
+  cycles?: string[][];
 }
 
 
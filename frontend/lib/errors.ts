@@ -66,7 +66,7 @@ export async function extractErrorData(response: Response): Promise<{ message?:
     if (contentType?.includes('application/json')) {
       const data = await response.json();
       return {
-        message: data.message || data.error || data.detail,
+        message: data.error?.message || data.message || data.error || data.detail,
         details: data,
       };
     }